@@ -1,11 +1,102 @@
 use anyhow::Result;
-use crossterm::event::KeyCode;
-use std::collections::HashSet;
+use crossterm::event::{KeyCode, KeyEvent};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
-use crate::autocomplete::{AutocompleteEngine, Suggestion};
-use crate::db::{Column, Constraint, DbConnection, ForeignKey, Index, QueryResult, Schema, Table, Trigger};
 
+mod action_palette;
+mod auto_connect;
+mod auto_limit;
+mod autosave;
+mod benchmark;
+mod browser_tree;
+mod catalog;
+mod cell_diff;
+mod cell_value;
+mod column_chooser;
+mod column_stats;
+mod commands;
 mod connection_selector;
+mod databases;
+mod destructive;
+mod ddl_preview;
+mod diagnostics;
+mod diagram;
+mod edit;
+mod editor;
+mod expand_star;
+mod explain;
+mod favorites;
+mod files;
+mod filter_completion;
+mod guard;
+mod history_picker;
+mod hooks;
+mod import;
+mod index_usage;
+mod insert_export;
+mod insert_form;
+mod json_paths;
+mod keepalive;
+mod inspect;
+mod layout;
+mod macros;
+mod matview;
+mod monitor;
+mod notices;
+mod notifications;
+mod params;
+mod preflight;
+mod prompt;
+mod qualify;
+mod recent_objects;
+mod reconnect;
+mod results;
+mod retry;
+mod rewrite;
+mod row_filter;
+mod schema_export;
+mod schema_search;
+mod script;
+mod scripting;
+mod sequences;
+mod snippets;
+mod table_copy;
+mod table_view;
+mod timeout;
+mod tour;
+mod watch;
+mod workspace;
+
+pub use action_palette::ActionPalette;
+pub use browser_tree::TreeNode;
+pub use cell_diff::{CellDiff, Change, MarkedCell};
+pub use cell_value::editor_command;
+pub use column_chooser::ColumnChooser;
+pub use column_stats::{column_stats, format_number};
+pub use databases::DatabasePicker;
+pub use destructive::DestructiveCheck;
+pub use favorites::FavoritesPicker;
+pub use preflight::CostWarning;
+pub use diagram::ErDiagram;
+pub use editor::VimMode;
+pub use guard::GuardedRun;
+pub use history_picker::ResultsPicker;
+pub use import::{ImportJob, ImportWizard};
+pub use index_usage::unused_indexes;
+pub use json_paths::JsonPathPicker;
+pub use matview::RefreshJob;
+pub use monitor::{format_duration, ActivityMonitor, MonitorTab};
+pub use notifications::{Notification, NotificationLevel};
+pub use reconnect::Reconnect;
+pub use recent_objects::RecentPicker;
+pub use params::ParamForm;
+pub use schema_search::{search_label, SchemaSearch};
+pub use snippets::SnippetSession;
+pub use table_view::AUTO_COLUMN_WIDTH;
+pub use tour::{tour_steps, TourAnchor};
+pub use watch::Watch;
+pub use workspace::Workspace;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AppMode {
@@ -13,6 +104,8 @@ pub enum AppMode {
     ConnectionEdit,
     Browser,
     Query,
+    Monitor,
+    Diagram,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -22,31 +115,183 @@ pub enum ConnectionField {
     Database,
     User,
     Password,
+    SavePassword,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FolderType {
     Tables,
     Views,
+    MaterializedViews,
     Functions,
+    Sequences,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TableDetailTab {
     Columns,
     Constraints,
     Indexes,
     Triggers,
     ForeignKeys,
+    Ddl,
+    Stats,
+    Definition,
+    Dependencies,
+    Privileges,
+}
+
+impl TableDetailTab {
+    pub fn title(self) -> &'static str {
+        match self {
+            TableDetailTab::Columns => "Columns",
+            TableDetailTab::Constraints => "Constraints",
+            TableDetailTab::Indexes => "Indexes",
+            TableDetailTab::Triggers => "Triggers",
+            TableDetailTab::ForeignKeys => "Foreign Keys",
+            TableDetailTab::Ddl => "DDL",
+            TableDetailTab::Stats => "Stats",
+            TableDetailTab::Definition => "Definition",
+            TableDetailTab::Dependencies => "Dependencies",
+            TableDetailTab::Privileges => "Privileges",
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum BrowserItem {
+    Database(String),
+    Favorite(usize),            // index into the profile's favorites
     Schema(String),
     Folder(String, FolderType), // schema, folder_type
     Table(String, String),      // schema, table_name
     View(String, String),       // schema, view_name
+    MaterializedView(String, String), // schema, view_name
     Function(String, String),   // schema, function_name
+    Sequence(String, String),   // schema, sequence_name
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryFocus {
+    Editor,
+    Results,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptAction {
+    SaveLayout,
+    EditCell,
+    OpenFile,
+    SaveFile,
+    GotoRow,
+    SearchResults,
+    ExportSession,
+    ImportCsv,
+    RestoreBuffers,
+    Benchmark,
+    ConfirmWrite,
+    SignalBackend,
+    RestartSequence,
+    RefreshMatview,
+    ConnectPassword,
+    SaveCell,
+    StatementTimeout,
+    ExportSchema,
+    ExportInserts,
+    StarQuery,
+    CloseConnection,
+}
+
+// Single-line input modal shown over the current screen
+#[derive(Debug, Clone)]
+pub struct Prompt {
+    pub title: String,
+    pub input: String,
+    pub action: PromptAction,
+    // Shown masked, like the password field of the connection form
+    pub secret: bool,
+}
+
+// Command palette listing the user-defined commands from the config
+#[derive(Debug, Clone, Default)]
+pub struct CommandPalette {
+    pub filter: String,
+    // Index into the filtered list
+    pub selected: usize,
+}
+
+// Key macro recorder: F7 <register> starts recording, F7 stops,
+// F8 <register> replays (F8 F8 replays the last one)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MacroState {
+    Idle,
+    AwaitingRecordRegister,
+    AwaitingReplayRegister,
+    Recording(char),
+}
+
+// What the main loop should do with a key after the macro recorder saw it
+pub enum MacroInput {
+    Consumed,
+    Dispatch(Vec<KeyEvent>),
+}
+
+// Form for inserting a row into the selected table; one field per column
+#[derive(Debug, Clone)]
+pub struct InsertForm {
+    pub schema: String,
+    pub table: String,
+    pub columns: Vec<crate::db::Column>,
+    // Empty means "leave out" so the column default applies
+    pub values: Vec<String>,
+    pub selected: usize,
+}
+
+// Generated SQL waiting for the user to confirm it
+#[derive(Debug, Clone)]
+pub struct PendingStatement {
+    pub title: String,
+    pub sql: String,
+}
+
+// Read-only result shown over the current screen
+#[derive(Debug, Clone)]
+pub struct ResultPopup {
+    pub title: String,
+    pub result: crate::db::QueryResult,
+    pub scroll: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StatementStatus {
+    Ok,
+    Failed(String),
+    Skipped,
+}
+
+// Per-statement result of a "run script" execution
+#[derive(Debug, Clone)]
+pub struct StatementOutcome {
+    pub sql: String,
+    pub status: StatementStatus,
+    pub row_count: usize,
+    pub elapsed: Duration,
+}
+
+// What `run_statement` did: the rows it read, whether `max_rows` cut them
+// short and the server's row count, or the error, and what came with them
+struct StatementRun {
+    result: Result<(crate::db::QueryResult, bool, Option<u64>)>,
+    elapsed: Duration,
+    notices: Vec<crate::db::ServerNotice>,
+    attempts: u32,
+    post_hooks: Result<()>,
 }
 
 pub struct App {
@@ -56,8 +301,6 @@ pub struct App {
     // Connection selector
     pub config: crate::config::Config,
     pub selected_profile: usize,
-    pub editing_profile_name: bool,
-    pub new_profile_name: String,
     
     // Connection fields
     pub host: String,
@@ -65,110 +308,149 @@ pub struct App {
     pub database: String,
     pub user: String,
     pub password: String,
+    pub save_password: bool,
     
-    // Database connection
-    pub db: DbConnection,
-    
-    // Browser state
-    pub schemas: Vec<Schema>,
-    pub tables: Vec<Table>,
-    pub columns: Vec<Column>,
-    pub browser_items: Vec<BrowserItem>,
-    pub browser_selected: usize,
-    pub browser_scroll_offset: usize,
-    
-    // Table details tab state
-    pub table_detail_tab: TableDetailTab,
-    pub selected_table: Option<(String, String)>, // (schema, table_name)
-    pub constraints: Vec<Constraint>,
-    pub indexes: Vec<Index>,
-    pub triggers: Vec<Trigger>,
-    pub foreign_keys: Vec<ForeignKey>,
+    // Local-only usage counters for the session summary
+    pub stats: crate::stats::SessionStats,
+    // Statements, connections and exports, for "export session"
+    pub audit: crate::audit::SessionLog,
     
-    // Query state
-    pub query_input: String,
-    pub query_result: Option<QueryResult>,
-    pub query_cursor: usize,
-    pub query_scroll_offset: usize,
-    pub result_scroll_offset: usize,
+    // Open connections; `ws` is the one on screen. Its slot in `workspaces`
+    // holds an empty placeholder until another tab is selected.
+    pub ws: Workspace,
+    pub workspaces: Vec<Workspace>,
+    pub active_workspace: usize,
+    // Workspace shown next to the active one in split query view
+    pub split_partner: Option<usize>,
     
     // UI state
-    pub error_message: Option<String>,
+    // Errors and feedback shown as toasts; kept as the session's message log
+    pub notifications: Vec<Notification>,
+    pub prompt: Option<Prompt>,
+    pub palette: Option<CommandPalette>,
+    pub action_palette: Option<ActionPalette>,
+    pub results_picker: Option<ResultsPicker>,
+    pub popup: Option<ResultPopup>,
+    pub pending: Option<PendingStatement>,
+    // Write on a prod connection waiting for its confirmation, and the
+    // go-ahead for its second run
+    pub guarded: Option<GuardedRun>,
+    pub write_confirmed: bool,
+    // When grid columns were last resized, until their widths are saved
+    pub columns_resized: Option<Instant>,
+    // DROP, TRUNCATE or an unrestricted DELETE/UPDATE waiting to be confirmed
+    pub destructive: Option<DestructiveCheck>,
+    pub cost_warning: Option<CostWarning>,
+    pub insert_form: Option<InsertForm>,
+    // Values for a query's placeholders, asked for before it runs
+    pub param_form: Option<ParamForm>,
+    pub import_wizard: Option<ImportWizard>,
+    pub import_job: Option<ImportJob>,
+    pub refresh_job: Option<RefreshJob>,
+    pub database_picker: Option<DatabasePicker>,
+    pub json_path_picker: Option<JsonPathPicker>,
+    pub column_chooser: Option<ColumnChooser>,
+    pub favorites_picker: Option<FavoritesPicker>,
+    pub recent_picker: Option<RecentPicker>,
+    pub schema_search: Option<SchemaSearch>,
+    // Cell marked for comparison, and the diff of it against another
+    pub cell_mark: Option<MarkedCell>,
+    pub cell_diff: Option<CellDiff>,
+    // File for the main loop to open in the user's editor, handing it the
+    // terminal meanwhile
+    pub external_editor: Option<std::path::PathBuf>,
+    // Server activity list shown in monitor mode
+    pub monitor: Option<ActivityMonitor>,
+    // Relationship diagram shown in diagram mode
+    pub diagram: Option<ErDiagram>,
+    // Step of the walkthrough overlay being shown
+    pub tour: Option<usize>,
     
-    // Filter state (browser)
-    pub filter_input: String,
-    pub filter_active: bool,
+    // Crash recovery: snapshots of crashed sessions awaiting an answer, and
+    // accepted buffers waiting for their connection
+    pub recovery_files: Vec<std::path::PathBuf>,
+    pub recovered: Vec<crate::recovery::RecoveredBuffer>,
+    pub last_autosave: Instant,
+    pub autosave_failing: bool,
     
-    // Filter state (results)
-    pub results_filter_input: String,
-    pub results_filter_active: bool,
+    // Hotkeys, with the config's overrides applied
+    pub keymap: crate::keymap::Keymap,
     
-    // Expanded items tracking
-    pub expanded_items: HashSet<String>,
-    
-    // Autocomplete
-    pub autocomplete_engine: AutocompleteEngine,
-    pub suggestions: Vec<Suggestion>,
-    pub suggestion_selected: usize,
-    pub show_autocomplete: bool,
-    pub autocomplete_schema_loaded: bool,
+    // Recorded key macros by register, plus the one being recorded
+    pub macro_state: MacroState,
+    pub macros: HashMap<char, Vec<KeyEvent>>,
+    pub macro_buffer: Vec<KeyEvent>,
+    pub last_macro: Option<char>,
 }
 
 impl App {
     pub fn new() -> Self {
-        // Load saved config
+        // Load saved config; without one this is the first launch
+        let first_run = !crate::config::Config::exists();
         let config = crate::config::Config::load().unwrap_or_default();
+        let (keymap, keymap_warnings) = crate::keymap::Keymap::new(&config.keybindings);
 
-        Self {
+        let mut app = Self {
             mode: AppMode::ConnectionSelector,
             connection_field: ConnectionField::Host,
             config,
             selected_profile: 0,
-            editing_profile_name: false,
-            new_profile_name: String::new(),
             host: "localhost".to_string(),
             port: "5432".to_string(),
             database: "postgres".to_string(),
             user: "postgres".to_string(),
             password: String::new(),
-            db: DbConnection::new(),
-            schemas: Vec::new(),
-            tables: Vec::new(),
-            columns: Vec::new(),
-            browser_items: Vec::new(),
-            browser_selected: 0,
-            browser_scroll_offset: 0,
-            table_detail_tab: TableDetailTab::Columns,
-            selected_table: None,
-            constraints: Vec::new(),
-            indexes: Vec::new(),
-            triggers: Vec::new(),
-            foreign_keys: Vec::new(),
-            query_input: String::new(),
-            query_result: None,
-            query_cursor: 0,
-            query_scroll_offset: 0,
-            result_scroll_offset: 0,
-            error_message: None,
-            filter_input: String::new(),
-            filter_active: false,
-            results_filter_input: String::new(),
-            results_filter_active: false,
-            expanded_items: HashSet::new(),
-            autocomplete_engine: AutocompleteEngine::new(),
-            suggestions: Vec::new(),
-            suggestion_selected: 0,
-            show_autocomplete: false,
-            autocomplete_schema_loaded: false,
-        }
-    }
-
-    pub fn set_error(&mut self, msg: String) {
-        self.error_message = Some(msg);
-    }
-
-    pub fn clear_error(&mut self) {
-        self.error_message = None;
+            save_password: false,
+            stats: crate::stats::SessionStats::new(),
+            audit: crate::audit::SessionLog::new(),
+            ws: Workspace::new(),
+            workspaces: Vec::new(),
+            active_workspace: 0,
+            split_partner: None,
+            notifications: Vec::new(),
+            prompt: None,
+            palette: None,
+            action_palette: None,
+            results_picker: None,
+            popup: None,
+            pending: None,
+            guarded: None,
+            write_confirmed: false,
+            columns_resized: None,
+            destructive: None,
+            cost_warning: None,
+            insert_form: None,
+            param_form: None,
+            import_wizard: None,
+            import_job: None,
+            refresh_job: None,
+            database_picker: None,
+            json_path_picker: None,
+            column_chooser: None,
+            favorites_picker: None,
+            recent_picker: None,
+            schema_search: None,
+            cell_mark: None,
+            cell_diff: None,
+            external_editor: None,
+            monitor: None,
+            diagram: None,
+            tour: first_run.then_some(0),
+            recovery_files: Vec::new(),
+            recovered: Vec::new(),
+            last_autosave: Instant::now(),
+            autosave_failing: false,
+            keymap,
+            macro_state: MacroState::Idle,
+            macros: HashMap::new(),
+            macro_buffer: Vec::new(),
+            last_macro: None,
+        };
+        for warning in keymap_warnings {
+            app.set_warning(warning);
+        }
+        app.offer_recovery();
+        app
     }
 
     // Connection field navigation
@@ -178,17 +460,19 @@ impl App {
             ConnectionField::Port => ConnectionField::Database,
             ConnectionField::Database => ConnectionField::User,
             ConnectionField::User => ConnectionField::Password,
-            ConnectionField::Password => ConnectionField::Host,
+            ConnectionField::Password => ConnectionField::SavePassword,
+            ConnectionField::SavePassword => ConnectionField::Host,
         };
     }
 
     pub fn prev_connection_field(&mut self) {
         self.connection_field = match self.connection_field {
-            ConnectionField::Host => ConnectionField::Password,
+            ConnectionField::Host => ConnectionField::SavePassword,
             ConnectionField::Port => ConnectionField::Host,
             ConnectionField::Database => ConnectionField::Port,
             ConnectionField::User => ConnectionField::Database,
             ConnectionField::Password => ConnectionField::User,
+            ConnectionField::SavePassword => ConnectionField::Password,
         };
     }
 
@@ -199,6 +483,12 @@ impl App {
             ConnectionField::Database => &mut self.database,
             ConnectionField::User => &mut self.user,
             ConnectionField::Password => &mut self.password,
+            ConnectionField::SavePassword => {
+                if c == ' ' {
+                    self.save_password = !self.save_password;
+                }
+                return;
+            }
         };
         field.push(c);
     }
@@ -210,6 +500,7 @@ impl App {
             ConnectionField::Database => &mut self.database,
             ConnectionField::User => &mut self.user,
             ConnectionField::Password => &mut self.password,
+            ConnectionField::SavePassword => return,
         };
         field.pop();
     }
@@ -217,368 +508,494 @@ impl App {
     // Database connection
     pub async fn connect(&mut self) -> Result<()> {
         let port: u16 = self.port.parse()?;
-        self.db
-            .connect(&self.host, port, &self.database, &self.user, &self.password)
+        // An empty password falls back to PGPASSWORD / ~/.pgpass
+        let password = if self.password.is_empty() {
+            crate::libpq::lookup_password(&self.host, &self.port, &self.database, &self.user)
+                .unwrap_or_default()
+        } else {
+            self.password.clone()
+        };
+        // Check if this profile already exists
+        let existing = self.config.connections.iter().position(|p| {
+            p.host == self.host && p.port == self.port && 
+            p.database == self.database && p.user == self.user
+        });
+        let saved = existing.map(|index| &self.config.connections[index]);
+        let read_only = saved.is_some_and(|saved| saved.read_only);
+        let settings = crate::db::SessionSettings {
+            read_only,
+            statement_timeout: saved.and_then(|saved| saved.statement_timeout.clone()),
+            target_session_attrs: saved
+                .and_then(|saved| saved.target_session_attrs.clone())
+                .or_else(|| std::env::var("PGTARGETSESSIONATTRS").ok().filter(|attrs| !attrs.is_empty())),
+        };
+
+        // Each connection gets its own workspace (tab)
+        let mut ws = Workspace::new();
+        ws.name = format!("{}@{}/{}", self.user, self.host, self.database);
+        ws.read_only = read_only;
+        ws.db
+            .connect(&self.host, port, &self.database, &self.user, &password, settings)
             .await?;
+        // A language server that fails to start only costs its features
+        let mut lsp_error = None;
+        if let Some(config) = &self.config.lsp {
+            let env = crate::lsp::ServerEnv {
+                host: &self.host,
+                port: &self.port,
+                database: &self.database,
+                user: &self.user,
+                password: &password,
+            };
+            match crate::lsp::LspClient::start(config, env).await {
+                Ok(client) => ws.lsp = Some(client),
+                Err(e) => lsp_error = Some(e),
+            }
+        }
+        if !self.has_workspaces() {
+            self.stats.record_connect();
+        }
+        self.open_workspace(ws);
+        self.audit.record_connect(&self.ws.name);
         
         // Save/update connection profile
-        let profile = crate::config::ConnectionProfile {
+        let mut profile = crate::config::ConnectionProfile {
             name: format!("{}@{}", self.user, self.host),
             host: self.host.clone(),
             port: self.port.clone(),
             database: self.database.clone(),
             user: self.user.clone(),
+            save_password: self.save_password && self.config.use_keyring && !self.password.is_empty(),
+            hooks: crate::config::QueryHooks::default(),
+            table_views: Default::default(),
+            environment: None,
+            color: None,
+            auto_connect: false,
+            read_only: false,
+            statement_timeout: None,
+            target_session_attrs: None,
+            favorites: Vec::new(),
         };
+        self.ws.profile = profile.keyring_account();
         
-        // Check if this profile already exists
-        let existing = self.config.connections.iter().position(|p| {
-            p.host == profile.host && p.port == profile.port && 
-            p.database == profile.database && p.user == profile.user
-        });
+
+        // Only touch the keyring when saving, or when un-ticking a profile
+        // that had a stored password
+        let was_saved = existing.is_some_and(|index| self.config.connections[index].save_password);
+        let keyring_result = if profile.save_password {
+            crate::secrets::store_password(&profile, &self.password)
+        } else if was_saved && !existing.is_some_and(|index| self.config.keyring_shared(index)) {
+            crate::secrets::delete_password(&profile)
+        } else {
+            Ok(())
+        };
+        if keyring_result.is_err() {
+            profile.save_password = was_saved;
+        }
+        self.ws.hooks = existing
+            .map(|index| self.config.connections[index].hooks.clone())
+            .unwrap_or_default();
+        if let Some(saved) = existing.map(|index| &self.config.connections[index]) {
+            self.ws.environment = saved.environment;
+            self.ws.environment_color = saved.environment_color().unwrap_or_default();
+        }
+
+        let config_result = match existing {
+            Some(index) if self.config.connections[index].save_password != profile.save_password => {
+                self.config.connections[index].save_password = profile.save_password;
+                self.config.save()
+            }
+            Some(_) => Ok(()),
+            None => {
+                self.config.connections.push(profile);
+                self.config.save()
+            }
+        };
         
-        if existing.is_none() {
-            self.config.connections.push(profile);
-            if let Err(e) = self.config.save() {
-                eprintln!("Warning: Could not save connection config: {}", e);
+        // Probe what this role/server supports before loading anything
+        if let Some(client) = self.ws.db.client() {
+            let capabilities = crate::db::check_capabilities(client).await;
+            // Only an unreadable catalog is worth interrupting for; the rest
+            // is listed in the details pane
+            if !capabilities.can_read_information_schema {
+                self.set_warning(capabilities.notes().remove(0));
+            } else {
+                self.clear_notifications();
             }
+            self.ws.capabilities = Some(capabilities);
         }
-        
+
+        // Connected all the same; only remembering the profile failed
+        if let Err(e) = keyring_result {
+            self.set_warning(format!("{:#}", e));
+        }
+        if let Err(e) = config_result {
+            self.set_warning(format!("Could not save the connection profile: {:#}", e));
+        }
+        if let Some(e) = lsp_error {
+            self.set_warning(format!("{:#}; using built-in completion", e));
+        }
+
+        self.restore_recovered_buffer();
+
         // Load initial data
         self.mode = AppMode::Browser;
         self.refresh_browser().await?;
+        self.start_catalog_load();
         Ok(())
     }
 
     pub async fn refresh_browser(&mut self) -> Result<()> {
-        if let Some(client) = self.db.client() {
-            self.schemas = crate::db::list_schemas(client, &self.database).await?;
-            self.browser_items = self
-                .schemas
-                .iter()
-                .map(|s| BrowserItem::Schema(s.name.clone()))
+        if let Some(client) = self.ws.db.client() {
+            self.ws.schemas = crate::db::list_schemas(client, &self.database).await?;
+            // The connected database heads the tree, followed by the
+            // connection's favorites
+            self.ws.browser_tree = std::iter::once(BrowserItem::Database(self.database.clone()))
+                .chain((0..self.favorites().len()).map(BrowserItem::Favorite))
+                .chain(self.ws.schemas.iter().map(|s| BrowserItem::Schema(s.name.clone())))
+                .map(TreeNode::new)
                 .collect();
+            // Everything starts collapsed again, with the cursor on the
+            // top-level node it was under
+            let root = self.ws.browser_paths.get(self.ws.browser_selected).and_then(|path| path.first().copied());
+            self.rebuild_browser_items_at(root.into_iter().collect());
         }
         Ok(())
     }
 
     // Browser navigation
     pub fn browser_up(&mut self) {
-        if self.browser_selected > 0 {
-            self.browser_selected -= 1;
+        if self.ws.browser_selected > 0 {
+            self.ws.browser_selected -= 1;
             // Adjust scroll offset if needed
-            if self.browser_selected < self.browser_scroll_offset {
-                self.browser_scroll_offset = self.browser_selected;
+            if self.ws.browser_selected < self.ws.browser_scroll_offset {
+                self.ws.browser_scroll_offset = self.ws.browser_selected;
             }
         }
     }
 
     pub fn browser_down(&mut self) {
-        if self.browser_selected < self.browser_items.len().saturating_sub(1) {
-            self.browser_selected += 1;
-        }
-    }
-
-    pub fn adjust_scroll(&mut self, visible_height: usize) {
-        // Ensure selected item is visible
-        if self.browser_selected >= self.browser_scroll_offset + visible_height {
-            self.browser_scroll_offset = self.browser_selected - visible_height + 1;
-        } else if self.browser_selected < self.browser_scroll_offset {
-            self.browser_scroll_offset = self.browser_selected;
+        if self.ws.browser_selected < self.ws.browser_items.len().saturating_sub(1) {
+            self.ws.browser_selected += 1;
         }
     }
 
     pub async fn browser_select(&mut self) -> Result<()> {
-        if self.browser_selected >= self.browser_items.len() {
+        if self.ws.browser_selected >= self.ws.browser_items.len() {
             return Ok(());
         }
 
-        if let Some(client) = self.db.client() {
-            match &self.browser_items[self.browser_selected].clone() {
-                BrowserItem::Schema(schema) => {
-                    let key = format!("schema:{}", schema);
-                    
-                    if self.expanded_items.contains(&key) {
-                        // COLLAPSE: Remove the 3 folders and their contents
-                        self.collapse_schema(&key);
-                    } else {                        // EXPAND: Insert folders after the schema
-                        let insert_pos = self.browser_selected + 1;
-                        self.browser_items.insert(
-                            insert_pos,
-                            BrowserItem::Folder(schema.clone(), FolderType::Tables),
-                        );
-                        self.browser_items.insert(
-                            insert_pos + 1,
-                            BrowserItem::Folder(schema.clone(), FolderType::Views),
-                        );
-                        self.browser_items.insert(
-                            insert_pos + 2,
-                            BrowserItem::Folder(schema.clone(), FolderType::Functions),
-                        );
-                        self.expanded_items.insert(key);
-                    }
+        if let BrowserItem::Favorite(index) = self.ws.browser_items[self.ws.browser_selected] {
+            // Boxed, as revealing the object selects it in turn
+            return Box::pin(self.open_favorite(index)).await;
+        }
+
+        self.ws.found_detail = None;
+
+        if let Some(client) = self.ws.db.client() {
+            match &self.ws.browser_items[self.ws.browser_selected].clone() {
+                BrowserItem::Database(database) => {
+                    self.ws.selected_table = None;
+                    self.ws.selected_function = None;
+                    self.ws.selected_sequence = None;
+                    self.ws.detail_notes.clear();
+                    self.ws.database_details = degrade(crate::db::get_database_details(client, database).await, TableDetailTab::Definition, &mut self.ws.detail_notes)?;
+                    self.ws.details_scroll = 0;
                 }
-                BrowserItem::Folder(schema, folder_type) => {
-                    let key = format!("folder:{}:{:?}", schema, folder_type);
-                    
-                    if self.expanded_items.contains(&key) {
-                        // COLLAPSE: Remove child items
-                        self.collapse_folder(&key);
+                BrowserItem::Favorite(_) => {}
+                BrowserItem::Schema(_) | BrowserItem::Folder(_, _) => {
+                    let row = self.ws.browser_selected;
+                    if self.is_expanded(row) {
+                        self.collapse_row(row);
                     } else {
-                        // EXPAND: Load and insert items
-                        let insert_pos = self.browser_selected + 1;
-                        
-                        match folder_type {
-                            FolderType::Tables => {
-                                // Load and insert tables
-                                self.tables = crate::db::list_tables(client, schema).await?;
-                                for (i, table) in self.tables.iter().enumerate() {
-                                    self.browser_items.insert(
-                                        insert_pos + i,
-                                        BrowserItem::Table(schema.clone(), table.name.clone()),
-                                    );
-                                }
-                            }
-                            FolderType::Views => {
-                                let views = crate::db::list_views(client, schema).await?;
-                                for (i, view) in views.iter().enumerate() {
-                                    self.browser_items.insert(
-                                        insert_pos + i,
-                                        BrowserItem::View(schema.clone(), view.name.clone()),
-                                    );
-                                }
-                            }
-                            FolderType::Functions => {
-                                // Load and insert functions
-                                let functions = crate::db::list_functions(client, schema).await?;
-                                for (i, func) in functions.iter().enumerate() {
-                                    self.browser_items.insert(
-                                        insert_pos + i,
-                                        BrowserItem::Function(schema.clone(), func.name.clone()),
-                                    );
-                                }
-                            }
-                        }
-                        self.expanded_items.insert(key);
+                        self.expand_row(row).await?;
                     }
                 }
                 BrowserItem::Table(schema, table) => {
-                    self.selected_table = Some((schema.clone(), table.clone()));
-                    self.table_detail_tab = TableDetailTab::Columns;
-                    self.columns = crate::db::describe_table(client, schema, table).await?;
-                    self.constraints = crate::db::list_table_constraints(client, schema, table).await?;
-                    self.indexes = crate::db::list_table_indexes(client, schema, table).await?;
-                    self.triggers = crate::db::list_table_triggers(client, schema, table).await?;
-                    self.foreign_keys = crate::db::list_table_foreign_keys(client, schema, table).await?;
+                    self.ws.selected_table = Some((schema.clone(), table.clone()));
+                    self.ws.selected_function = None;
+                    self.ws.selected_sequence = None;
+                    self.ws.database_details = None;
+                    self.ws.table_detail_tab = TableDetailTab::Columns;
+                    self.ws.selected_is_view = false;
+                    self.ws.selected_is_matview = false;
+                    if schema == "public" {
+                        self.stats.record_table(table);
+                    } else {
+                        self.stats.record_table(&format!("{}.{}", schema, table));
+                    }
+                    let notes = &mut self.ws.detail_notes;
+                    notes.clear();
+                    self.ws.columns = degrade(crate::db::describe_table(client, schema, table).await, TableDetailTab::Columns, notes)?;
+                    self.ws.constraints = degrade(crate::db::list_table_constraints(client, schema, table).await, TableDetailTab::Constraints, notes)?;
+                    self.ws.indexes = degrade(crate::db::list_table_indexes(client, schema, table).await, TableDetailTab::Indexes, notes)?;
+                    self.ws.triggers = degrade(crate::db::list_table_triggers(client, schema, table).await, TableDetailTab::Triggers, notes)?;
+                    self.ws.foreign_keys = degrade(crate::db::list_table_foreign_keys(client, schema, table).await, TableDetailTab::ForeignKeys, notes)?;
+                    self.ws.table_ddl = degrade(crate::db::get_table_ddl(client, schema, table).await.map(Some), TableDetailTab::Ddl, notes)?;
+                    self.ws.table_stats = degrade(crate::db::get_table_stats(client, schema, table).await, TableDetailTab::Stats, notes)?;
+                    self.ws.dependencies = degrade(crate::db::object_dependencies(client, schema, table).await, TableDetailTab::Dependencies, notes)?;
+                    self.ws.privileges = degrade(crate::db::object_privileges(client, schema, table).await, TableDetailTab::Privileges, notes)?;
+                    self.ws.view_definition = None;
+                    self.ws.details_scroll = 0;
                 }
                 BrowserItem::View(schema, view) => {
-                    self.selected_table = Some((schema.clone(), view.clone()));
-                    self.table_detail_tab = TableDetailTab::Columns;
-                    self.columns = crate::db::describe_table(client, schema, view).await?;
+                    self.ws.selected_table = Some((schema.clone(), view.clone()));
+                    self.ws.selected_function = None;
+                    self.ws.selected_sequence = None;
+                    self.ws.database_details = None;
+                    self.ws.table_detail_tab = TableDetailTab::Columns;
+                    self.ws.selected_is_view = true;
+                    self.ws.selected_is_matview = false;
+                    let notes = &mut self.ws.detail_notes;
+                    notes.clear();
+                    self.ws.columns = degrade(crate::db::describe_table(client, schema, view).await, TableDetailTab::Columns, notes)?;
                     // Views don't have constraints, indexes, triggers, or foreign keys
-                    self.constraints.clear();
-                    self.indexes.clear();
-                    self.triggers.clear();
-                    self.foreign_keys.clear();
+                    self.ws.constraints.clear();
+                    self.ws.indexes.clear();
+                    self.ws.triggers.clear();
+                    self.ws.foreign_keys.clear();
+                    self.ws.table_ddl = None;
+                    self.ws.table_stats = None;
+                    self.ws.view_definition = degrade(crate::db::get_view_definition(client, schema, view).await.map(Some), TableDetailTab::Definition, notes)?;
+                    self.ws.dependencies = degrade(crate::db::object_dependencies(client, schema, view).await, TableDetailTab::Dependencies, notes)?;
+                    self.ws.privileges = degrade(crate::db::object_privileges(client, schema, view).await, TableDetailTab::Privileges, notes)?;
+                    self.ws.details_scroll = 0;
                 }
-                BrowserItem::Function(_schema, _function) => {
-                    self.selected_table = None;
-                    // For now, just show a message that function details aren't implemented yet
-                    self.columns.clear();
-                    self.constraints.clear();
-                    self.indexes.clear();
-                    self.triggers.clear();
-                    self.foreign_keys.clear();
+                BrowserItem::MaterializedView(schema, view) => {
+                    self.ws.selected_table = Some((schema.clone(), view.clone()));
+                    self.ws.selected_function = None;
+                    self.ws.selected_sequence = None;
+                    self.ws.database_details = None;
+                    self.ws.table_detail_tab = TableDetailTab::Columns;
+                    // Read-only like a view, but with indexes of its own
+                    self.ws.selected_is_view = true;
+                    self.ws.selected_is_matview = true;
+                    let notes = &mut self.ws.detail_notes;
+                    notes.clear();
+                    self.ws.columns = degrade(crate::db::describe_materialized_view(client, schema, view).await, TableDetailTab::Columns, notes)?;
+                    self.ws.indexes = degrade(crate::db::list_table_indexes(client, schema, view).await, TableDetailTab::Indexes, notes)?;
+                    self.ws.constraints.clear();
+                    self.ws.triggers.clear();
+                    self.ws.foreign_keys.clear();
+                    self.ws.table_ddl = None;
+                    self.ws.table_stats = None;
+                    self.ws.view_definition = degrade(crate::db::get_materialized_view_definition(client, schema, view).await.map(Some), TableDetailTab::Definition, notes)?;
+                    self.ws.dependencies = degrade(crate::db::object_dependencies(client, schema, view).await, TableDetailTab::Dependencies, notes)?;
+                    self.ws.privileges = degrade(crate::db::object_privileges(client, schema, view).await, TableDetailTab::Privileges, notes)?;
+                    self.ws.details_scroll = 0;
                 }
-            }
-        }
-
-        Ok(())
-    }
-
-    fn collapse_schema(&mut self, key: &str) {
-        // Find how many items to remove (3 folders + their children)
-        let mut remove_count = 0;
-        let start_pos = self.browser_selected + 1;
-        
-        // Count folders (should be 3) and their children
-        let mut i = start_pos;
-        let mut folders_found = 0;
-        
-        while i < self.browser_items.len() && folders_found < 3 {
-            match &self.browser_items[i] {
-                BrowserItem::Folder(schema, folder_type) => {
-                    // Remove this folder from expanded set
-                    let folder_key = format!("folder:{}:{:?}", schema, folder_type);
-                    self.expanded_items.remove(&folder_key);
-                    remove_count += 1;
-                    i += 1;
-                    folders_found += 1;
-                    
-                    // Count children of this folder
-                    while i < self.browser_items.len() {
-                        match &self.browser_items[i] {
-                            BrowserItem::Table(_, _) | BrowserItem::View(_, _) | BrowserItem::Function(_, _) => {
-                                remove_count += 1;
-                                i += 1;
-                            }
-                            _ => break,
-                        }
-                    }
+                BrowserItem::Function(schema, function) => {
+                    self.ws.selected_table = None;
+                    self.ws.detail_notes.clear();
+                    self.ws.function_details = degrade(crate::db::get_function_details(client, schema, function).await, TableDetailTab::Definition, &mut self.ws.detail_notes)?;
+                    self.ws.selected_function = Some((schema.clone(), function.clone()));
+                    self.ws.selected_sequence = None;
+                    self.ws.database_details = None;
+                    self.ws.details_scroll = 0;
+                    self.ws.columns.clear();
+                    self.ws.constraints.clear();
+                    self.ws.indexes.clear();
+                    self.ws.triggers.clear();
+                    self.ws.foreign_keys.clear();
+                    self.ws.table_ddl = None;
+                    self.ws.table_stats = None;
+                    self.ws.view_definition = None;
+                    self.ws.dependencies.clear();
+                    self.ws.privileges.clear();
                 }
-                _ => break,
-            }
-        }
-        
-        // Remove all items
-        for _ in 0..remove_count {
-            if start_pos < self.browser_items.len() {
-                self.browser_items.remove(start_pos);
-            }
-        }
-        
-        // Adjust selection if it was on a removed item
-        if self.browser_selected >= start_pos && self.browser_selected < start_pos + remove_count {
-            self.browser_selected = start_pos - 1; // Move to the schema itself
-        } else if self.browser_selected >= start_pos + remove_count {
-            self.browser_selected -= remove_count;
-        }
-        
-        self.expanded_items.remove(key);
-    }
-
-    fn collapse_folder(&mut self, key: &str) {
-        // Find how many child items to remove
-        let mut remove_count = 0;
-        let start_pos = self.browser_selected + 1;
-        
-        // Count children
-        let mut i = start_pos;
-        while i < self.browser_items.len() {
-            match &self.browser_items[i] {
-                BrowserItem::Table(_, _) | BrowserItem::View(_, _) | BrowserItem::Function(_, _) => {
-                    remove_count += 1;
-                    i += 1;
+                BrowserItem::Sequence(schema, sequence) => {
+                    self.ws.selected_table = None;
+                    self.ws.selected_function = None;
+                    self.ws.detail_notes.clear();
+                    self.ws.sequence_details = degrade(crate::db::get_sequence_details(client, schema, sequence).await, TableDetailTab::Definition, &mut self.ws.detail_notes)?;
+                    self.ws.selected_sequence = Some((schema.clone(), sequence.clone()));
+                    self.ws.database_details = None;
+                    self.ws.details_scroll = 0;
                 }
-                _ => break,
-            }
-        }
-        
-        // Remove all child items
-        for _ in 0..remove_count {
-            if start_pos < self.browser_items.len() {
-                self.browser_items.remove(start_pos);
             }
+            self.remember_recent_object();
         }
-        
-        // Adjust selection if it was on a removed item
-        if self.browser_selected >= start_pos && self.browser_selected < start_pos + remove_count {
-            self.browser_selected = start_pos - 1; // Move to the folder itself
-        } else if self.browser_selected >= start_pos + remove_count {
-            self.browser_selected -= remove_count;
-        }
-        
-        self.expanded_items.remove(key);
+
+        Ok(())
     }
 
-    // Query handling
-    pub fn handle_query_input(&mut self, key: KeyCode) {
-        match key {
-            KeyCode::Char(c) => {
-                self.query_input.insert(self.query_cursor, c);
-                self.query_cursor += 1;
-            }
-            KeyCode::Backspace => {
-                if self.query_cursor > 0 {
-                    self.query_input.remove(self.query_cursor - 1);
-                    self.query_cursor -= 1;
-                }
-            }
-            KeyCode::Left => {
-                if self.query_cursor > 0 {
-                    self.query_cursor -= 1;
-                }
-            }
-            KeyCode::Right => {
-                if self.query_cursor < self.query_input.len() {
-                    self.query_cursor += 1;
-                }
-            }
-            KeyCode::Enter => {
-                self.query_input.push('\n');
-                self.query_cursor += 1;
-            }
-            _ => {}
-        }
+    // Insert a pasted block at the cursor in one edit
+    pub fn paste_query_text(&mut self, text: &str) {
+        let text = text.replace("\r\n", "\n").replace('\r', "\n");
+        let (length, at) = (self.ws.query_input.len(), self.ws.query_cursor);
+        self.ws.query_input.insert_str(self.ws.query_cursor, &text);
+        self.ws.query_cursor += text.len();
+        self.snippet_edited(at, length);
+        self.ws.goal_column = None;
+        self.hide_autocomplete();
     }
 
     pub fn adjust_query_scroll(&mut self, visible_lines: usize) {
         // Calculate which line the cursor is on
-        let text_before_cursor = &self.query_input[..self.query_cursor.min(self.query_input.len())];
+        let text_before_cursor = &self.ws.query_input[..self.ws.query_cursor.min(self.ws.query_input.len())];
         let cursor_line = text_before_cursor.matches('\n').count();
         
         // Adjust scroll to keep cursor visible
-        if cursor_line < self.query_scroll_offset {
+        if cursor_line < self.ws.query_scroll_offset {
             // Cursor is above visible area, scroll up
-            self.query_scroll_offset = cursor_line;
-        } else if cursor_line >= self.query_scroll_offset + visible_lines {
+            self.ws.query_scroll_offset = cursor_line;
+        } else if cursor_line >= self.ws.query_scroll_offset + visible_lines {
             // Cursor is below visible area, scroll down
-            self.query_scroll_offset = cursor_line - visible_lines + 1;
+            self.ws.query_scroll_offset = cursor_line - visible_lines + 1;
         }
     }
 
     pub fn scroll_results_left(&mut self) {
-        if self.result_scroll_offset > 0 {
-            self.result_scroll_offset -= 1;
+        if self.ws.result_scroll_offset > 0 {
+            self.ws.result_scroll_offset -= 1;
         }
     }
 
     pub fn scroll_results_right(&mut self) {
-        if let Some(result) = &self.query_result {
-            if self.result_scroll_offset < result.columns.len().saturating_sub(1) {
-                self.result_scroll_offset += 1;
+        if let Some(result) = &self.ws.query_result {
+            if self.ws.result_scroll_offset < result.columns.len().saturating_sub(1) {
+                self.ws.result_scroll_offset += 1;
             }
         }
     }
 
     pub async fn execute_query(&mut self) -> Result<()> {
-        if let Some(client) = self.db.client() {
-            // Extract the query at cursor position (DBeaver-like behavior)
-            let sql = self.extract_current_query();
-            
+        // Other results replace the watched query's
+        self.ws.watch = None;
+        // Extract the query at cursor position (DBeaver-like behavior)
+        let sql = self.extract_current_query();
+        self.refuse_on_read_only(&sql)?;
+        if self.hold_destructive(&sql, GuardedRun::Query) || self.hold_write(&sql, GuardedRun::Query) {
+            return Ok(());
+        }
+        if self.editor_selection().is_some() {
+            self.ws.vim.mode = VimMode::Normal;
+        }
+        if self.hold_params(&sql).await? || self.hold_costly(&sql, &[]).await {
+            return Ok(());
+        }
+        self.run_interactive_query(sql, Vec::new(), true).await
+    }
+
+    // Run a query for the grid, binding `params` to its $n placeholders;
+    // with `allow_limit` an unbounded SELECT gets the configured auto LIMIT
+    async fn run_interactive_query(&mut self, sql: String, params: Vec<Option<String>>, allow_limit: bool) -> Result<()> {
+        if self.ws.db.is_connected() {
+            let limited = if allow_limit { auto_limit::limit_select(&sql, self.config.auto_limit) } else { None };
+            let original = limited.as_ref().map(|_| sql.clone());
+            let sql = limited.unwrap_or(sql);
+
             if !sql.trim().is_empty() {
-                match crate::db::execute_query(client, &sql).await {
-                    Ok(result) => {
-                        self.query_result = Some(result);
-                        self.clear_error();
+                // SQL post-query hooks need the connection, so unread rows
+                // can't be left on it
+                let whole = self.ws.hooks.post_query.iter().any(|hook| matches!(hook, crate::config::Hook::Sql(_)));
+                let min_rows = if whole { usize::MAX } else { 0 };
+                // Toasts of earlier runs go; ones from this run, like a retry
+                // on a new session, stay
+                self.clear_notifications();
+                let run = match self.run_statement(&sql, &params, self.config.max_result_rows, min_rows).await {
+                    Ok(run) => run,
+                    Err(e) => {
+                        self.set_error(format!("{:#}", e));
+                        return Ok(());
+                    }
+                };
+                match run.result {
+                    Ok((result, capped, affected)) => {
+                        self.ws.result_tag = affected.and_then(|n| results::command_tag(&sql, n));
+                        self.ws.result_elapsed = Some(run.elapsed);
+                        self.ws.query_result = Some(result);
+                        self.ws.result_capped = capped;
+                        self.ws.result_sql = Some(sql.clone());
+                        self.ws.result_params = params;
+                        self.ws.auto_limited = original;
+                        self.ws.script_outcomes = None;
+                        self.ws.result_notices = run.notices;
+                        self.report_retries(run.attempts);
+                        self.remember_result();
                     }
                     Err(e) => {
-                        self.set_error(format!("Query error: {}", e));
+                        self.set_error(format!("Query error: {}", self.describe_failure(&e, run.attempts)));
                     }
                 }
+                if let Err(e) = run.post_hooks {
+                    self.set_error(format!("{:#}", e));
+                }
             }
         }
         Ok(())
     }
+
+    // Run `sql` the way every statement from the editor or the palette
+    // runs: pre-query hooks (whose failure is the error returned), retries
+    // after a dropped connection, the session log and stats, then the
+    // post-query hooks. Rows are read as by `load_result`.
+    async fn run_statement(
+        &mut self,
+        sql: &str,
+        params: &[Option<String>],
+        max_rows: usize,
+        min_rows: usize,
+    ) -> Result<StatementRun> {
+        hooks::run_pre_query(&self.ws.db, &self.ws.hooks, sql).await?;
+        let started = std::time::Instant::now();
+        let mut attempts = 0;
+        self.discard_notices();
+        let result = loop {
+            attempts += 1;
+            match results::load_result(&self.ws.db, sql, params, max_rows, min_rows).await {
+                Err(e) if self.retry_after(sql, &e, attempts).await => continue,
+                result => break result,
+            }
+        };
+        let elapsed = started.elapsed();
+        let notices = self.collect_notices();
+        // Rows changed by DML; rows read for queries
+        let rows = result.as_ref().ok().map(|(r, _, affected)| match affected {
+            Some(n) if r.rows.is_empty() => *n as usize,
+            _ => r.row_count,
+        });
+        self.stats.record_query(sql, rows);
+        let outcome = result.as_ref().map(|_| rows.unwrap_or_default()).map_err(|e| format!("{:#}", e));
+        self.audit.record_statement(&self.ws.name, sql, elapsed, outcome);
+        if result.is_ok() {
+            self.note_transaction(sql);
+        }
+        // Shell hooks leave unread rows where they are
+        let post_hooks = hooks::run_post_query(&self.ws.db, &self.ws.hooks, sql, rows).await;
+        Ok(StatementRun {
+            result,
+            elapsed,
+            notices,
+            attempts,
+            post_hooks,
+        })
+    }
     
     fn extract_current_query(&self) -> String {
+        // A visual selection runs as-is
+        if let Some(selection) = self.editor_selection() {
+            return self.ws.query_input[selection].trim().to_string();
+        }
+
         // If input is empty, return empty
-        if self.query_input.is_empty() {
+        if self.ws.query_input.is_empty() {
             return String::new();
         }
         
         // Find all semicolon positions
-        let semicolons: Vec<usize> = self.query_input
+        let semicolons: Vec<usize> = self.ws.query_input
             .char_indices()
             .filter_map(|(i, c)| if c == ';' { Some(i) } else { None })
             .collect();
         
         // If no semicolons, return the entire input
         if semicolons.is_empty() {
-            return self.query_input.trim().to_string();
+            return self.ws.query_input.trim().to_string();
         }
         
         // Find which query the cursor is in
-        let cursor_pos = self.query_cursor;
+        let cursor_pos = self.ws.query_cursor;
         
         // Find the start of current query (after previous semicolon or beginning)
         let query_start = semicolons
@@ -593,96 +1010,82 @@ impl App {
             .iter()
             .find(|&&pos| pos >= cursor_pos)
             .copied()
-            .unwrap_or(self.query_input.len()); // Or to the end
+            .unwrap_or(self.ws.query_input.len()); // Or to the end
         
         // Extract the query
-        let query = &self.query_input[query_start..query_end];
+        let query = &self.ws.query_input[query_start..query_end];
         query.trim().to_string()
     }
 
     // Results filter methods
     pub fn activate_results_filter(&mut self) {
-        self.results_filter_active = true;
+        self.ws.results_filter_active = true;
     }
 
     pub fn clear_results_filter(&mut self) {
-        self.results_filter_input.clear();
-        self.results_filter_active = false;
+        self.ws.results_filter_input.clear();
+        self.ws.results_filter_active = false;
     }
 
     pub fn handle_results_filter_input(&mut self, key: KeyCode) {
         match key {
             KeyCode::Char(c) => {
-                self.results_filter_input.push(c);
+                self.ws.results_filter_input.push(c);
+                self.ws.result_selected_row = 0;
             }
             KeyCode::Backspace => {
-                self.results_filter_input.pop();
+                self.ws.results_filter_input.pop();
+                self.ws.result_selected_row = 0;
             }
+            KeyCode::Tab => self.complete_results_filter(),
             _ => {}
         }
     }
 
     pub fn get_filtered_rows(&self) -> Option<Vec<usize>> {
-        if !self.results_filter_active || self.results_filter_input.is_empty() {
-            return None;
-        }
-
-        if let Some(result) = &self.query_result {
-            let filter_lower = self.results_filter_input.to_lowercase();
-            let mut filtered_indices = Vec::new();
-
-            for (row_idx, row) in result.rows.iter().enumerate() {
-                // Check if any cell in the row contains the filter text
-                let matches = row.iter().any(|cell| {
-                    cell.to_lowercase().contains(&filter_lower)
-                });
-
-                if matches {
-                    filtered_indices.push(row_idx);
-                }
-            }
-
-            Some(filtered_indices)
-        } else {
-            None
-        }
+        self.ws.filtered_rows()
     }
 
     // Filter methods
     pub fn activate_filter(&mut self) {
-        self.filter_active = true;
+        self.ws.filter_active = true;
     }
 
     pub fn clear_filter(&mut self) {
-        self.filter_input.clear();
-        self.filter_active = false;
+        self.ws.filter_input.clear();
+        self.ws.filter_active = false;
     }
 
     pub fn handle_filter_input(&mut self, key: KeyCode) {
         match key {
             KeyCode::Char(c) => {
-                self.filter_input.push(c);
+                self.ws.filter_input.push(c);
             }
             KeyCode::Backspace => {
-                self.filter_input.pop();
+                self.ws.filter_input.pop();
             }
+            KeyCode::Tab => self.complete_browser_filter(),
             _ => {}
         }
     }
 
     pub fn get_filtered_items(&self) -> Vec<usize> {
-        if !self.filter_active || self.filter_input.is_empty() {
-            return (0..self.browser_items.len()).collect();
+        if !self.ws.filter_active || self.ws.filter_input.is_empty() {
+            return (0..self.ws.browser_items.len()).collect();
         }
 
-        let filter_lower = self.filter_input.to_lowercase();
+        let filter_lower = self.ws.filter_input.to_lowercase();
         let mut filtered = Vec::new();
 
-        for (idx, item) in self.browser_items.iter().enumerate() {
+        for (idx, item) in self.ws.browser_items.iter().enumerate() {
             let matches = match item {
-                BrowserItem::Schema(name) => {
+                BrowserItem::Database(name) | BrowserItem::Schema(name) => {
                     name.to_lowercase().contains(&filter_lower)
                 }
+                BrowserItem::Favorite(index) => self
+                    .favorites()
+                    .get(*index)
+                    .is_some_and(|favorite| favorite.label().to_lowercase().contains(&filter_lower)),
                 BrowserItem::Folder(_, _) => {
                     false
                 }
@@ -690,11 +1093,11 @@ impl App {
                     name.to_lowercase().contains(&filter_lower)
                         || schema.to_lowercase().contains(&filter_lower)
                 }
-                BrowserItem::View(schema, name) => {
+                BrowserItem::View(schema, name) | BrowserItem::MaterializedView(schema, name) => {
                     name.to_lowercase().contains(&filter_lower)
                         || schema.to_lowercase().contains(&filter_lower)
                 }
-                BrowserItem::Function(schema, name) => {
+                BrowserItem::Function(schema, name) | BrowserItem::Sequence(schema, name) => {
                     name.to_lowercase().contains(&filter_lower)
                         || schema.to_lowercase().contains(&filter_lower)
                 }
@@ -709,127 +1112,195 @@ impl App {
     }
 
     // Tab navigation
+    pub fn detail_tabs(&self) -> Vec<TableDetailTab> {
+        if self.ws.selected_is_matview {
+            vec![
+                TableDetailTab::Columns,
+                TableDetailTab::Indexes,
+                TableDetailTab::Definition,
+                TableDetailTab::Dependencies,
+                TableDetailTab::Privileges,
+            ]
+        } else if self.ws.selected_is_view {
+            vec![
+                TableDetailTab::Columns,
+                TableDetailTab::Definition,
+                TableDetailTab::Dependencies,
+                TableDetailTab::Privileges,
+            ]
+        } else {
+            vec![
+                TableDetailTab::Columns,
+                TableDetailTab::Constraints,
+                TableDetailTab::Indexes,
+                TableDetailTab::Triggers,
+                TableDetailTab::ForeignKeys,
+                TableDetailTab::Ddl,
+                TableDetailTab::Stats,
+                TableDetailTab::Dependencies,
+                TableDetailTab::Privileges,
+            ]
+        }
+    }
+
     pub fn next_tab(&mut self) {
-        self.table_detail_tab = match self.table_detail_tab {
-            TableDetailTab::Columns => TableDetailTab::Constraints,
-            TableDetailTab::Constraints => TableDetailTab::Indexes,
-            TableDetailTab::Indexes => TableDetailTab::Triggers,
-            TableDetailTab::Triggers => TableDetailTab::ForeignKeys,
-            TableDetailTab::ForeignKeys => TableDetailTab::Columns,
-        };
+        let tabs = self.detail_tabs();
+        let current = tabs.iter().position(|&t| t == self.ws.table_detail_tab).unwrap_or(0);
+        self.ws.table_detail_tab = tabs[(current + 1) % tabs.len()];
+        self.ws.details_scroll = 0;
     }
 
     pub fn prev_tab(&mut self) {
-        self.table_detail_tab = match self.table_detail_tab {
-            TableDetailTab::Columns => TableDetailTab::ForeignKeys,
-            TableDetailTab::Constraints => TableDetailTab::Columns,
-            TableDetailTab::Indexes => TableDetailTab::Constraints,
-            TableDetailTab::Triggers => TableDetailTab::Indexes,
-            TableDetailTab::ForeignKeys => TableDetailTab::Triggers,
+        let tabs = self.detail_tabs();
+        let current = tabs.iter().position(|&t| t == self.ws.table_detail_tab).unwrap_or(0);
+        self.ws.table_detail_tab = tabs[(current + tabs.len() - 1) % tabs.len()];
+        self.ws.details_scroll = 0;
+    }
+
+    // Jump to the SQL source of the selected object (DDL or view definition)
+    pub fn show_table_ddl(&mut self) {
+        if self.ws.table_ddl.is_some() {
+            self.ws.table_detail_tab = TableDetailTab::Ddl;
+            self.ws.details_scroll = 0;
+        } else if self.ws.selected_is_view {
+            self.ws.table_detail_tab = TableDetailTab::Definition;
+            self.ws.details_scroll = 0;
+        }
+    }
+
+    // Tables of the schema under the cursor, biggest first
+    pub async fn show_largest_tables(&mut self) {
+        let Some(schema) = self.browser_schema() else {
+            return;
+        };
+        let Some(client) = self.ws.db.client() else {
+            return;
         };
+        match crate::db::largest_tables(client, &schema, 50).await {
+            Ok(result) => {
+                self.popup = Some(ResultPopup {
+                    title: format!("Largest tables in {}", schema),
+                    result,
+                    scroll: 0,
+                });
+            }
+            Err(e) => self.set_error(format!("{:#}", e)),
+        }
+    }
+
+    pub fn scroll_details_up(&mut self, amount: u16) {
+        self.ws.details_scroll = self.ws.details_scroll.saturating_sub(amount);
+    }
+
+    pub fn scroll_details_down(&mut self, amount: u16) {
+        self.ws.details_scroll = self.ws.details_scroll.saturating_add(amount);
     }
     
     // Autocomplete methods
     pub async fn update_autocomplete(&mut self) -> Result<()> {
-        // Lazy load schema on first use
-        if !self.autocomplete_schema_loaded {
-            if let Some(client) = self.db.client() {
-                let mut tables_with_columns = Vec::new();
-                
-                for schema in &self.schemas {
-                    let tables = crate::db::list_tables(client, &schema.name).await?;
-                    
-                    for table in tables {
-                        let columns = crate::db::describe_table(client, &schema.name, &table.name).await?;
-                        let column_names: Vec<String> = columns.iter().map(|c| c.name.clone()).collect();
-                        tables_with_columns.push((table.name.clone(), column_names));
+        // Names the catalog hasn't delivered yet just aren't offered yet
+        self.start_catalog_load();
+        
+        self.ws.suggestions = match &mut self.ws.lsp {
+            Some(lsp) => match lsp.completion(&self.ws.query_input, self.ws.query_cursor).await {
+                Ok(suggestions) => suggestions,
+                // Slow answers fall back for this keystroke; a dead server for good
+                Err(_) => {
+                    if !lsp.is_running() {
+                        self.ws.lsp = None;
+                        self.set_warning("Language server exited; using built-in completion".to_string());
                     }
+                    self.ws.autocomplete_engine.get_suggestions(&self.ws.query_input, self.ws.query_cursor)
                 }
-                
-                self.autocomplete_engine.update_schema(tables_with_columns);
-                self.autocomplete_schema_loaded = true;
-            }
-        }
-        
-        self.suggestions = self.autocomplete_engine.get_suggestions(&self.query_input, self.query_cursor);
-        self.show_autocomplete = !self.suggestions.is_empty();
-        self.suggestion_selected = 0;
+            },
+            None => self.ws.autocomplete_engine.get_suggestions(&self.ws.query_input, self.ws.query_cursor),
+        };
+        self.ws.show_autocomplete = !self.ws.suggestions.is_empty();
+        self.ws.suggestion_selected = 0;
         Ok(())
     }
     
     pub fn select_next_suggestion(&mut self) {
-        if !self.suggestions.is_empty() {
-            self.suggestion_selected = (self.suggestion_selected + 1) % self.suggestions.len();
+        if !self.ws.suggestions.is_empty() {
+            self.ws.suggestion_selected = (self.ws.suggestion_selected + 1) % self.ws.suggestions.len();
         }
     }
     
     pub fn select_prev_suggestion(&mut self) {
-        if !self.suggestions.is_empty() {
-            if self.suggestion_selected == 0 {
-                self.suggestion_selected = self.suggestions.len() - 1;
+        if !self.ws.suggestions.is_empty() {
+            if self.ws.suggestion_selected == 0 {
+                self.ws.suggestion_selected = self.ws.suggestions.len() - 1;
             } else {
-                self.suggestion_selected -= 1;
+                self.ws.suggestion_selected -= 1;
             }
         }
     }
     
     pub fn accept_suggestion(&mut self) {
-        if self.suggestion_selected < self.suggestions.len() {
-            let suggestion = &self.suggestions[self.suggestion_selected];
+        if self.ws.suggestion_selected < self.ws.suggestions.len() {
+            let suggestion = &self.ws.suggestions[self.ws.suggestion_selected];
             
             // Find the start of the current word being typed
-            let mut word_start = self.query_cursor;
-            let chars: Vec<char> = self.query_input.chars().collect();
+            let before = &self.ws.query_input[..self.ws.query_cursor];
+            let word_start = before
+                .rfind(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .map_or(0, |i| i + before[i..].chars().next().map_or(1, char::len_utf8));
             
-            while word_start > 0 {
-                let prev_char = chars[word_start - 1];
-                if prev_char.is_alphanumeric() || prev_char == '_' {
-                    word_start -= 1;
-                } else {
-                    break;
+            if suggestion.suggestion_type == crate::autocomplete::SuggestionType::Snippet {
+                if let Some(template) = crate::autocomplete::snippet_template(&suggestion.text) {
+                    self.ws.autocomplete_engine.note_used(&suggestion.text);
+                    self.expand_snippet(word_start..self.ws.query_cursor, template);
+                    self.hide_autocomplete();
+                    return;
                 }
             }
-            
-            // Remove the partial word
-            self.query_input.drain(word_start..self.query_cursor);
-            
-            // Insert the suggestion
+
+            // Replace the partial word with the suggestion
+            let length = self.ws.query_input.len();
             let insert_text = suggestion.text.clone();
-            for (i, c) in insert_text.chars().enumerate() {
-                self.query_input.insert(word_start + i, c);
-            }
+            self.ws.query_input.replace_range(word_start..self.ws.query_cursor, &insert_text);
+            self.ws.autocomplete_engine.note_used(&insert_text);
             
             // Move cursor to end of inserted text
-            self.query_cursor = word_start + insert_text.len();
+            self.ws.query_cursor = word_start + insert_text.len();
             
-            // Add a space after keywords
-            if matches!(suggestion.suggestion_type, crate::autocomplete::SuggestionType::Keyword) {
-                self.query_input.insert(self.query_cursor, ' ');
-                self.query_cursor += 1;
+            // Add a space after keywords; functions get their parens with the
+            // cursor between them
+            match suggestion.suggestion_type {
+                crate::autocomplete::SuggestionType::Keyword | crate::autocomplete::SuggestionType::Setting => {
+                    self.ws.query_input.insert(self.ws.query_cursor, ' ');
+                    self.ws.query_cursor += 1;
+                }
+                crate::autocomplete::SuggestionType::Function => {
+                    self.ws.query_input.insert_str(self.ws.query_cursor, "()");
+                    self.ws.query_cursor += 1;
+                }
+                _ => {}
             }
+            self.snippet_edited(word_start, length);
             
             // Hide autocomplete
-            self.show_autocomplete = false;
-            self.suggestions.clear();
+            self.ws.show_autocomplete = false;
+            self.ws.suggestions.clear();
         }
     }
     
     pub fn hide_autocomplete(&mut self) {
-        self.show_autocomplete = false;
-        self.suggestions.clear();
-        self.suggestion_selected = 0;
+        self.ws.show_autocomplete = false;
+        self.ws.suggestions.clear();
+        self.ws.suggestion_selected = 0;
     }
     
     // Query formatting
     pub fn format_current_query(&mut self) {
         use crate::formatter::SqlFormatter;
         
-        if self.query_input.is_empty() {
+        if self.ws.query_input.is_empty() {
             return;
         }
         
         // Find all semicolon positions
-        let semicolons: Vec<usize> = self.query_input
+        let semicolons: Vec<usize> = self.ws.query_input
             .char_indices()
             .filter_map(|(i, c)| if c == ';' { Some(i) } else { None })
             .collect();
@@ -837,14 +1308,14 @@ impl App {
         // If no semicolons, format the entire input
         if semicolons.is_empty() {
             let formatter = SqlFormatter::new();
-            let formatted = formatter.format(&self.query_input);
-            self.query_cursor = formatted.len(); // Move cursor to end
-            self.query_input = formatted;
+            let formatted = formatter.format(&self.ws.query_input);
+            self.ws.query_cursor = formatted.len(); // Move cursor to end
+            self.ws.query_input = formatted;
             return;
         }
         
         // Find which query the cursor is in
-        let cursor_pos = self.query_cursor;
+        let cursor_pos = self.ws.query_cursor;
         
         // Find the start of current query (after previous semicolon or beginning)
         let query_start = semicolons
@@ -859,10 +1330,10 @@ impl App {
             .iter()
             .find(|&&pos| pos >= cursor_pos)
             .copied()
-            .unwrap_or(self.query_input.len());
+            .unwrap_or(self.ws.query_input.len());
         
         // Extract the query
-        let query = &self.query_input[query_start..query_end];
+        let query = &self.ws.query_input[query_start..query_end];
         
         // Format it
         let formatter = SqlFormatter::new();
@@ -872,7 +1343,7 @@ impl App {
         let mut new_input = String::new();
         
         // Add everything before the query
-        new_input.push_str(&self.query_input[..query_start]);
+        new_input.push_str(&self.ws.query_input[..query_start]);
         
         // Add formatted query
         if query_start > 0 {
@@ -881,13 +1352,47 @@ impl App {
         new_input.push_str(&formatted);
         
         // Add everything after the query
-        if query_end < self.query_input.len() {
-            new_input.push_str(&self.query_input[query_end..]);
+        if query_end < self.ws.query_input.len() {
+            new_input.push_str(&self.ws.query_input[query_end..]);
         }
         
         // Update cursor to end of formatted query
-        self.query_cursor = query_start + formatted.len() + if query_start > 0 { 1 } else { 0 };
-        self.query_input = new_input;
+        self.ws.query_cursor = query_start + formatted.len() + if query_start > 0 { 1 } else { 0 };
+        self.ws.query_input = new_input;
+    }
+}
+
+// Turn a permission error into a note for `tab` so the pane can explain
+// itself instead of failing the whole selection
+fn degrade<T: Default>(
+    result: Result<T>,
+    tab: TableDetailTab,
+    notes: &mut HashMap<TableDetailTab, String>,
+) -> Result<T> {
+    match result {
+        Err(e) if crate::db::is_permission_denied(&e) => {
+            notes.insert(
+                tab,
+                format!("Not available: your role lacks the privileges to read this ({}).", e.root_cause()),
+            );
+            Ok(T::default())
+        }
+        other => other,
+    }
+}
+
+fn degrade_listing<T: Default>(
+    result: Result<T>,
+    what: &str,
+    schema: &str,
+    note: &mut Option<String>,
+) -> Result<T> {
+    match result {
+        Err(e) if crate::db::is_permission_denied(&e) => {
+            *note = Some(format!("No privilege to list {} in schema {}", what, schema));
+            Ok(T::default())
+        }
+        other => other,
     }
 }
 
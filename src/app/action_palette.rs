@@ -0,0 +1,80 @@
+use crossterm::event::KeyCode;
+
+use crate::app::App;
+use crate::autocomplete::fuzzy_match;
+use crate::keymap::{actions, Action};
+
+// Every action by its title, searched as you type, to run without knowing
+// its key
+pub struct ActionPalette {
+    pub filter: String,
+    // Actions matching the filter, best first, with the characters of the
+    // title it matched
+    pub matches: Vec<(Action, &'static str, Vec<usize>)>,
+    pub selected: usize,
+}
+
+impl ActionPalette {
+    fn update_matches(&mut self) {
+        let mut matches: Vec<(i32, usize, Action, &'static str, Vec<usize>)> = actions()
+            // Macros wait for a register key, which the palette can't give
+            .filter(|(action, _)| !matches!(action, Action::RecordMacro | Action::ReplayMacro | Action::CommandSearch))
+            .enumerate()
+            .filter_map(|(i, (action, title))| {
+                let (score, matched) = fuzzy_match(title, &self.filter)?;
+                Some((score, i, action, title, matched))
+            })
+            .collect();
+        // Ties keep the keymap's order, which groups actions by screen
+        matches.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+        self.matches = matches
+            .into_iter()
+            .map(|(_, _, action, title, matched)| (action, title, matched))
+            .collect();
+        self.selected = 0;
+    }
+}
+
+impl App {
+    pub fn open_action_palette(&mut self) {
+        let mut palette = ActionPalette {
+            filter: String::new(),
+            matches: Vec::new(),
+            selected: 0,
+        };
+        palette.update_matches();
+        self.action_palette = Some(palette);
+    }
+
+    pub fn close_action_palette(&mut self) {
+        self.action_palette = None;
+    }
+
+    pub fn handle_action_palette_input(&mut self, key: KeyCode) {
+        let Some(palette) = &mut self.action_palette else {
+            return;
+        };
+        let last = palette.matches.len().saturating_sub(1);
+        match key {
+            KeyCode::Up => palette.selected = palette.selected.saturating_sub(1),
+            KeyCode::Down => palette.selected = (palette.selected + 1).min(last),
+            KeyCode::PageUp => palette.selected = palette.selected.saturating_sub(10),
+            KeyCode::PageDown => palette.selected = (palette.selected + 10).min(last),
+            KeyCode::Char(c) => {
+                palette.filter.push(c);
+                palette.update_matches();
+            }
+            KeyCode::Backspace => {
+                palette.filter.pop();
+                palette.update_matches();
+            }
+            _ => {}
+        }
+    }
+
+    // Close the palette with the chosen action, for the caller to run
+    pub fn take_palette_action(&mut self) -> Option<Action> {
+        let palette = self.action_palette.take()?;
+        palette.matches.get(palette.selected).map(|(action, _, _)| *action)
+    }
+}
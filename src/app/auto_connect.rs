@@ -0,0 +1,68 @@
+use anyhow::Result;
+
+use crate::app::{App, PromptAction};
+
+// Connecting at startup without going through the selector: the profile
+// named by `--connect`, or else the one marked `auto_connect`
+impl App {
+    pub async fn auto_connect(&mut self, requested: Option<&str>) {
+        let index = match requested {
+            Some(name) => {
+                let found = self
+                    .config
+                    .connections
+                    .iter()
+                    .position(|p| p.name == name || p.keyring_account() == name);
+                let Some(index) = found else {
+                    self.set_error(format!("No saved connection named '{}'", name));
+                    return;
+                };
+                index
+            }
+            None => match self.config.connections.iter().position(|p| p.auto_connect) {
+                Some(index) => index,
+                None => return,
+            },
+        };
+        self.selected_profile = index;
+        self.load_selected_profile();
+
+        // Without a saved password, PGPASSWORD or ~/.pgpass can still have one
+        let needs_password = self.password.is_empty()
+            && crate::libpq::lookup_password(&self.host, &self.port, &self.database, &self.user).is_none();
+        if needs_password {
+            // The crash recovery question comes first; the form behind it
+            // waits on its password field
+            if self.prompt.is_none() {
+                let title = format!("Password for {}", self.config.connections[index].name);
+                self.open_secret_prompt(&title, PromptAction::ConnectPassword);
+            }
+            return;
+        }
+        self.connect_from_form().await;
+    }
+
+    pub async fn connect_with_password(&mut self, password: &str) {
+        self.password = password.to_string();
+        self.connect_from_form().await;
+    }
+
+    // A failed attempt leaves the connection form open to fix it
+    async fn connect_from_form(&mut self) {
+        if let Err(e) = self.connect().await {
+            self.set_error(format!("Connection failed: {}", e));
+        }
+    }
+
+    // Start the selected profile at launch; at most one profile does
+    pub fn toggle_auto_connect(&mut self) -> Result<()> {
+        let selected = self.selected_profile;
+        let Some(enable) = self.config.connections.get(selected).map(|p| !p.auto_connect) else {
+            return Ok(());
+        };
+        for (i, profile) in self.config.connections.iter_mut().enumerate() {
+            profile.auto_connect = enable && i == selected;
+        }
+        self.config.save()
+    }
+}
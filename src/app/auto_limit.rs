@@ -0,0 +1,89 @@
+use anyhow::Result;
+
+use crate::app::guard::{blank_quoted, verb_words};
+use crate::app::rewrite::statement_at;
+use crate::app::App;
+
+// Top-level words after which a SELECT already returns a bounded or no
+// result set
+const BOUNDED: &[&str] = &["LIMIT", "FETCH", "INTO"];
+
+// `sql` with `LIMIT limit` appended when it is a SELECT, or a WITH query
+// ending in one, without a LIMIT (or FETCH FIRST) of its own; None when it
+// is left as it is. Words in literals, dollar-quoted bodies and comments
+// don't count.
+pub(super) fn limit_select(sql: &str, limit: usize) -> Option<String> {
+    if limit == 0 {
+        return None;
+    }
+    let tokens = statement_at(&blank_quoted(sql), 0);
+    if !matches!(tokens.first()?.upper.as_str(), "SELECT" | "WITH") || verb_words(sql).first()? != "SELECT" {
+        return None;
+    }
+    let mut depth = 0usize;
+    for tok in &tokens {
+        match tok.text.as_str() {
+            "(" => depth += 1,
+            ")" => depth = depth.saturating_sub(1),
+            _ if depth == 0 && BOUNDED.contains(&tok.upper.as_str()) => return None,
+            _ => {}
+        }
+    }
+    // Before a trailing semicolon or comment
+    let end = tokens.last()?.end;
+    Some(format!("{} LIMIT {}{}", &sql[..end], limit, &sql[end..]))
+}
+
+impl App {
+    // Run the query behind auto-limited results again as written
+    pub async fn rerun_without_limit(&mut self) -> Result<()> {
+        let Some(sql) = self.ws.auto_limited.clone() else {
+            self.set_status("The results are not auto-limited".to_string());
+            return Ok(());
+        };
+        let params = self.ws.result_params.clone();
+        self.ws.watch = None;
+        self.run_interactive_query(sql, params, false).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selects_get_a_limit() {
+        assert_eq!(limit_select("SELECT * FROM t", 100).as_deref(), Some("SELECT * FROM t LIMIT 100"));
+        assert_eq!(limit_select("SELECT 1; -- done", 5).as_deref(), Some("SELECT 1 LIMIT 5; -- done"));
+        assert_eq!(limit_select("SELECT 1 UNION SELECT 2", 5).as_deref(), Some("SELECT 1 UNION SELECT 2 LIMIT 5"));
+        assert_eq!(limit_select("SELECT 1", 0), None);
+    }
+
+    #[test]
+    fn bounded_selects_are_left_alone() {
+        assert_eq!(limit_select("SELECT * FROM t LIMIT 10", 100), None);
+        assert_eq!(limit_select("SELECT * FROM t FETCH FIRST 3 ROWS ONLY", 100), None);
+        assert_eq!(limit_select("SELECT * INTO copy FROM t", 100), None);
+        assert_eq!(limit_select("INSERT INTO t SELECT 1", 100), None);
+        assert_eq!(limit_select("EXPLAIN SELECT 1", 100), None);
+    }
+
+    #[test]
+    fn with_queries_are_limited_by_their_last_statement() {
+        assert_eq!(
+            limit_select("WITH a AS (SELECT * FROM t LIMIT 3) SELECT * FROM a", 100).as_deref(),
+            Some("WITH a AS (SELECT * FROM t LIMIT 3) SELECT * FROM a LIMIT 100")
+        );
+        assert_eq!(limit_select("WITH a AS (SELECT 1) DELETE FROM t USING a", 100), None);
+        assert_eq!(limit_select("WITH a AS (SELECT 1) SELECT * FROM a LIMIT 1", 100), None);
+    }
+
+    #[test]
+    fn quoted_words_and_comments_are_skipped() {
+        assert_eq!(
+            limit_select("SELECT $fn$ limit 1 $fn$, 'ü;' FROM t /* limit */", 10).as_deref(),
+            Some("SELECT $fn$ limit 1 $fn$, 'ü;' FROM t LIMIT 10 /* limit */")
+        );
+        assert_eq!(limit_select("SELECT $$x$$ LIMIT 2", 10), None);
+    }
+}
@@ -0,0 +1,122 @@
+use std::time::{Duration, Instant};
+
+use crate::app::{App, PromptAction};
+use crate::recovery::RecoveredBuffer;
+
+// Periodic snapshots of unsaved editor text, and restoring them after a crash
+impl App {
+    fn autosave_interval(&self) -> Option<Duration> {
+        (self.config.autosave_interval_secs > 0).then(|| Duration::from_secs(self.config.autosave_interval_secs))
+    }
+
+    // On startup: ask about buffers left behind by sessions that crashed
+    pub fn offer_recovery(&mut self) {
+        let Some(interval) = self.autosave_interval() else {
+            return;
+        };
+        // Give a running instance a few missed snapshots before calling it dead
+        let files = crate::recovery::orphaned((interval * 3).max(Duration::from_secs(30)));
+        let count: usize = files
+            .iter()
+            .filter_map(|path| crate::recovery::load(path).ok())
+            .map(|buffers| buffers.len())
+            .sum();
+        if count == 0 {
+            crate::recovery::remove(&files);
+            return;
+        }
+        self.recovery_files = files;
+        self.open_prompt(
+            &format!("Restore {} unsaved editor buffer(s) from a crashed session? (y/n)", count),
+            "y",
+            PromptAction::RestoreBuffers,
+        );
+    }
+
+    // "y" keeps the buffers for the connections they came from; anything
+    // else throws them away. Closing the prompt with Esc asks again next launch.
+    pub fn answer_recovery(&mut self, answer: &str) {
+        let files = std::mem::take(&mut self.recovery_files);
+        if answer.eq_ignore_ascii_case("y") || answer.eq_ignore_ascii_case("yes") {
+            self.recovered = files
+                .iter()
+                .filter_map(|path| crate::recovery::load(path).ok())
+                .flatten()
+                .collect();
+            // They live on in this session's snapshot from now on
+            self.autosave_now();
+            self.set_status(format!(
+                "{} buffer(s) will open in the editor when you connect to their connection",
+                self.recovered.len()
+            ));
+        }
+        crate::recovery::remove(&files);
+    }
+
+    // A new connection's editor gets the buffer recovered for its profile
+    pub fn restore_recovered_buffer(&mut self) {
+        if !self.ws.query_input.is_empty() {
+            return;
+        }
+        let Some(index) = self.recovered.iter().position(|b| b.profile == self.ws.profile) else {
+            return;
+        };
+        let buffer = self.recovered.remove(index);
+        self.ws.query_input = buffer.text;
+        self.ws.query_cursor = self.ws.query_input.len();
+        self.ws.file_path = buffer.file_path;
+        // Still unsaved, so the file shows as modified
+        self.ws.saved_input.clear();
+        self.set_status(format!("Restored the unsaved editor buffer of {}", buffer.connection));
+    }
+
+    // Called on every pass of the event loop; snapshots once per interval
+    pub fn autosave(&mut self) {
+        let Some(interval) = self.autosave_interval() else {
+            return;
+        };
+        if self.last_autosave.elapsed() >= interval {
+            self.autosave_now();
+        }
+    }
+
+    fn autosave_now(&mut self) {
+        self.last_autosave = Instant::now();
+        let mut buffers: Vec<RecoveredBuffer> = std::iter::once(&self.ws)
+            .chain(
+                self.workspaces
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| *i != self.active_workspace)
+                    .map(|(_, ws)| ws),
+            )
+            .filter(|ws| ws.has_unsaved_text())
+            .map(|ws| RecoveredBuffer {
+                profile: ws.profile.clone(),
+                connection: ws.name.clone(),
+                file_path: ws.file_path.clone(),
+                text: ws.query_input.clone(),
+            })
+            .collect();
+        buffers.extend(self.recovered.iter().cloned());
+        // Warn once, not on every interval, while saving keeps failing
+        match crate::recovery::save(&buffers) {
+            Ok(()) => self.autosave_failing = false,
+            Err(e) if !self.autosave_failing => {
+                self.autosave_failing = true;
+                self.set_warning(format!("Autosave failed: {:#}", e));
+            }
+            Err(_) => {}
+        }
+    }
+
+    // On a clean exit the snapshot goes, unless recovered buffers were never
+    // restored; those are offered again next time
+    pub fn finish_autosave(&self) {
+        if self.recovered.is_empty() {
+            let _ = crate::recovery::discard();
+        } else {
+            let _ = crate::recovery::save(&self.recovered);
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::app::guard::write_keyword;
+use crate::app::{App, PromptAction, ResultPopup};
+use crate::db::QueryResult;
+
+// Most runs one benchmark may ask for
+const MAX_RUNS: usize = 1000;
+
+// Run the query at the cursor N times and report its latency distribution
+impl App {
+    pub fn begin_benchmark(&mut self) {
+        if self.extract_current_query().trim().is_empty() {
+            return;
+        }
+        let runs = self.config.benchmark_runs.to_string();
+        self.open_prompt("Benchmark: number of runs", &runs, PromptAction::Benchmark);
+    }
+
+    pub async fn run_benchmark(&mut self, input: &str) -> Result<()> {
+        let runs: usize = input.parse().map_err(|_| anyhow!("Not a number of runs: {}", input))?;
+        if runs == 0 || runs > MAX_RUNS {
+            bail!("Runs must be between 1 and {}", MAX_RUNS);
+        }
+        let sql = self.extract_current_query();
+        // Running a write N times is not a benchmark anyone wants by accident
+        if let Some(word) = write_keyword(&sql) {
+            bail!("Only queries can be benchmarked; this statement runs {}", word);
+        }
+        let warmup = self.config.benchmark_warmup;
+        let client = self.ws.db.client().ok_or_else(|| anyhow!("Not connected"))?;
+
+        // The first run warms caches and plans; it is left out when configured
+        let total = runs + usize::from(warmup);
+        let mut timings = Vec::with_capacity(total);
+        let mut rows = Vec::with_capacity(total);
+        for run in 1..=total {
+            let started = Instant::now();
+            let outcome = crate::db::execute_statement(client, &sql).await;
+            let elapsed = started.elapsed();
+            let audited = outcome.as_ref().map(|&n| n as usize).map_err(|e| format!("{:#}", e));
+            self.audit.record_statement(&self.ws.name, &sql, elapsed, audited);
+            rows.push(outcome.with_context(|| format!("Run {} failed", run))?);
+            timings.push(elapsed);
+        }
+        if warmup {
+            timings.remove(0);
+            rows.remove(0);
+        }
+        self.stats.record_query(&sql, rows.last().map(|&n| n as usize));
+
+        self.popup = Some(ResultPopup {
+            title: format!("Benchmark ({} runs{})", runs, if warmup { ", first run discarded" } else { "" }),
+            result: benchmark_report(&timings, &rows),
+            scroll: 0,
+        });
+        Ok(())
+    }
+}
+
+fn benchmark_report(timings: &[Duration], rows: &[u64]) -> QueryResult {
+    let mut sorted = timings.to_vec();
+    sorted.sort();
+    // Nearest-rank percentile
+    let percentile = |p: usize| sorted[(sorted.len() * p).div_ceil(100).max(1) - 1];
+    let mean = timings.iter().sum::<Duration>() / timings.len() as u32;
+    let (fewest, most) = (rows.iter().min().copied().unwrap_or(0), rows.iter().max().copied().unwrap_or(0));
+
+    let mut report = vec![
+        vec!["Min".to_string(), format_ms(sorted[0])],
+        vec!["Median".to_string(), format_ms(percentile(50))],
+        vec!["p95".to_string(), format_ms(percentile(95))],
+        vec!["Max".to_string(), format_ms(sorted[sorted.len() - 1])],
+        vec!["Mean".to_string(), format_ms(mean)],
+        vec![
+            "Rows".to_string(),
+            if fewest == most { fewest.to_string() } else { format!("{}-{}", fewest, most) },
+        ],
+    ];
+    report.extend(
+        timings
+            .iter()
+            .enumerate()
+            .map(|(i, &timing)| vec![format!("Run {}", i + 1), format_ms(timing)]),
+    );
+    QueryResult::from_text(vec!["Measure".to_string(), "Value".to_string()], report)
+}
+
+fn format_ms(duration: Duration) -> String {
+    format!("{:.2} ms", duration.as_secs_f64() * 1000.0)
+}
@@ -0,0 +1,318 @@
+use anyhow::Result;
+
+use crate::app::{degrade_listing, App, BrowserItem, FolderType};
+
+// The folders every schema opens into, in the order they are listed
+const FOLDERS: [FolderType; 5] = [
+    FolderType::Tables,
+    FolderType::Views,
+    FolderType::MaterializedViews,
+    FolderType::Functions,
+    FolderType::Sequences,
+];
+
+// One node of the browser tree. Schemas and folders get their children when
+// expanded and drop them when collapsed, so expanding again lists the
+// catalog as it is then.
+#[derive(Debug, Clone)]
+pub struct TreeNode {
+    pub item: BrowserItem,
+    pub expanded: bool,
+    pub children: Vec<TreeNode>,
+    // Shown dimmed after the name: a folder's item count, a table's
+    // estimated rows
+    pub note: Option<String>,
+}
+
+impl TreeNode {
+    pub fn new(item: BrowserItem) -> Self {
+        Self {
+            item,
+            expanded: false,
+            children: Vec::new(),
+            note: None,
+        }
+    }
+
+    fn with_note(item: BrowserItem, note: Option<String>) -> Self {
+        Self { note, ..Self::new(item) }
+    }
+
+    fn expandable(&self) -> bool {
+        matches!(self.item, BrowserItem::Schema(_) | BrowserItem::Folder(_, _))
+    }
+
+    fn collapse(&mut self) {
+        self.expanded = false;
+        self.children.clear();
+    }
+
+    // This node's row and, when expanded, its descendants', depth first
+    fn flatten(&self, path: &mut Vec<usize>, items: &mut Vec<BrowserItem>, paths: &mut Vec<Vec<usize>>) {
+        items.push(self.item.clone());
+        paths.push(path.clone());
+        if self.expanded {
+            for (i, child) in self.children.iter().enumerate() {
+                path.push(i);
+                child.flatten(path, items, paths);
+                path.pop();
+            }
+        }
+    }
+}
+
+impl App {
+    fn tree_node(&self, path: &[usize]) -> Option<&TreeNode> {
+        let (first, rest) = path.split_first()?;
+        rest.iter()
+            .try_fold(self.ws.browser_tree.get(*first)?, |node, &i| node.children.get(i))
+    }
+
+    fn tree_node_mut(&mut self, path: &[usize]) -> Option<&mut TreeNode> {
+        let (first, rest) = path.split_first()?;
+        rest.iter()
+            .try_fold(self.ws.browser_tree.get_mut(*first)?, |node, &i| node.children.get_mut(i))
+    }
+
+    // Lay the tree out again as the browser's rows, keeping the cursor on
+    // the same node
+    pub(super) fn rebuild_browser_items(&mut self) {
+        let selected = self.ws.browser_paths.get(self.ws.browser_selected).cloned().unwrap_or_default();
+        self.rebuild_browser_items_at(selected);
+    }
+
+    // Lay the tree out again with the cursor on the node at `path`, or on
+    // its nearest ancestor still shown
+    pub(super) fn rebuild_browser_items_at(&mut self, path: Vec<usize>) {
+        let (mut items, mut paths) = (Vec::new(), Vec::new());
+        for (i, node) in self.ws.browser_tree.iter().enumerate() {
+            node.flatten(&mut vec![i], &mut items, &mut paths);
+        }
+        self.ws.browser_selected = (1..=path.len())
+            .rev()
+            .find_map(|n| paths.iter().position(|p| *p == path[..n]))
+            .unwrap_or(0)
+            .min(items.len().saturating_sub(1));
+        self.ws.browser_items = items;
+        self.ws.browser_paths = paths;
+    }
+
+    pub fn browser_note(&self, row: usize) -> Option<&str> {
+        let path = self.ws.browser_paths.get(row)?;
+        self.tree_node(path)?.note.as_deref()
+    }
+
+    pub(super) fn is_expanded(&self, row: usize) -> bool {
+        self.ws
+            .browser_paths
+            .get(row)
+            .and_then(|path| self.tree_node(path))
+            .is_some_and(|node| node.expanded)
+    }
+
+    // Expand the node at `row`, loading a folder's objects
+    pub(super) async fn expand_row(&mut self, row: usize) -> Result<()> {
+        let Some(path) = self.ws.browser_paths.get(row).cloned() else {
+            return Ok(());
+        };
+        let mut notes = Vec::new();
+        self.expand_node(&path, &mut notes).await?;
+        self.rebuild_browser_items();
+        if let Some(note) = notes.pop() {
+            self.set_warning(note);
+        }
+        Ok(())
+    }
+
+    pub(super) fn collapse_row(&mut self, row: usize) {
+        let Some(path) = self.ws.browser_paths.get(row).cloned() else {
+            return;
+        };
+        if let Some(node) = self.tree_node_mut(&path) {
+            node.collapse();
+        }
+        self.rebuild_browser_items_at(path);
+    }
+
+    async fn expand_node(&mut self, path: &[usize], notes: &mut Vec<String>) -> Result<()> {
+        let Some(node) = self.tree_node(path) else {
+            return Ok(());
+        };
+        if node.expanded || !node.expandable() {
+            return Ok(());
+        }
+        let item = node.item.clone();
+        let mut note = None;
+        let children = self.load_children(&item, &mut note).await?;
+        notes.extend(note);
+        if let Some(node) = self.tree_node_mut(path) {
+            // What was listed is the count from now on
+            if matches!(node.item, BrowserItem::Folder(_, _)) {
+                node.note = Some(format!("({})", children.len()));
+            }
+            node.children = children;
+            node.expanded = true;
+        }
+        Ok(())
+    }
+
+    // A schema's folders with how many objects each holds, or a folder's
+    // objects read from the catalog
+    async fn load_children(&mut self, item: &BrowserItem, note: &mut Option<String>) -> Result<Vec<TreeNode>> {
+        let Some(client) = self.ws.db.client() else {
+            return Ok(Vec::new());
+        };
+        let (schema, folder_type) = match item {
+            BrowserItem::Schema(schema) => {
+                // Only decoration, so a failure leaves the folders without counts
+                let counts = crate::db::folder_counts(client, schema).await.ok();
+                let folders = FOLDERS.iter().enumerate().map(|(i, folder)| {
+                    let count = counts.map(|counts| format!("({})", counts[i]));
+                    TreeNode::with_note(BrowserItem::Folder(schema.clone(), *folder), count)
+                });
+                return Ok(folders.collect());
+            }
+            BrowserItem::Folder(schema, folder_type) => (schema.clone(), *folder_type),
+            _ => return Ok(Vec::new()),
+        };
+        let nodes = match folder_type {
+            FolderType::Tables => {
+                self.ws.tables = degrade_listing(crate::db::list_tables(client, &schema).await, "tables", &schema, note)?;
+                let row_counts = self.config.browser_row_counts;
+                self.ws
+                    .tables
+                    .iter()
+                    .map(|t| {
+                        let rows = t.row_count.filter(|_| row_counts).map(approximate_rows);
+                        TreeNode::with_note(BrowserItem::Table(schema.clone(), t.name.clone()), rows)
+                    })
+                    .collect()
+            }
+            FolderType::Views => degrade_listing(crate::db::list_views(client, &schema).await, "views", &schema, note)?
+                .into_iter()
+                .map(|v| TreeNode::new(BrowserItem::View(schema.clone(), v.name)))
+                .collect(),
+            FolderType::MaterializedViews => degrade_listing(
+                crate::db::list_materialized_views(client, &schema).await,
+                "materialized views",
+                &schema,
+                note,
+            )?
+            .into_iter()
+            .map(|v| TreeNode::new(BrowserItem::MaterializedView(schema.clone(), v.name)))
+            .collect(),
+            FolderType::Functions => {
+                degrade_listing(crate::db::list_functions(client, &schema).await, "functions", &schema, note)?
+                    .into_iter()
+                    .map(|f| TreeNode::new(BrowserItem::Function(schema.clone(), f.name)))
+                    .collect()
+            }
+            FolderType::Sequences => {
+                degrade_listing(crate::db::list_sequences(client, &schema).await, "sequences", &schema, note)?
+                    .into_iter()
+                    .map(|s| TreeNode::new(BrowserItem::Sequence(schema.clone(), s.name)))
+                    .collect()
+            }
+        };
+        Ok(nodes)
+    }
+
+    // The node whose subtree `*` and `-` act on: the one under the cursor,
+    // or the folder of an object
+    fn subtree_path(&self) -> Option<Vec<usize>> {
+        let path = self.ws.browser_paths.get(self.ws.browser_selected)?;
+        let node = self.tree_node(path)?;
+        if node.expandable() {
+            Some(path.clone())
+        } else if path.len() > 1 {
+            Some(path[..path.len() - 1].to_vec())
+        } else {
+            None
+        }
+    }
+
+    // Expand the node under the cursor and everything beneath it
+    pub async fn expand_all(&mut self) -> Result<()> {
+        let Some(root) = self.subtree_path() else {
+            return Ok(());
+        };
+        let mut notes = Vec::new();
+        let mut pending = vec![root.clone()];
+        while let Some(path) = pending.pop() {
+            self.expand_node(&path, &mut notes).await?;
+            let Some(node) = self.tree_node(&path) else {
+                continue;
+            };
+            for (i, child) in node.children.iter().enumerate().rev() {
+                if child.expandable() {
+                    pending.push([path.as_slice(), &[i]].concat());
+                }
+            }
+        }
+        self.rebuild_browser_items_at(root);
+        if let Some(note) = notes.pop() {
+            self.set_warning(note);
+        }
+        Ok(())
+    }
+
+    // Collapse the node under the cursor and everything beneath it, or the
+    // whole tree from the database node or a favorite
+    pub fn collapse_all(&mut self) {
+        match self.subtree_path() {
+            Some(path) => {
+                if let Some(node) = self.tree_node_mut(&path) {
+                    node.collapse();
+                }
+                self.rebuild_browser_items_at(path);
+            }
+            None => {
+                self.ws.browser_tree.iter_mut().for_each(TreeNode::collapse);
+                self.rebuild_browser_items();
+            }
+        }
+    }
+
+    // Collapse the node under the cursor, or move up to its parent
+    pub fn browser_left(&mut self) {
+        let row = self.ws.browser_selected;
+        if self.is_expanded(row) {
+            self.collapse_row(row);
+        } else if let Some(path) = self.ws.browser_paths.get(row).filter(|path| path.len() > 1) {
+            let parent = path[..path.len() - 1].to_vec();
+            self.rebuild_browser_items_at(parent);
+        }
+    }
+
+    // Expand the node under the cursor, or move down to its first child
+    pub async fn browser_right(&mut self) -> Result<()> {
+        let row = self.ws.browser_selected;
+        if self.is_expanded(row) {
+            let has_children = self.ws.browser_paths.get(row + 1).is_some_and(|next| {
+                next.len() > self.ws.browser_paths[row].len()
+            });
+            if has_children {
+                self.ws.browser_selected = row + 1;
+            }
+            Ok(())
+        } else {
+            self.expand_row(row).await
+        }
+    }
+}
+
+// "~950", "~12k", "~3.4M": estimated rows, rounded to what a glance needs
+fn approximate_rows(rows: i64) -> String {
+    let rows = rows as f64;
+    let (value, unit) = match rows {
+        r if r >= 1e9 => (r / 1e9, "G"),
+        r if r >= 1e6 => (r / 1e6, "M"),
+        r if r >= 1e3 => (r / 1e3, "k"),
+        r => return format!("~{}", r),
+    };
+    if value < 10.0 {
+        format!("~{:.1}{}", value, unit)
+    } else {
+        format!("~{:.0}{}", value, unit)
+    }
+}
@@ -0,0 +1,80 @@
+use anyhow::{anyhow, Result};
+use tokio::task::JoinHandle;
+
+use crate::app::{App, Workspace};
+use crate::db::CompletionCatalog;
+
+pub type CatalogJob = JoinHandle<Result<CompletionCatalog>>;
+
+// The names completion offers, loaded in the background once per connection
+// so typing never waits for them
+impl App {
+    // Start loading the active connection's catalog unless it is loaded or
+    // already on its way
+    pub fn start_catalog_load(&mut self) {
+        if self.ws.autocomplete_schema_loaded || self.ws.catalog_job.is_some() {
+            return;
+        }
+        let Some(client) = self.ws.db.shared_client() else {
+            return;
+        };
+        self.ws.catalog_job = Some(tokio::spawn(async move { crate::db::load_completion_catalog(&client).await }));
+    }
+
+    // The catalog, waiting for it if it is still loading; for commands that
+    // need every name, like expanding `*`
+    pub async fn load_autocomplete_schema(&mut self) -> Result<()> {
+        self.start_catalog_load();
+        let Some(job) = self.ws.catalog_job.take() else {
+            return Ok(());
+        };
+        let catalog = job.await.map_err(|e| anyhow!("Loading the completion catalog failed: {}", e))??;
+        apply_catalog(&mut self.ws, catalog);
+        Ok(())
+    }
+
+    // Take in the catalogs that finished loading, for every tab; called on
+    // every pass of the event loop
+    pub async fn poll_catalog(&mut self) {
+        if let Some(Err(e)) = finish_catalog(&mut self.ws).await {
+            self.set_warning(format!("Completion catalog: {:#}", e));
+        }
+        // A background tab that failed tries again once it is active
+        for ws in &mut self.workspaces {
+            finish_catalog(ws).await;
+        }
+    }
+
+    // Forget the cached names, e.g. after a migration, and load them again
+    pub fn reload_catalog(&mut self) {
+        if !self.ws.db.is_connected() {
+            return;
+        }
+        if let Some(job) = self.ws.catalog_job.take() {
+            job.abort();
+        }
+        self.ws.autocomplete_schema_loaded = false;
+        self.start_catalog_load();
+        self.set_status("Reloading the completion catalog".to_string());
+    }
+}
+
+async fn finish_catalog(ws: &mut Workspace) -> Option<Result<()>> {
+    if !ws.catalog_job.as_ref().is_some_and(|job| job.is_finished()) {
+        return None;
+    }
+    let loaded = match ws.catalog_job.take()?.await {
+        Ok(loaded) => loaded,
+        Err(e) => Err(anyhow!("Loading the completion catalog failed: {}", e)),
+    };
+    Some(loaded.map(|catalog| apply_catalog(ws, catalog)))
+}
+
+fn apply_catalog(ws: &mut Workspace, catalog: CompletionCatalog) {
+    let engine = &mut ws.autocomplete_engine;
+    engine.update_schema(catalog.tables);
+    engine.update_catalog(catalog.roles, catalog.schemas, catalog.databases);
+    engine.update_settings(catalog.settings);
+    engine.update_functions(catalog.functions);
+    ws.autocomplete_schema_loaded = true;
+}
@@ -0,0 +1,246 @@
+use crossterm::event::KeyCode;
+use serde_json::Value;
+
+use crate::app::App;
+use crate::keymap::Action;
+
+// Above this many token pairs the changed middle of two values is shown as
+// one removal and one addition instead of being aligned word by word
+const MAX_ALIGNED: usize = 2_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Same,
+    Removed,
+    Added,
+}
+
+// A cell set aside with the diff key, to compare with the next one
+pub struct MarkedCell {
+    pub label: String,
+    pub value: String,
+}
+
+// Word-level differences between a marked cell and the one under the cursor
+pub struct CellDiff {
+    pub before: String,
+    pub after: String,
+    // Both values were JSON and are compared pretty-printed
+    pub json: bool,
+    pub pieces: Vec<(Change, String)>,
+    pub words_removed: usize,
+    pub words_added: usize,
+    pub scroll: usize,
+}
+
+impl CellDiff {
+    pub fn line_count(&self) -> usize {
+        self.pieces.iter().map(|(_, text)| text.matches('\n').count()).sum::<usize>() + 1
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Class {
+    Word,
+    Space,
+    Other,
+}
+
+fn class(c: char) -> Class {
+    match c {
+        '\n' => Class::Other,
+        c if c.is_whitespace() => Class::Space,
+        c if c.is_alphanumeric() || c == '_' => Class::Word,
+        _ => Class::Other,
+    }
+}
+
+impl App {
+    // First press marks the cell under the cursor, the next compares the
+    // cell it is pressed on with it. The mark is kept by value, so it
+    // survives running the query again or switching tabs.
+    pub fn diff_cell(&mut self) {
+        let Some(value) = self.selected_cell_value().map(|value| value.unwrap_or("NULL").to_string()) else {
+            return;
+        };
+        let label = self.selected_cell_label();
+        let Some(mark) = self.cell_mark.take() else {
+            let key = self.keymap.label(Action::DiffCell);
+            self.set_status(format!("Marked {}; {} on another cell compares it", label, key));
+            self.cell_mark = Some(MarkedCell { label, value });
+            return;
+        };
+        if mark.value == value {
+            self.set_status(format!("{} and {} are identical", mark.label, label));
+            return;
+        }
+
+        let parsed = (serde_json::from_str::<Value>(&mark.value), serde_json::from_str::<Value>(&value));
+        let (before, after, json) = match parsed {
+            (Ok(old), Ok(new)) if [&old, &new].iter().any(|v| v.is_object() || v.is_array()) => {
+                let pretty = |v: &Value| serde_json::to_string_pretty(v).unwrap_or_default();
+                (pretty(&old), pretty(&new), true)
+            }
+            _ => (mark.value, value, false),
+        };
+        let pieces = diff_words(&before, &after);
+        if pieces.iter().all(|(change, _)| *change == Change::Same) {
+            // Only key order or formatting told the two JSON values apart
+            self.set_status(format!("{} and {} hold the same JSON value", mark.label, label));
+            return;
+        }
+        let words = |kind| {
+            pieces
+                .iter()
+                .filter(|(change, _)| *change == kind)
+                .map(|(_, text)| tokens(text).iter().filter(|t| t.starts_with(|c| class(c) == Class::Word)).count())
+                .sum::<usize>()
+        };
+        self.cell_diff = Some(CellDiff {
+            words_removed: words(Change::Removed),
+            words_added: words(Change::Added),
+            before: mark.label,
+            after: label,
+            json,
+            pieces,
+            scroll: 0,
+        });
+    }
+
+    pub fn close_cell_diff(&mut self) {
+        self.cell_diff = None;
+    }
+
+    pub fn handle_cell_diff_input(&mut self, key: KeyCode) {
+        let Some(diff) = &mut self.cell_diff else {
+            return;
+        };
+        let last = diff.line_count().saturating_sub(1);
+        match key {
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => self.close_cell_diff(),
+            KeyCode::Up => diff.scroll = diff.scroll.saturating_sub(1),
+            KeyCode::Down => diff.scroll = (diff.scroll + 1).min(last),
+            KeyCode::PageUp => diff.scroll = diff.scroll.saturating_sub(20),
+            KeyCode::PageDown => diff.scroll = (diff.scroll + 20).min(last),
+            KeyCode::Home => diff.scroll = 0,
+            KeyCode::End => diff.scroll = last,
+            _ => {}
+        }
+    }
+
+    // "name, row 3" (plus the tab when there are several connections)
+    fn selected_cell_label(&self) -> String {
+        let column = self
+            .ws
+            .query_result
+            .as_ref()
+            .and_then(|result| result.columns.get(self.ws.result_selected_col))
+            .cloned()
+            .unwrap_or_default();
+        let label = format!("{}, row {}", column, self.ws.result_selected_row + 1);
+        if self.workspaces.len() > 1 {
+            format!("{} ({})", label, self.ws.name)
+        } else {
+            label
+        }
+    }
+}
+
+// Words, runs of whitespace, and single other characters (line breaks
+// included, so changes stay on their lines)
+fn tokens(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let kind = class(c);
+        let mut end = start + c.len_utf8();
+        if kind != Class::Other {
+            while let Some(&(i, next)) = chars.peek() {
+                if class(next) != kind {
+                    break;
+                }
+                end = i + next.len_utf8();
+                chars.next();
+            }
+        }
+        tokens.push(&text[start..end]);
+    }
+    tokens
+}
+
+// `old` turned into `new`, as runs of kept, removed and added text. Within
+// each changed stretch the removed text comes before the added.
+fn diff_words(old: &str, new: &str) -> Vec<(Change, String)> {
+    let (a, b) = (tokens(old), tokens(new));
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (ma, mb) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+
+    let mut steps: Vec<(Change, &str)> = a[..prefix].iter().map(|t| (Change::Same, *t)).collect();
+    if ma.len().saturating_mul(mb.len()) > MAX_ALIGNED {
+        steps.extend(ma.iter().map(|t| (Change::Removed, *t)));
+        steps.extend(mb.iter().map(|t| (Change::Added, *t)));
+    } else {
+        // Longest common subsequence of the tokens after (i, j)
+        let width = mb.len() + 1;
+        let mut lcs = vec![0u32; (ma.len() + 1) * width];
+        for i in (0..ma.len()).rev() {
+            for j in (0..mb.len()).rev() {
+                lcs[i * width + j] = if ma[i] == mb[j] {
+                    lcs[(i + 1) * width + j + 1] + 1
+                } else {
+                    lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < ma.len() && j < mb.len() {
+            if ma[i] == mb[j] {
+                steps.push((Change::Same, ma[i]));
+                i += 1;
+                j += 1;
+            } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+                steps.push((Change::Removed, ma[i]));
+                i += 1;
+            } else {
+                steps.push((Change::Added, mb[j]));
+                j += 1;
+            }
+        }
+        steps.extend(ma[i..].iter().map(|t| (Change::Removed, *t)));
+        steps.extend(mb[j..].iter().map(|t| (Change::Added, *t)));
+    }
+    steps.extend(a[a.len() - suffix..].iter().map(|t| (Change::Same, *t)));
+
+    let mut pieces: Vec<(Change, String)> = Vec::new();
+    let (mut removed, mut added) = (String::new(), String::new());
+    for (change, token) in steps {
+        match change {
+            Change::Removed => removed.push_str(token),
+            Change::Added => added.push_str(token),
+            Change::Same => {
+                flush(&mut pieces, &mut removed, &mut added);
+                match pieces.last_mut() {
+                    Some((Change::Same, text)) => text.push_str(token),
+                    _ => pieces.push((Change::Same, token.to_string())),
+                }
+            }
+        }
+    }
+    flush(&mut pieces, &mut removed, &mut added);
+    pieces
+}
+
+fn flush(pieces: &mut Vec<(Change, String)>, removed: &mut String, added: &mut String) {
+    if !removed.is_empty() {
+        pieces.push((Change::Removed, std::mem::take(removed)));
+    }
+    if !added.is_empty() {
+        pieces.push((Change::Added, std::mem::take(added)));
+    }
+}
@@ -0,0 +1,88 @@
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::app::commands::expand_home;
+use crate::app::{App, PromptAction};
+
+impl App {
+    pub fn begin_save_cell(&mut self) {
+        match self.selected_cell_bytes() {
+            Ok((_, extension)) => {
+                let initial = format!("{}cell.{}", self.ws.file_dir(), extension);
+                self.open_prompt("Save cell value to", &initial, PromptAction::SaveCell);
+            }
+            Err(e) => self.set_error(format!("{:#}", e)),
+        }
+    }
+
+    // The whole value, however much of it the grid shows; bytea as the
+    // bytes it holds rather than their hex
+    pub fn save_cell(&mut self, path: &str) -> Result<()> {
+        let path = expand_home(path);
+        let (bytes, _) = self.selected_cell_bytes()?;
+        std::fs::write(&path, &bytes).with_context(|| format!("Failed to write {}", path))?;
+        self.set_status(format!("Wrote {} bytes to {}", bytes.len(), path));
+        Ok(())
+    }
+
+    // Write the value to a temporary file for the main loop to open in
+    // $VISUAL or $EDITOR. Changes made there are not written back.
+    pub fn open_cell_in_editor(&mut self) -> Result<()> {
+        let (bytes, extension) = self.selected_cell_bytes()?;
+        let path = std::env::temp_dir().join(format!("pg-tui-cell-{}.{}", std::process::id(), extension));
+        std::fs::write(&path, &bytes).with_context(|| format!("Failed to write {}", path.display()))?;
+        self.external_editor = Some(path);
+        Ok(())
+    }
+
+    // The selected cell's contents, and a file extension that suits them
+    fn selected_cell_bytes(&self) -> Result<(Vec<u8>, &'static str)> {
+        let value = self
+            .selected_cell_value()
+            .ok_or_else(|| anyhow!("No cell selected"))?
+            .ok_or_else(|| anyhow!("The cell is NULL"))?;
+        let column_type = self
+            .ws
+            .query_result
+            .as_ref()
+            .and_then(|result| result.column_type(self.ws.result_selected_col));
+        Ok(match column_type {
+            Some("bytea") => (decode_hex(value)?, "bin"),
+            Some("json" | "jsonb") => (value.as_bytes().to_vec(), "json"),
+            Some("xml") => (value.as_bytes().to_vec(), "xml"),
+            _ => (value.as_bytes().to_vec(), "txt"),
+        })
+    }
+}
+
+// bytea's "\x0a1b" output back into bytes
+fn decode_hex(value: &str) -> Result<Vec<u8>> {
+    let Some(digits) = value.strip_prefix("\\x") else {
+        bail!("Not a bytea value in hex format");
+    };
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| {
+            digits
+                .get(i..i + 2)
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| anyhow!("Invalid hex in bytea value"))
+        })
+        .collect()
+}
+
+// The editor command: $VISUAL, else $EDITOR, else vi; may carry arguments
+// ("code --wait")
+pub fn editor_command(path: &Path) -> Command {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .ok()
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string());
+    let mut words = editor.split_whitespace();
+    let mut command = Command::new(words.next().unwrap_or("vi"));
+    command.args(words).arg(path);
+    command
+}
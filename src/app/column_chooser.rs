@@ -0,0 +1,115 @@
+use anyhow::{anyhow, bail, Result};
+use crossterm::event::KeyCode;
+
+use super::table_view::{arrange_columns, query_signature};
+use crate::app::App;
+
+// Every column of the results, hidden ones included, to show or hide and
+// put in order
+pub struct ColumnChooser {
+    // Name and whether it is shown, in grid order
+    pub columns: Vec<(String, bool)>,
+    pub selected: usize,
+}
+
+impl App {
+    // Shown columns as the grid has them, then the hidden ones
+    pub fn open_column_chooser(&mut self) {
+        let Some(result) = &self.ws.query_result else {
+            return;
+        };
+        if result.columns.is_empty() {
+            return;
+        }
+        let columns = result
+            .columns
+            .iter()
+            .map(|name| (name.clone(), true))
+            .chain(self.ws.result_hidden.iter().map(|name| (name.clone(), false)))
+            .collect();
+        let selected = self.ws.result_selected_col.min(result.columns.len() - 1);
+        self.column_chooser = Some(ColumnChooser { columns, selected });
+    }
+
+    pub fn close_column_chooser(&mut self) {
+        self.column_chooser = None;
+    }
+
+    pub fn handle_column_chooser_input(&mut self, key: KeyCode) {
+        let Some(chooser) = &mut self.column_chooser else {
+            return;
+        };
+        let last = chooser.columns.len().saturating_sub(1);
+        let at = chooser.selected;
+        match key {
+            KeyCode::Up => chooser.selected = at.saturating_sub(1),
+            KeyCode::Down => chooser.selected = (at + 1).min(last),
+            KeyCode::Char(' ') => chooser.columns[at].1 = !chooser.columns[at].1,
+            KeyCode::Char('K') if at > 0 => {
+                chooser.columns.swap(at, at - 1);
+                chooser.selected = at - 1;
+            }
+            KeyCode::Char('J') if at < last => {
+                chooser.columns.swap(at, at + 1);
+                chooser.selected = at + 1;
+            }
+            _ => {}
+        }
+    }
+
+    // Lay the grid out as chosen and keep that for the query's text. Hiding
+    // drops a column's values, so showing one again runs the query again.
+    pub async fn apply_column_chooser(&mut self) -> Result<()> {
+        let Some(chooser) = self.column_chooser.take() else {
+            return Ok(());
+        };
+        let order: Vec<String> = chooser.columns.iter().map(|(name, _)| name.clone()).collect();
+        let hidden: Vec<String> = chooser
+            .columns
+            .iter()
+            .filter(|(_, shown)| !shown)
+            .map(|(name, _)| name.clone())
+            .collect();
+        if hidden.len() == order.len() {
+            bail!("At least one column has to be shown");
+        }
+        let result = self.ws.query_result.as_ref().ok_or_else(|| anyhow!("No results"))?;
+        let sort = self.ws.result_sort.map(|(col, direction)| (result.columns[col].clone(), direction));
+        let selected = result.columns.get(self.ws.result_selected_col).cloned();
+
+        if self.ws.result_hidden.iter().any(|name| !hidden.contains(name)) {
+            let sql = self
+                .ws
+                .result_sql
+                .clone()
+                .ok_or_else(|| anyhow!("These results have no query to run again for their hidden columns"))?;
+            let params = &self.ws.result_params;
+            let (result, capped, _) =
+                super::results::load_result(&self.ws.db, &sql, params, self.config.max_result_rows, 0).await?;
+            self.ws.query_result = Some(result);
+            self.ws.result_capped = capped;
+        }
+        let Some(result) = self.ws.query_result.as_mut() else {
+            return Ok(());
+        };
+        arrange_columns(result, &order, &hidden);
+        let position = |name: &String| result.columns.iter().position(|c| c == name);
+        self.ws.result_sort = sort.and_then(|(name, direction)| Some((position(&name)?, direction)));
+        self.ws.result_selected_col = selected.and_then(|name| position(&name)).unwrap_or(0);
+        self.ws.result_pinned = self.ws.result_pinned.min(result.columns.len());
+        self.ws.result_scroll_offset = self.ws.result_scroll_offset.min(self.ws.result_selected_col);
+        self.ws.result_hidden = hidden;
+
+        let Some(sql) = self.ws.result_sql.as_deref() else {
+            self.set_status("Column layout changed".to_string());
+            return Ok(());
+        };
+        let key = query_signature(sql);
+        if let Some(view) = self.current_table_view() {
+            self.config.query_layouts.insert(key, view);
+            self.config.save()?;
+        }
+        self.set_status("Column layout saved for this query".to_string());
+        Ok(())
+    }
+}
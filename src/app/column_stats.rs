@@ -0,0 +1,54 @@
+use std::collections::HashSet;
+
+use crate::app::row_filter::compare_cells;
+
+// Types whose values are summed and averaged; money is left out as its
+// text carries a currency symbol
+const NUMERIC_TYPES: &[&str] = &["int2", "int4", "int8", "float4", "float8", "numeric", "oid"];
+
+// Quick figures over one column of the rows on screen, for the grid's
+// footer
+pub struct ColumnStats {
+    pub rows: usize,
+    pub nulls: usize,
+    pub distinct: usize,
+    // Set for numeric columns with a value
+    pub sum: Option<f64>,
+    pub avg: Option<f64>,
+    pub min: Option<String>,
+    pub max: Option<String>,
+}
+
+// Results without types (shown from the results history) count as numeric
+// when every value parses as a number
+pub fn column_stats(rows: &[&Vec<Option<String>>], col: usize, column_type: Option<&str>) -> ColumnStats {
+    let values: Vec<&str> = rows.iter().filter_map(|row| row.get(col)?.as_deref()).collect();
+    let numbers: Option<Vec<f64>> = match column_type {
+        Some(kind) if !NUMERIC_TYPES.contains(&kind) => None,
+        _ => values.iter().map(|value| value.parse::<f64>().ok()).collect(),
+    };
+    let numbers = numbers.filter(|numbers| !numbers.is_empty());
+    let compare = |a: &&str, b: &&str| match &numbers {
+        Some(_) => compare_cells(a, b),
+        None => a.cmp(b),
+    };
+    let sum = numbers.as_ref().map(|numbers| numbers.iter().sum::<f64>());
+    ColumnStats {
+        rows: rows.len(),
+        nulls: rows.len() - values.len(),
+        distinct: values.iter().collect::<HashSet<_>>().len(),
+        avg: sum.zip(numbers.as_ref()).map(|(sum, numbers)| sum / numbers.len() as f64),
+        sum,
+        min: values.iter().min_by(|a, b| compare(a, b)).map(|value| value.to_string()),
+        max: values.iter().max_by(|a, b| compare(a, b)).map(|value| value.to_string()),
+    }
+}
+
+// Whole numbers without a fraction, the rest to four places at most
+pub fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        return format!("{}", value as i64);
+    }
+    let text = format!("{:.4}", value);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
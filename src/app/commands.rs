@@ -0,0 +1,194 @@
+use anyhow::{anyhow, bail, Context, Result};
+use crossterm::event::KeyCode;
+
+use crate::app::{App, AppMode, CommandPalette, GuardedRun, ResultPopup};
+use crate::config::{CommandTarget, UserCommand};
+use crate::db::QueryResult;
+
+// Command palette and user-defined commands
+impl App {
+    pub fn open_palette(&mut self) {
+        if self.config.commands.is_empty() {
+            self.set_error("No commands defined; add them to the \"commands\" section of the config file".to_string());
+            return;
+        }
+        self.palette = Some(CommandPalette::default());
+    }
+
+    pub fn close_palette(&mut self) {
+        self.palette = None;
+    }
+
+    // Commands whose name contains the palette filter, in config order
+    pub fn palette_commands(&self) -> Vec<&UserCommand> {
+        let filter = self
+            .palette
+            .as_ref()
+            .map(|p| p.filter.to_lowercase())
+            .unwrap_or_default();
+        self.config
+            .commands
+            .iter()
+            .filter(|cmd| cmd.name.to_lowercase().contains(&filter))
+            .collect()
+    }
+
+    pub fn handle_palette_input(&mut self, key: KeyCode) {
+        let count = self.palette_commands().len();
+        let Some(palette) = &mut self.palette else {
+            return;
+        };
+        match key {
+            KeyCode::Up => palette.selected = palette.selected.saturating_sub(1),
+            KeyCode::Down => palette.selected = (palette.selected + 1).min(count.saturating_sub(1)),
+            KeyCode::Char(c) => {
+                palette.filter.push(c);
+                palette.selected = 0;
+            }
+            KeyCode::Backspace => {
+                palette.filter.pop();
+                palette.selected = 0;
+            }
+            _ => {}
+        }
+    }
+
+    pub async fn run_palette_selection(&mut self) -> Result<()> {
+        let Some(palette) = &self.palette else {
+            return Ok(());
+        };
+        let command = self.palette_commands().get(palette.selected).map(|cmd| (*cmd).clone());
+        self.palette = None;
+        match command {
+            Some(command) => self.run_user_command(&command).await,
+            None => Ok(()),
+        }
+    }
+
+    pub(super) async fn run_user_command(&mut self, command: &UserCommand) -> Result<()> {
+        if let Some(script) = &command.script {
+            return self.run_script_command(command, script).await;
+        }
+        let sql = self.expand_command_template(&command.sql)?;
+        self.refuse_on_read_only(&sql)?;
+        if self.hold_destructive(&sql, GuardedRun::Command(command.clone()))
+            || self.hold_write(&sql, GuardedRun::Command(command.clone()))
+        {
+            return Ok(());
+        }
+        let path = match &command.target {
+            // Shown like a query from the editor, streamed and all
+            CommandTarget::Results => {
+                self.ws.watch = None;
+                self.run_interactive_query(sql, Vec::new(), false).await?;
+                self.reset_result_view();
+                self.mode = AppMode::Query;
+                return Ok(());
+            }
+            CommandTarget::Popup => None,
+            CommandTarget::File(path) => Some(expand_home(path)),
+        };
+        if !self.ws.db.is_connected() {
+            bail!("Not connected");
+        }
+        self.clear_notifications();
+        let run = self.run_statement(&sql, &[], 0, usize::MAX).await?;
+        let (result, _, _) = run.result.with_context(|| format!("Command '{}' failed", command.name))?;
+        run.post_hooks?;
+
+        match path {
+            None => {
+                self.popup = Some(ResultPopup {
+                    title: command.name.clone(),
+                    result,
+                    scroll: 0,
+                });
+            }
+            Some(path) => {
+                std::fs::write(&path, to_csv(&result))
+                    .with_context(|| format!("Failed to write {}", path))?;
+                self.audit.record_export(&path, result.row_count);
+                self.set_status(format!("{}: wrote {} rows to {}", command.name, result.row_count, path));
+            }
+        }
+        Ok(())
+    }
+
+    fn expand_command_template(&self, template: &str) -> Result<String> {
+        let mut sql = template.to_string();
+        if sql.contains("{schema}") || sql.contains("{table}") {
+            let (schema, table) = self
+                .ws
+                .selected_table
+                .as_ref()
+                .ok_or_else(|| anyhow!("This command needs a table selected in the browser"))?;
+            sql = sql
+                .replace("{schema}", &crate::db::quote_ident(schema))
+                .replace("{table}", &crate::db::quote_ident(table));
+        }
+        if sql.contains("{selection}") {
+            let value = self
+                .selected_cell_value()
+                .ok_or_else(|| anyhow!("This command needs a cell selected in the results grid"))?;
+            sql = sql.replace("{selection}", &value.map_or("NULL".to_string(), crate::db::quote_literal));
+        }
+        Ok(sql)
+    }
+
+    pub fn close_popup(&mut self) {
+        self.popup = None;
+    }
+
+    // The popup's rows as tab-separated lines, header first
+    pub fn copy_popup(&mut self) {
+        let Some(popup) = &self.popup else {
+            return;
+        };
+        let result = &popup.result;
+        let mut text = vec![result.columns.join("\t")];
+        // NULLs as empty fields
+        text.extend(
+            result
+                .rows
+                .iter()
+                .map(|row| row.iter().map(|cell| cell.as_deref().unwrap_or("")).collect::<Vec<_>>().join("\t")),
+        );
+        let count = result.rows.len();
+        match crate::clipboard::copy(&text.join("\n")) {
+            Ok(()) => self.set_status(format!("Copied {} row(s) to the clipboard", count)),
+            Err(e) => self.set_error(format!("{:#}", e)),
+        }
+    }
+
+    pub fn popup_scroll(&mut self, delta: isize) {
+        if let Some(popup) = &mut self.popup {
+            let last = popup.result.rows.len().saturating_sub(1);
+            popup.scroll = popup.scroll.saturating_add_signed(delta).min(last);
+        }
+    }
+}
+
+pub(super) fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest).to_string_lossy().into_owned(),
+        _ => path.to_string(),
+    }
+}
+
+// As COPY ... CSV writes it: NULL is an empty field, an empty string a
+// quoted one
+pub(super) fn to_csv(result: &QueryResult) -> String {
+    let field = |value: &str| {
+        if value.is_empty() || value.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    };
+    let line = |fields: Vec<String>| fields.join(",") + "\n";
+    let mut out = line(result.columns.iter().map(|name| field(name)).collect());
+    for row in &result.rows {
+        out.push_str(&line(row.iter().map(|cell| cell.as_deref().map(field).unwrap_or_default()).collect()));
+    }
+    out
+}
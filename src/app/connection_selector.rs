@@ -1,7 +1,8 @@
 use anyhow::Result;
-use crossterm::event::KeyCode;
 
 use crate::app::App;
+use crate::config::Environment;
+use crate::libpq;
 
 // Connection selector navigation and actions
 impl App {
@@ -24,29 +25,67 @@ impl App {
             self.database = profile.database.clone();
             self.user = profile.user.clone();
             self.password = String::new();
+            self.save_password = profile.save_password;
             self.mode = crate::app::AppMode::ConnectionEdit;
             self.connection_field = crate::app::ConnectionField::Password;
+
+            if profile.save_password && self.config.use_keyring {
+                match crate::secrets::load_password(profile) {
+                    Ok(Some(password)) => self.password = password,
+                    Ok(None) => {}
+                    Err(e) => self.set_error(format!("{:#}", e)),
+                }
+            }
         }
     }
 
     pub fn create_new_connection(&mut self) {
-        self.host = "localhost".to_string();
-        self.port = "5432".to_string();
-        self.database = "postgres".to_string();
-        self.user = "postgres".to_string();
+        // Same defaults libpq would pick
+        self.host = libpq::env_or("PGHOST", "localhost");
+        self.port = libpq::env_or("PGPORT", "5432");
+        self.user = libpq::env_or("PGUSER", "postgres");
+        self.database = libpq::env_or("PGDATABASE", &self.user);
         self.password = String::new();
+        self.save_password = false;
         self.mode = crate::app::AppMode::ConnectionEdit;
         self.connection_field = crate::app::ConnectionField::Host;
     }
 
-    pub fn delete_selected_profile(&mut self) -> Result<()> {
-        if self.selected_profile < self.config.connections.len() {
-            self.config.connections.remove(self.selected_profile);
-            if self.selected_profile > 0 {
-                self.selected_profile -= 1;
-            }
+    // Move the selected profile on to the next environment tag. The tag is
+    // independent of read-only: prod asks before writes, read-only refuses them.
+    pub fn tag_selected_profile(&mut self) -> Result<()> {
+        if let Some(profile) = self.config.connections.get_mut(self.selected_profile) {
+            profile.environment = Environment::cycle(profile.environment);
+            self.config.save()?;
+        }
+        Ok(())
+    }
+
+    // Takes effect from the profile's next connection
+    pub fn toggle_read_only(&mut self) -> Result<()> {
+        if let Some(profile) = self.config.connections.get_mut(self.selected_profile) {
+            profile.read_only = !profile.read_only;
             self.config.save()?;
         }
         Ok(())
     }
+
+    pub fn delete_selected_profile(&mut self) -> Result<()> {
+        let Some(profile) = self.config.connections.get(self.selected_profile) else {
+            return Ok(());
+        };
+        if profile.save_password && !self.config.keyring_shared(self.selected_profile) {
+            crate::secrets::delete_password(profile)?;
+        }
+        // The profile stays listed unless the config without it is saved
+        let profile = self.config.connections.remove(self.selected_profile);
+        if let Err(e) = self.config.save() {
+            self.config.connections.insert(self.selected_profile, profile);
+            return Err(e);
+        }
+        if self.selected_profile > 0 {
+            self.selected_profile -= 1;
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,97 @@
+use anyhow::Result;
+use crossterm::event::KeyCode;
+
+use crate::app::{App, AppMode, Workspace};
+use crate::db::Database;
+
+// Databases of the active connection's server to switch to
+pub struct DatabasePicker {
+    pub databases: Vec<Database>,
+    pub selected: usize,
+}
+
+// Switching the active connection to another database on the same server
+impl App {
+    pub async fn open_database_picker(&mut self) {
+        let Some(client) = self.ws.db.client() else {
+            return;
+        };
+        match crate::db::list_databases(client).await {
+            Ok(databases) => {
+                // Start on the database in use
+                let selected = databases.iter().position(|d| d.name == self.database).unwrap_or(0);
+                self.database_picker = Some(DatabasePicker { databases, selected });
+            }
+            Err(e) => self.set_error(format!("{:#}", e)),
+        }
+    }
+
+    pub fn close_database_picker(&mut self) {
+        self.database_picker = None;
+    }
+
+    pub fn handle_database_picker_input(&mut self, key: KeyCode) {
+        let Some(picker) = &mut self.database_picker else {
+            return;
+        };
+        let last = picker.databases.len().saturating_sub(1);
+        match key {
+            KeyCode::Up => picker.selected = picker.selected.saturating_sub(1),
+            KeyCode::Down => picker.selected = (picker.selected + 1).min(last),
+            KeyCode::Home => picker.selected = 0,
+            KeyCode::End => picker.selected = last,
+            _ => {}
+        }
+    }
+
+    pub async fn pick_database(&mut self) {
+        let Some(picker) = self.database_picker.take() else {
+            return;
+        };
+        let Some(database) = picker.databases.get(picker.selected).map(|d| d.name.clone()) else {
+            return;
+        };
+        if database == self.database {
+            return;
+        }
+        if let Err(e) = self.switch_database(&database).await {
+            self.set_error(format!("{:#}", e));
+        }
+    }
+
+    // Reconnect with the same host, port, user and password to `database`.
+    // The editor is kept; browser, details, results and completion start over.
+    async fn switch_database(&mut self, database: &str) -> Result<()> {
+        self.ws.db.switch_database(database).await?;
+        self.audit.record_disconnect(&self.ws.name);
+
+        let old = std::mem::replace(&mut self.ws, Workspace::new());
+        self.ws.name = match old.name.rsplit_once('/') {
+            Some((server, _)) => format!("{}/{}", server, database),
+            None => format!("{}/{}", old.name, database),
+        };
+        self.ws.hooks = old.hooks;
+        self.ws.profile = old.profile;
+        self.ws.environment = old.environment;
+        self.ws.environment_color = old.environment_color;
+        self.ws.read_only = old.read_only;
+        self.ws.db = old.db;
+        self.ws.query_input = old.query_input;
+        self.ws.file_path = old.file_path;
+        self.ws.saved_input = old.saved_input;
+        self.ws.query_cursor = old.query_cursor;
+        self.ws.query_scroll_offset = old.query_scroll_offset;
+        self.ws.lsp = old.lsp;
+        self.database = database.to_string();
+        self.audit.record_connect(&self.ws.name);
+
+        if let Some(client) = self.ws.db.client() {
+            self.ws.capabilities = Some(crate::db::check_capabilities(client).await);
+        }
+        self.mode = AppMode::Browser;
+        self.refresh_browser().await?;
+        self.start_catalog_load();
+        self.set_status(format!("Switched to database {}", database));
+        Ok(())
+    }
+}
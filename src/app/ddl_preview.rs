@@ -0,0 +1,63 @@
+use anyhow::{anyhow, bail, Result};
+
+use crate::app::rewrite::statement_at;
+use crate::app::script::split_statements;
+use crate::app::{App, ResultPopup};
+use crate::db::{ChangeKind, QueryResult, SchemaChange};
+
+// Statements that would end the preview transaction, keeping what ran so far
+const TRANSACTION_CONTROL: &[&str] = &[
+    "BEGIN", "START", "COMMIT", "END", "ROLLBACK", "ABORT", "SAVEPOINT", "RELEASE", "PREPARE",
+];
+
+// Dry run of DDL: the statement at the cursor runs in a transaction that is
+// rolled back, and the schema inside it is compared with the one before
+impl App {
+    pub async fn preview_ddl(&mut self) {
+        let sql = self.extract_current_query();
+        if sql.trim().is_empty() {
+            return;
+        }
+        match self.run_ddl_preview(&sql).await {
+            Ok(changes) if changes.is_empty() => {
+                self.set_status("The statement changes nothing in the schema (rolled back)".to_string())
+            }
+            Ok(changes) => {
+                self.popup = Some(ResultPopup {
+                    title: format!("DDL preview, rolled back: {} change(s)", changes.len()),
+                    result: changes_result(changes),
+                    scroll: 0,
+                });
+                self.clear_notifications();
+            }
+            Err(e) => self.set_error(format!("Preview failed, nothing was changed: {:#}", e)),
+        }
+    }
+
+    async fn run_ddl_preview(&self, sql: &str) -> Result<Vec<SchemaChange>> {
+        for statement in split_statements(sql) {
+            let first = statement_at(&statement, 0).into_iter().next();
+            if let Some(word) = first.filter(|tok| TRANSACTION_CONTROL.contains(&tok.upper.as_str())) {
+                bail!("{} cannot be previewed; it would end the preview transaction", word.upper);
+            }
+        }
+        let client = self.ws.db.client().ok_or_else(|| anyhow!("Not connected"))?;
+        crate::db::preview_ddl(client, sql).await
+    }
+}
+
+fn changes_result(changes: Vec<SchemaChange>) -> QueryResult {
+    let rows: Vec<Vec<String>> = changes
+        .into_iter()
+        .map(|change| {
+            let kind = match change.change {
+                ChangeKind::Added => "added",
+                ChangeKind::Removed => "removed",
+                ChangeKind::Changed => "changed",
+            };
+            vec![kind.to_string(), change.object, change.name, change.definition]
+        })
+        .collect();
+    let columns = vec!["Change".to_string(), "Object".to_string(), "Name".to_string(), "Definition".to_string()];
+    QueryResult::from_text(columns, rows)
+}
@@ -0,0 +1,227 @@
+use anyhow::Result;
+use crossterm::event::KeyCode;
+
+use crate::app::rewrite::{statement_at, Tok};
+use crate::app::script::split_statements;
+use crate::app::{App, GuardedRun};
+
+// Words that end the object list of a DROP or TRUNCATE
+const AFTER_OBJECTS: &[&str] = &["CASCADE", "RESTRICT", "RESTART", "CONTINUE", "ON"];
+
+// A statement that drops, empties or rewrites a whole table, held back until
+// it is confirmed
+pub struct DestructiveCheck {
+    // "DROP TABLE", "TRUNCATE", "DELETE without WHERE", ...
+    pub action: String,
+    // The objects it affects, as written in the statement
+    pub object: String,
+    pub statement: String,
+    // Further destructive statements in the same run
+    pub others: usize,
+    // Name to type before it runs (DROP and TRUNCATE); otherwise y confirms
+    pub expected: Option<String>,
+    pub input: String,
+    run: GuardedRun,
+}
+
+struct Finding {
+    action: String,
+    object: String,
+    expected: Option<String>,
+}
+
+impl App {
+    // True when `sql` contains a destructive statement and now waits for
+    // the user to confirm it. A confirmation also answers the prod write
+    // question that would follow.
+    pub(super) fn hold_destructive(&mut self, sql: &str, run: GuardedRun) -> bool {
+        if self.write_confirmed {
+            return false;
+        }
+        let mut findings = split_statements(sql)
+            .into_iter()
+            .filter_map(|statement| Some((inspect(&statement)?, statement)));
+        let Some((finding, statement)) = findings.next() else {
+            return false;
+        };
+        self.destructive = Some(DestructiveCheck {
+            action: finding.action,
+            object: finding.object,
+            statement: statement.trim().to_string(),
+            others: findings.count(),
+            expected: finding.expected,
+            input: String::new(),
+            run,
+        });
+        true
+    }
+
+    pub fn cancel_destructive(&mut self) {
+        self.destructive = None;
+        self.set_status("Nothing was run".to_string());
+    }
+
+    pub async fn handle_destructive_input(&mut self, key: KeyCode) -> Result<()> {
+        let Some(check) = &mut self.destructive else {
+            return Ok(());
+        };
+        let expected = check.expected.clone();
+        match (key, expected) {
+            (KeyCode::Esc, _) => self.cancel_destructive(),
+            (KeyCode::Char('n'), None) => self.cancel_destructive(),
+            (KeyCode::Enter | KeyCode::Char('y'), None) => return self.run_destructive().await,
+            (KeyCode::Enter, Some(expected)) => {
+                if check.input.trim() == expected {
+                    return self.run_destructive().await;
+                }
+                self.set_warning(format!("Type {} to go ahead, or Esc to cancel", expected));
+            }
+            (KeyCode::Char(c), Some(_)) => check.input.push(c),
+            (KeyCode::Backspace, Some(_)) => {
+                check.input.pop();
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    async fn run_destructive(&mut self) -> Result<()> {
+        let Some(check) = self.destructive.take() else {
+            return Ok(());
+        };
+        self.run_confirmed(check.run).await
+    }
+}
+
+// "DROP TABLE t": the first destructive statement in `sql`, for callers
+// that cannot stop to ask, like scripts
+pub(super) fn destructive_action(sql: &str) -> Option<String> {
+    split_statements(sql)
+        .iter()
+        .find_map(|statement| inspect(statement))
+        .map(|finding| format!("{} {}", finding.action, finding.object))
+}
+
+// What makes a statement destructive: DROP and TRUNCATE always, DELETE and
+// UPDATE when they have no WHERE clause of their own
+fn inspect(statement: &str) -> Option<Finding> {
+    let tokens = statement_at(statement, 0);
+    let first = tokens.first()?;
+    match first.upper.as_str() {
+        "DROP" => {
+            // DROP [MATERIALIZED] VIEW [IF EXISTS] names
+            let words = if matches!(tokens.get(1)?.upper.as_str(), "MATERIALIZED" | "FOREIGN" | "EVENT" | "ACCESS") {
+                2
+            } else {
+                1
+            };
+            let kind: Vec<&str> = tokens.get(1..=words)?.iter().map(|tok| tok.upper.as_str()).collect();
+            let object = object_list(statement, &tokens[words + 1..])?;
+            Some(Finding {
+                action: format!("DROP {}", kind.join(" ")),
+                expected: Some(unqualified(&object)),
+                object,
+            })
+        }
+        "TRUNCATE" => {
+            let names = &tokens[1..];
+            let object = object_list(statement, names)?;
+            Some(Finding {
+                action: "TRUNCATE".to_string(),
+                expected: Some(unqualified(&object)),
+                object,
+            })
+        }
+        _ => {
+            // The verb after any WITH queries, at the top level
+            let mut depth = 0i32;
+            let mut verb = None;
+            let mut has_where = false;
+            for (i, tok) in tokens.iter().enumerate() {
+                match tok.text.as_str() {
+                    "(" => depth += 1,
+                    ")" => depth -= 1,
+                    _ if depth > 0 => {}
+                    _ => match tok.upper.as_str() {
+                        "SELECT" | "INSERT" | "MERGE" | "VALUES" if verb.is_none() => return None,
+                        "DELETE" | "UPDATE" if verb.is_none() => verb = Some(i),
+                        "WHERE" if verb.is_some() => has_where = true,
+                        _ => {}
+                    },
+                }
+            }
+            let verb = verb?;
+            if has_where || (verb > 0 && first.upper != "WITH") {
+                return None;
+            }
+            // DELETE FROM [ONLY] name, UPDATE [ONLY] name
+            let name = tokens[verb + 1..]
+                .iter()
+                .find(|tok| !matches!(tok.upper.as_str(), "FROM" | "ONLY"))?;
+            let object = leading_name(&statement[name.start..]);
+            Some(Finding {
+                action: format!("{} without WHERE", tokens[verb].upper),
+                object,
+                expected: None,
+            })
+        }
+    }
+}
+
+// The possibly qualified and quoted name `text` starts with
+fn leading_name(text: &str) -> String {
+    let mut end = 0;
+    let mut quoted = false;
+    for (i, c) in text.char_indices() {
+        if c == '"' {
+            quoted = !quoted;
+        } else if !(quoted || c.is_alphanumeric() || c == '_' || c == '.' || c == '$') {
+            break;
+        }
+        end = i + c.len_utf8();
+    }
+    text[..end].to_string()
+}
+
+// "a.b, c" from the tokens after a DROP's object type or TRUNCATE, without
+// TABLE / ONLY / IF EXISTS / CONCURRENTLY and the trailing options
+fn object_list(statement: &str, tokens: &[Tok]) -> Option<String> {
+    let tokens: Vec<&Tok> = tokens
+        .iter()
+        .skip_while(|tok| matches!(tok.upper.as_str(), "TABLE" | "ONLY" | "IF" | "EXISTS" | "CONCURRENTLY"))
+        .take_while(|tok| !AFTER_OBJECTS.contains(&tok.upper.as_str()))
+        .collect();
+    let (first, last) = (tokens.first()?, tokens.last()?);
+    Some(statement[first.start..last.end].trim().to_string())
+}
+
+// The name to type for "schema.table" or "\"My Table\"": the last part,
+// unquoted. With several objects, the first one's.
+fn unqualified(objects: &str) -> String {
+    let first = split_top_level(objects, ',').into_iter().next().unwrap_or_default();
+    let last = split_top_level(first.trim(), '.').pop().unwrap_or_default();
+    // Function signatures: only the name before the argument list
+    let last = split_top_level(&last, '(').into_iter().next().unwrap_or_default();
+    let last = last.trim();
+    match last.strip_prefix('"').and_then(|rest| rest.strip_suffix('"')) {
+        Some(quoted) => quoted.replace("\"\"", "\""),
+        None => last.to_string(),
+    }
+}
+
+// `text` split at `separator` outside double quotes
+fn split_top_level(text: &str, separator: char) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut quoted = false;
+    for c in text.chars() {
+        if c == '"' {
+            quoted = !quoted;
+        }
+        if c == separator && !quoted {
+            parts.push(String::new());
+        } else if let Some(part) = parts.last_mut() {
+            part.push(c);
+        }
+    }
+    parts
+}
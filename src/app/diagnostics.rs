@@ -0,0 +1,76 @@
+use crate::app::{App, ResultPopup, Workspace};
+use crate::db::QueryResult;
+
+// Lint warnings for the query editor, from the built-in lint pass and the
+// language server when one is running
+impl App {
+    // Called while the user is idle; only re-lints when something changed
+    pub async fn lint_editor(&mut self) {
+        if let Some(lsp) = &mut self.ws.lsp {
+            if lsp.sync(&self.ws.query_input).await.is_err() && !lsp.is_running() {
+                self.ws.lsp = None;
+                self.set_warning("Language server exited; using built-in completion".to_string());
+            }
+        }
+        self.refresh_lint(self.config.lint, false);
+    }
+
+    fn refresh_lint(&mut self, builtin: bool, force: bool) {
+        let published = self.ws.lsp.as_mut().and_then(|lsp| lsp.take_diagnostics());
+        let text_changed = self.ws.linted_input != self.ws.query_input;
+        if !text_changed && !force && published.is_none() {
+            return;
+        }
+        match published {
+            Some(diagnostics) => self.ws.lsp_diagnostics = diagnostics,
+            // Server diagnostics for older text would point at the wrong tokens
+            None if text_changed => self.ws.lsp_diagnostics.clear(),
+            None => {}
+        }
+
+        let mut diagnostics = if builtin {
+            crate::lint::lint(&self.ws.query_input)
+        } else {
+            Vec::new()
+        };
+        diagnostics.extend(self.ws.lsp_diagnostics.iter().cloned());
+        diagnostics.sort_by_key(|d| d.start);
+        self.ws.lint_diagnostics = diagnostics;
+        self.ws.linted_input = self.ws.query_input.clone();
+    }
+
+    // The list is available on request even with idle linting turned off
+    pub fn show_lint_popup(&mut self) {
+        self.refresh_lint(true, true);
+        let text = &self.ws.query_input;
+        let rows: Vec<Vec<String>> = self
+            .ws
+            .current_diagnostics()
+            .iter()
+            .map(|d| {
+                let (line, col) = crate::lint::line_col(text, d.start);
+                vec![line.to_string(), col.to_string(), d.message.clone()]
+            })
+            .collect();
+        if rows.is_empty() {
+            self.set_status("No lint warnings".to_string());
+            return;
+        }
+        self.popup = Some(ResultPopup {
+            title: "Lint warnings".to_string(),
+            result: QueryResult::from_text(vec!["Line".to_string(), "Col".to_string(), "Warning".to_string()], rows),
+            scroll: 0,
+        });
+    }
+}
+
+impl Workspace {
+    // Warnings that still match the editor text; empty while typing
+    pub fn current_diagnostics(&self) -> &[crate::lint::Diagnostic] {
+        if self.linted_input == self.query_input {
+            &self.lint_diagnostics
+        } else {
+            &[]
+        }
+    }
+}
@@ -0,0 +1,419 @@
+use std::collections::{HashMap, HashSet};
+
+use crossterm::event::KeyCode;
+
+use crate::app::{App, AppMode, BrowserItem};
+use crate::db::{ErLink, ErTable};
+
+// Columns moved by Left/Right and lines moved by PgUp/PgDn
+const STEP_X: u16 = 4;
+const PAGE: u16 = 20;
+// Space between boxes of a layer
+const BOX_GAP: usize = 3;
+// Width the boxes of tables without foreign keys wrap at
+const WRAP_WIDTH: usize = 120;
+
+// Line directions of a cell, combined when lines meet or cross
+const UP: u8 = 1;
+const DOWN: u8 = 2;
+const LEFT: u8 = 4;
+const RIGHT: u8 = 8;
+
+// Relationship overview of one schema, drawn once and scrolled around
+pub struct ErDiagram {
+    pub schema: String,
+    pub lines: Vec<String>,
+    pub tables: usize,
+    pub links: usize,
+    pub scroll_x: u16,
+    pub scroll_y: u16,
+    // Screen to go back to
+    return_mode: AppMode,
+}
+
+impl App {
+    // Schema of the browser item under the cursor, or of the selected table
+    pub(super) fn browser_schema(&self) -> Option<String> {
+        match self.ws.browser_items.get(self.ws.browser_selected) {
+            Some(BrowserItem::Schema(schema))
+            | Some(BrowserItem::Folder(schema, _))
+            | Some(BrowserItem::Table(schema, _))
+            | Some(BrowserItem::View(schema, _))
+            | Some(BrowserItem::MaterializedView(schema, _))
+            | Some(BrowserItem::Function(schema, _))
+            | Some(BrowserItem::Sequence(schema, _)) => Some(schema.clone()),
+            Some(BrowserItem::Database(_)) | Some(BrowserItem::Favorite(_)) | None => self.ws.selected_table.as_ref().map(|(schema, _)| schema.clone()),
+        }
+    }
+
+    pub async fn open_diagram(&mut self) {
+        let Some(schema) = self.browser_schema() else {
+            return;
+        };
+        let Some(client) = self.ws.db.client() else {
+            return;
+        };
+        match crate::db::schema_relationships(client, &schema).await {
+            Ok((tables, _)) if tables.is_empty() => self.set_status(format!("{} has no tables", schema)),
+            Ok((tables, links)) => {
+                self.diagram = Some(ErDiagram {
+                    lines: draw_er_diagram(&tables, &links),
+                    tables: tables.len(),
+                    links: links.len(),
+                    schema,
+                    scroll_x: 0,
+                    scroll_y: 0,
+                    return_mode: self.mode,
+                });
+                self.mode = AppMode::Diagram;
+            }
+            Err(e) => self.set_error(format!("{:#}", e)),
+        }
+    }
+
+    pub fn close_diagram(&mut self) {
+        if let Some(diagram) = self.diagram.take() {
+            self.mode = diagram.return_mode;
+        }
+    }
+
+    pub fn handle_diagram_input(&mut self, key: KeyCode) {
+        let Some(diagram) = &mut self.diagram else {
+            return;
+        };
+        let bottom = diagram.lines.len().saturating_sub(1) as u16;
+        let right = diagram.lines.iter().map(|l| l.chars().count()).max().unwrap_or(0) as u16;
+        match key {
+            KeyCode::Esc | KeyCode::Char('q') => self.close_diagram(),
+            KeyCode::Up => diagram.scroll_y = diagram.scroll_y.saturating_sub(1),
+            KeyCode::Down => diagram.scroll_y = (diagram.scroll_y + 1).min(bottom),
+            KeyCode::Left => diagram.scroll_x = diagram.scroll_x.saturating_sub(STEP_X),
+            KeyCode::Right => diagram.scroll_x = (diagram.scroll_x + STEP_X).min(right.saturating_sub(1)),
+            KeyCode::PageUp => diagram.scroll_y = diagram.scroll_y.saturating_sub(PAGE),
+            KeyCode::PageDown => diagram.scroll_y = (diagram.scroll_y + PAGE).min(bottom),
+            KeyCode::Home => {
+                diagram.scroll_x = 0;
+                diagram.scroll_y = 0;
+            }
+            KeyCode::End => diagram.scroll_y = bottom,
+            _ => {}
+        }
+    }
+}
+
+// A table's box: its name, a rule and one line per primary key column
+struct TableBox<'a> {
+    name: &'a str,
+    primary_key: &'a [String],
+    width: usize,
+    height: usize,
+}
+
+impl<'a> TableBox<'a> {
+    fn new(table: &'a ErTable) -> Self {
+        let text = table
+            .primary_key
+            .iter()
+            .map(|column| column.chars().count() + 3)
+            .chain([table.name.chars().count()])
+            .max()
+            .unwrap_or(0);
+        let height = if table.primary_key.is_empty() { 3 } else { 4 + table.primary_key.len() };
+        TableBox {
+            name: &table.name,
+            primary_key: &table.primary_key,
+            width: text + 4,
+            height,
+        }
+    }
+
+    fn draw(&self, grid: &mut Grid, x: usize, y: usize) {
+        let inner = self.width - 2;
+        grid.text(x, y, &format!("┌{}┐", "─".repeat(inner)));
+        grid.text(x, y + 1, &format!("│ {:<w$} │", self.name, w = inner - 2));
+        if !self.primary_key.is_empty() {
+            grid.text(x, y + 2, &format!("├{}┤", "─".repeat(inner)));
+            for (i, column) in self.primary_key.iter().enumerate() {
+                grid.text(x, y + 3 + i, &format!("│ PK {:<w$} │", column, w = inner - 5));
+            }
+        }
+        grid.text(x, y + self.height - 1, &format!("└{}┘", "─".repeat(inner)));
+    }
+
+    // Column of the `index`th of `count` lines leaving a border, spread
+    // over its width
+    fn anchor(&self, x: usize, index: usize, count: usize) -> usize {
+        x + 1 + (index + 1) * (self.width - 2) / (count + 1)
+    }
+}
+
+// Boxes as text, and the foreign key lines between them as directions that
+// become box drawing characters once every line is in
+struct Grid {
+    text: Vec<Vec<char>>,
+    lines: Vec<Vec<u8>>,
+}
+
+impl Grid {
+    fn new(width: usize, height: usize) -> Self {
+        Grid {
+            text: vec![vec![' '; width]; height],
+            lines: vec![vec![0; width]; height],
+        }
+    }
+
+    fn text(&mut self, x: usize, y: usize, s: &str) {
+        for (i, c) in s.chars().enumerate() {
+            self.text[y][x + i] = c;
+        }
+    }
+
+    // Line through `points`, each segment horizontal or vertical
+    fn polyline(&mut self, points: &[(usize, usize)]) {
+        for pair in points.windows(2) {
+            let ((x1, y1), (x2, y2)) = (pair[0], pair[1]);
+            if y1 == y2 {
+                let (from, to) = (x1.min(x2), x1.max(x2));
+                for x in from..=to {
+                    self.lines[y1][x] |= if x > from { LEFT } else { 0 } | if x < to { RIGHT } else { 0 };
+                }
+            } else {
+                let (from, to) = (y1.min(y2), y1.max(y2));
+                for y in from..=to {
+                    self.lines[y][x1] |= if y > from { UP } else { 0 } | if y < to { DOWN } else { 0 };
+                }
+            }
+        }
+    }
+
+    fn into_lines(self) -> Vec<String> {
+        self.text
+            .into_iter()
+            .zip(self.lines)
+            .map(|(text, lines)| {
+                let line: String = text
+                    .into_iter()
+                    .zip(lines)
+                    .map(|(c, directions)| if c == ' ' { line_char(directions) } else { c })
+                    .collect();
+                line.trim_end().to_string()
+            })
+            .collect()
+    }
+}
+
+fn line_char(directions: u8) -> char {
+    match directions {
+        0 => ' ',
+        d if d == UP | DOWN | LEFT | RIGHT => '┼',
+        d if d == UP | DOWN | RIGHT => '├',
+        d if d == UP | DOWN | LEFT => '┤',
+        d if d == LEFT | RIGHT | DOWN => '┬',
+        d if d == LEFT | RIGHT | UP => '┴',
+        d if d == DOWN | RIGHT => '┌',
+        d if d == DOWN | LEFT => '┐',
+        d if d == UP | RIGHT => '└',
+        d if d == UP | LEFT => '┘',
+        d if d & (UP | DOWN) != 0 => '│',
+        _ => '─',
+    }
+}
+
+// A foreign key drawn as a line: from the referenced table's bottom border
+// down through the channel below it, and for tables more than one layer
+// apart along a gutter right of the boxes, into the referencing table's top
+struct Edge {
+    child: usize,
+    parent: usize,
+    leave_track: usize,
+    // Track in the channel above the child's layer, and gutter column
+    detour: Option<(usize, usize)>,
+}
+
+/// Unicode drawing of `tables` as boxes with their primary key columns,
+/// referenced tables above the ones referencing them, followed by the tables
+/// no foreign key touches and a list of every foreign key.
+pub fn draw_er_diagram(tables: &[ErTable], links: &[ErLink]) -> Vec<String> {
+    let index: HashMap<&str, usize> = tables.iter().enumerate().map(|(i, t)| (t.name.as_str(), i)).collect();
+    let boxes: Vec<TableBox> = tables.iter().map(TableBox::new).collect();
+    let pairs: Vec<(usize, usize)> = links
+        .iter()
+        .filter_map(|link| Some((*index.get(link.table.as_str())?, *index.get(link.referenced_table.as_str())?)))
+        .filter(|(child, parent)| child != parent)
+        .collect();
+    let linked: HashSet<usize> = pairs.iter().flat_map(|&(child, parent)| [child, parent]).collect();
+
+    // Longest path layering: a table sits one layer below the lowest table it
+    // references. Rounds are capped so that cycles end; the foreign keys
+    // closing a cycle point down and are only listed.
+    let mut layer = vec![0usize; tables.len()];
+    for _ in 0..tables.len() {
+        let mut changed = false;
+        for &(child, parent) in &pairs {
+            if layer[child] < layer[parent] + 1 {
+                layer[child] = layer[parent] + 1;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    let mut used: Vec<usize> = linked.iter().map(|&t| layer[t]).collect();
+    used.sort_unstable();
+    used.dedup();
+    for &t in &linked {
+        layer[t] = used.binary_search(&layer[t]).unwrap_or(0);
+    }
+
+    // Layer by layer, tables ordered under the middle of the tables they
+    // reference, so that lines stay short
+    let mut layers: Vec<Vec<usize>> = vec![Vec::new(); used.len()];
+    let mut x = vec![0usize; tables.len()];
+    for (l, members) in layers.iter_mut().enumerate() {
+        let mut placed: Vec<(f64, usize)> = linked
+            .iter()
+            .filter(|&&t| layer[t] == l)
+            .map(|&t| {
+                let centers: Vec<f64> = pairs
+                    .iter()
+                    .filter(|&&(child, parent)| child == t && layer[parent] < l)
+                    .map(|&(_, parent)| (x[parent] + boxes[parent].width / 2) as f64)
+                    .collect();
+                let center = if centers.is_empty() { 0.0 } else { centers.iter().sum::<f64>() / centers.len() as f64 };
+                (center, t)
+            })
+            .collect();
+        placed.sort_by(|a, b| a.0.total_cmp(&b.0).then_with(|| tables[a.1].name.cmp(&tables[b.1].name)));
+        let mut next = 0;
+        for &(_, t) in &placed {
+            x[t] = next;
+            next += boxes[t].width + BOX_GAP;
+        }
+        *members = placed.into_iter().map(|(_, t)| t).collect();
+    }
+    let layers_width = layers
+        .iter()
+        .filter_map(|members| members.last().map(|&t| x[t] + boxes[t].width))
+        .max()
+        .unwrap_or(0);
+
+    // Foreign keys pointing up get lines; each takes its own track in the
+    // channels it runs along, in order of where they start
+    let mut drawn: Vec<(usize, usize)> = pairs.iter().copied().filter(|&(c, p)| layer[p] < layer[c]).collect();
+    drawn.sort_unstable();
+    drawn.dedup();
+    drawn.sort_by_key(|&(child, parent)| (layer[parent], x[parent], x[child]));
+    let mut tracks = vec![0usize; layers.len()];
+    let mut gutters = 0;
+    let edges: Vec<Edge> = drawn
+        .iter()
+        .map(|&(child, parent)| {
+            let leave_track = tracks[layer[parent]];
+            tracks[layer[parent]] += 1;
+            let detour = (layer[child] > layer[parent] + 1).then(|| {
+                let above = layer[child] - 1;
+                tracks[above] += 1;
+                gutters += 1;
+                (tracks[above] - 1, gutters - 1)
+            });
+            Edge { child, parent, leave_track, detour }
+        })
+        .collect();
+
+    let mut top = Vec::with_capacity(layers.len());
+    let mut channel = Vec::with_capacity(layers.len());
+    let mut y = 0;
+    for (l, members) in layers.iter().enumerate() {
+        top.push(y);
+        y += members.iter().map(|&t| boxes[t].height).max().unwrap_or(0);
+        channel.push(y);
+        if l + 1 < layers.len() {
+            y += tracks[l] + 2;
+        }
+    }
+
+    // Tables no foreign key touches, wrapped below the layers
+    let gutter_x = layers_width + 2;
+    let wrap = WRAP_WIDTH.max(layers_width);
+    let mut loose = Vec::new();
+    let (mut lx, mut row_height, mut loose_width) = (0, 0, 0);
+    if !linked.is_empty() {
+        y += 1;
+    }
+    for t in (0..tables.len()).filter(|t| !linked.contains(t)) {
+        if lx > 0 && lx + boxes[t].width > wrap {
+            y += row_height + 1;
+            lx = 0;
+            row_height = 0;
+        }
+        loose.push((t, lx, y));
+        loose_width = loose_width.max(lx + boxes[t].width);
+        lx += boxes[t].width + BOX_GAP;
+        row_height = row_height.max(boxes[t].height);
+    }
+    y += row_height;
+
+    let width = loose_width.max(gutter_x + 2 * gutters) + 1;
+    let mut grid = Grid::new(width, y);
+    for (l, members) in layers.iter().enumerate() {
+        for &t in members {
+            boxes[t].draw(&mut grid, x[t], top[l]);
+        }
+    }
+    for &(t, bx, by) in &loose {
+        boxes[t].draw(&mut grid, bx, by);
+    }
+
+    // Anchors: every line leaving a parent's bottom or entering a child's top
+    // gets its own column on that border
+    let (mut leaving, mut entering) = (vec![0usize; tables.len()], vec![0usize; tables.len()]);
+    for edge in &edges {
+        leaving[edge.parent] += 1;
+        entering[edge.child] += 1;
+    }
+    let mut seen_parent = vec![0usize; tables.len()];
+    let mut seen_child = vec![0usize; tables.len()];
+    for edge in &edges {
+        let (p, c) = (edge.parent, edge.child);
+        let px = boxes[p].anchor(x[p], seen_parent[p], leaving[p]);
+        let cx = boxes[c].anchor(x[c], seen_child[c], entering[c]);
+        seen_parent[p] += 1;
+        seen_child[c] += 1;
+        let bottom = top[layer[p]] + boxes[p].height - 1;
+        let child_top = top[layer[c]];
+        let leave = channel[layer[p]] + 1 + edge.leave_track;
+        let points = match edge.detour {
+            None => vec![(px, bottom + 1), (px, leave), (cx, leave), (cx, child_top - 1)],
+            Some((track, gutter)) => {
+                let arrive = channel[layer[c] - 1] + 1 + track;
+                let gx = gutter_x + 2 * gutter;
+                vec![(px, bottom + 1), (px, leave), (gx, leave), (gx, arrive), (cx, arrive), (cx, child_top - 1)]
+            }
+        };
+        grid.polyline(&points);
+        // The line joins the borders it starts and ends on
+        grid.lines[bottom + 1][px] |= UP;
+        grid.lines[child_top - 1][cx] |= DOWN;
+        grid.text[bottom][px] = '┬';
+        grid.text[child_top][cx] = '┴';
+    }
+
+    let mut lines = grid.into_lines();
+    if !links.is_empty() {
+        lines.push(String::new());
+        lines.push("Foreign keys (referencing → referenced):".to_string());
+        for link in links {
+            let note = match (index.get(link.table.as_str()), index.get(link.referenced_table.as_str())) {
+                (Some(c), Some(p)) if c == p => "  (self reference)",
+                (Some(&c), Some(&p)) if layer[p] >= layer[c] => "  (closes a cycle, not drawn)",
+                _ => "",
+            };
+            lines.push(format!(
+                "  {}({}) → {}({}){}",
+                link.table, link.columns, link.referenced_table, link.referenced_columns, note
+            ));
+        }
+    }
+    lines
+}
@@ -0,0 +1,122 @@
+use anyhow::{anyhow, bail, Result};
+use std::time::Instant;
+
+use crate::app::{hooks, App, PendingStatement, PromptAction};
+use crate::db::{qualified_name, quote_ident, quote_literal};
+
+// Inline cell editing in the results grid. Only results whose table columns
+// all come from one table with its full primary key selected can be edited.
+impl App {
+    pub fn begin_cell_edit(&mut self) {
+        match self.editable_cell() {
+            Ok((column, value)) => {
+                self.open_prompt(&format!("Edit {} (NULL for null)", column), &value, PromptAction::EditCell);
+            }
+            Err(e) => self.set_error(format!("{:#}", e)),
+        }
+    }
+
+    // Table column name and current value of the cell under the cursor
+    fn editable_cell(&self) -> Result<(String, String)> {
+        if self.ws.read_only {
+            bail!("{} is read-only: cells cannot be edited", self.ws.name);
+        }
+        let result = self.ws.query_result.as_ref().ok_or_else(|| anyhow!("No results to edit"))?;
+        let source = result
+            .source
+            .as_ref()
+            .ok_or_else(|| anyhow!("Results do not come from a single table"))?;
+        if !source.is_editable() {
+            bail!("Results must include the primary key of {} to be edited", source.table);
+        }
+        let column = source
+            .column_names
+            .get(self.ws.result_selected_col)
+            .cloned()
+            .flatten()
+            .ok_or_else(|| anyhow!("This column is computed and cannot be edited"))?;
+        // NULL is what the edit prompt takes for NULL
+        let value = self.selected_cell_value().flatten().unwrap_or("NULL").to_string();
+        Ok((column, value))
+    }
+
+    // Build the UPDATE for the edited cell and ask for confirmation
+    pub fn preview_cell_update(&mut self, new_value: &str) -> Result<()> {
+        let (column, _) = self.editable_cell()?;
+        let result = self.ws.query_result.as_ref().ok_or_else(|| anyhow!("No results to edit"))?;
+        let source = result.source.as_ref().ok_or_else(|| anyhow!("Results do not come from a single table"))?;
+        let row = self
+            .selected_result_row()
+            .and_then(|i| result.rows.get(i))
+            .ok_or_else(|| anyhow!("No row selected"))?;
+
+        let mut conditions = Vec::new();
+        for &pk in &source.pk_columns {
+            let name = source.column_names[pk].as_deref().unwrap_or_default();
+            let Some(value) = &row[pk] else {
+                bail!("Cannot identify the row: primary key column {} is NULL", name);
+            };
+            conditions.push(format!("{} = {}", quote_ident(name), quote_literal(value)));
+        }
+
+        let value = if new_value == "NULL" {
+            "NULL".to_string()
+        } else {
+            quote_literal(new_value)
+        };
+        let sql = format!(
+            "UPDATE {}\nSET {} = {}\nWHERE {};",
+            qualified_name(&source.schema, &source.table),
+            quote_ident(&column),
+            value,
+            conditions.join("\n  AND "),
+        );
+
+        self.pending = Some(PendingStatement {
+            title: format!("Update {}.{}", source.table, column),
+            sql,
+        });
+        Ok(())
+    }
+
+    pub fn cancel_pending(&mut self) {
+        self.pending = None;
+    }
+
+    // Run the confirmed statement, then re-run the query behind the grid
+    pub async fn execute_pending(&mut self) -> Result<()> {
+        let Some(pending) = self.pending.take() else {
+            return Ok(());
+        };
+        self.refuse_on_read_only(&pending.sql)?;
+        let client = self.ws.db.client().ok_or_else(|| anyhow!("Not connected"))?;
+
+        hooks::run_pre_query(&self.ws.db, &self.ws.hooks, &pending.sql).await?;
+        let started = Instant::now();
+        let affected = crate::db::execute_statement(client, &pending.sql).await;
+        let rows = affected.as_ref().ok().map(|&n| n as usize);
+        self.stats.record_query(&pending.sql, rows);
+        let outcome = affected.as_ref().map(|&n| n as usize).map_err(|e| format!("{:#}", e));
+        self.audit.record_statement(&self.ws.name, &pending.sql, started.elapsed(), outcome);
+        hooks::run_post_query(&self.ws.db, &self.ws.hooks, &pending.sql, rows).await?;
+        let affected = affected?;
+
+        if let Some(sql) = self.ws.result_sql.clone() {
+            // As many rows as were loaded before, so the cursor stays put
+            let loaded = self.ws.query_result.as_ref().map_or(0, |r| r.rows.len());
+            let params = &self.ws.result_params;
+            let (refreshed, capped, _) =
+                super::results::load_result(&self.ws.db, &sql, params, self.config.max_result_rows, loaded).await?;
+            let columns = self.ws.query_result.as_ref().map(|r| r.columns.clone()).unwrap_or_default();
+            self.ws.query_result = Some(refreshed);
+            self.ws.result_capped = capped;
+            self.keep_result_layout(&columns);
+            // Keep the cursor where it was, as far as the new rows allow
+            let last = self.displayed_row_count().saturating_sub(1);
+            self.ws.result_selected_row = self.ws.result_selected_row.min(last);
+        }
+
+        self.set_status(format!("{}: {} row(s) affected", pending.title, affected));
+        Ok(())
+    }
+}
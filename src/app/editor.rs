@@ -0,0 +1,399 @@
+use std::ops::Range;
+
+use crossterm::event::KeyCode;
+
+use crate::app::{App, Workspace};
+
+// The query editor: cursor movement and editing of `query_input`, with
+// optional vim-style modal keys. `query_cursor` is a byte offset that always
+// sits on a char boundary.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VimMode {
+    #[default]
+    Normal,
+    Insert,
+    Visual,
+    VisualLine,
+}
+
+impl VimMode {
+    pub fn label(self) -> &'static str {
+        match self {
+            VimMode::Normal => "NORMAL",
+            VimMode::Insert => "INSERT",
+            VimMode::Visual => "VISUAL",
+            VimMode::VisualLine => "VISUAL LINE",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct VimState {
+    pub mode: VimMode,
+    // First key of a two-key command: `d`, `y`, `c` or `g`
+    pending: Option<char>,
+    // Where the visual selection started
+    anchor: usize,
+    // Last yanked or deleted text, and whether it was whole lines
+    register: String,
+    linewise: bool,
+}
+
+impl App {
+    // Vim mode of the editor; None when vim keys are turned off
+    pub fn vim_mode(&self) -> Option<VimMode> {
+        self.config.vim_mode.then_some(self.ws.vim.mode)
+    }
+
+    // Typed characters go into the text (and drive autocomplete)
+    pub fn editor_inserting(&self) -> bool {
+        matches!(self.vim_mode(), None | Some(VimMode::Insert))
+    }
+
+    // Byte range of the visual selection, when there is one
+    pub fn editor_selection(&self) -> Option<Range<usize>> {
+        let ws = &self.ws;
+        // The text may have been replaced under the selection
+        let mut anchor = ws.vim.anchor.min(ws.query_input.len());
+        while !ws.query_input.is_char_boundary(anchor) {
+            anchor -= 1;
+        }
+        let (from, to) = (anchor.min(ws.query_cursor), anchor.max(ws.query_cursor));
+        match self.vim_mode()? {
+            VimMode::Visual => Some(from..ws.next_char(to)),
+            VimMode::VisualLine => Some(ws.line_start(from)..ws.line_range(to).end),
+            _ => None,
+        }
+    }
+
+    pub fn editor_key(&mut self, key: KeyCode) {
+        // Consecutive Up/Down keep aiming for the column they started from,
+        // across shorter lines in between
+        let vertical = matches!(key, KeyCode::Up | KeyCode::Down)
+            || (!self.editor_inserting() && matches!(key, KeyCode::Char('j' | 'k')));
+        let goal = self.ws.goal_column.take();
+        if vertical {
+            self.ws.goal_column = Some(goal.unwrap_or_else(|| self.ws.column(self.ws.query_cursor)));
+        }
+        if self.editor_inserting() {
+            self.insert_key(key);
+        } else {
+            self.vim_key(key);
+        }
+    }
+
+    // Ctrl+Left/Right: start of the previous or next word
+    pub fn editor_word_jump(&mut self, forward: bool) {
+        let motion = if forward { KeyCode::Char('w') } else { KeyCode::Char('b') };
+        if let Some(target) = self.ws.motion(motion) {
+            self.ws.query_cursor = target;
+        }
+        self.ws.goal_column = None;
+        self.hide_autocomplete();
+    }
+
+    fn insert_key(&mut self, key: KeyCode) {
+        let ws = &mut self.ws;
+        match key {
+            KeyCode::Char(c) => ws.insert_text(c.encode_utf8(&mut [0; 4])),
+            KeyCode::Enter => ws.insert_text("\n"),
+            KeyCode::Backspace if ws.query_cursor > 0 => {
+                let start = ws.prev_char(ws.query_cursor);
+                ws.query_input.replace_range(start..ws.query_cursor, "");
+                ws.query_cursor = start;
+            }
+            KeyCode::Delete if ws.query_cursor < ws.query_input.len() => {
+                let end = ws.next_char(ws.query_cursor);
+                ws.query_input.replace_range(ws.query_cursor..end, "");
+            }
+            KeyCode::Esc if self.config.vim_mode => {
+                ws.vim.mode = VimMode::Normal;
+                // Vim steps back onto the last inserted character
+                if ws.query_cursor > ws.line_start(ws.query_cursor) {
+                    ws.query_cursor = ws.prev_char(ws.query_cursor);
+                }
+            }
+            _ => {
+                if let Some(target) = ws.motion(key) {
+                    ws.query_cursor = target;
+                }
+            }
+        }
+    }
+
+    fn vim_key(&mut self, key: KeyCode) {
+        let selection = self.editor_selection();
+        let ws = &mut self.ws;
+        let pos = ws.query_cursor;
+
+        if let Some(operator) = ws.vim.pending.take() {
+            let range = match (operator, key) {
+                ('g', KeyCode::Char('g')) => {
+                    ws.query_cursor = 0;
+                    return;
+                }
+                // dd, yy, cc work on whole lines
+                ('d', KeyCode::Char('d')) | ('y', KeyCode::Char('y')) => {
+                    let range = ws.line_range(pos);
+                    ws.yank(range.clone(), true);
+                    if operator == 'd' {
+                        ws.delete_lines(range);
+                    }
+                    return;
+                }
+                ('c', KeyCode::Char('c')) => ws.line_start(pos)..ws.line_end(pos),
+                ('d' | 'y' | 'c', KeyCode::Char('e')) => pos..ws.motion(key).map_or(pos, |end| ws.next_char(end)),
+                ('d' | 'y' | 'c', _) => match ws.motion(key) {
+                    Some(target) => pos.min(target)..pos.max(target),
+                    None => return,
+                },
+                _ => return,
+            };
+            ws.yank(range.clone(), false);
+            match operator {
+                'y' => ws.query_cursor = range.start,
+                'c' => {
+                    ws.delete(range);
+                    ws.vim.mode = VimMode::Insert;
+                }
+                _ => ws.delete(range),
+            }
+            return;
+        }
+
+        if let Some(range) = selection {
+            match key {
+                KeyCode::Char('d' | 'x') => {
+                    ws.yank(range.clone(), ws.vim.mode == VimMode::VisualLine);
+                    ws.delete(range);
+                    ws.vim.mode = VimMode::Normal;
+                    return;
+                }
+                KeyCode::Char('y') => {
+                    ws.yank(range.clone(), ws.vim.mode == VimMode::VisualLine);
+                    ws.query_cursor = range.start;
+                    ws.vim.mode = VimMode::Normal;
+                    return;
+                }
+                _ => {}
+            }
+        }
+
+        match key {
+            KeyCode::Esc => ws.vim.mode = VimMode::Normal,
+            KeyCode::Char('i') => ws.vim.mode = VimMode::Insert,
+            KeyCode::Char('a') => {
+                if pos < ws.line_end(pos) {
+                    ws.query_cursor = ws.next_char(pos);
+                }
+                ws.vim.mode = VimMode::Insert;
+            }
+            KeyCode::Char('I') => {
+                ws.query_cursor = ws.first_non_blank(pos);
+                ws.vim.mode = VimMode::Insert;
+            }
+            KeyCode::Char('A') => {
+                ws.query_cursor = ws.line_end(pos);
+                ws.vim.mode = VimMode::Insert;
+            }
+            KeyCode::Char('o') => {
+                ws.query_cursor = ws.line_end(pos);
+                ws.insert_text("\n");
+                ws.vim.mode = VimMode::Insert;
+            }
+            KeyCode::Char('O') => {
+                ws.query_cursor = ws.line_start(pos);
+                ws.insert_text("\n");
+                ws.query_cursor -= 1;
+                ws.vim.mode = VimMode::Insert;
+            }
+            KeyCode::Char('x') if pos < ws.line_end(pos) => {
+                let range = pos..ws.next_char(pos);
+                ws.yank(range.clone(), false);
+                ws.delete(range);
+            }
+            KeyCode::Char('p') => ws.put(true),
+            KeyCode::Char('P') => ws.put(false),
+            KeyCode::Char('v') => ws.toggle_visual(VimMode::Visual),
+            KeyCode::Char('V') => ws.toggle_visual(VimMode::VisualLine),
+            KeyCode::Char(c @ ('d' | 'y' | 'c' | 'g')) => ws.vim.pending = Some(c),
+            _ => {
+                if let Some(target) = ws.motion(key) {
+                    ws.query_cursor = target;
+                }
+            }
+        }
+    }
+}
+
+// Text primitives. Vim's h/l stay on the line; the arrow keys in insert mode
+// use the same motions.
+impl Workspace {
+    pub fn insert_text(&mut self, text: &str) {
+        self.query_input.insert_str(self.query_cursor, text);
+        self.query_cursor += text.len();
+    }
+
+    fn prev_char(&self, pos: usize) -> usize {
+        self.query_input[..pos].char_indices().next_back().map_or(0, |(i, _)| i)
+    }
+
+    fn next_char(&self, pos: usize) -> usize {
+        self.query_input[pos..].chars().next().map_or(pos, |c| pos + c.len_utf8())
+    }
+
+    pub(super) fn line_start(&self, pos: usize) -> usize {
+        self.query_input[..pos].rfind('\n').map_or(0, |i| i + 1)
+    }
+
+    // Position of the line's newline, or the end of the text
+    fn line_end(&self, pos: usize) -> usize {
+        self.query_input[pos..].find('\n').map_or(self.query_input.len(), |i| pos + i)
+    }
+
+    // The line at `pos` including its newline
+    fn line_range(&self, pos: usize) -> Range<usize> {
+        let end = self.line_end(pos);
+        self.line_start(pos)..if end < self.query_input.len() { end + 1 } else { end }
+    }
+
+    fn first_non_blank(&self, pos: usize) -> usize {
+        let start = self.line_start(pos);
+        let line = &self.query_input[start..self.line_end(pos)];
+        start + (line.len() - line.trim_start().len())
+    }
+
+    // Column of `pos` in chars
+    fn column(&self, pos: usize) -> usize {
+        self.query_input[self.line_start(pos)..pos].chars().count()
+    }
+
+    // Same column (or the goal column) on the line `delta` lines away
+    fn move_vertical(&self, pos: usize, delta: isize) -> usize {
+        let column = self.goal_column.unwrap_or_else(|| self.column(pos));
+        let mut start = self.line_start(pos);
+        for _ in 0..delta.unsigned_abs() {
+            if delta < 0 {
+                if start == 0 {
+                    return pos;
+                }
+                start = self.line_start(start - 1);
+            } else {
+                let end = self.line_end(start);
+                if end == self.query_input.len() {
+                    return pos;
+                }
+                start = end + 1;
+            }
+        }
+        let line = &self.query_input[start..self.line_end(start)];
+        start + line.chars().take(column).map(char::len_utf8).sum::<usize>()
+    }
+
+    // Where a movement key takes the cursor
+    fn motion(&self, key: KeyCode) -> Option<usize> {
+        let pos = self.query_cursor;
+        let text = &self.query_input;
+        Some(match key {
+            KeyCode::Char('h') | KeyCode::Left if pos > self.line_start(pos) => self.prev_char(pos),
+            KeyCode::Char('l') | KeyCode::Right if pos < self.line_end(pos) => self.next_char(pos),
+            KeyCode::Char('h' | 'l') | KeyCode::Left | KeyCode::Right => pos,
+            KeyCode::Char('k') | KeyCode::Up => self.move_vertical(pos, -1),
+            KeyCode::Char('j') | KeyCode::Down => self.move_vertical(pos, 1),
+            KeyCode::Char('0') | KeyCode::Home => self.line_start(pos),
+            KeyCode::Char('$') | KeyCode::End => self.line_end(pos),
+            KeyCode::Char('G') => self.line_start(text.len()),
+            KeyCode::Char('w') => {
+                // Rest of this word (or run of punctuation), then blanks
+                let class = text[pos..].chars().next().map(char_class);
+                let rest = text[pos..].find(|c| Some(char_class(c)) != class).map_or(text.len(), |i| pos + i);
+                text[rest..].find(|c: char| !c.is_whitespace()).map_or(text.len(), |i| rest + i)
+            }
+            KeyCode::Char('b') => {
+                let before = text[..pos].trim_end_matches(char::is_whitespace);
+                let class = before.chars().next_back().map(char_class);
+                before.rfind(|c| Some(char_class(c)) != class).map_or(0, |i| self.next_char(i))
+            }
+            KeyCode::Char('e') => {
+                let from = self.next_char(pos);
+                let skipped = text[from..].find(|c: char| !c.is_whitespace()).map_or(text.len(), |i| from + i);
+                let class = text[skipped..].chars().next().map(char_class);
+                let end = text[skipped..].find(|c| Some(char_class(c)) != class).map_or(text.len(), |i| skipped + i);
+                self.prev_char(end).max(skipped)
+            }
+            _ => return None,
+        })
+    }
+
+    fn yank(&mut self, range: Range<usize>, linewise: bool) {
+        self.vim.register = self.query_input[range].to_string();
+        if linewise && !self.vim.register.ends_with('\n') {
+            self.vim.register.push('\n');
+        }
+        self.vim.linewise = linewise;
+    }
+
+    fn delete(&mut self, range: Range<usize>) {
+        self.query_cursor = range.start;
+        self.query_input.replace_range(range, "");
+    }
+
+    // Whole lines; the last line takes the newline before it instead
+    fn delete_lines(&mut self, range: Range<usize>) {
+        let range = if range.end == self.query_input.len() && range.start > 0 {
+            range.start - 1..range.end
+        } else {
+            range
+        };
+        self.delete(range);
+        self.query_cursor = self.first_non_blank(self.query_cursor.min(self.query_input.len()));
+    }
+
+    // `p` (after the cursor / below the line) or `P` (before / above)
+    fn put(&mut self, after: bool) {
+        let text = self.vim.register.clone();
+        if text.is_empty() {
+            return;
+        }
+        let pos = self.query_cursor;
+        if self.vim.linewise {
+            let at = if after { self.line_range(pos).end } else { self.line_start(pos) };
+            // Below the last line, which has no newline of its own
+            if after && at == self.query_input.len() && !self.query_input.ends_with('\n') && !self.query_input.is_empty() {
+                self.query_input.push('\n');
+                self.query_input.push_str(text.trim_end_matches('\n'));
+                self.query_cursor = at + 1;
+            } else {
+                self.query_input.insert_str(at, &text);
+                self.query_cursor = at;
+            }
+        } else {
+            let at = if after { self.next_char(pos) } else { pos };
+            self.query_input.insert_str(at, &text);
+            self.query_cursor = self.prev_char(at + text.len()).max(at);
+        }
+    }
+
+    fn toggle_visual(&mut self, mode: VimMode) {
+        if self.vim.mode == mode {
+            self.vim.mode = VimMode::Normal;
+        } else {
+            if !matches!(self.vim.mode, VimMode::Visual | VimMode::VisualLine) {
+                self.vim.anchor = self.query_cursor;
+            }
+            self.vim.mode = mode;
+        }
+    }
+}
+
+// Words, punctuation and blanks are separate runs for w/b/e
+fn char_class(c: char) -> u8 {
+    if c.is_alphanumeric() || c == '_' {
+        0
+    } else if c.is_whitespace() {
+        1
+    } else {
+        2
+    }
+}
@@ -0,0 +1,201 @@
+use anyhow::{bail, Result};
+
+use crate::app::rewrite::{statement_at, Edit, NOT_ALIAS};
+use crate::app::App;
+use crate::autocomplete::AutocompleteEngine;
+use crate::db::quote_ident;
+use crate::syntax::TokenType;
+
+// "Expand SELECT *": replaces `*` and `alias.*` in the select list of the
+// statement at the cursor with the columns of the FROM tables, taken from
+// the autocomplete cache
+impl App {
+    pub async fn expand_select_star(&mut self) -> Result<()> {
+        self.load_autocomplete_schema().await?;
+        let edits = match star_expansions(&self.ws.query_input, self.ws.query_cursor, &self.ws.autocomplete_engine) {
+            Ok(edits) => edits,
+            Err(e) => {
+                self.set_error(format!("{:#}", e));
+                return Ok(());
+            }
+        };
+        if edits.is_empty() {
+            self.set_status("No SELECT * in the statement at the cursor".to_string());
+            return Ok(());
+        }
+        self.apply_edits(edits);
+        Ok(())
+    }
+}
+
+struct Star {
+    start: usize,
+    end: usize,
+    // `t` for `t.*`
+    qualifier: Option<String>,
+}
+
+struct TableRef {
+    name: String,
+    alias: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Clause {
+    Select,
+    From,
+    Other,
+}
+
+// Select list and FROM items of one parenthesis level
+struct Level {
+    clause: Clause,
+    stars: Vec<Star>,
+    tables: Vec<TableRef>,
+    // FROM has a subquery or function whose columns aren't cached
+    opaque: bool,
+}
+
+impl Level {
+    fn new() -> Self {
+        Self {
+            clause: Clause::Other,
+            stars: Vec::new(),
+            tables: Vec::new(),
+            opaque: false,
+        }
+    }
+}
+
+fn star_expansions(sql: &str, cursor: usize, engine: &AutocompleteEngine) -> Result<Vec<Edit>> {
+    let statement = &statement_at(sql, cursor)[..];
+
+    let mut edits = Vec::new();
+    let mut levels = vec![Level::new()];
+    for (i, tok) in statement.iter().enumerate() {
+        let prev = |n: usize| i.checked_sub(n).map(|p| statement[p].upper.as_str()).unwrap_or("");
+        match tok.text.as_str() {
+            "(" => {
+                let level = levels.last_mut().unwrap();
+                if level.clause == Clause::From {
+                    level.opaque = true;
+                }
+                levels.push(Level::new());
+                continue;
+            }
+            ")" if levels.len() > 1 => {
+                resolve(levels.pop().unwrap(), engine, &mut edits)?;
+                continue;
+            }
+            _ => {}
+        }
+        let level = levels.last_mut().unwrap();
+
+        if matches!(tok.kind, TokenType::Keyword | TokenType::Identifier) {
+            match tok.upper.as_str() {
+                // Each branch of a UNION has its own FROM
+                "SELECT" => {
+                    let done = std::mem::replace(level, Level::new());
+                    resolve(done, engine, &mut edits)?;
+                    level.clause = Clause::Select;
+                    continue;
+                }
+                "FROM" if level.clause == Clause::Select => {
+                    level.clause = Clause::From;
+                    continue;
+                }
+                "JOIN" => {
+                    level.clause = Clause::From;
+                    continue;
+                }
+                "WHERE" | "GROUP" | "ORDER" | "HAVING" | "LIMIT" | "OFFSET" | "WINDOW" | "ON" | "USING"
+                | "UNION" | "INTERSECT" | "EXCEPT" | "RETURNING" | "INTO" => {
+                    level.clause = Clause::Other;
+                    continue;
+                }
+                _ => {}
+            }
+        }
+
+        match level.clause {
+            Clause::Select if tok.text == "*" => {
+                let list_start = |p: &str| matches!(p, "SELECT" | "DISTINCT" | "ALL" | ",");
+                if list_start(prev(1)) {
+                    level.stars.push(Star { start: tok.start, end: tok.end, qualifier: None });
+                } else if prev(1) == "." && i >= 2 && statement[i - 2].kind == TokenType::Identifier && list_start(prev(3)) {
+                    let qualifier = &statement[i - 2];
+                    level.stars.push(Star { start: qualifier.start, end: tok.end, qualifier: Some(qualifier.text.clone()) });
+                }
+            }
+            Clause::From if tok.kind == TokenType::Identifier && matches!(prev(1), "FROM" | "JOIN" | ",") => {
+                // [schema.]table [[AS] alias]
+                let mut last = i;
+                if statement.get(i + 1).is_some_and(|t| t.text == ".")
+                    && statement.get(i + 2).is_some_and(|t| t.kind == TokenType::Identifier)
+                {
+                    last = i + 2;
+                }
+                if statement.get(last + 1).is_some_and(|t| t.text == "(") {
+                    level.opaque = true;
+                    continue;
+                }
+                let mut next = last + 1;
+                if statement.get(next).is_some_and(|t| t.upper == "AS") {
+                    next += 1;
+                }
+                let alias = statement
+                    .get(next)
+                    .filter(|t| t.kind == TokenType::Identifier && !NOT_ALIAS.contains(&t.upper.as_str()))
+                    .map(|t| t.text.clone());
+                level.tables.push(TableRef { name: statement[last].text.clone(), alias });
+            }
+            _ => {}
+        }
+    }
+    for level in levels.into_iter().rev() {
+        resolve(level, engine, &mut edits)?;
+    }
+    Ok(edits)
+}
+
+// Turn the stars of a finished level into edits
+fn resolve(level: Level, engine: &AutocompleteEngine, edits: &mut Vec<Edit>) -> Result<()> {
+    for star in &level.stars {
+        let (tables, qualify): (Vec<&TableRef>, bool) = match &star.qualifier {
+            Some(qualifier) => {
+                let table = level
+                    .tables
+                    .iter()
+                    .find(|t| t.alias.as_deref().unwrap_or(&t.name).eq_ignore_ascii_case(qualifier));
+                match table {
+                    Some(table) => (vec![table], true),
+                    None => bail!("{} is not a table in the FROM clause", qualifier),
+                }
+            }
+            None => {
+                if level.opaque {
+                    bail!("Cannot expand * over a subquery or function in FROM");
+                }
+                if level.tables.is_empty() {
+                    bail!("No FROM table to expand * from");
+                }
+                (level.tables.iter().collect(), level.tables.len() > 1)
+            }
+        };
+
+        let mut columns = Vec::new();
+        for table in tables {
+            let Some(names) = engine.table_columns(&table.name) else {
+                bail!("No cached columns for {}", table.name);
+            };
+            let prefix = if qualify {
+                format!("{}.", table.alias.as_deref().unwrap_or(&table.name))
+            } else {
+                String::new()
+            };
+            columns.extend(names.iter().map(|name| format!("{}{}", prefix, quote_ident(name))));
+        }
+        edits.push(Edit { start: star.start, end: star.end, text: columns.join(", ") });
+    }
+    Ok(())
+}
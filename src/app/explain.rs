@@ -0,0 +1,58 @@
+use std::time::Instant;
+
+use anyhow::{anyhow, Result};
+
+use crate::app::guard::write_keyword;
+use crate::app::{App, ResultPopup};
+use crate::db::{Plan, QueryResult};
+
+// Plan of the statement at the cursor, shown with the planner settings it
+// was made under
+impl App {
+    pub async fn explain_query(&mut self) {
+        let sql = self.extract_current_query();
+        if sql.trim().is_empty() {
+            return;
+        }
+        match self.capture_plan(&sql).await {
+            Ok(plan) => {
+                let server = match &self.ws.capabilities {
+                    Some(capabilities) => format!("{}, PostgreSQL {}", self.ws.name, capabilities.server_version),
+                    None => self.ws.name.clone(),
+                };
+                let kind = if plan.analyzed { "EXPLAIN ANALYZE, BUFFERS" } else { "EXPLAIN, not executed" };
+                self.popup = Some(ResultPopup {
+                    title: format!("Plan ({}) on {}", kind, server),
+                    result: plan_result(plan),
+                    scroll: 0,
+                });
+            }
+            Err(e) => self.set_error(format!("{:#}", e)),
+        }
+    }
+
+    async fn capture_plan(&mut self, sql: &str) -> Result<Plan> {
+        // ANALYZE runs the statement, which a write must not do behind the user's back
+        let analyze = write_keyword(sql).is_none();
+        let client = self.ws.db.client().ok_or_else(|| anyhow!("Not connected"))?;
+        let started = Instant::now();
+        let plan = crate::db::explain(client, sql, analyze).await;
+        if analyze {
+            let outcome = plan.as_ref().map(|_| 0).map_err(|e| format!("{:#}", e));
+            self.audit
+                .record_statement(&self.ws.name, &format!("EXPLAIN (ANALYZE, BUFFERS) {}", sql), started.elapsed(), outcome);
+        }
+        plan
+    }
+}
+
+fn plan_result(plan: Plan) -> QueryResult {
+    let mut rows: Vec<Vec<String>> = plan.lines.into_iter().map(|line| vec![line]).collect();
+    rows.push(vec![String::new()]);
+    rows.push(vec!["Planner settings (* = not the server default)".to_string()]);
+    rows.extend(plan.settings.into_iter().map(|setting| {
+        let marker = if setting.changed { "*" } else { " " };
+        vec![format!("{} {} = {}", marker, setting.name, setting.value)]
+    }));
+    QueryResult::from_text(vec!["QUERY PLAN".to_string()], rows)
+}
@@ -0,0 +1,274 @@
+use anyhow::{anyhow, bail, Result};
+use crossterm::event::KeyCode;
+
+use crate::app::{App, AppMode, BrowserItem, PromptAction, TreeNode};
+use crate::autocomplete::fuzzy_match;
+use crate::config::Favorite;
+use crate::db::ObjectKind;
+use crate::keymap::Action;
+
+// The connection's favorites, searched as you type, to open in one key
+pub struct FavoritesPicker {
+    pub filter: String,
+    // Indexes into the profile's favorites, best match first, with the
+    // characters of their label the filter matched
+    pub matches: Vec<(usize, Vec<usize>)>,
+    pub selected: usize,
+}
+
+impl App {
+    // Favorites of the active connection's profile
+    pub fn favorites(&self) -> &[Favorite] {
+        self.config
+            .connections
+            .iter()
+            .find(|p| p.keyring_account() == self.ws.profile)
+            .map(|p| p.favorites.as_slice())
+            .unwrap_or_default()
+    }
+
+    fn favorites_mut(&mut self) -> Result<&mut Vec<Favorite>> {
+        self.config
+            .connections
+            .iter_mut()
+            .find(|p| p.keyring_account() == self.ws.profile)
+            .map(|p| &mut p.favorites)
+            .ok_or_else(|| anyhow!("This connection has no saved profile"))
+    }
+
+    // Star the browser object under the cursor, or unstar it when it
+    // already is or the cursor is on the favorite itself
+    pub fn toggle_favorite(&mut self) {
+        if let Err(e) = self.try_toggle_favorite() {
+            self.set_error(format!("{:#}", e));
+        }
+    }
+
+    fn try_toggle_favorite(&mut self) -> Result<()> {
+        let favorite = match self.ws.browser_items.get(self.ws.browser_selected).cloned() {
+            Some(BrowserItem::Favorite(index)) => self.favorites().get(index).cloned(),
+            Some(BrowserItem::Table(schema, name)) => Some(Favorite::Table { schema, name }),
+            Some(BrowserItem::View(schema, name)) => Some(Favorite::View { schema, name }),
+            Some(BrowserItem::MaterializedView(schema, name)) => Some(Favorite::MaterializedView { schema, name }),
+            Some(BrowserItem::Function(schema, name)) => Some(Favorite::Function { schema, name }),
+            _ => None,
+        };
+        let Some(favorite) = favorite else {
+            self.set_warning("Only tables, views and functions can be starred".to_string());
+            return Ok(());
+        };
+        let favorites = self.favorites_mut()?;
+        let starred = match favorites.iter().position(|f| *f == favorite) {
+            Some(index) => {
+                favorites.remove(index);
+                false
+            }
+            None => {
+                favorites.push(favorite.clone());
+                true
+            }
+        };
+        self.config.save()?;
+        self.sync_favorite_items();
+        let verb = if starred { "Starred" } else { "Unstarred" };
+        self.set_status(format!("{} {}", verb, favorite.label()));
+        Ok(())
+    }
+
+    pub fn begin_star_query(&mut self) {
+        if self.ws.query_input.trim().is_empty() {
+            self.set_warning("The editor is empty".to_string());
+            return;
+        }
+        let initial = self
+            .ws
+            .file_path
+            .as_deref()
+            .and_then(|path| std::path::Path::new(path).file_stem())
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        self.open_prompt("Save the query as a favorite named", &initial, PromptAction::StarQuery);
+    }
+
+    // Keep the editor's text as a favorite query, replacing one of the
+    // same name
+    pub fn star_query(&mut self, name: &str) -> Result<()> {
+        if name.is_empty() {
+            bail!("A favorite query needs a name");
+        }
+        let favorite = Favorite::Query {
+            name: name.to_string(),
+            sql: self.ws.query_input.trim().to_string(),
+        };
+        let favorites = self.favorites_mut()?;
+        match favorites.iter_mut().find(|f| matches!(f, Favorite::Query { name: n, .. } if n == name)) {
+            Some(existing) => *existing = favorite,
+            None => favorites.push(favorite),
+        }
+        self.config.save()?;
+        self.sync_favorite_items();
+        self.set_status(format!("Saved favorite query {}", name));
+        Ok(())
+    }
+
+    // List the favorites again under the database node after they change,
+    // keeping the cursor on the same node
+    fn sync_favorite_items(&mut self) {
+        if self.ws.browser_tree.is_empty() {
+            return;
+        }
+        let old = self
+            .ws
+            .browser_tree
+            .iter()
+            .filter(|node| matches!(node.item, BrowserItem::Favorite(_)))
+            .count();
+        let new = self.favorites().len();
+        let nodes = (0..new).map(|i| TreeNode::new(BrowserItem::Favorite(i)));
+        self.ws.browser_tree.splice(1..1 + old, nodes);
+        let mut path = self.ws.browser_paths.get(self.ws.browser_selected).cloned().unwrap_or_default();
+        if let Some(root) = path.first_mut() {
+            if *root > old {
+                *root = *root - old + new;
+            } else if *root > new {
+                *root = new;
+            }
+        }
+        self.rebuild_browser_items_at(path);
+    }
+
+    pub fn open_favorites_picker(&mut self) {
+        if self.favorites().is_empty() {
+            self.set_warning(format!(
+                "No favorites for {}; star objects with {} in the browser or queries with {}",
+                self.ws.name,
+                self.keymap.label(Action::ToggleFavorite),
+                self.keymap.label(Action::StarQuery)
+            ));
+            return;
+        }
+        self.favorites_picker = Some(FavoritesPicker {
+            filter: String::new(),
+            matches: Vec::new(),
+            selected: 0,
+        });
+        self.update_favorite_matches();
+    }
+
+    pub fn close_favorites_picker(&mut self) {
+        self.favorites_picker = None;
+    }
+
+    pub fn handle_favorites_picker_input(&mut self, key: KeyCode) {
+        if key == KeyCode::Delete {
+            if let Err(e) = self.remove_picked_favorite() {
+                self.set_error(format!("{:#}", e));
+            }
+            return;
+        }
+        let Some(picker) = &mut self.favorites_picker else {
+            return;
+        };
+        let last = picker.matches.len().saturating_sub(1);
+        match key {
+            KeyCode::Up => picker.selected = picker.selected.saturating_sub(1),
+            KeyCode::Down => picker.selected = (picker.selected + 1).min(last),
+            KeyCode::Char(c) => {
+                picker.filter.push(c);
+                picker.selected = 0;
+                self.update_favorite_matches();
+            }
+            KeyCode::Backspace => {
+                picker.filter.pop();
+                picker.selected = 0;
+                self.update_favorite_matches();
+            }
+            _ => {}
+        }
+    }
+
+    fn update_favorite_matches(&mut self) {
+        let Some(filter) = self.favorites_picker.as_ref().map(|picker| picker.filter.clone()) else {
+            return;
+        };
+        let matches = favorite_matches(self.favorites(), &filter);
+        if let Some(picker) = &mut self.favorites_picker {
+            picker.selected = picker.selected.min(matches.len().saturating_sub(1));
+            picker.matches = matches;
+        }
+    }
+
+    fn remove_picked_favorite(&mut self) -> Result<()> {
+        let Some(index) = self
+            .favorites_picker
+            .as_ref()
+            .and_then(|picker| picker.matches.get(picker.selected))
+            .map(|(index, _)| *index)
+        else {
+            return Ok(());
+        };
+        let removed = self.favorites_mut()?.remove(index);
+        self.config.save()?;
+        self.sync_favorite_items();
+        self.set_status(format!("Unstarred {}", removed.label()));
+        if self.favorites().is_empty() {
+            self.favorites_picker = None;
+        } else {
+            self.update_favorite_matches();
+        }
+        Ok(())
+    }
+
+    pub async fn open_picked_favorite(&mut self) {
+        let Some(picker) = self.favorites_picker.take() else {
+            return;
+        };
+        if let Some((index, _)) = picker.matches.get(picker.selected) {
+            if let Err(e) = self.open_favorite(*index).await {
+                self.set_error(format!("{:#}", e));
+            }
+        }
+    }
+
+    // Select a starred object in the browser, or put a favorite query in
+    // the editor in place of its text
+    pub(super) async fn open_favorite(&mut self, index: usize) -> Result<()> {
+        let Some(favorite) = self.favorites().get(index).cloned() else {
+            return Ok(());
+        };
+        let (schema, name, kind) = match favorite {
+            Favorite::Table { schema, name } => (schema, name, ObjectKind::Table),
+            Favorite::View { schema, name } => (schema, name, ObjectKind::View),
+            Favorite::MaterializedView { schema, name } => (schema, name, ObjectKind::MaterializedView),
+            Favorite::Function { schema, name } => (schema, name, ObjectKind::Function),
+            Favorite::Query { name, sql } => {
+                self.ws.query_cursor = sql.len();
+                self.ws.query_input = sql;
+                self.ws.query_scroll_offset = 0;
+                self.hide_autocomplete();
+                self.mode = AppMode::Query;
+                self.set_status(format!("Loaded favorite query {}", name));
+                return Ok(());
+            }
+        };
+        if !self.reveal_in_browser(&schema, kind, &name).await? {
+            self.set_warning(format!("{}.{} is not listed in the browser; it may have been dropped", schema, name));
+        }
+        Ok(())
+    }
+}
+
+// Favorites whose label matches `filter`, best first; ties keep the order
+// they were starred in
+fn favorite_matches(favorites: &[Favorite], filter: &str) -> Vec<(usize, Vec<usize>)> {
+    let mut matches: Vec<(i32, usize, Vec<usize>)> = favorites
+        .iter()
+        .enumerate()
+        .filter_map(|(i, favorite)| {
+            let (score, matched) = fuzzy_match(&favorite.label(), filter)?;
+            Some((score, i, matched))
+        })
+        .collect();
+    matches.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    matches.into_iter().map(|(_, i, matched)| (i, matched)).collect()
+}
@@ -0,0 +1,88 @@
+use anyhow::{Context, Result};
+
+use crate::app::{commands::expand_home, App, PromptAction, Workspace};
+
+// Loading and saving the query editor buffer as a .sql file
+impl App {
+    pub fn open_file_prompt(&mut self) {
+        let title = if self.ws.is_modified() {
+            "Open SQL file (unsaved changes will be lost)"
+        } else {
+            "Open SQL file"
+        };
+        let initial = self.ws.file_dir();
+        self.open_prompt(title, &initial, PromptAction::OpenFile);
+    }
+
+    // Save to the open file, asking for a path the first time
+    pub fn save_file(&mut self) {
+        match self.ws.file_path.clone() {
+            Some(path) => {
+                if let Err(e) = self.write_sql_file(&path) {
+                    self.set_error(format!("{:#}", e));
+                }
+            }
+            None => {
+                let initial = self.ws.file_dir();
+                self.open_prompt("Save SQL file as", &initial, PromptAction::SaveFile);
+            }
+        }
+    }
+
+    pub fn load_sql_file(&mut self, path: &str) -> Result<()> {
+        let path = expand_home(path);
+        let sql = std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path))?;
+        self.ws.query_input = sql.clone();
+        self.ws.query_cursor = 0;
+        self.ws.query_scroll_offset = 0;
+        self.ws.saved_input = sql;
+        self.ws.file_path = Some(path.clone());
+        self.hide_autocomplete();
+        self.set_status(format!("Opened {}", path));
+        Ok(())
+    }
+
+    pub fn write_sql_file(&mut self, path: &str) -> Result<()> {
+        let path = expand_home(path);
+        std::fs::write(&path, &self.ws.query_input).with_context(|| format!("Failed to write {}", path))?;
+        self.ws.saved_input = self.ws.query_input.clone();
+        self.ws.file_path = Some(path.clone());
+        self.set_status(format!("Saved {}", path));
+        Ok(())
+    }
+
+    // Session report: JSON for a .json path, Markdown otherwise
+    pub fn export_session(&mut self, path: &str) -> Result<()> {
+        let path = expand_home(path);
+        let report = if path.ends_with(".json") {
+            self.audit.to_json()?
+        } else {
+            self.audit.to_markdown()
+        };
+        std::fs::write(&path, report).with_context(|| format!("Failed to write {}", path))?;
+        self.set_status(format!("Wrote session report to {}", path));
+        Ok(())
+    }
+}
+
+impl Workspace {
+    // The editor holds a file whose text has changed since it was loaded or saved
+    pub fn is_modified(&self) -> bool {
+        self.file_path.is_some() && self.query_input != self.saved_input
+    }
+
+    // Editor text that would be lost with the workspace: a scratch buffer
+    // with anything in it, or a file changed since it was saved
+    pub fn has_unsaved_text(&self) -> bool {
+        !self.query_input.trim().is_empty() && (self.file_path.is_none() || self.is_modified())
+    }
+
+    // Directory of the open file, as a starting point for path prompts
+    pub(super) fn file_dir(&self) -> String {
+        self.file_path
+            .as_deref()
+            .and_then(|path| std::path::Path::new(path).parent())
+            .map(|dir| format!("{}/", dir.display()))
+            .unwrap_or_default()
+    }
+}
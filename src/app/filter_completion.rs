@@ -0,0 +1,97 @@
+use std::collections::BTreeSet;
+
+use crate::app::row_filter::{find_operator, unquote};
+use crate::app::{App, BrowserItem};
+
+// Most candidates listed in the status line when Tab can't decide
+const LISTED: usize = 8;
+
+// Tab completion in the browser and results filter boxes
+impl App {
+    // Complete the browser filter from the names in the tree and, once
+    // loaded, the cached catalog
+    pub fn complete_browser_filter(&mut self) {
+        let mut names: BTreeSet<&str> = self
+            .ws
+            .browser_items
+            .iter()
+            .filter_map(|item| match item {
+                BrowserItem::Folder(_, _) | BrowserItem::Favorite(_) => None,
+                BrowserItem::Database(name) | BrowserItem::Schema(name) => Some(name.as_str()),
+                BrowserItem::Table(_, name)
+                | BrowserItem::View(_, name)
+                | BrowserItem::MaterializedView(_, name)
+                | BrowserItem::Function(_, name)
+                | BrowserItem::Sequence(_, name) => Some(name.as_str()),
+            })
+            .collect();
+        names.extend(self.ws.autocomplete_engine.table_names().iter().map(String::as_str));
+
+        let typed = self.ws.filter_input.clone();
+        let candidates: Vec<String> = names.into_iter().map(str::to_string).collect();
+        if let Some(completed) = self.complete_word(&typed, &candidates) {
+            self.ws.filter_input = completed;
+        }
+    }
+
+    // Complete the last term of the results filter: a column name, or after
+    // `column<op>` one of that column's values in the current result
+    pub fn complete_results_filter(&mut self) {
+        let Some(result) = &self.ws.query_result else {
+            return;
+        };
+        let input = &self.ws.results_filter_input;
+        let start = input.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        let term = &input[start..];
+
+        let (prefix, typed, candidates) = match find_operator(term) {
+            Some((at, op)) => {
+                let name = unquote(&term[..at]);
+                let Some(col) = result.columns.iter().position(|c| c.eq_ignore_ascii_case(name)) else {
+                    return;
+                };
+                let values: BTreeSet<&String> = result.rows.iter().filter_map(|row| row.get(col)?.as_ref()).collect();
+                (
+                    input[..start + at + op.len()].to_string(),
+                    unquote(&term[at + op.len()..]).to_string(),
+                    values.into_iter().cloned().collect::<Vec<_>>(),
+                )
+            }
+            None => (input[..start].to_string(), term.to_string(), result.columns.clone()),
+        };
+        if let Some(completed) = self.complete_word(&typed, &candidates) {
+            // Values with spaces need quotes to stay one term
+            let completed = if completed.contains(char::is_whitespace) {
+                format!("\"{}\"", completed)
+            } else {
+                completed
+            };
+            self.ws.results_filter_input = format!("{}{}", prefix, completed);
+            self.ws.result_selected_row = 0;
+        }
+    }
+
+    // `typed` extended to the longest prefix shared by every candidate it
+    // starts (case-insensitively); lists the candidates when that adds nothing
+    fn complete_word(&mut self, typed: &str, candidates: &[String]) -> Option<String> {
+        let lower = typed.to_lowercase();
+        let matches: Vec<&String> = candidates.iter().filter(|c| c.to_lowercase().starts_with(&lower)).collect();
+        let first = matches.first()?;
+        let common = matches.iter().skip(1).fold(first.chars().count(), |len, m| {
+            first
+                .chars()
+                .zip(m.chars())
+                .take(len)
+                .take_while(|(a, b)| a.eq_ignore_ascii_case(b))
+                .count()
+        });
+        let completed: String = first.chars().take(common).collect();
+        if matches.len() > 1 && completed.chars().count() <= typed.chars().count() {
+            let listed: Vec<&str> = matches.iter().take(LISTED).map(|m| m.as_str()).collect();
+            let more = if matches.len() > LISTED { format!(" (+{} more)", matches.len() - LISTED) } else { String::new() };
+            self.set_status(format!("{}{}", listed.join("  "), more));
+            return None;
+        }
+        Some(completed)
+    }
+}
@@ -0,0 +1,399 @@
+use anyhow::{bail, Result};
+
+use crate::app::rewrite::{statement_at, Tok};
+use crate::app::script::{dollar_tag, split_statements};
+use crate::app::{App, PromptAction};
+use crate::config::{Environment, UserCommand};
+
+// Verbs of statements that only read. Any other statement counts as a
+// write, so a command this list does not know is confirmed, not let through.
+const READS: &[&str] = &["SELECT", "SHOW", "VALUES", "TABLE"];
+
+// Transaction control, which writes nothing by itself
+const TRANSACTION: &[&str] = &["BEGIN", "START", "COMMIT", "END", "ROLLBACK", "ABORT", "SAVEPOINT", "RELEASE"];
+
+// Functions a SELECT can call that change a sequence
+const SEQUENCE_WRITES: &[&str] = &["NEXTVAL", "SETVAL"];
+
+// Words of statements a read-only connection refuses before sending them
+const DESTRUCTIVE: &[&str] = &["UPDATE", "DELETE", "DROP", "TRUNCATE", "ALTER"];
+
+// What makes `sql` write, in the first of its statements that does
+pub(super) fn write_keyword(sql: &str) -> Option<String> {
+    split_statements(sql).iter().find_map(|statement| statement_write(statement))
+}
+
+// The statement's verb when it is not a read, SELECT INTO, a sequence
+// function it calls, or the verb of a WITH query that writes under a
+// SELECT. EXPLAIN reads unless ANALYZE runs the statement.
+fn statement_write(statement: &str) -> Option<String> {
+    let tokens = statement_at(&blank_quoted(statement), 0);
+    let first = tokens.first()?;
+    if first.upper == "EXPLAIN" && !explain_analyzes(&tokens) {
+        return None;
+    }
+    let words = verb_words(statement);
+    // A statement in parentheses can only be a query
+    let verb = if first.text == "(" { "SELECT" } else { words.first()?.as_str() };
+    if !READS.contains(&verb) && !TRANSACTION.contains(&verb) {
+        return Some(verb.to_string());
+    }
+    if verb == "SELECT" && words.iter().any(|word| word == "INTO") {
+        return Some("SELECT INTO".to_string());
+    }
+    let call = tokens
+        .windows(2)
+        .find(|pair| SEQUENCE_WRITES.contains(&pair[0].upper.as_str()) && pair[1].text == "(");
+    if let Some(pair) = call {
+        return Some(format!("{}()", pair[0].text.to_lowercase()));
+    }
+    with_query_verbs(statement)
+        .into_iter()
+        .find(|verb| !READS.contains(&verb.as_str()))
+}
+
+// Whether the options of an EXPLAIN include ANALYZE, bare or in the
+// parenthesized list and not turned off
+fn explain_analyzes(tokens: &[Tok]) -> bool {
+    let mut depth = 0i32;
+    for (i, tok) in tokens.iter().enumerate().skip(1) {
+        match tok.upper.as_str() {
+            "(" => depth += 1,
+            ")" => depth -= 1,
+            "ANALYZE" | "ANALYSE" => {
+                let off = depth > 0
+                    && tokens
+                        .get(i + 1)
+                        .is_some_and(|next| matches!(next.upper.as_str(), "FALSE" | "OFF" | "0"));
+                if !off {
+                    return true;
+                }
+            }
+            "VERBOSE" | "," => {}
+            _ if depth == 0 => return false,
+            _ => {}
+        }
+    }
+    false
+}
+
+// Verb of the first statement in `sql` that a read-only connection refuses
+fn destructive_verb(sql: &str) -> Option<String> {
+    split_statements(sql)
+        .iter()
+        .filter_map(|statement| verb_words(statement).into_iter().next())
+        .find(|verb| DESTRUCTIVE.contains(&verb.as_str()))
+}
+
+// Whether `sql` leaves a transaction block open (true) or ends it (false),
+// going by the last of its statements that does either
+pub(super) fn transaction_change(sql: &str) -> Option<bool> {
+    split_statements(sql).iter().rev().find_map(|statement| {
+        let words = verb_words(statement);
+        match words.first()?.as_str() {
+            "BEGIN" | "START" => Some(true),
+            "COMMIT" | "END" | "ABORT" => Some(false),
+            // ROLLBACK TO SAVEPOINT stays in the block
+            "ROLLBACK" if !words.iter().any(|word| word == "TO") => Some(false),
+            "PREPARE" if words.get(1).is_some_and(|word| word == "TRANSACTION") => Some(false),
+            _ => None,
+        }
+    })
+}
+
+// First word of each query in a WITH prefix: the one right inside the
+// parenthesis after AS or MATERIALIZED
+fn with_query_verbs(statement: &str) -> Vec<String> {
+    let tokens = statement_at(&blank_quoted(statement), 0);
+    if tokens.first().is_none_or(|tok| tok.upper != "WITH") {
+        return Vec::new();
+    }
+    let mut verbs = Vec::new();
+    let mut depth = 0i32;
+    for (i, tok) in tokens.iter().enumerate() {
+        match tok.text.as_str() {
+            "(" => {
+                if depth == 0 && i > 0 && matches!(tokens[i - 1].upper.as_str(), "AS" | "MATERIALIZED") {
+                    verbs.extend(tokens.get(i + 1).map(|tok| tok.upper.clone()));
+                }
+                depth += 1;
+            }
+            ")" => depth -= 1,
+            _ => {}
+        }
+    }
+    verbs
+}
+
+// Top-level words of a statement from its verb on. The verb is the first
+// word past EXPLAIN and its options, or after a WITH prefix the word that
+// follows its queries; a quoted name or literal is never one.
+pub(super) fn verb_words(statement: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut depth = 0i32;
+    for tok in statement_at(&blank_quoted(statement), 0) {
+        match tok.text.as_str() {
+            "(" => depth += 1,
+            ")" => {
+                depth -= 1;
+                if depth == 0 {
+                    words.push(tok.text);
+                }
+            }
+            _ if depth == 0 => words.push(tok.upper),
+            _ => {}
+        }
+    }
+    if words.first().is_some_and(|word| word == "EXPLAIN") {
+        let options = words[1..]
+            .iter()
+            .take_while(|word| matches!(word.as_str(), ")" | "ANALYZE" | "ANALYSE" | "VERBOSE"))
+            .count();
+        words.drain(..=options);
+    }
+    if words.first().is_some_and(|word| word == "WITH") {
+        // name [(columns)] AS [[NOT] MATERIALIZED] (query) [, ...] verb
+        let verb = words
+            .windows(2)
+            .position(|pair| pair[0] == ")" && pair[1] != "," && pair[1] != "AS")
+            .map_or(words.len(), |at| at + 1);
+        words.drain(..verb);
+    }
+    words.retain(|word| word != ")");
+    words
+}
+
+// `sql` with comments and the insides of literals, quoted names and
+// dollar-quoted bodies blanked, so no word in them is read as a keyword.
+// Byte offsets stay those of `sql`.
+pub(super) fn blank_quoted(sql: &str) -> String {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut out = String::with_capacity(sql.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let (open, close, comment) = match chars[i] {
+            '\'' => ("'".to_string(), "'".to_string(), false),
+            '"' => ("\"".to_string(), "\"".to_string(), false),
+            '-' if chars.get(i + 1) == Some(&'-') => ("--".to_string(), "\n".to_string(), true),
+            '/' if chars.get(i + 1) == Some(&'*') => ("/*".to_string(), "*/".to_string(), true),
+            '$' => match dollar_tag(&chars, i) {
+                Some(tag) => (tag.clone(), tag, false),
+                None => {
+                    out.push('$');
+                    i += 1;
+                    continue;
+                }
+            },
+            c => {
+                out.push(c);
+                i += 1;
+                continue;
+            }
+        };
+        let blank = |text: &str| if comment { " ".repeat(text.len()) } else { text.to_string() };
+        out.push_str(&blank(&open));
+        i += open.chars().count();
+        let close: Vec<char> = close.chars().collect();
+        while i < chars.len() && !chars[i..].starts_with(&close) {
+            out.push_str(&" ".repeat(chars[i].len_utf8()));
+            i += 1;
+        }
+        if i < chars.len() {
+            out.push_str(&blank(&close.iter().collect::<String>()));
+            i += close.len();
+        }
+    }
+    out
+}
+
+// Editor or palette run held back until a write on a prod connection is
+// confirmed; it runs again from the start once it is
+pub enum GuardedRun {
+    Query,
+    Script,
+    Command(UserCommand),
+}
+
+impl App {
+    // The server refuses writes in a read-only session, but a statement
+    // that would destroy data is stopped here, with a clearer error, before
+    // it is sent at all
+    pub(super) fn refuse_on_read_only(&self, sql: &str) -> Result<()> {
+        if !self.ws.read_only {
+            return Ok(());
+        }
+        match destructive_verb(sql) {
+            Some(word) => bail!("{} refused: {} is a read-only connection", word, self.ws.name),
+            None => Ok(()),
+        }
+    }
+
+    // True when `sql` writes on a prod connection and now waits for the
+    // user to confirm it
+    pub(super) fn hold_write(&mut self, sql: &str, run: GuardedRun) -> bool {
+        if std::mem::take(&mut self.write_confirmed) || self.ws.environment != Some(Environment::Prod) {
+            return false;
+        }
+        let Some(word) = write_keyword(sql) else {
+            return false;
+        };
+        self.guarded = Some(run);
+        self.open_prompt(
+            &format!("PRODUCTION {}: this runs {}. Type yes to go ahead", self.ws.name, word),
+            "",
+            PromptAction::ConfirmWrite,
+        );
+        true
+    }
+
+    pub async fn confirm_write(&mut self, answer: &str) -> Result<()> {
+        let Some(run) = self.guarded.take() else {
+            return Ok(());
+        };
+        if !answer.eq_ignore_ascii_case("yes") {
+            self.set_status("Nothing was run".to_string());
+            return Ok(());
+        }
+        self.run_confirmed(run).await
+    }
+
+    // Run a held-back query, script or command again, past the checks that
+    // held it
+    pub(super) async fn run_confirmed(&mut self, run: GuardedRun) -> Result<()> {
+        self.write_confirmed = true;
+        let result = match run {
+            GuardedRun::Query => self.execute_query().await.map(|()| self.reset_result_view()),
+            GuardedRun::Script => self.execute_script().await.map(|()| self.reset_result_view()),
+            GuardedRun::Command(command) => self.run_user_command(&command).await,
+        };
+        self.write_confirmed = false;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn verb(sql: &str) -> Option<String> {
+        verb_words(sql).into_iter().next()
+    }
+
+    #[test]
+    fn quoted_words_are_not_verbs() {
+        assert_eq!(verb(r#"SELECT "delete" FROM t"#).as_deref(), Some("SELECT"));
+        assert_eq!(verb(r#"SELECT 'x' AS "update""#).as_deref(), Some("SELECT"));
+        assert_eq!(verb("SELECT $$drop table$$").as_deref(), Some("SELECT"));
+        assert_eq!(verb("/* drop */ SELECT 1").as_deref(), Some("SELECT"));
+    }
+
+    #[test]
+    fn verb_follows_with_queries() {
+        assert_eq!(verb("WITH gone AS (DELETE FROM t RETURNING *) SELECT * FROM gone").as_deref(), Some("SELECT"));
+        assert_eq!(
+            verb("WITH a(x) AS (SELECT 1), b AS MATERIALIZED (SELECT 2) DELETE FROM t USING a").as_deref(),
+            Some("DELETE")
+        );
+        assert_eq!(verb(r#"WITH "drop" AS (SELECT 1) UPDATE t SET x = 1"#).as_deref(), Some("UPDATE"));
+    }
+
+    #[test]
+    fn verb_follows_explain_options() {
+        assert_eq!(verb("EXPLAIN ANALYZE VERBOSE DELETE FROM t").as_deref(), Some("DELETE"));
+        assert_eq!(verb("EXPLAIN (ANALYZE, BUFFERS) TRUNCATE t").as_deref(), Some("TRUNCATE"));
+        assert_eq!(verb("explain select 1").as_deref(), Some("SELECT"));
+    }
+
+    #[test]
+    fn read_only_refuses_destructive_verbs_only() {
+        assert_eq!(destructive_verb("SELECT 1; DELETE FROM t").as_deref(), Some("DELETE"));
+        assert_eq!(destructive_verb(r#"SELECT "delete" FROM t"#), None);
+        assert_eq!(destructive_verb(r#"SELECT 'x' AS "update""#), None);
+        assert_eq!(destructive_verb("SELECT $$drop table$$"), None);
+        assert_eq!(destructive_verb("WITH d AS (SELECT 1) DELETE FROM t").as_deref(), Some("DELETE"));
+    }
+
+    #[test]
+    fn writes_are_found_by_verb() {
+        assert_eq!(write_keyword("SELECT 1; INSERT INTO t VALUES (1)").as_deref(), Some("INSERT"));
+        assert_eq!(write_keyword("SELECT * INTO copy FROM t").as_deref(), Some("SELECT INTO"));
+        assert_eq!(
+            write_keyword("WITH gone AS (DELETE FROM t RETURNING *) SELECT * FROM gone").as_deref(),
+            Some("DELETE")
+        );
+        assert_eq!(write_keyword("EXPLAIN ANALYZE UPDATE t SET x = 1").as_deref(), Some("UPDATE"));
+        assert_eq!(write_keyword("EXPLAIN (ANALYZE, BUFFERS) DELETE FROM t").as_deref(), Some("DELETE"));
+        assert_eq!(write_keyword("SELECT * FROM t FOR UPDATE"), None);
+    }
+
+    #[test]
+    fn anything_but_a_read_is_a_write() {
+        for (sql, verb) in [
+            ("REFRESH MATERIALIZED VIEW m", "REFRESH"),
+            ("COMMENT ON TABLE t IS 'x'", "COMMENT"),
+            ("REINDEX TABLE t", "REINDEX"),
+            ("CLUSTER t USING t_pkey", "CLUSTER"),
+            ("VACUUM (ANALYZE) t", "VACUUM"),
+            ("ANALYZE t", "ANALYZE"),
+            ("REASSIGN OWNED BY a TO b", "REASSIGN"),
+            ("SECURITY LABEL FOR selinux ON TABLE t IS 'x'", "SECURITY"),
+            ("IMPORT FOREIGN SCHEMA s FROM SERVER f INTO public", "IMPORT"),
+            ("LOCK TABLE t IN ACCESS EXCLUSIVE MODE", "LOCK"),
+            ("SET ROLE admin", "SET"),
+        ] {
+            assert_eq!(write_keyword(sql).as_deref(), Some(verb), "{}", sql);
+        }
+    }
+
+    #[test]
+    fn reads_and_transaction_control_are_not_writes() {
+        for sql in [
+            "SELECT 1",
+            "(SELECT 1) UNION (SELECT 2)",
+            "SHOW search_path",
+            "VALUES (1), (2)",
+            "TABLE t",
+            "WITH v AS (VALUES (1)) TABLE v",
+            "EXPLAIN UPDATE t SET x = 1",
+            "EXPLAIN (ANALYZE false, COSTS) DELETE FROM t",
+            "EXPLAIN ANALYZE SELECT 1",
+            "BEGIN; SAVEPOINT s; RELEASE s; COMMIT",
+        ] {
+            assert_eq!(write_keyword(sql), None, "{}", sql);
+        }
+    }
+
+    #[test]
+    fn sequence_functions_are_writes() {
+        assert_eq!(write_keyword("SELECT nextval('s')").as_deref(), Some("nextval()"));
+        assert_eq!(write_keyword("SELECT pg_catalog.setval('s', 1)").as_deref(), Some("setval()"));
+        assert_eq!(write_keyword("SELECT currval('s')"), None);
+        assert_eq!(write_keyword("SELECT 'nextval(1)'"), None);
+    }
+
+    #[test]
+    fn quoted_words_are_not_writes() {
+        assert_eq!(write_keyword(r#"SELECT "delete" FROM t"#), None);
+        assert_eq!(write_keyword(r#"SELECT 'x' AS "update""#), None);
+        assert_eq!(write_keyword("SELECT $$drop table$$"), None);
+        assert_eq!(write_keyword(r#"SELECT "into" FROM t"#), None);
+        assert_eq!(write_keyword("WITH c AS (SELECT 'insert') SELECT * FROM c"), None);
+    }
+
+    #[test]
+    fn transaction_blocks_are_followed() {
+        assert_eq!(transaction_change("BEGIN"), Some(true));
+        assert_eq!(transaction_change("start transaction isolation level serializable"), Some(true));
+        assert_eq!(transaction_change("BEGIN; UPDATE t SET a = 1; COMMIT"), Some(false));
+        assert_eq!(transaction_change("ROLLBACK TO SAVEPOINT s"), None);
+        assert_eq!(transaction_change("rollback"), Some(false));
+        assert_eq!(transaction_change("PREPARE TRANSACTION 'x'"), Some(false));
+        assert_eq!(transaction_change("SELECT 'BEGIN'"), None);
+    }
+
+    #[test]
+    fn blanking_keeps_quotes_and_drops_comments() {
+        assert_eq!(blank_quoted("SELECT 'a;b', $x$drop$x$ -- c\n"), "SELECT '   ', $x$    $x$      ");
+        assert_eq!(blank_quoted("SELECT 'é' /* ü */").len(), "SELECT 'é' /* ü */".len());
+    }
+}
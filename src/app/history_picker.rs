@@ -0,0 +1,99 @@
+use crossterm::event::KeyCode;
+
+use crate::app::{format_duration, App, AppMode};
+use crate::results_history::{self, SavedResult};
+
+// Recent results of the current connection, kept on disk, to show again
+// without running their queries
+pub struct ResultsPicker {
+    pub entries: Vec<SavedResult>,
+    pub selected: usize,
+}
+
+impl App {
+    // Keep the result just shown, newest first, up to `results_history` of
+    // them per connection
+    pub(super) fn remember_result(&mut self) {
+        let keep = self.config.results_history;
+        let (Some(sql), Some(result)) = (&self.ws.result_sql, &self.ws.query_result) else {
+            return;
+        };
+        // Every run of a watched query would crowd the others out
+        if keep == 0 || result.columns.is_empty() || self.ws.watch.is_some() {
+            return;
+        }
+        let partial = self.ws.result_capped || self.ws.db.has_pending_rows();
+        let elapsed = self.ws.result_elapsed.map(|d| d.as_millis() as u64);
+        let saved = SavedResult::new(sql, result, self.ws.result_tag.clone(), elapsed, partial);
+        let profile = self.ws.profile.clone();
+        let stored = results_history::load(&profile).and_then(|mut entries| {
+            entries.insert(0, saved);
+            entries.truncate(keep);
+            results_history::save(&profile, &entries)
+        });
+        if let Err(e) = stored {
+            self.set_warning(format!("Results history: {:#}", e));
+        }
+    }
+
+    pub fn open_results_history(&mut self) {
+        match results_history::load(&self.ws.profile) {
+            Ok(entries) if entries.is_empty() => {
+                let hint = if self.config.results_history == 0 {
+                    "; set results_history in the config file to keep recent results"
+                } else {
+                    ""
+                };
+                self.set_warning(format!("No results kept for {}{}", self.ws.name, hint));
+            }
+            Ok(entries) => self.results_picker = Some(ResultsPicker { entries, selected: 0 }),
+            Err(e) => self.set_error(format!("{:#}", e)),
+        }
+    }
+
+    pub fn close_results_history(&mut self) {
+        self.results_picker = None;
+    }
+
+    pub fn handle_results_history_input(&mut self, key: KeyCode) {
+        let Some(picker) = &mut self.results_picker else {
+            return;
+        };
+        let last = picker.entries.len().saturating_sub(1);
+        match key {
+            KeyCode::Up => picker.selected = picker.selected.saturating_sub(1),
+            KeyCode::Down => picker.selected = (picker.selected + 1).min(last),
+            _ => {}
+        }
+    }
+
+    // Put the chosen result in the grid as it was. It isn't tied to a live
+    // query, so it can't be edited, refreshed or fetched further.
+    pub fn show_saved_result(&mut self) {
+        let Some(picker) = self.results_picker.take() else {
+            return;
+        };
+        let Some(saved) = picker.entries.into_iter().nth(picker.selected) else {
+            return;
+        };
+        self.ws.db.park_rows(None);
+        self.ws.query_result = Some(saved.to_result());
+        self.ws.result_capped = false;
+        self.ws.result_sql = None;
+        self.ws.result_params = Vec::new();
+        self.ws.auto_limited = None;
+        self.ws.script_outcomes = None;
+        self.ws.result_notices = Vec::new();
+        self.ws.result_tag = saved.tag.clone();
+        self.ws.result_elapsed = saved.elapsed_ms.map(std::time::Duration::from_millis);
+        self.mode = AppMode::Query;
+        self.reset_result_view();
+        let partial = if saved.partial { ", not every row was kept" } else { "" };
+        self.set_status(format!(
+            "Result from {} ago, not run again{}: {}",
+            format_duration(saved.age() as f64),
+            partial,
+            saved.sql.lines().next().unwrap_or_default()
+        ));
+    }
+}
@@ -0,0 +1,181 @@
+use anyhow::{bail, Context, Result};
+use std::process::Stdio;
+
+use tokio_postgres::Client;
+
+use crate::config::{Hook, QueryHooks};
+use crate::db::DbConnection;
+
+// Pre/post query hooks from the connected profile. SQL hooks run on the
+// same connection as the query, taken only when one runs since that drops
+// rows still waiting to be read; shell hooks get the query details in
+// PGTUI_* environment variables instead of placeholders, so nothing from
+// the query is ever spliced into a command line.
+
+pub async fn run_pre_query(db: &DbConnection, hooks: &QueryHooks, sql: &str) -> Result<()> {
+    run_hooks(|| db.client(), &hooks.pre_query, sql, None)
+        .await
+        .context("Pre-query hook failed")
+}
+
+/// `rows` is `None` when the query itself failed.
+pub async fn run_post_query(
+    db: &DbConnection,
+    hooks: &QueryHooks,
+    sql: &str,
+    rows: Option<usize>,
+) -> Result<()> {
+    run_hooks(|| db.client(), &hooks.post_query, sql, Some(rows))
+        .await
+        .context("Post-query hook failed")
+}
+
+// The same for scripts, which run on a client of their own
+pub async fn run_pre_query_on(client: &Client, hooks: &QueryHooks, sql: &str) -> Result<()> {
+    run_hooks(|| Some(client), &hooks.pre_query, sql, None)
+        .await
+        .context("Pre-query hook failed")
+}
+
+pub async fn run_post_query_on(client: &Client, hooks: &QueryHooks, sql: &str, rows: Option<usize>) -> Result<()> {
+    run_hooks(|| Some(client), &hooks.post_query, sql, Some(rows))
+        .await
+        .context("Post-query hook failed")
+}
+
+async fn run_hooks<'a>(
+    client: impl Fn() -> Option<&'a Client>,
+    hooks: &[Hook],
+    sql: &str,
+    outcome: Option<Option<usize>>,
+) -> Result<()> {
+    for hook in hooks {
+        run_hook(&client, hook, sql, outcome).await?;
+    }
+    Ok(())
+}
+
+async fn run_hook<'a>(
+    client: &impl Fn() -> Option<&'a Client>,
+    hook: &Hook,
+    sql: &str,
+    outcome: Option<Option<usize>>,
+) -> Result<()> {
+    let status = match outcome {
+        None => "pending",
+        Some(Some(_)) => "ok",
+        Some(None) => "error",
+    };
+    let rows = outcome.flatten().unwrap_or(0);
+
+    match hook {
+        Hook::Sql(template) => {
+            let statement = fill_placeholders(
+                template,
+                &[
+                    ("{query}", crate::db::quote_literal(sql)),
+                    ("{status}", crate::db::quote_literal(status)),
+                    ("{rows}", rows.to_string()),
+                ],
+            );
+            let client = client().context("Not connected")?;
+            client
+                .batch_execute(&statement)
+                .await
+                .with_context(|| format!("Failed to run hook SQL: {}", template))
+        }
+        Hook::Shell(command) => {
+            let output = shell(command)
+                .env("PGTUI_QUERY", sql)
+                .env("PGTUI_STATUS", status)
+                .env("PGTUI_ROWS", rows.to_string())
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::piped())
+                .output()
+                .await
+                .with_context(|| format!("Failed to start hook command: {}", command))?;
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                bail!("`{}` exited with {}: {}", command, output.status, stderr.trim());
+            }
+            Ok(())
+        }
+    }
+}
+
+// `template` with each placeholder replaced by its value in one pass, so a
+// placeholder inside a value already put in (a query that mentions
+// `{status}`) is left as it is
+fn fill_placeholders(template: &str, values: &[(&str, String)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while !rest.is_empty() {
+        match values.iter().find(|(placeholder, _)| rest.starts_with(placeholder)) {
+            Some((placeholder, value)) => {
+                out.push_str(value);
+                rest = &rest[placeholder.len()..];
+            }
+            None => {
+                let next = rest.chars().next().map_or(1, char::len_utf8);
+                out.push_str(&rest[..next]);
+                rest = &rest[next..];
+            }
+        }
+    }
+    out
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> tokio::process::Command {
+    let mut cmd = tokio::process::Command::new("cmd");
+    cmd.arg("/C").arg(command);
+    cmd
+}
+
+#[cfg(not(windows))]
+fn shell(command: &str) -> tokio::process::Command {
+    let mut cmd = tokio::process::Command::new("sh");
+    cmd.arg("-c").arg(command);
+    cmd
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::results::load_result;
+    use crate::db::test_connection;
+
+    #[test]
+    fn placeholders_in_values_stay_as_they_are() {
+        let values = [
+            ("{query}", crate::db::quote_literal("SELECT '{status}', '{rows}'")),
+            ("{status}", crate::db::quote_literal("ok")),
+            ("{rows}", "3".to_string()),
+        ];
+        assert_eq!(
+            fill_placeholders("INSERT INTO log VALUES ({query}, {status}, {rows})", &values),
+            "INSERT INTO log VALUES ('SELECT ''{status}'', ''{rows}''', 'ok', 3)"
+        );
+    }
+
+    #[tokio::test]
+    async fn shell_post_hooks_keep_unread_rows() {
+        let Some(db) = test_connection().await else {
+            return;
+        };
+        let hooks = QueryHooks {
+            pre_query: Vec::new(),
+            post_query: vec![Hook::Shell("exit 0".to_string())],
+        };
+        let sql = "SELECT generate_series(1, 1200)";
+        // One batch of 500 read, the rest parked on the connection
+        let (result, _, _) = load_result(&db, sql, &[], 0, 0).await.unwrap();
+        assert_eq!(result.rows.len(), 500);
+
+        run_post_query(&db, &hooks, sql, Some(result.rows.len())).await.unwrap();
+        let mut pending = db.take_rows().expect("post-query hook dropped the unread rows");
+        let (rows, _) = pending.fetch(1000).await.unwrap();
+        assert_eq!(rows.len(), 700);
+    }
+}
@@ -0,0 +1,210 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use anyhow::{anyhow, bail, Context, Result};
+use crossterm::event::KeyCode;
+use tokio::task::JoinHandle;
+
+use crate::app::{commands::expand_home, App, PromptAction};
+use crate::db::{qualified_name, quote_ident};
+
+// Sample rows shown per CSV field in the wizard
+const PREVIEW_ROWS: usize = 3;
+
+// Where each field of a CSV file goes before it is loaded
+pub struct ImportWizard {
+    pub schema: String,
+    pub table: String,
+    pub path: String,
+    pub headers: Vec<String>,
+    pub samples: Vec<Vec<String>>,
+    pub columns: Vec<crate::db::Column>,
+    // Target column per CSV field; None skips the field
+    pub mapping: Vec<Option<usize>>,
+    pub selected: usize,
+}
+
+// A COPY running in the background; polled from the event loop
+pub struct ImportJob {
+    pub table: String,
+    pub path: String,
+    pub total_bytes: u64,
+    pub progress: Arc<AtomicU64>,
+    connection: String,
+    sql: String,
+    started: Instant,
+    handle: JoinHandle<Result<u64>>,
+}
+
+impl ImportJob {
+    // Share of the file sent so far, 0.0 to 1.0
+    pub fn ratio(&self) -> f64 {
+        if self.total_bytes == 0 {
+            return 1.0;
+        }
+        (self.progress.load(Ordering::Relaxed) as f64 / self.total_bytes as f64).min(1.0)
+    }
+}
+
+// Bulk CSV import into the table selected in the browser
+impl App {
+    pub fn begin_csv_import(&mut self) {
+        let Some((_, table)) = self.ws.selected_table.clone() else {
+            return;
+        };
+        if self.ws.selected_is_view {
+            self.set_error("Rows can only be imported into tables".to_string());
+            return;
+        }
+        if self.import_job.is_some() {
+            self.set_error("An import is already running".to_string());
+            return;
+        }
+        let initial = self.ws.file_dir();
+        self.open_prompt(&format!("Import CSV into {}", table), &initial, PromptAction::ImportCsv);
+    }
+
+    // Read the file's header and a few rows, and match fields to columns by name
+    pub fn open_import_wizard(&mut self, path: &str) -> Result<()> {
+        let (schema, table) = self.ws.selected_table.clone().ok_or_else(|| anyhow!("No table selected"))?;
+        if self.ws.columns.is_empty() {
+            bail!("No columns available for {}", table);
+        }
+        let path = expand_home(path);
+        let (headers, samples) = crate::db::read_csv_preview(&path, PREVIEW_ROWS)?;
+        let columns = self.ws.columns.clone();
+        let mapping = headers
+            .iter()
+            .map(|header| columns.iter().position(|c| c.name.eq_ignore_ascii_case(header.trim())))
+            .collect();
+        self.import_wizard = Some(ImportWizard {
+            schema,
+            table,
+            path,
+            headers,
+            samples,
+            columns,
+            mapping,
+            selected: 0,
+        });
+        Ok(())
+    }
+
+    pub fn close_import_wizard(&mut self) {
+        self.import_wizard = None;
+    }
+
+    pub fn handle_import_wizard_input(&mut self, key: KeyCode) {
+        let Some(wizard) = &mut self.import_wizard else {
+            return;
+        };
+        let last = wizard.headers.len().saturating_sub(1);
+        let count = wizard.columns.len();
+        let target = wizard.mapping[wizard.selected];
+        match key {
+            KeyCode::Up | KeyCode::BackTab => wizard.selected = wizard.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Tab => wizard.selected = (wizard.selected + 1).min(last),
+            // Cycle through the columns, with "skip" between the last and first
+            KeyCode::Right => {
+                wizard.mapping[wizard.selected] = match target {
+                    None => Some(0),
+                    Some(c) if c + 1 < count => Some(c + 1),
+                    Some(_) => None,
+                }
+            }
+            KeyCode::Left => {
+                wizard.mapping[wizard.selected] = match target {
+                    None => count.checked_sub(1),
+                    Some(0) => None,
+                    Some(c) => Some(c - 1),
+                }
+            }
+            KeyCode::Delete | KeyCode::Backspace => wizard.mapping[wizard.selected] = None,
+            _ => {}
+        }
+    }
+
+    // Start the COPY in the background with the wizard's mapping
+    pub fn start_import(&mut self) -> Result<()> {
+        let wizard = self.import_wizard.as_ref().ok_or_else(|| anyhow!("No import open"))?;
+
+        let (fields, columns): (Vec<usize>, Vec<usize>) = wizard
+            .mapping
+            .iter()
+            .enumerate()
+            .filter_map(|(field, column)| column.map(|c| (field, c)))
+            .unzip();
+        if fields.is_empty() {
+            bail!("Map at least one CSV field to a column");
+        }
+        if let Some((_, &twice)) = columns.iter().enumerate().find(|&(i, c)| columns[..i].contains(c)) {
+            bail!("Column {} is mapped more than once", wizard.columns[twice].name);
+        }
+
+        let client = self.ws.db.shared_client().ok_or_else(|| anyhow!("Not connected"))?;
+        let total_bytes = std::fs::metadata(&wizard.path)
+            .with_context(|| format!("Failed to read {}", wizard.path))?
+            .len();
+        let names: Vec<String> = columns.iter().map(|&c| quote_ident(&wizard.columns[c].name)).collect();
+        let sql = crate::db::copy_csv_sql(&qualified_name(&wizard.schema, &wizard.table), &names);
+        let progress = Arc::new(AtomicU64::new(0));
+        let handle = tokio::spawn(crate::db::copy_csv_in(
+            client,
+            sql.clone(),
+            fields,
+            wizard.headers.len(),
+            wizard.path.clone(),
+            progress.clone(),
+        ));
+
+        let table = wizard.table.clone();
+        self.import_job = Some(ImportJob {
+            table: table.clone(),
+            path: wizard.path.clone(),
+            total_bytes,
+            progress,
+            connection: self.ws.name.clone(),
+            sql,
+            started: Instant::now(),
+            handle,
+        });
+        self.import_wizard = None;
+        self.set_status(format!("Importing into {}...", table));
+        Ok(())
+    }
+
+    // Report a finished import; called on every pass of the event loop
+    pub async fn poll_import(&mut self) {
+        if !self.import_job.as_ref().is_some_and(|job| job.handle.is_finished()) {
+            return;
+        }
+        let Some(job) = self.import_job.take() else {
+            return;
+        };
+        let elapsed = job.started.elapsed();
+        let outcome = match job.handle.await {
+            Ok(outcome) => outcome,
+            Err(e) => Err(anyhow!("Import task failed: {}", e)),
+        };
+        match outcome {
+            Ok(rows) => {
+                let rows = rows as usize;
+                self.stats.record_query(&job.sql, Some(rows));
+                self.audit.record_statement(&job.connection, &job.sql, elapsed, Ok(rows));
+                self.set_status(format!(
+                    "Imported {} row(s) from {} into {} in {:.1}s",
+                    rows,
+                    job.path,
+                    job.table,
+                    elapsed.as_secs_f64()
+                ));
+            }
+            Err(e) => {
+                self.stats.record_query(&job.sql, None);
+                self.audit.record_statement(&job.connection, &job.sql, elapsed, Err(format!("{:#}", e)));
+                self.set_error(format!("Import into {} failed: {:#}", job.table, e));
+            }
+        }
+    }
+}
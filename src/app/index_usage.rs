@@ -0,0 +1,40 @@
+use crate::app::{App, AppMode, QueryFocus};
+use crate::db::{qualified_name, Index};
+
+// Indexes that have never been scanned and can be dropped on their own
+pub fn unused_indexes(indexes: &[Index]) -> impl Iterator<Item = &Index> {
+    indexes
+        .iter()
+        .filter(|index| index.scans == Some(0) && !index.backs_constraint)
+}
+
+impl App {
+    // Append DROP INDEX statements for the selected table's never-scanned
+    // indexes to the editor, to review and run by hand
+    pub fn write_drop_unused_indexes(&mut self) {
+        let Some((schema, table)) = self.ws.selected_table.clone() else {
+            return;
+        };
+        let drops: Vec<String> = unused_indexes(&self.ws.indexes)
+            .map(|index| format!("DROP INDEX {}; -- {}", qualified_name(&schema, &index.name), index.size))
+            .collect();
+        if drops.is_empty() {
+            self.set_warning(format!("{}.{} has no unused index that can be dropped on its own", schema, table));
+            return;
+        }
+        let mut snippet = format!(
+            "-- Indexes of {} never scanned since the statistics were last reset\n{}\n",
+            qualified_name(&schema, &table),
+            drops.join("\n")
+        );
+        if !self.ws.query_input.is_empty() {
+            let separator = if self.ws.query_input.ends_with('\n') { "\n" } else { "\n\n" };
+            snippet.insert_str(0, separator);
+        }
+        self.ws.query_cursor = self.ws.query_input.len();
+        self.paste_query_text(&snippet);
+        self.mode = AppMode::Query;
+        self.ws.query_focus = QueryFocus::Editor;
+        self.set_status(format!("Wrote {} DROP INDEX statement(s) to the editor", drops.len()));
+    }
+}
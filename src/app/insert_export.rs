@@ -0,0 +1,89 @@
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::app::commands::expand_home;
+use crate::app::{App, PromptAction};
+use crate::db::{qualified_name, quote_ident, quote_literal, QueryResult};
+
+// Types whose values are written without quotes when they are plain numbers
+const NUMERIC_TYPES: &[&str] = &["int2", "int4", "int8", "float4", "float8", "numeric", "oid"];
+
+// Rows of the grid as INSERT statements for their table, to carry a few
+// rows over to another database
+impl App {
+    pub fn begin_insert_export(&mut self) {
+        let Some(source) = self.ws.query_result.as_ref().and_then(|result| result.source.as_ref()) else {
+            self.set_warning("Only results from a single table can be exported as INSERTs".to_string());
+            return;
+        };
+        let initial = format!("{}{}.sql", self.ws.file_dir(), source.table);
+        self.open_prompt("Export rows as INSERT statements to", &initial, PromptAction::ExportInserts);
+    }
+
+    // The rows as the grid shows them, filtered and sorted
+    pub fn export_inserts(&mut self, path: &str) -> Result<()> {
+        let result = self.ws.query_result.as_ref().ok_or_else(|| anyhow!("No results"))?;
+        let rows = self
+            .ws
+            .filtered_rows()
+            .unwrap_or_else(|| (0..result.rows.len()).collect());
+        let sql = insert_statements(result, &rows)?;
+        let path = expand_home(path);
+        std::fs::write(&path, sql).with_context(|| format!("Failed to write {}", path))?;
+        self.audit.record_export(&path, rows.len());
+        let unread = if self.ws.db.has_pending_rows() || self.ws.result_capped {
+            " (rows not fetched yet were left out)"
+        } else {
+            ""
+        };
+        self.set_status(format!("Wrote {} INSERT statement(s) to {}{}", rows.len(), path, unread));
+        Ok(())
+    }
+}
+
+// One INSERT per row into the result's table, with the columns that come
+// from it; computed columns are left out
+fn insert_statements(result: &QueryResult, rows: &[usize]) -> Result<String> {
+    let source = result
+        .source
+        .as_ref()
+        .ok_or_else(|| anyhow!("Only results from a single table can be exported as INSERTs"))?;
+    let columns: Vec<(usize, &str)> = source
+        .column_names
+        .iter()
+        .enumerate()
+        .filter_map(|(i, name)| Some((i, name.as_deref()?)))
+        .collect();
+    if columns.is_empty() {
+        bail!("None of the columns comes straight from {}", source.table);
+    }
+    let prefix = format!(
+        "INSERT INTO {} ({}) VALUES (",
+        qualified_name(&source.schema, &source.table),
+        columns.iter().map(|(_, name)| quote_ident(name)).collect::<Vec<_>>().join(", ")
+    );
+    let mut out = String::new();
+    for &row in rows {
+        let values: Vec<String> = columns
+            .iter()
+            .map(|&(col, _)| literal(result.rows[row][col].as_deref(), result.column_type(col)))
+            .collect();
+        out.push_str(&format!("{}{});\n", prefix, values.join(", ")));
+    }
+    Ok(out)
+}
+
+// A cell as a literal its column reads back: NULL, bare numbers and
+// booleans, and everything else quoted, which the server casts to the
+// column's type on insert
+fn literal(value: Option<&str>, column_type: Option<&str>) -> String {
+    let Some(value) = value else {
+        return "NULL".to_string();
+    };
+    match column_type {
+        Some("bool") => value.to_uppercase(),
+        Some(kind) if NUMERIC_TYPES.contains(&kind) && value.parse::<f64>().is_ok_and(f64::is_finite) => {
+            value.to_string()
+        }
+        _ => quote_literal(value),
+    }
+}
@@ -0,0 +1,95 @@
+use anyhow::{anyhow, bail, Result};
+use crossterm::event::KeyCode;
+
+use crate::app::{App, InsertForm, PendingStatement};
+use crate::db::{qualified_name, quote_ident, quote_literal};
+
+// INSERT form for the table selected in the browser
+impl App {
+    pub fn open_insert_form(&mut self) {
+        let Some((schema, table)) = self.ws.selected_table.clone() else {
+            return;
+        };
+        if self.ws.selected_is_view {
+            self.set_error("Rows can only be inserted into tables".to_string());
+            return;
+        }
+        if self.ws.columns.is_empty() {
+            self.set_error(format!("No columns available for {}", table));
+            return;
+        }
+        self.insert_form = Some(InsertForm {
+            schema,
+            table,
+            values: vec![String::new(); self.ws.columns.len()],
+            columns: self.ws.columns.clone(),
+            selected: 0,
+        });
+    }
+
+    pub fn close_insert_form(&mut self) {
+        self.insert_form = None;
+    }
+
+    pub fn handle_insert_form_input(&mut self, key: KeyCode) {
+        let Some(form) = &mut self.insert_form else {
+            return;
+        };
+        let last = form.columns.len().saturating_sub(1);
+        match key {
+            KeyCode::Up | KeyCode::BackTab => form.selected = form.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Tab => form.selected = (form.selected + 1).min(last),
+            KeyCode::Char(c) => form.values[form.selected].push(c),
+            KeyCode::Backspace => {
+                form.values[form.selected].pop();
+            }
+            _ => {}
+        }
+    }
+
+    // Build the INSERT from the filled-in fields and ask for confirmation
+    pub fn preview_insert(&mut self) -> Result<()> {
+        let form = self.insert_form.as_ref().ok_or_else(|| anyhow!("No insert form open"))?;
+
+        let missing: Vec<&str> = form
+            .columns
+            .iter()
+            .zip(&form.values)
+            .filter(|(column, value)| value.is_empty() && column.is_required())
+            .map(|(column, _)| column.name.as_str())
+            .collect();
+        if !missing.is_empty() {
+            bail!("Required column(s) left empty: {}", missing.join(", "));
+        }
+
+        let (names, values): (Vec<String>, Vec<String>) = form
+            .columns
+            .iter()
+            .zip(&form.values)
+            .filter(|(_, value)| !value.is_empty())
+            .map(|(column, value)| {
+                let literal = if value == "NULL" { "NULL".to_string() } else { quote_literal(value) };
+                (quote_ident(&column.name), literal)
+            })
+            .unzip();
+
+        let target = qualified_name(&form.schema, &form.table);
+        let sql = if names.is_empty() {
+            format!("INSERT INTO {}\nDEFAULT VALUES;", target)
+        } else {
+            format!(
+                "INSERT INTO {} ({})\nVALUES ({});",
+                target,
+                names.join(", "),
+                values.join(", ")
+            )
+        };
+
+        self.pending = Some(PendingStatement {
+            title: format!("Insert into {}", form.table),
+            sql,
+        });
+        self.insert_form = None;
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use crate::app::{App, ResultPopup};
+use crate::db::QueryResult;
+
+// "Inspect token": documentation for the word under the editor cursor
+impl App {
+    // The language server answers first when there is one; the built-in
+    // reference covers keywords and common functions otherwise
+    pub async fn inspect_token(&mut self) {
+        let (text, cursor) = (&self.ws.query_input, self.ws.query_cursor);
+        if let Some(lsp) = &mut self.ws.lsp {
+            match lsp.hover(text, cursor).await {
+                Ok(Some(hover)) => {
+                    self.popup = Some(ResultPopup::text("Hover", &hover));
+                    return;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    if !lsp.is_running() {
+                        self.ws.lsp = None;
+                    }
+                    if crate::docs::lookup(text, cursor).is_none() {
+                        self.set_error(format!("{:#}", e));
+                        return;
+                    }
+                }
+            }
+        }
+        match crate::docs::lookup(&self.ws.query_input, self.ws.query_cursor) {
+            Some(entry) => self.popup = Some(ResultPopup::text(entry.name, &entry.render())),
+            None => self.set_status("No documentation for the word under the cursor".to_string()),
+        }
+    }
+}
+
+impl ResultPopup {
+    // A popup listing `text` one line per row
+    pub fn text(title: &str, text: &str) -> Self {
+        let rows: Vec<Vec<String>> = text.lines().map(|line| vec![line.to_string()]).collect();
+        Self {
+            title: title.to_string(),
+            result: QueryResult::from_text(vec!["Documentation".to_string()], rows),
+            scroll: 0,
+        }
+    }
+}
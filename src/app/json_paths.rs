@@ -0,0 +1,252 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use crossterm::event::KeyCode;
+use serde_json::Value;
+
+use crate::app::{App, QueryFocus};
+use crate::db::{quote_ident, quote_literal};
+
+// Rows of the result looked at, and how deep into nested values
+const SAMPLE_ROWS: usize = 500;
+const MAX_DEPTH: usize = 4;
+
+// One step into a JSON value: an object key, or every element of an array
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Step {
+    Key(String),
+    Each,
+}
+
+// A path found in the sampled values of a JSON column
+pub struct JsonPath {
+    // a.b[*].c
+    pub path: String,
+    // Sampled rows that have it
+    pub rows: usize,
+    // JSON types seen at the path
+    pub types: Vec<&'static str>,
+    // column->'a'->>'b'; paths through arrays have none
+    pub operators: Option<String>,
+    // jsonb_path_query(column, '$.a.b')
+    pub jsonpath: String,
+}
+
+impl JsonPath {
+    pub fn expression(&self, jsonpath: bool) -> &str {
+        match &self.operators {
+            Some(operators) if !jsonpath => operators,
+            _ => &self.jsonpath,
+        }
+    }
+}
+
+// Paths of the JSON column under the results cursor, to insert into the editor
+pub struct JsonPathPicker {
+    pub column: String,
+    pub sampled: usize,
+    pub paths: Vec<JsonPath>,
+    pub selected: usize,
+    // Insert jsonpath expressions instead of -> / ->> operators
+    pub jsonpath: bool,
+}
+
+impl App {
+    pub fn open_json_paths(&mut self) {
+        let Some(result) = &self.ws.query_result else {
+            return;
+        };
+        let col = self.ws.result_selected_col;
+        let Some(name) = result.columns.get(col) else {
+            return;
+        };
+        // The table's own column name works in a query on that table even
+        // when the result renamed it
+        let column = result
+            .source
+            .as_ref()
+            .and_then(|source| source.column_names.get(col).cloned().flatten())
+            .unwrap_or_else(|| name.clone());
+
+        let values: Vec<Value> = result
+            .rows
+            .iter()
+            .take(SAMPLE_ROWS)
+            .filter_map(|row| serde_json::from_str::<Value>(row.get(col)?.as_deref()?).ok())
+            .filter(|value| value.is_object() || value.is_array())
+            .collect();
+        if values.is_empty() {
+            self.set_warning(format!("{} holds no JSON objects or arrays in the fetched rows", name));
+            return;
+        }
+        let paths = infer_paths(&values, &quote_ident(&column));
+        if paths.is_empty() {
+            self.set_warning(format!("The fetched values of {} are all empty", name));
+            return;
+        }
+        self.json_path_picker = Some(JsonPathPicker {
+            column,
+            sampled: values.len(),
+            paths,
+            selected: 0,
+            jsonpath: false,
+        });
+    }
+
+    pub fn close_json_paths(&mut self) {
+        self.json_path_picker = None;
+    }
+
+    pub fn handle_json_paths_input(&mut self, key: KeyCode) {
+        let Some(picker) = &mut self.json_path_picker else {
+            return;
+        };
+        let last = picker.paths.len().saturating_sub(1);
+        match key {
+            KeyCode::Esc => self.close_json_paths(),
+            KeyCode::Up => picker.selected = picker.selected.saturating_sub(1),
+            KeyCode::Down => picker.selected = (picker.selected + 1).min(last),
+            KeyCode::Home => picker.selected = 0,
+            KeyCode::End => picker.selected = last,
+            KeyCode::Tab | KeyCode::BackTab => picker.jsonpath = !picker.jsonpath,
+            KeyCode::Enter => self.insert_json_path(),
+            _ => {}
+        }
+    }
+
+    // Put the selected expression at the editor's cursor and go there
+    fn insert_json_path(&mut self) {
+        let Some(picker) = self.json_path_picker.take() else {
+            return;
+        };
+        let Some(path) = picker.paths.get(picker.selected) else {
+            return;
+        };
+        self.ws.insert_text(path.expression(picker.jsonpath));
+        self.ws.query_focus = QueryFocus::Editor;
+    }
+}
+
+// Every key path of `values`, most common first, with the expressions that
+// read it from `column`
+fn infer_paths(values: &[Value], column: &str) -> Vec<JsonPath> {
+    let mut found: BTreeMap<Vec<Step>, (usize, BTreeSet<&'static str>)> = BTreeMap::new();
+    for value in values {
+        let mut in_row = HashSet::new();
+        walk(value, &mut Vec::new(), &mut in_row, &mut found);
+        for steps in in_row {
+            found.entry(steps).or_default().0 += 1;
+        }
+    }
+
+    let mut paths: Vec<JsonPath> = found
+        .into_iter()
+        .filter(|(steps, _)| steps.iter().any(|step| matches!(step, Step::Key(_))))
+        .map(|(steps, (rows, types))| {
+            let scalar = types.iter().all(|t| !matches!(*t, "object" | "array"));
+            JsonPath {
+                path: display_path(&steps),
+                rows,
+                operators: operator_expression(&steps, column, scalar),
+                jsonpath: format!("jsonb_path_query({}, {})", column, quote_literal(&jsonpath(&steps))),
+                types: types.into_iter().collect(),
+            }
+        })
+        .collect();
+    paths.sort_by(|a, b| b.rows.cmp(&a.rows).then_with(|| a.path.cmp(&b.path)));
+    paths
+}
+
+fn walk(
+    value: &Value,
+    steps: &mut Vec<Step>,
+    in_row: &mut HashSet<Vec<Step>>,
+    found: &mut BTreeMap<Vec<Step>, (usize, BTreeSet<&'static str>)>,
+) {
+    if steps.len() >= MAX_DEPTH {
+        return;
+    }
+    let mut visit = |step: Step, child: &Value| {
+        steps.push(step);
+        found.entry(steps.clone()).or_default().1.insert(type_name(child));
+        in_row.insert(steps.clone());
+        walk(child, steps, in_row, found);
+        steps.pop();
+    };
+    match value {
+        Value::Object(fields) => {
+            for (key, child) in fields {
+                visit(Step::Key(key.clone()), child);
+            }
+        }
+        Value::Array(elements) => {
+            for child in elements {
+                visit(Step::Each, child);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn display_path(steps: &[Step]) -> String {
+    let mut path = String::new();
+    for step in steps {
+        match step {
+            Step::Key(key) => {
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path.push_str(key);
+            }
+            Step::Each => path.push_str("[*]"),
+        }
+    }
+    path
+}
+
+// column->'a'->>'b', text at the last step unless the values there are
+// objects or arrays. Arrays would need a set-returning function, so paths
+// through them only get the jsonpath form.
+fn operator_expression(steps: &[Step], column: &str, scalar: bool) -> Option<String> {
+    let mut expression = column.to_string();
+    for (i, step) in steps.iter().enumerate() {
+        let Step::Key(key) = step else {
+            return None;
+        };
+        let operator = if i + 1 == steps.len() && scalar { "->>" } else { "->" };
+        expression.push_str(operator);
+        expression.push_str(&quote_literal(key));
+    }
+    Some(expression)
+}
+
+// $.a."b c"[*].d
+fn jsonpath(steps: &[Step]) -> String {
+    let mut path = "$".to_string();
+    for step in steps {
+        match step {
+            Step::Key(key) => {
+                let plain = key.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                    && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+                if plain {
+                    path.push('.');
+                    path.push_str(key);
+                } else {
+                    path.push_str(&format!(".\"{}\"", key.replace('\\', "\\\\").replace('"', "\\\"")));
+                }
+            }
+            Step::Each => path.push_str("[*]"),
+        }
+    }
+    path
+}
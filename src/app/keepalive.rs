@@ -0,0 +1,64 @@
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use tokio::task::JoinHandle;
+
+use crate::app::{App, Workspace};
+
+// A ping that takes longer than this counts as failed
+const PING_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub type KeepaliveJob = JoinHandle<Result<()>>;
+
+// `SELECT 1` on every open connection once per `keepalive_secs`, in the
+// background, so idle sessions survive NAT and firewall timeouts
+impl App {
+    // Called on every pass of the event loop
+    pub async fn poll_keepalive(&mut self) {
+        if self.config.keepalive_secs == 0 {
+            return;
+        }
+        let interval = Duration::from_secs(self.config.keepalive_secs);
+        let mut failures = Vec::new();
+        for ws in std::iter::once(&mut self.ws).chain(&mut self.workspaces) {
+            if let Some(Err(e)) = finish_ping(ws).await {
+                failures.push(format!("Keepalive on {} failed: {:#}", ws.name, e));
+            }
+            start_ping(ws, interval);
+        }
+        for failure in failures {
+            self.set_warning(failure);
+        }
+    }
+}
+
+fn start_ping(ws: &mut Workspace, interval: Duration) {
+    // Unread rows hold the connection, and a lost one is being reconnected
+    if ws.keepalive.is_some()
+        || ws.last_ping.elapsed() < interval
+        || ws.db.has_pending_rows()
+        || ws.db.lost().is_some()
+    {
+        return;
+    }
+    let Some(client) = ws.db.shared_client() else {
+        return;
+    };
+    ws.last_ping = Instant::now();
+    ws.keepalive = Some(tokio::spawn(async move {
+        match tokio::time::timeout(PING_TIMEOUT, client.simple_query("SELECT 1")).await {
+            Ok(result) => result.map(|_| ()).map_err(|e| anyhow!(e)),
+            Err(_) => Err(anyhow!("no answer within {}s", PING_TIMEOUT.as_secs())),
+        }
+    }));
+}
+
+async fn finish_ping(ws: &mut Workspace) -> Option<Result<()>> {
+    if !ws.keepalive.as_ref().is_some_and(|job| job.is_finished()) {
+        return None;
+    }
+    match ws.keepalive.take()?.await {
+        Ok(result) => Some(result),
+        Err(e) => Some(Err(anyhow!("{}", e))),
+    }
+}
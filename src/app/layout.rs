@@ -0,0 +1,84 @@
+use anyhow::Result;
+
+use crate::app::App;
+use crate::config::LayoutProfile;
+
+// Window layout profiles
+impl App {
+    pub fn layout(&self) -> LayoutProfile {
+        self.config
+            .layouts
+            .get(self.config.active_layout)
+            .cloned()
+            .unwrap_or_else(LayoutProfile::exploration)
+    }
+
+    fn layout_mut(&mut self) -> &mut LayoutProfile {
+        if self.config.layouts.is_empty() {
+            self.config.layouts.push(LayoutProfile::exploration());
+        }
+        let index = self.config.active_layout.min(self.config.layouts.len() - 1);
+        self.config.active_layout = index;
+        &mut self.config.layouts[index]
+    }
+
+    fn save_layouts(&mut self) {
+        if let Err(e) = self.config.save() {
+            self.set_error(format!("Failed to save layout: {}", e));
+        }
+    }
+
+    pub fn cycle_layout(&mut self) {
+        if self.config.layouts.is_empty() {
+            return;
+        }
+        self.config.active_layout = (self.config.active_layout + 1) % self.config.layouts.len();
+        self.save_layouts();
+    }
+
+    pub fn resize_browser(&mut self, delta: i16) {
+        let layout = self.layout_mut();
+        layout.browser_width = (layout.browser_width as i16 + delta).clamp(10, 90) as u16;
+        self.save_layouts();
+    }
+
+    pub fn resize_editor(&mut self, delta: i16) {
+        let layout = self.layout_mut();
+        layout.editor_height = (layout.editor_height as i16 + delta).clamp(3, 40) as u16;
+        self.save_layouts();
+    }
+
+    pub fn toggle_details_pane(&mut self) {
+        let layout = self.layout_mut();
+        layout.show_details = !layout.show_details;
+        self.save_layouts();
+    }
+
+    pub fn toggle_status_bar(&mut self) {
+        let layout = self.layout_mut();
+        layout.show_status_bar = !layout.show_status_bar;
+        self.save_layouts();
+    }
+
+    // Save the current layout under `name`, replacing a layout with the same name
+    pub fn save_layout_as(&mut self, name: &str) -> Result<()> {
+        if name.is_empty() {
+            return Ok(());
+        }
+
+        let mut layout = self.layout();
+        layout.name = name.to_string();
+
+        match self.config.layouts.iter().position(|l| l.name == name) {
+            Some(index) => {
+                self.config.layouts[index] = layout;
+                self.config.active_layout = index;
+            }
+            None => {
+                self.config.layouts.push(layout);
+                self.config.active_layout = self.config.layouts.len() - 1;
+            }
+        }
+        self.config.save()
+    }
+}
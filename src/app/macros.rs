@@ -0,0 +1,71 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::app::{App, MacroInput, MacroState};
+use crate::keymap::{Action, Scope};
+
+// Key macro recording and replay
+impl App {
+    // Runs before normal key handling. Recorded keys are replayed through
+    // the regular dispatch, so a macro does exactly what typing it would.
+    pub fn macro_input(&mut self, key: KeyEvent) -> MacroInput {
+        let action = self.keymap.lookup(key, Scope::Global);
+        match (self.macro_state, key.code) {
+            (MacroState::Recording(register), _) if action == Some(Action::RecordMacro) => {
+                let keys = std::mem::take(&mut self.macro_buffer);
+                self.set_status(format!("Recorded macro '{}' ({} keys)", register, keys.len()));
+                self.macros.insert(register, keys);
+                self.last_macro = Some(register);
+                self.macro_state = MacroState::Idle;
+                MacroInput::Consumed
+            }
+            (MacroState::Recording(_), _) => {
+                self.macro_buffer.push(key);
+                MacroInput::Dispatch(vec![key])
+            }
+            (MacroState::Idle, _) if action == Some(Action::RecordMacro) => {
+                self.macro_state = MacroState::AwaitingRecordRegister;
+                self.set_status("Record macro: press a register key (a-z)".to_string());
+                MacroInput::Consumed
+            }
+            (MacroState::Idle, _) if action == Some(Action::ReplayMacro) => {
+                self.macro_state = MacroState::AwaitingReplayRegister;
+                let replay = self.keymap.label(Action::ReplayMacro);
+                self.set_status(format!("Replay macro: press a register key, or {} for the last one", replay));
+                MacroInput::Consumed
+            }
+            (MacroState::AwaitingRecordRegister, KeyCode::Char(register)) if register.is_ascii_alphanumeric() => {
+                self.macro_buffer.clear();
+                self.macro_state = MacroState::Recording(register);
+                let stop = self.keymap.label(Action::RecordMacro);
+                self.set_status(format!("Recording macro '{}' ({} to stop)", register, stop));
+                MacroInput::Consumed
+            }
+            (MacroState::AwaitingReplayRegister, code)
+                if action == Some(Action::ReplayMacro) || matches!(code, KeyCode::Char(_)) =>
+            {
+                self.macro_state = MacroState::Idle;
+                let register = match code {
+                    KeyCode::Char(c) if action != Some(Action::ReplayMacro) => Some(c),
+                    _ => self.last_macro,
+                };
+                match register.and_then(|r| self.macros.get(&r).map(|keys| (r, keys.clone()))) {
+                    Some((register, keys)) => {
+                        self.last_macro = Some(register);
+                        self.clear_notifications();
+                        MacroInput::Dispatch(keys)
+                    }
+                    None => {
+                        self.set_error("No such macro".to_string());
+                        MacroInput::Consumed
+                    }
+                }
+            }
+            (MacroState::AwaitingRecordRegister | MacroState::AwaitingReplayRegister, _) => {
+                self.macro_state = MacroState::Idle;
+                self.clear_notifications();
+                MacroInput::Consumed
+            }
+            (MacroState::Idle, _) => MacroInput::Dispatch(vec![key]),
+        }
+    }
+}
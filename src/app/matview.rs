@@ -0,0 +1,85 @@
+use std::time::Instant;
+
+use anyhow::{anyhow, bail, Context, Result};
+use tokio::task::JoinHandle;
+
+use crate::app::{App, PromptAction};
+
+// A REFRESH MATERIALIZED VIEW running in the background; polled from the
+// event loop like an import
+pub struct RefreshJob {
+    pub view: String,
+    pub started: Instant,
+    connection: String,
+    sql: String,
+    handle: JoinHandle<Result<()>>,
+}
+
+// Refreshing the materialized view selected in the browser
+impl App {
+    pub fn begin_matview_refresh(&mut self) {
+        let Some((schema, view)) = &self.ws.selected_table else {
+            return;
+        };
+        if self.refresh_job.is_some() {
+            self.set_error("A refresh is already running".to_string());
+            return;
+        }
+        let title = format!(
+            "Refresh materialized view {}.{}? (y = refresh, c = concurrently, n = cancel)",
+            schema, view
+        );
+        self.open_prompt(&title, "", PromptAction::RefreshMatview);
+    }
+
+    pub fn answer_matview_refresh(&mut self, answer: &str) -> Result<()> {
+        let concurrently = match answer.to_ascii_lowercase().as_str() {
+            "y" | "yes" => false,
+            "c" | "concurrently" => true,
+            _ => return Ok(()),
+        };
+        let (schema, view) = self.ws.selected_table.clone().ok_or_else(|| anyhow!("No view selected"))?;
+        // Checked here because the server's own error doesn't say what to do
+        if concurrently && !self.ws.indexes.iter().any(|index| index.is_unique) {
+            bail!("REFRESH ... CONCURRENTLY needs a unique index on {}", view);
+        }
+        let client = self.ws.db.shared_client().ok_or_else(|| anyhow!("Not connected"))?;
+        let sql = crate::db::refresh_materialized_view_sql(&schema, &view, concurrently);
+        let statement = sql.clone();
+        let handle = tokio::spawn(async move {
+            client
+                .batch_execute(&statement)
+                .await
+                .context("Refresh failed")
+        });
+        self.refresh_job = Some(RefreshJob {
+            view: format!("{}.{}", schema, view),
+            started: Instant::now(),
+            connection: self.ws.name.clone(),
+            sql,
+            handle,
+        });
+        Ok(())
+    }
+
+    // Report a finished refresh; called on every pass of the event loop
+    pub async fn poll_refresh(&mut self) {
+        if !self.refresh_job.as_ref().is_some_and(|job| job.handle.is_finished()) {
+            return;
+        }
+        let Some(job) = self.refresh_job.take() else {
+            return;
+        };
+        let elapsed = job.started.elapsed();
+        let outcome = match job.handle.await {
+            Ok(outcome) => outcome,
+            Err(e) => Err(anyhow!("Refresh task failed: {}", e)),
+        };
+        let audited = outcome.as_ref().map(|_| 0).map_err(|e| format!("{:#}", e));
+        self.audit.record_statement(&job.connection, &job.sql, elapsed, audited);
+        match outcome {
+            Ok(()) => self.set_status(format!("Refreshed {} in {:.1}s", job.view, elapsed.as_secs_f64())),
+            Err(e) => self.set_error(format!("Refreshing {} failed: {:#}", job.view, e)),
+        }
+    }
+}
@@ -0,0 +1,331 @@
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use crossterm::event::KeyCode;
+
+use crate::app::{App, AppMode, PromptAction, ResultPopup};
+use crate::db::{Activity, LockWait, QueryResult, Transient};
+
+// Rows moved by PgUp/PgDn
+const PAGE: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MonitorTab {
+    Activity,
+    Locks,
+}
+
+// Live pg_stat_activity of the active connection's server
+pub struct ActivityMonitor {
+    pub tab: MonitorTab,
+    pub sessions: Vec<Activity>,
+    pub locks: Vec<LockWait>,
+    // Blocker -> blocked tree of `locks` as (depth, index) lines
+    pub lock_tree: Vec<(usize, usize)>,
+    pub selected: usize,
+    // Longest running first instead of by pid
+    pub by_duration: bool,
+    // Why the last refresh failed; the previous list stays up meanwhile
+    pub error: Option<String>,
+    refreshed: Option<Instant>,
+    // When the connection dropped under the monitor, e.g. in a failover.
+    // Refreshes wait until the connection's reconnect gets it back.
+    lost_since: Option<Instant>,
+    // Screen to go back to
+    return_mode: AppMode,
+    // Backend waiting for confirmation, and whether to terminate it
+    // rather than cancel its query
+    signal: Option<(i32, bool)>,
+}
+
+impl ActivityMonitor {
+    fn len(&self) -> usize {
+        match self.tab {
+            MonitorTab::Activity => self.sessions.len(),
+            MonitorTab::Locks => self.lock_tree.len(),
+        }
+    }
+
+    // Pid and user of the backend under the cursor
+    fn selected_backend(&self) -> Option<(i32, &str)> {
+        match self.tab {
+            MonitorTab::Activity => self.sessions.get(self.selected).map(|s| (s.pid, s.user.as_str())),
+            MonitorTab::Locks => {
+                let &(_, index) = self.lock_tree.get(self.selected)?;
+                self.locks.get(index).map(|l| (l.pid, l.user.as_str()))
+            }
+        }
+    }
+
+    fn sort(&mut self) {
+        let pid = self.sessions.get(self.selected).map(|s| s.pid);
+        if self.by_duration {
+            self.sessions
+                .sort_by(|a, b| b.duration.unwrap_or(-1.0).total_cmp(&a.duration.unwrap_or(-1.0)));
+        } else {
+            self.sessions.sort_by_key(|s| s.pid);
+        }
+        // The cursor stays on the same backend while the list changes under it
+        self.selected = pid
+            .and_then(|pid| self.sessions.iter().position(|s| s.pid == pid))
+            .unwrap_or(self.selected)
+            .min(self.sessions.len().saturating_sub(1));
+    }
+}
+
+// Lines of the blocker -> blocked tree: every backend that blocks others
+// without waiting itself is a root, with the backends waiting for it below.
+// A backend waiting for several others shows up under each of them.
+pub fn lock_tree(locks: &[LockWait]) -> Vec<(usize, usize)> {
+    fn add(locks: &[LockWait], index: usize, depth: usize, path: &mut Vec<i32>, lines: &mut Vec<(usize, usize)>) {
+        lines.push((depth, index));
+        path.push(locks[index].pid);
+        for (child, lock) in locks.iter().enumerate() {
+            // A deadlock is a cycle; it is shown once around
+            if lock.blocked_by.contains(&locks[index].pid) && !path.contains(&lock.pid) {
+                add(locks, child, depth + 1, path, lines);
+            }
+        }
+        path.pop();
+    }
+
+    let known = |pid: &i32| locks.iter().any(|l| l.pid == *pid);
+    let mut roots: Vec<usize> = (0..locks.len()).filter(|&i| !locks[i].blocked_by.iter().any(known)).collect();
+    // Only waiters left means every one of them is in a deadlock cycle
+    if roots.is_empty() && !locks.is_empty() {
+        roots.push(0);
+    }
+    let mut lines = Vec::new();
+    for root in roots {
+        add(locks, root, 0, &mut Vec::new(), &mut lines);
+    }
+    lines
+}
+
+impl App {
+    pub fn open_monitor(&mut self) {
+        if !self.ws.db.is_connected() {
+            return;
+        }
+        self.monitor = Some(ActivityMonitor {
+            tab: MonitorTab::Activity,
+            sessions: Vec::new(),
+            locks: Vec::new(),
+            lock_tree: Vec::new(),
+            selected: 0,
+            by_duration: false,
+            error: None,
+            refreshed: None,
+            lost_since: None,
+            return_mode: self.mode,
+            signal: None,
+        });
+        self.mode = AppMode::Monitor;
+    }
+
+    pub fn close_monitor(&mut self) {
+        if let Some(monitor) = self.monitor.take() {
+            self.mode = monitor.return_mode;
+        }
+    }
+
+    pub fn monitor_interval(&self) -> Duration {
+        Duration::from_secs(self.config.monitor_refresh_secs.max(1))
+    }
+
+    // Called on every pass of the event loop; reloads the list once per
+    // interval, or tries to get the lost connection back
+    pub async fn poll_monitor(&mut self) {
+        let interval = self.monitor_interval();
+        let Some(monitor) = &self.monitor else {
+            return;
+        };
+        if monitor.refreshed.is_some_and(|at| at.elapsed() < interval) {
+            return;
+        }
+        if monitor.lost_since.is_some() {
+            self.resume_monitor().await;
+        } else {
+            self.refresh_monitor().await;
+        }
+    }
+
+    // Pick the refreshes up where they stopped once the connection's own
+    // reconnect (with its backoff and timeout) got the session back; the
+    // gap is reported
+    async fn resume_monitor(&mut self) {
+        let reconnecting = self.ws.reconnect_status();
+        let Some(monitor) = &mut self.monitor else {
+            return;
+        };
+        let Some(lost_since) = monitor.lost_since else {
+            return;
+        };
+        let gap = format_duration(lost_since.elapsed().as_secs_f64());
+        if let Some(status) = reconnecting {
+            monitor.refreshed = Some(Instant::now());
+            monitor.error = Some(format!("Connection lost {} ago; {}", gap, status));
+            return;
+        }
+        monitor.lost_since = None;
+        self.set_warning(format!(
+            "Back on {} after {} without a connection; the activity monitor resumed",
+            self.ws.name, gap
+        ));
+        self.refresh_monitor().await;
+    }
+
+    // Reload the list of the tab being shown
+    async fn refresh_monitor(&mut self) {
+        let (Some(client), Some(monitor)) = (self.ws.db.client(), &mut self.monitor) else {
+            return;
+        };
+        monitor.refreshed = Some(Instant::now());
+        let loaded = match monitor.tab {
+            MonitorTab::Activity => crate::db::list_activity(client).await.map(|sessions| {
+                monitor.sessions = sessions;
+                monitor.sort();
+            }),
+            MonitorTab::Locks => crate::db::list_lock_waits(client).await.map(|locks| {
+                monitor.lock_tree = lock_tree(&locks);
+                monitor.locks = locks;
+                monitor.selected = monitor.selected.min(monitor.lock_tree.len().saturating_sub(1));
+            }),
+        };
+        if let Err(e) = &loaded {
+            if crate::db::transient_failure(e) == Some(Transient::Disconnected) && monitor.lost_since.is_none() {
+                monitor.lost_since = Some(Instant::now());
+            }
+        }
+        monitor.error = loaded.err().map(|e| match monitor.lost_since {
+            Some(_) => format!("Connection lost, reconnecting: {:#}", e),
+            None => format!("{:#}", e),
+        });
+    }
+
+    pub async fn handle_monitor_input(&mut self, key: KeyCode) {
+        let Some(monitor) = &mut self.monitor else {
+            return;
+        };
+        let last = monitor.len().saturating_sub(1);
+        match key {
+            KeyCode::Esc | KeyCode::Char('q') => self.close_monitor(),
+            KeyCode::Tab | KeyCode::BackTab => {
+                monitor.tab = match monitor.tab {
+                    MonitorTab::Activity => MonitorTab::Locks,
+                    MonitorTab::Locks => MonitorTab::Activity,
+                };
+                monitor.selected = 0;
+                self.refresh_monitor().await;
+            }
+            KeyCode::Up => monitor.selected = monitor.selected.saturating_sub(1),
+            KeyCode::Down => monitor.selected = (monitor.selected + 1).min(last),
+            KeyCode::PageUp => monitor.selected = monitor.selected.saturating_sub(PAGE),
+            KeyCode::PageDown => monitor.selected = (monitor.selected + PAGE).min(last),
+            KeyCode::Home => monitor.selected = 0,
+            KeyCode::End => monitor.selected = last,
+            KeyCode::Char('s') if monitor.tab == MonitorTab::Activity => {
+                monitor.by_duration = !monitor.by_duration;
+                monitor.sort();
+            }
+            KeyCode::Char('r') => self.refresh_monitor().await,
+            KeyCode::Char('c') => self.confirm_signal(false),
+            KeyCode::Char('k') => self.confirm_signal(true),
+            KeyCode::Enter => self.show_session(),
+            _ => {}
+        }
+    }
+
+    fn confirm_signal(&mut self, terminate: bool) {
+        let Some(monitor) = &mut self.monitor else {
+            return;
+        };
+        let Some((pid, user)) = monitor.selected_backend() else {
+            return;
+        };
+        let title = if terminate {
+            format!("Terminate backend {} of {}, ending its session? (y/n)", pid, user)
+        } else {
+            format!("Cancel the running query of backend {} ({})? (y/n)", pid, user)
+        };
+        monitor.signal = Some((pid, terminate));
+        self.open_prompt(&title, "", PromptAction::SignalBackend);
+    }
+
+    pub async fn answer_signal(&mut self, answer: &str) -> Result<()> {
+        let Some((pid, terminate)) = self.monitor.as_mut().and_then(|m| m.signal.take()) else {
+            return Ok(());
+        };
+        if !answer.eq_ignore_ascii_case("y") && !answer.eq_ignore_ascii_case("yes") {
+            return Ok(());
+        }
+        let client = self.ws.db.client().ok_or_else(|| anyhow!("Not connected"))?;
+        let function = if terminate { "pg_terminate_backend" } else { "pg_cancel_backend" };
+        let started = Instant::now();
+        let signalled = crate::db::signal_backend(client, pid, terminate).await;
+        let outcome = signalled.as_ref().map(|_| 1).map_err(|e| format!("{:#}", e));
+        self.audit
+            .record_statement(&self.ws.name, &format!("SELECT {}({})", function, pid), started.elapsed(), outcome);
+        match signalled? {
+            true if terminate => self.set_status(format!("Backend {} terminated", pid)),
+            true => self.set_status(format!("Query of backend {} cancelled", pid)),
+            false => self.set_warning(format!("Backend {} is gone", pid)),
+        }
+        self.refresh_monitor().await;
+        Ok(())
+    }
+
+    // Every field of the selected backend, with its full query
+    fn show_session(&mut self) {
+        let Some(monitor) = &self.monitor else {
+            return;
+        };
+        let field = |name: &str, value: String| vec![name.to_string(), value];
+        let rows = match monitor.tab {
+            MonitorTab::Activity => {
+                let Some(session) = monitor.sessions.get(monitor.selected) else {
+                    return;
+                };
+                vec![
+                    field("pid", session.pid.to_string()),
+                    field("user", session.user.clone()),
+                    field("database", session.database.clone()),
+                    field("state", session.state.clone()),
+                    field("wait event", session.wait_event.clone()),
+                    field("duration", session.duration.map(format_duration).unwrap_or_default()),
+                    field("query", session.query.clone()),
+                ]
+            }
+            MonitorTab::Locks => {
+                let Some(lock) = monitor.lock_tree.get(monitor.selected).map(|&(_, i)| &monitor.locks[i]) else {
+                    return;
+                };
+                let blockers: Vec<String> = lock.blocked_by.iter().map(|pid| pid.to_string()).collect();
+                vec![
+                    field("pid", lock.pid.to_string()),
+                    field("user", lock.user.clone()),
+                    field("state", lock.state.clone()),
+                    field("waiting for", lock.waiting_for.clone()),
+                    field("blocked by", blockers.join(", ")),
+                    field("duration", lock.duration.map(format_duration).unwrap_or_default()),
+                    field("query", lock.query.clone()),
+                ]
+            }
+        };
+        self.popup = Some(ResultPopup {
+            title: format!("Backend {}", rows[0][1]),
+            result: QueryResult::from_text(vec!["Field".to_string(), "Value".to_string()], rows),
+            scroll: 0,
+        });
+    }
+}
+
+// "4.2s", "3m 05s", "2h 14m"
+pub fn format_duration(seconds: f64) -> String {
+    let whole = seconds.max(0.0) as u64;
+    match whole {
+        0..60 => format!("{:.1}s", seconds.max(0.0)),
+        60..3600 => format!("{}m {:02}s", whole / 60, whole % 60),
+        _ => format!("{}h {:02}m", whole / 3600, whole % 3600 / 60),
+    }
+}
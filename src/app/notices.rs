@@ -0,0 +1,23 @@
+use crate::app::{App, NotificationLevel};
+use crate::db::ServerNotice;
+
+// NOTICE/WARNING messages the server sends while a statement runs, kept with
+// the statement's results
+impl App {
+    // Whatever arrived before a statement (hooks, background refreshes)
+    // doesn't belong to it
+    pub(super) fn discard_notices(&self) {
+        self.ws.db.take_notices();
+    }
+
+    // Messages of the statement that just ran; each also goes to the
+    // message log, where they outlive the results
+    pub(super) fn collect_notices(&mut self) -> Vec<ServerNotice> {
+        let notices = self.ws.db.take_notices();
+        for notice in &notices {
+            let level = if notice.is_warning() { NotificationLevel::Warn } else { NotificationLevel::Info };
+            self.log_message(level, notice.describe());
+        }
+        notices
+    }
+}
@@ -0,0 +1,143 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::app::{App, ResultPopup};
+use crate::db::QueryResult;
+
+// Oldest entries are dropped from the log past this many
+const LOG_LIMIT: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationLevel {
+    Info,
+    Warn,
+    Error,
+}
+
+impl NotificationLevel {
+    pub fn label(self) -> &'static str {
+        match self {
+            NotificationLevel::Info => "INFO",
+            NotificationLevel::Warn => "WARN",
+            NotificationLevel::Error => "ERROR",
+        }
+    }
+
+    // Errors stay up longest so they aren't missed
+    fn lifetime(self) -> Duration {
+        match self {
+            NotificationLevel::Info => Duration::from_secs(4),
+            NotificationLevel::Warn => Duration::from_secs(8),
+            NotificationLevel::Error => Duration::from_secs(12),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub level: NotificationLevel,
+    pub message: String,
+    pub created: Instant,
+    // Wall-clock time, for the log
+    pub at: SystemTime,
+    // Hidden before expiry, e.g. when the next action succeeded
+    pub dismissed: bool,
+}
+
+impl Notification {
+    // Still shown as a toast
+    pub fn is_active(&self) -> bool {
+        !self.dismissed && self.created.elapsed() < self.level.lifetime()
+    }
+}
+
+// Toasts for errors and feedback, plus the log of everything shown this session
+impl App {
+    pub fn notify(&mut self, level: NotificationLevel, message: String) {
+        self.push_notification(level, message, false);
+    }
+
+    // Straight to the log without a toast, e.g. each failure of a script
+    // run that gets one summary toast
+    pub fn log_message(&mut self, level: NotificationLevel, message: String) {
+        self.push_notification(level, message, true);
+    }
+
+    fn push_notification(&mut self, level: NotificationLevel, message: String, dismissed: bool) {
+        if self.notifications.len() == LOG_LIMIT {
+            self.notifications.remove(0);
+        }
+        self.notifications.push(Notification {
+            level,
+            message,
+            created: Instant::now(),
+            at: SystemTime::now(),
+            dismissed,
+        });
+    }
+
+    pub fn set_error(&mut self, msg: String) {
+        self.notify(NotificationLevel::Error, msg);
+    }
+
+    pub fn set_warning(&mut self, msg: String) {
+        self.notify(NotificationLevel::Warn, msg);
+    }
+
+    pub fn set_status(&mut self, msg: String) {
+        self.notify(NotificationLevel::Info, msg);
+    }
+
+    // Hides the current toasts; they stay in the log
+    pub fn clear_notifications(&mut self) {
+        for notification in &mut self.notifications {
+            notification.dismissed = true;
+        }
+    }
+
+    // Newest last
+    pub fn active_notifications(&self) -> impl Iterator<Item = &Notification> {
+        self.notifications.iter().filter(|n| n.is_active())
+    }
+
+    pub fn show_notification_log(&mut self) {
+        if self.notifications.is_empty() {
+            self.set_status("No messages yet".to_string());
+            return;
+        }
+        let rows: Vec<Vec<String>> = self
+            .notifications
+            .iter()
+            .rev()
+            .map(|n| {
+                vec![
+                    format_clock(n.at),
+                    format_age(n.created.elapsed()),
+                    n.level.label().to_string(),
+                    n.message.clone(),
+                ]
+            })
+            .collect();
+        let columns = vec!["Time (UTC)".to_string(), "Age".to_string(), "Level".to_string(), "Message".to_string()];
+        self.popup = Some(ResultPopup {
+            title: "Messages".to_string(),
+            result: QueryResult::from_text(columns, rows),
+            scroll: 0,
+        });
+    }
+}
+
+// "42s ago", "3m ago", "1h 5m ago"
+fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    match secs {
+        0..=59 => format!("{}s ago", secs),
+        60..=3599 => format!("{}m ago", secs / 60),
+        _ => format!("{}h {}m ago", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+// "14:03:27"
+fn format_clock(at: SystemTime) -> String {
+    let secs = at.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0) % 86_400;
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs % 3600 / 60, secs % 60)
+}
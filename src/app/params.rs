@@ -0,0 +1,173 @@
+use anyhow::{anyhow, bail, Result};
+use crossterm::event::KeyCode;
+
+use crate::app::App;
+
+// Values to bind to a query's placeholders before it runs
+pub struct ParamForm {
+    // The query with `:name` placeholders rewritten to $1, $2, ...
+    pub sql: String,
+    // "$1" or ":name", in parameter order
+    pub names: Vec<String>,
+    // The type the server infers for each
+    pub types: Vec<String>,
+    pub values: Vec<String>,
+    pub selected: usize,
+}
+
+impl App {
+    // True when `sql` has placeholders and now waits for their values
+    pub(super) async fn hold_params(&mut self, sql: &str) -> Result<bool> {
+        let Some((sql, names)) = placeholders(sql)? else {
+            return Ok(false);
+        };
+        let client = self.ws.db.client().ok_or_else(|| anyhow!("Not connected"))?;
+        let types = crate::db::param_types(client, &sql).await?;
+        // $n numbering can skip some; the server still wants them all
+        let names: Vec<String> = if names.is_empty() {
+            (1..=types.len()).map(|n| format!("${}", n)).collect()
+        } else {
+            names
+        };
+        let values = names
+            .iter()
+            .map(|name| self.ws.param_values.get(name).cloned().unwrap_or_default())
+            .collect();
+        self.param_form = Some(ParamForm {
+            sql,
+            names,
+            types,
+            values,
+            selected: 0,
+        });
+        Ok(true)
+    }
+
+    pub fn close_param_form(&mut self) {
+        self.param_form = None;
+    }
+
+    pub fn handle_param_form_input(&mut self, key: KeyCode) {
+        let Some(form) = &mut self.param_form else {
+            return;
+        };
+        let last = form.names.len().saturating_sub(1);
+        match key {
+            KeyCode::Up | KeyCode::BackTab => form.selected = form.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Tab => form.selected = (form.selected + 1).min(last),
+            KeyCode::Char(c) => form.values[form.selected].push(c),
+            KeyCode::Backspace => {
+                form.values[form.selected].pop();
+            }
+            _ => {}
+        }
+    }
+
+    // Run the query with the filled-in values, each sent as text for the
+    // server to read as the parameter's type; NULL binds a null
+    pub async fn submit_params(&mut self) -> Result<()> {
+        let Some(form) = self.param_form.take() else {
+            return Ok(());
+        };
+        let params: Vec<Option<String>> = form
+            .values
+            .iter()
+            .map(|value| (value != "NULL").then(|| value.clone()))
+            .collect();
+        for (name, value) in form.names.into_iter().zip(form.values) {
+            self.ws.param_values.insert(name, value);
+        }
+        if self.hold_costly(&form.sql, &params).await {
+            return Ok(());
+        }
+        self.run_interactive_query(form.sql, params, true).await?;
+        self.reset_result_view();
+        Ok(())
+    }
+}
+
+// The query's placeholders: `$n` ones as written (with no names, as the
+// server numbers them), or `:name` ones rewritten to $1, $2, ... with their
+// names in that order. None when there are none. Strings, quoted names,
+// comments, dollar-quoted bodies and `::` casts are skipped.
+fn placeholders(sql: &str) -> Result<Option<(String, Vec<String>)>> {
+    let bytes = sql.as_bytes();
+    let mut rewritten = String::new();
+    let mut names: Vec<String> = Vec::new();
+    let mut numbered = false;
+    let mut copied = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let rest = &sql[i..];
+        let after_word = i > 0 && (is_word(bytes[i - 1]) || bytes[i - 1] == b']' || bytes[i - 1] == b')');
+        match bytes[i] {
+            b'\'' | b'"' => i = closing(sql, i + 1, bytes[i]),
+            b'-' if rest.starts_with("--") => i = rest.find('\n').map_or(sql.len(), |end| i + end),
+            b'/' if rest.starts_with("/*") => i = rest[2..].find("*/").map_or(sql.len(), |end| i + end + 4),
+            b':' if rest.starts_with("::") => i += 2,
+            b':' if !after_word && rest[1..].starts_with(|c: char| c.is_alphabetic() || c == '_') => {
+                let length = 1 + rest[1..].find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len() - 1);
+                let name = &rest[..length];
+                let position = match names.iter().position(|known| known == name) {
+                    Some(at) => at,
+                    None => {
+                        names.push(name.to_string());
+                        names.len() - 1
+                    }
+                };
+                rewritten.push_str(&sql[copied..i]);
+                rewritten.push_str(&format!("${}", position + 1));
+                i += length;
+                copied = i;
+            }
+            b'$' if !after_word && rest[1..].starts_with(|c: char| c.is_ascii_digit()) => {
+                numbered = true;
+                i += 1 + rest[1..].find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len() - 1);
+            }
+            b'$' if !after_word => {
+                // $$ or $tag$ opens a body that the same tag closes
+                let tag_end = rest[1..].find(|c: char| !(c.is_alphanumeric() || c == '_')).map(|end| end + 1);
+                match tag_end.filter(|&end| rest[end..].starts_with('$')) {
+                    Some(end) => {
+                        let tag = &rest[..=end];
+                        i += tag.len();
+                        i = sql[i..].find(tag).map_or(sql.len(), |close| i + close + tag.len());
+                    }
+                    None => i += 1,
+                }
+            }
+            _ => i += sql[i..].chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    match (numbered, names.is_empty()) {
+        (false, true) => Ok(None),
+        (true, true) => Ok(Some((sql.to_string(), Vec::new()))),
+        (false, false) => {
+            rewritten.push_str(&sql[copied..]);
+            Ok(Some((rewritten, names)))
+        }
+        (true, false) => bail!("Use either $n or :name placeholders, not both"),
+    }
+}
+
+fn is_word(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'$' || byte >= 0x80
+}
+
+// Past the `quote` that closes a string or quoted name starting at `from`,
+// where a doubled quote stands for itself
+fn closing(sql: &str, from: usize, quote: u8) -> usize {
+    let bytes = sql.as_bytes();
+    let mut i = from;
+    while i < bytes.len() {
+        if bytes[i] == quote {
+            if bytes.get(i + 1) == Some(&quote) {
+                i += 2;
+                continue;
+            }
+            return i + 1;
+        }
+        i += 1;
+    }
+    bytes.len()
+}
@@ -0,0 +1,83 @@
+use anyhow::Result;
+use crossterm::event::KeyCode;
+
+use crate::app::auto_limit::limit_select;
+use crate::app::rewrite::statement_at;
+use crate::app::script::split_statements;
+use crate::app::App;
+use crate::db::PlanEstimate;
+
+// Statements a plain EXPLAIN can plan
+const EXPLAINABLE: &[&str] = &["SELECT", "WITH", "VALUES", "TABLE", "INSERT", "UPDATE", "DELETE", "MERGE"];
+
+// A query the planner expects to be costlier than the configured limits,
+// held back until it is confirmed
+pub struct CostWarning {
+    pub sql: String,
+    pub params: Vec<Option<String>>,
+    pub estimate: PlanEstimate,
+    // Each limit it goes over, as "cost 1234 > 1000"
+    pub exceeded: Vec<String>,
+}
+
+impl App {
+    // True when a pre-flight limit is set, the plan of `sql` goes over one
+    // and the query now waits for the user to confirm it. A statement that
+    // can't be explained isn't held; running it reports what is wrong.
+    pub(super) async fn hold_costly(&mut self, sql: &str, params: &[Option<String>]) -> bool {
+        let (max_cost, max_rows) = (self.config.preflight_max_cost, self.config.preflight_max_rows);
+        if max_cost.is_none() && max_rows.is_none() {
+            return false;
+        }
+        let statements = split_statements(sql);
+        let [statement] = statements.as_slice() else {
+            return false;
+        };
+        let explainable = statement_at(statement, 0)
+            .first()
+            .is_some_and(|tok| EXPLAINABLE.contains(&tok.upper.as_str()));
+        let Some(client) = self.ws.db.client().filter(|_| explainable) else {
+            return false;
+        };
+        // Planned as it will run, with the auto LIMIT
+        let planned = limit_select(sql, self.config.auto_limit).unwrap_or_else(|| sql.to_string());
+        let Ok(estimate) = crate::db::estimate(client, &planned, params).await else {
+            return false;
+        };
+        let mut exceeded = Vec::new();
+        if let Some(max) = max_cost.filter(|&max| estimate.cost > max) {
+            exceeded.push(format!("cost {:.0} > {:.0}", estimate.cost, max));
+        }
+        if let Some(max) = max_rows.filter(|&max| estimate.rows > max) {
+            exceeded.push(format!("rows {:.0} > {:.0}", estimate.rows, max));
+        }
+        if exceeded.is_empty() {
+            return false;
+        }
+        self.cost_warning = Some(CostWarning {
+            sql: sql.to_string(),
+            params: params.to_vec(),
+            estimate,
+            exceeded,
+        });
+        true
+    }
+
+    pub async fn handle_cost_warning_input(&mut self, key: KeyCode) -> Result<()> {
+        match key {
+            KeyCode::Enter | KeyCode::Char('y') => {
+                let Some(warning) = self.cost_warning.take() else {
+                    return Ok(());
+                };
+                self.run_interactive_query(warning.sql, warning.params, true).await?;
+                self.reset_result_view();
+            }
+            KeyCode::Esc | KeyCode::Char('n') => {
+                self.cost_warning = None;
+                self.set_status("Nothing was run".to_string());
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+use anyhow::Result;
+use crossterm::event::KeyCode;
+
+use crate::app::{App, Prompt, PromptAction};
+
+// Generic single-line input prompt
+impl App {
+    pub fn open_prompt(&mut self, title: &str, initial: &str, action: PromptAction) {
+        self.prompt = Some(Prompt {
+            title: title.to_string(),
+            input: initial.to_string(),
+            action,
+            secret: false,
+        });
+    }
+
+    pub fn open_secret_prompt(&mut self, title: &str, action: PromptAction) {
+        self.open_prompt(title, "", action);
+        if let Some(prompt) = &mut self.prompt {
+            prompt.secret = true;
+        }
+    }
+
+    pub fn cancel_prompt(&mut self) {
+        self.prompt = None;
+    }
+
+    pub fn handle_prompt_input(&mut self, key: KeyCode) {
+        if let Some(prompt) = &mut self.prompt {
+            match key {
+                KeyCode::Char(c) => prompt.input.push(c),
+                KeyCode::Backspace => {
+                    prompt.input.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Pasted text goes in as one line
+    pub fn paste_into_prompt(&mut self, text: &str) {
+        if let Some(prompt) = &mut self.prompt {
+            prompt.input.push_str(text.trim_end().replace(['\r', '\n'], " ").as_str());
+        }
+    }
+
+    pub async fn submit_prompt(&mut self) -> Result<()> {
+        let Some(prompt) = self.prompt.take() else {
+            return Ok(());
+        };
+        match prompt.action {
+            PromptAction::SaveLayout => self.save_layout_as(prompt.input.trim())?,
+            // Cell values are taken verbatim, surrounding spaces included
+            PromptAction::EditCell => self.preview_cell_update(&prompt.input)?,
+            PromptAction::OpenFile => self.load_sql_file(prompt.input.trim())?,
+            PromptAction::SaveFile => self.write_sql_file(prompt.input.trim())?,
+            PromptAction::GotoRow => self.goto_result_row(prompt.input.trim()).await?,
+            PromptAction::SearchResults => self.search_results(prompt.input.trim()),
+            PromptAction::ExportSession => self.export_session(prompt.input.trim())?,
+            PromptAction::ImportCsv => self.open_import_wizard(prompt.input.trim())?,
+            PromptAction::RestoreBuffers => self.answer_recovery(prompt.input.trim()),
+            PromptAction::Benchmark => self.run_benchmark(prompt.input.trim()).await?,
+            PromptAction::ConfirmWrite => self.confirm_write(prompt.input.trim()).await?,
+            PromptAction::SignalBackend => self.answer_signal(prompt.input.trim()).await?,
+            PromptAction::RestartSequence => self.restart_sequence(prompt.input.trim()).await?,
+            PromptAction::RefreshMatview => self.answer_matview_refresh(prompt.input.trim())?,
+            // Passwords are taken verbatim
+            PromptAction::ConnectPassword => self.connect_with_password(&prompt.input).await,
+            PromptAction::SaveCell => self.save_cell(prompt.input.trim())?,
+            PromptAction::StatementTimeout => self.set_statement_timeout(prompt.input.trim()).await?,
+            PromptAction::ExportSchema => self.export_schema(prompt.input.trim()).await?,
+            PromptAction::ExportInserts => self.export_inserts(prompt.input.trim())?,
+            PromptAction::StarQuery => self.star_query(prompt.input.trim())?,
+            PromptAction::CloseConnection => self.answer_close_workspace(prompt.input.trim()),
+        }
+        Ok(())
+    }
+}
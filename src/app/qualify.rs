@@ -0,0 +1,301 @@
+use std::collections::HashSet;
+
+use anyhow::{bail, Result};
+
+use crate::app::rewrite::{statement_at, Edit, NOT_ALIAS};
+use crate::app::App;
+use crate::autocomplete::AutocompleteEngine;
+use crate::syntax::{SqlHighlighter, TokenType};
+
+// "Qualify columns": gives every FROM table of the SELECT at the cursor an
+// alias (made from its name when it has none) and prefixes each bare column
+// reference with the alias of the one table that has that column
+impl App {
+    pub async fn qualify_columns(&mut self) -> Result<()> {
+        self.load_autocomplete_schema().await?;
+        let outcome = match qualify(&self.ws.query_input, self.ws.query_cursor, &self.ws.autocomplete_engine) {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                self.set_error(format!("{:#}", e));
+                return Ok(());
+            }
+        };
+        self.apply_edits(outcome.edits);
+        if outcome.ambiguous.is_empty() {
+            self.set_status(format!("Qualified {} column reference(s)", outcome.qualified));
+        } else {
+            self.set_warning(format!(
+                "Qualified {} column reference(s); left ambiguous: {}",
+                outcome.qualified,
+                outcome.ambiguous.join(", ")
+            ));
+        }
+        Ok(())
+    }
+}
+
+struct Outcome {
+    edits: Vec<Edit>,
+    qualified: usize,
+    // Columns found in more than one FROM table
+    ambiguous: Vec<String>,
+}
+
+struct TableRef {
+    name: String,
+    // As written, or generated when `generated`
+    alias: Option<String>,
+    generated: bool,
+    // End of the table name, where a generated alias goes
+    name_end: usize,
+}
+
+// One SELECT; each branch of a UNION and each subquery is its own
+#[derive(Default)]
+struct Branch {
+    parent: Option<usize>,
+    tables: Vec<TableRef>,
+    // A subquery or function in FROM, whose columns aren't cached
+    opaque: bool,
+    // Output column names, which ORDER BY may refer to
+    output_aliases: HashSet<String>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Clause {
+    Select,
+    From,
+    Other,
+}
+
+// Open parenthesis (or the statement itself): the branch its tokens belong
+// to and the clause they are in
+struct Group {
+    branch: usize,
+    clause: Clause,
+    // The column list of JOIN ... USING
+    using: bool,
+}
+
+// Short words an alias can't be
+const RESERVED_SHORT: &[&str] = &["AS", "AT", "BY", "DO", "IF", "IN", "IS", "OF", "ON", "OR", "TO"];
+
+fn qualify(sql: &str, cursor: usize, engine: &AutocompleteEngine) -> Result<Outcome> {
+    let tokens = statement_at(sql, cursor);
+    if !tokens.first().is_some_and(|t| matches!(t.upper.as_str(), "SELECT" | "WITH")) {
+        bail!("Only SELECT statements can be qualified");
+    }
+
+    // Pass 1: branch of every token, FROM tables, and tokens that are
+    // definitely not column references
+    let mut branches = vec![Branch::default()];
+    let mut branch_of = vec![0; tokens.len()];
+    let mut clause_of = vec![Clause::Other; tokens.len()];
+    let mut not_column = vec![false; tokens.len()];
+    let mut groups = vec![Group { branch: 0, clause: Clause::Other, using: false }];
+
+    for (i, tok) in tokens.iter().enumerate() {
+        let prev = |n: usize| i.checked_sub(n).map(|p| tokens[p].upper.as_str()).unwrap_or("");
+        let group = groups.last().unwrap();
+        match tok.text.as_str() {
+            "(" => {
+                let branch = group.branch;
+                if group.clause == Clause::From && matches!(prev(1), "FROM" | "JOIN" | ",") {
+                    branches[branch].opaque = true;
+                }
+                // A subquery gets a branch of its own at its SELECT
+                groups.push(Group { branch, clause: Clause::Other, using: prev(1) == "USING" });
+                branch_of[i] = branch;
+                continue;
+            }
+            ")" if groups.len() > 1 => {
+                groups.pop();
+                branch_of[i] = groups.last().unwrap().branch;
+                continue;
+            }
+            _ => {}
+        }
+
+        let group = groups.last_mut().unwrap();
+        if matches!(tok.kind, TokenType::Keyword | TokenType::Identifier) {
+            match tok.upper.as_str() {
+                "SELECT" => {
+                    branches.push(Branch { parent: Some(group.branch), ..Branch::default() });
+                    group.branch = branches.len() - 1;
+                    group.clause = Clause::Select;
+                }
+                "FROM" if group.clause == Clause::Select => group.clause = Clause::From,
+                "JOIN" => group.clause = Clause::From,
+                "WHERE" | "GROUP" | "ORDER" | "HAVING" | "LIMIT" | "OFFSET" | "WINDOW" | "ON" | "USING"
+                | "UNION" | "INTERSECT" | "EXCEPT" | "INTO" => group.clause = Clause::Other,
+                _ => {}
+            }
+        }
+        branch_of[i] = group.branch;
+        clause_of[i] = group.clause;
+        if group.using {
+            not_column[i] = true;
+        }
+        if tok.kind != TokenType::Identifier {
+            continue;
+        }
+        let branch = &mut branches[group.branch];
+
+        match group.clause {
+            // Output names: `expr AS name` or `expr name`
+            Clause::Select => {
+                let implicit = prev(1) == ")"
+                    || (i > 0
+                        && matches!(tokens[i - 1].kind, TokenType::Identifier | TokenType::Number | TokenType::String));
+                if prev(1) == "AS" || (implicit && prev(1) != ".") {
+                    not_column[i] = true;
+                    branch.output_aliases.insert(tok.upper.clone());
+                }
+            }
+            Clause::From if matches!(prev(1), "FROM" | "JOIN" | ",") => {
+                // [schema.]table [[AS] alias]
+                let mut last = i;
+                if tokens.get(i + 1).is_some_and(|t| t.text == ".")
+                    && tokens.get(i + 2).is_some_and(|t| t.kind == TokenType::Identifier)
+                {
+                    last = i + 2;
+                }
+                if tokens.get(last + 1).is_some_and(|t| t.text == "(") {
+                    branch.opaque = true;
+                    continue;
+                }
+                let mut next = last + 1;
+                if tokens.get(next).is_some_and(|t| t.upper == "AS") {
+                    next += 1;
+                }
+                let alias = tokens
+                    .get(next)
+                    .filter(|t| t.kind == TokenType::Identifier && !NOT_ALIAS.contains(&t.upper.as_str()));
+                let end = if alias.is_some() { next } else { last };
+                not_column[i..=end].fill(true);
+                branch.tables.push(TableRef {
+                    name: tokens[last].text.clone(),
+                    alias: alias.map(|t| t.text.clone()),
+                    generated: false,
+                    name_end: tokens[last].end,
+                });
+            }
+            // Alias of a subquery in FROM
+            Clause::From if prev(1) == ")" || (prev(1) == "AS" && prev(2) == ")") => not_column[i] = true,
+            _ => {}
+        }
+    }
+
+    // Pass 2: aliases for unaliased tables, unique within the statement
+    let mut taken: HashSet<String> = branches
+        .iter()
+        .flat_map(|b| &b.tables)
+        .flat_map(|t| [Some(&t.name), t.alias.as_ref()])
+        .flatten()
+        .map(|name| name.to_lowercase())
+        .collect();
+    let mut edits = Vec::new();
+    for table in branches.iter_mut().flat_map(|b| &mut b.tables) {
+        if table.alias.is_some() {
+            continue;
+        }
+        let alias = generate_alias(&table.name, &taken);
+        taken.insert(alias.clone());
+        edits.push(Edit { start: table.name_end, end: table.name_end, text: format!(" {}", alias) });
+        table.alias = Some(alias);
+        table.generated = true;
+    }
+
+    // Pass 3: column references and qualifiers
+    let mut qualified = 0;
+    let mut ambiguous: Vec<String> = Vec::new();
+    for (i, tok) in tokens.iter().enumerate() {
+        if tok.kind != TokenType::Identifier || not_column[i] {
+            continue;
+        }
+        let prev = i.checked_sub(1).map(|p| tokens[p].text.as_str()).unwrap_or("");
+        let next = tokens.get(i + 1).map(|t| t.text.as_str()).unwrap_or("");
+        if prev == "." || prev == ":" {
+            continue;
+        }
+        let branch = &branches[branch_of[i]];
+
+        // `users.id` has to follow `users` to its new alias
+        if next == "." {
+            if let Some(table) = visible_tables(&branches, branch_of[i])
+                .find(|t| t.generated && t.name.eq_ignore_ascii_case(&tok.text))
+            {
+                edits.push(Edit { start: tok.start, end: tok.end, text: table.alias.clone().unwrap_or_default() });
+            }
+            continue;
+        }
+        if next == "(" || (clause_of[i] == Clause::Other && branch.output_aliases.contains(&tok.upper)) {
+            continue;
+        }
+
+        let Some(owners) = column_owners(branch, &tok.text, engine) else {
+            continue;
+        };
+        match owners.as_slice() {
+            [] => {}
+            [table] => {
+                let alias = table.alias.as_deref().unwrap_or(&table.name);
+                edits.push(Edit { start: tok.start, end: tok.start, text: format!("{}.", alias) });
+                qualified += 1;
+            }
+            _ => {
+                if !ambiguous.iter().any(|name| name.eq_ignore_ascii_case(&tok.text)) {
+                    ambiguous.push(tok.text.clone());
+                }
+            }
+        }
+    }
+
+    Ok(Outcome { edits, qualified, ambiguous })
+}
+
+// Tables of `branch` that have a cached column `column`; None when the
+// branch reads from something whose columns aren't known
+fn column_owners<'a>(branch: &'a Branch, column: &str, engine: &AutocompleteEngine) -> Option<Vec<&'a TableRef>> {
+    if branch.opaque {
+        return None;
+    }
+    let mut owners = Vec::new();
+    for table in &branch.tables {
+        let columns = engine.table_columns(&table.name)?;
+        if columns.iter().any(|c| c.eq_ignore_ascii_case(column)) {
+            owners.push(table);
+        }
+    }
+    Some(owners)
+}
+
+// Tables of a branch and the queries enclosing it, which correlated
+// subqueries can refer to
+fn visible_tables(branches: &[Branch], branch: usize) -> impl Iterator<Item = &TableRef> {
+    std::iter::successors(Some(branch), |&b| branches[b].parent).flat_map(|b| &branches[b].tables)
+}
+
+// Initials of the words in the table name (`order_items` -> `oi`), with a
+// number added when that is taken or not a valid alias
+fn generate_alias(table: &str, taken: &HashSet<String>) -> String {
+    let base: String = table
+        .split('_')
+        .filter_map(|word| word.chars().next())
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect();
+    let base = if base.starts_with(|c: char| c.is_alphabetic()) { base } else { format!("t{}", base) };
+    let usable = |alias: &str| !taken.contains(alias) && !is_reserved(alias);
+    if usable(&base) {
+        return base;
+    }
+    (2..).map(|n| format!("{}{}", base, n)).find(|alias| usable(alias)).unwrap()
+}
+
+fn is_reserved(alias: &str) -> bool {
+    let upper = alias.to_uppercase();
+    RESERVED_SHORT.contains(&upper.as_str())
+        || NOT_ALIAS.contains(&upper.as_str())
+        || SqlHighlighter::new().tokenize(alias).iter().any(|t| t.token_type == TokenType::Keyword)
+}
@@ -0,0 +1,124 @@
+use crossterm::event::KeyCode;
+
+use crate::app::{App, BrowserItem};
+use crate::autocomplete::fuzzy_match;
+use crate::db::ObjectKind;
+
+// Objects kept in a connection's recent list
+const MAX_RECENT: usize = 20;
+
+// Tables, views and functions opened lately, searched as you type, to jump
+// back to without walking the tree
+pub struct RecentPicker {
+    pub filter: String,
+    // Indexes into the workspace's recent objects, best match first, with
+    // the characters of "schema.name" the filter matched
+    pub matches: Vec<(usize, Vec<usize>)>,
+    pub selected: usize,
+}
+
+impl App {
+    // Put the object just opened in the browser at the head of the list
+    pub(super) fn remember_recent_object(&mut self) {
+        let recent = match self.ws.browser_items.get(self.ws.browser_selected) {
+            Some(BrowserItem::Table(schema, name)) => (ObjectKind::Table, schema.clone(), name.clone()),
+            Some(BrowserItem::View(schema, name)) => (ObjectKind::View, schema.clone(), name.clone()),
+            Some(BrowserItem::MaterializedView(schema, name)) => (ObjectKind::MaterializedView, schema.clone(), name.clone()),
+            Some(BrowserItem::Function(schema, name)) => (ObjectKind::Function, schema.clone(), name.clone()),
+            _ => return,
+        };
+        self.ws.recent_objects.retain(|r| *r != recent);
+        self.ws.recent_objects.insert(0, recent);
+        self.ws.recent_objects.truncate(MAX_RECENT);
+    }
+
+    pub fn open_recent_objects(&mut self) {
+        if self.ws.recent_objects.is_empty() {
+            self.set_warning(format!("Nothing opened in the browser of {} yet", self.ws.name));
+            return;
+        }
+        let mut picker = RecentPicker {
+            filter: String::new(),
+            matches: recent_matches(&self.ws.recent_objects, ""),
+            selected: 0,
+        };
+        // The object on screen heads the list, so start on the one before
+        // it to flip between two with a single Enter
+        let current = self.ws.browser_items.get(self.ws.browser_selected);
+        let (_, schema, name) = &self.ws.recent_objects[0];
+        let showing = match current {
+            Some(BrowserItem::Table(s, n))
+            | Some(BrowserItem::View(s, n))
+            | Some(BrowserItem::MaterializedView(s, n))
+            | Some(BrowserItem::Function(s, n)) => s == schema && n == name,
+            _ => false,
+        };
+        if showing && picker.matches.len() > 1 {
+            picker.selected = 1;
+        }
+        self.recent_picker = Some(picker);
+    }
+
+    pub fn close_recent_objects(&mut self) {
+        self.recent_picker = None;
+    }
+
+    pub fn handle_recent_objects_input(&mut self, key: KeyCode) {
+        let recent = &self.ws.recent_objects;
+        let Some(picker) = &mut self.recent_picker else {
+            return;
+        };
+        let last = picker.matches.len().saturating_sub(1);
+        match key {
+            KeyCode::Up => picker.selected = picker.selected.saturating_sub(1),
+            KeyCode::Down => picker.selected = (picker.selected + 1).min(last),
+            KeyCode::Char(c) => {
+                picker.filter.push(c);
+                picker.matches = recent_matches(recent, &picker.filter);
+                picker.selected = 0;
+            }
+            KeyCode::Backspace => {
+                picker.filter.pop();
+                picker.matches = recent_matches(recent, &picker.filter);
+                picker.selected = 0;
+            }
+            _ => {}
+        }
+    }
+
+    pub async fn jump_to_recent_object(&mut self) {
+        let Some(picker) = self.recent_picker.take() else {
+            return;
+        };
+        let Some((kind, schema, name)) = picker
+            .matches
+            .get(picker.selected)
+            .map(|(i, _)| self.ws.recent_objects[*i].clone())
+        else {
+            return;
+        };
+        match self.reveal_in_browser(&schema, kind, &name).await {
+            Ok(true) => {}
+            Ok(false) => {
+                self.ws.recent_objects.retain(|(k, s, n)| !(*k == kind && *s == schema && *n == name));
+                self.set_warning(format!("{}.{} is not listed in the browser any more", schema, name));
+            }
+            Err(e) => self.set_error(format!("{:#}", e)),
+        }
+    }
+}
+
+// Recent objects whose "schema.name" matches `filter`, best first; ties
+// keep the most recent first
+fn recent_matches(recent: &[(ObjectKind, String, String)], filter: &str) -> Vec<(usize, Vec<usize>)> {
+    let mut matches: Vec<(i32, usize, Vec<usize>)> = recent
+        .iter()
+        .enumerate()
+        .filter_map(|(i, (_, schema, name))| {
+            let (score, matched) = fuzzy_match(&format!("{}.{}", schema, name), filter)?;
+            Some((score, i, matched))
+        })
+        .collect();
+    matches.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    matches.into_iter().map(|(_, i, matched)| (i, matched)).collect()
+}
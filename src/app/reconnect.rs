@@ -0,0 +1,121 @@
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+
+use crate::app::{format_duration, App, Workspace};
+
+// First wait between automatic reconnect attempts, doubled after each
+// failure up to MAX_BACKOFF
+const FIRST_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+// An attempt that takes longer than this (a host that doesn't answer) counts
+// as failed, so the screen isn't frozen for the TCP timeout
+const ATTEMPT_TIMEOUT: Duration = Duration::from_secs(5);
+
+// A connection whose session ended under it, and the attempts to get it back
+#[derive(Debug, Clone)]
+pub struct Reconnect {
+    pub since: Instant,
+    pub attempts: u32,
+    pub next_attempt: Instant,
+    // Why the session ended, then why the last attempt failed
+    pub reason: String,
+}
+
+impl Reconnect {
+    // Seconds until the next attempt, for the status bar
+    pub fn due_in(&self) -> u64 {
+        self.next_attempt.saturating_duration_since(Instant::now()).as_secs()
+    }
+}
+
+impl Workspace {
+    // While the session is lost, what getting it back is up to; for panels
+    // that wait for it
+    pub fn reconnect_status(&self) -> Option<String> {
+        self.db.lost()?;
+        Some(match &self.reconnect {
+            Some(reconnect) if reconnect.attempts > 0 => {
+                format!("reconnecting, next attempt in {}s ({})", reconnect.due_in(), reconnect.reason)
+            }
+            _ => "reconnecting".to_string(),
+        })
+    }
+}
+
+impl App {
+    // Notice a lost connection and try to get it back, waiting longer after
+    // each failed attempt
+    pub async fn poll_connection(&mut self) {
+        let lost = self.ws.db.lost();
+        match (&self.ws.reconnect, lost) {
+            // Back, e.g. through a retried query or the activity monitor
+            (Some(_), None) => self.ws.reconnect = None,
+            (None, Some(reason)) => {
+                self.audit.record_disconnect(&self.ws.name);
+                self.set_warning(format!("Lost the connection to {}: {}; reconnecting", self.ws.name, reason));
+                self.ws.reconnect = Some(Reconnect {
+                    since: Instant::now(),
+                    attempts: 0,
+                    next_attempt: Instant::now(),
+                    reason,
+                });
+            }
+            (Some(reconnect), Some(_)) if reconnect.next_attempt <= Instant::now() => self.try_reconnect().await,
+            _ => {}
+        }
+    }
+
+    // Reconnect right away: after a lost connection without waiting for the
+    // next attempt, otherwise to start over with a fresh session
+    pub async fn reconnect_now(&mut self) {
+        if !self.ws.db.is_connected() {
+            return;
+        }
+        if self.ws.reconnect.is_none() {
+            self.ws.reconnect = Some(Reconnect {
+                since: Instant::now(),
+                attempts: 0,
+                next_attempt: Instant::now(),
+                reason: "reconnect requested".to_string(),
+            });
+        }
+        self.try_reconnect().await;
+    }
+
+    // Connect again with the same settings, giving up after ATTEMPT_TIMEOUT;
+    // the new session goes into the session log
+    pub(super) async fn open_new_session(&mut self) -> Result<()> {
+        let result = match tokio::time::timeout(ATTEMPT_TIMEOUT, self.ws.db.reconnect()).await {
+            Ok(result) => result,
+            Err(_) => Err(anyhow!("no answer within {}s", ATTEMPT_TIMEOUT.as_secs())),
+        };
+        if result.is_ok() {
+            self.audit.record_connect(&self.ws.name);
+        }
+        result
+    }
+
+    async fn try_reconnect(&mut self) {
+        let result = self.open_new_session().await;
+        let Some(reconnect) = &mut self.ws.reconnect else {
+            return;
+        };
+        match result {
+            Ok(()) => {
+                let gap = format_duration(reconnect.since.elapsed().as_secs_f64());
+                self.ws.reconnect = None;
+                self.set_warning(format!(
+                    "Reconnected to {} after {}; this is a new session, so open transactions, temporary tables and SET values are gone",
+                    self.ws.name, gap
+                ));
+            }
+            Err(e) => {
+                let backoff = FIRST_BACKOFF.saturating_mul(1 << reconnect.attempts.min(5)).min(MAX_BACKOFF);
+                reconnect.attempts += 1;
+                reconnect.next_attempt = Instant::now() + backoff;
+                reconnect.reason = format!("{:#}", e);
+            }
+        }
+    }
+}
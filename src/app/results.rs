@@ -0,0 +1,412 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, bail, Result};
+use unicode_width::UnicodeWidthStr;
+
+use crate::app::rewrite::statement_at;
+use crate::app::{App, PromptAction, QueryFocus, ResultPopup, SortDirection};
+use crate::db::{DbConnection, PendingRows, QueryResult};
+
+// Rows read from the server at a time for the results grid
+const FETCH_BATCH: usize = 500;
+// Characters an expanded column scrolls per key press
+const EXPANDED_STEP: usize = 10;
+
+// Run `sql` with `params` for the grid, reading a first batch or `min_rows`
+// if that is more (usize::MAX reads it all); the rest stays on the
+// connection for `fetch_more_rows`. Returns whether `max_rows` cut the
+// result short.
+pub(super) async fn load_result(
+    db: &DbConnection,
+    sql: &str,
+    params: &[Option<String>],
+    max_rows: usize,
+    min_rows: usize,
+) -> Result<(QueryResult, bool, Option<u64>)> {
+    let client = db.client().ok_or_else(|| anyhow!("Not connected"))?;
+    let limit = match max_rows {
+        0 => FETCH_BATCH.max(min_rows),
+        max => FETCH_BATCH.max(min_rows).min(max),
+    };
+    let (result, pending, affected) = crate::db::start_query(client, sql, params, limit).await?;
+    let capped = park_rows(db, pending, result.rows.len(), max_rows);
+    Ok((result, capped, affected))
+}
+
+// Keep unread rows for later unless the grid already holds `max_rows`, in
+// which case they are dropped and true is returned
+fn park_rows(db: &DbConnection, pending: Option<PendingRows>, loaded: usize, max_rows: usize) -> bool {
+    let Some(pending) = pending else {
+        return false;
+    };
+    if max_rows > 0 && loaded >= max_rows {
+        return true;
+    }
+    db.park_rows(Some(pending));
+    false
+}
+
+// Words between CREATE / ALTER / DROP and the kind of object
+const OBJECT_MODIFIERS: &[&str] = &["OR", "REPLACE", "UNIQUE", "TEMP", "TEMPORARY", "UNLOGGED", "GLOBAL", "LOCAL"];
+
+// "UPDATE 42", "SELECT 0", "CREATE TABLE": what psql would report for
+// `sql`, given the row count the server sent back
+pub(super) fn command_tag(sql: &str, affected: u64) -> Option<String> {
+    let tokens = statement_at(sql, 0);
+    let first = tokens.first()?;
+    // The verb after any WITH queries
+    let mut depth = 0i32;
+    let verb = tokens.iter().find(|tok| {
+        match tok.text.as_str() {
+            "(" => depth += 1,
+            ")" => depth -= 1,
+            _ => {}
+        }
+        depth == 0 && matches!(tok.upper.as_str(), "SELECT" | "INSERT" | "UPDATE" | "DELETE" | "MERGE" | "VALUES")
+    });
+    match verb {
+        Some(verb) if first.upper == "WITH" || verb.start == first.start => {
+            let verb = if verb.upper == "VALUES" { "SELECT" } else { verb.upper.as_str() };
+            return Some(format!("{} {}", verb, affected));
+        }
+        _ => {}
+    }
+    match first.upper.as_str() {
+        "CREATE" | "ALTER" | "DROP" => {
+            let mut kind = tokens[1..].iter().skip_while(|tok| OBJECT_MODIFIERS.contains(&tok.upper.as_str()));
+            let object = kind.next()?;
+            match object.upper.as_str() {
+                "MATERIALIZED" | "FOREIGN" | "EVENT" | "ACCESS" => {
+                    let rest = kind.next().map(|tok| tok.upper.as_str()).unwrap_or_default();
+                    Some(format!("{} {} {}", first.upper, object.upper, rest))
+                }
+                _ => Some(format!("{} {}", first.upper, object.upper)),
+            }
+        }
+        "COPY" | "FETCH" | "MOVE" => Some(format!("{} {}", first.upper, affected)),
+        _ => Some(first.upper.clone()),
+    }
+}
+
+// Results grid focus and cursor movement
+impl App {
+    pub fn toggle_query_focus(&mut self) {
+        self.ws.query_focus = match self.ws.query_focus {
+            QueryFocus::Editor if self.ws.query_result.is_some() => {
+                self.hide_autocomplete();
+                QueryFocus::Results
+            }
+            _ => QueryFocus::Editor,
+        };
+    }
+
+    pub fn reset_result_view(&mut self) {
+        self.ws.result_scroll_offset = 0;
+        self.ws.result_selected_row = 0;
+        self.ws.result_selected_col = 0;
+        self.ws.result_sort = None;
+        self.ws.result_search = None;
+        self.ws.result_expanded = None;
+        self.ws.result_pinned = 0;
+        self.ws.result_fitted_widths.clear();
+        self.apply_table_view();
+    }
+
+    // Size every column by its widest value in all fetched rows rather than
+    // the ones on screen when the grid was first drawn
+    pub fn fit_result_columns(&mut self) {
+        let Some(result) = &self.ws.query_result else {
+            return;
+        };
+        let mut widths: HashMap<String, usize> = HashMap::new();
+        for (col, name) in result.columns.iter().enumerate() {
+            let widest = result.rows.iter().filter_map(|row| row.get(col)?.as_ref()).map(|cell| cell.width()).max();
+            let width = widths.entry(name.clone()).or_default();
+            *width = (*width).max(widest.unwrap_or(0));
+        }
+        let rows = result.rows.len();
+        self.ws.result_fitted_widths = widths;
+        self.set_status(format!("Column widths fitted to {} row(s)", rows));
+    }
+
+    // Sort by the column under the cursor: ascending, descending, then off
+    pub fn cycle_result_sort(&mut self) {
+        if self.ws.query_result.is_none() {
+            return;
+        }
+        let col = self.ws.result_selected_col;
+        self.ws.result_sort = match self.ws.result_sort {
+            Some((c, SortDirection::Ascending)) if c == col => Some((col, SortDirection::Descending)),
+            Some((c, SortDirection::Descending)) if c == col => None,
+            _ => Some((col, SortDirection::Ascending)),
+        };
+        self.ws.result_selected_row = 0;
+    }
+
+    // Number of rows currently shown in the grid (after filtering)
+    pub fn displayed_row_count(&self) -> usize {
+        match self.get_filtered_rows() {
+            Some(indices) => indices.len(),
+            None => self.ws.query_result.as_ref().map(|r| r.rows.len()).unwrap_or(0),
+        }
+    }
+
+    // Index into `query_result.rows` of the row under the grid cursor,
+    // honoring the results filter
+    pub fn selected_result_row(&self) -> Option<usize> {
+        match self.get_filtered_rows() {
+            Some(indices) => indices.get(self.ws.result_selected_row).copied(),
+            None => Some(self.ws.result_selected_row),
+        }
+    }
+
+    // The cell under the grid cursor; None inside for NULL
+    pub fn selected_cell_value(&self) -> Option<Option<&str>> {
+        let result = self.ws.query_result.as_ref()?;
+        let row = self.selected_result_row()?;
+        result.rows.get(row)?.get(self.ws.result_selected_col).map(Option::as_deref)
+    }
+
+    fn result_column_count(&self) -> usize {
+        self.ws.query_result.as_ref().map(|r| r.columns.len()).unwrap_or(0)
+    }
+
+    pub fn result_row_up(&mut self, amount: usize) {
+        self.ws.result_selected_row = self.ws.result_selected_row.saturating_sub(amount);
+    }
+
+    pub fn result_row_down(&mut self, amount: usize) {
+        let last = self.displayed_row_count().saturating_sub(1);
+        self.ws.result_selected_row = (self.ws.result_selected_row + amount).min(last);
+    }
+
+    pub fn result_first_row(&mut self) {
+        self.ws.result_selected_row = 0;
+    }
+
+    pub fn result_last_row(&mut self) {
+        self.ws.result_selected_row = self.displayed_row_count().saturating_sub(1);
+    }
+
+    // Moving onto the last row loaded pulls in the next batch
+    pub async fn fetch_rows_at_end(&mut self) {
+        if self.ws.result_selected_row + 1 >= self.displayed_row_count() {
+            self.fetch_more_rows().await;
+        }
+    }
+
+    // Read the next batch of a result that is only partly loaded
+    pub async fn fetch_more_rows(&mut self) {
+        let Some(mut pending) = self.ws.db.take_rows() else {
+            return;
+        };
+        let Some(result) = self.ws.query_result.as_mut() else {
+            return;
+        };
+        let max = self.config.max_result_rows;
+        let limit = if max == 0 { FETCH_BATCH } else { FETCH_BATCH.min(max.saturating_sub(result.rows.len())) };
+        let (rows, more) = match pending.fetch(limit).await {
+            Ok(fetched) => fetched,
+            Err(e) => {
+                self.set_error(format!("{:#}", e));
+                return;
+            }
+        };
+        // Rows arrive in the server's column order, the grid may have its own
+        let positions = column_positions(&result.columns, &pending.columns);
+        result
+            .rows
+            .extend(rows.into_iter().map(|row| positions.iter().map(|&i| row[i].clone()).collect::<Vec<_>>()));
+        result.row_count = result.rows.len();
+        let loaded = result.rows.len();
+        self.ws.result_capped = more && park_rows(&self.ws.db, Some(pending), loaded, max);
+    }
+
+    // The row under the grid cursor as column/value pairs, like psql's \x
+    pub fn show_record(&mut self) {
+        let (Some(result), Some(index)) = (&self.ws.query_result, self.selected_result_row()) else {
+            return;
+        };
+        let Some(row) = result.rows.get(index) else {
+            return;
+        };
+        let rows: Vec<Vec<Option<String>>> = result
+            .columns
+            .iter()
+            .zip(row)
+            .map(|(column, value)| vec![Some(column.clone()), value.clone()])
+            .collect();
+        self.popup = Some(ResultPopup {
+            title: format!("Row {}", self.ws.result_selected_row + 1),
+            result: QueryResult {
+                columns: vec!["Column".to_string(), "Value".to_string()],
+                row_count: rows.len(),
+                rows,
+                types: Vec::new(),
+                source: None,
+            },
+            scroll: 0,
+        });
+    }
+
+    pub fn begin_goto_row(&mut self) {
+        let rows = self.displayed_row_count();
+        if rows > 0 {
+            // Rows past the loaded ones are fetched on the way
+            let more = if self.ws.db.has_pending_rows() { "+" } else { "" };
+            self.open_prompt(&format!("Go to row (1-{}{})", rows, more), "", PromptAction::GotoRow);
+        }
+    }
+
+    // 1-based, counting the rows as displayed (filtered and sorted). Rows
+    // not fetched yet are fetched until the row is there or none are left.
+    pub async fn goto_result_row(&mut self, input: &str) -> Result<()> {
+        let row = input.parse::<usize>().unwrap_or(0);
+        while row > self.displayed_row_count() && self.ws.db.has_pending_rows() {
+            self.fetch_more_rows().await;
+        }
+        let rows = self.displayed_row_count();
+        if !(1..=rows).contains(&row) {
+            bail!("Row must be a number from 1 to {}", rows);
+        }
+        self.ws.result_selected_row = row - 1;
+        Ok(())
+    }
+
+    pub fn begin_result_search(&mut self) {
+        if self.ws.query_result.is_some() {
+            let current = self.ws.result_search.clone().unwrap_or_default();
+            self.open_prompt("Search results (empty clears)", &current, PromptAction::SearchResults);
+        }
+    }
+
+    // Highlight cells containing `term` (ignoring case) and jump to the first
+    pub fn search_results(&mut self, term: &str) {
+        if term.is_empty() {
+            self.ws.result_search = None;
+            return;
+        }
+        self.ws.result_search = Some(term.to_lowercase());
+        let matches = self.ws.search_matches();
+        match matches.first() {
+            Some(&first) => {
+                self.select_result_cell(first);
+                self.set_status(format!("{} matching cell(s)", matches.len()));
+            }
+            None => self.set_warning(format!("No cells match '{}'", term)),
+        }
+    }
+
+    // n / N: the next or previous match after the grid cursor, wrapping
+    pub fn find_next_match(&mut self, forward: bool) {
+        let matches = self.ws.search_matches();
+        if matches.is_empty() {
+            if self.ws.result_search.is_some() {
+                self.set_warning("No matching cells".to_string());
+            }
+            return;
+        }
+        let cursor = (self.ws.result_selected_row, self.ws.result_selected_col);
+        let found = if forward {
+            matches.iter().find(|&&m| m > cursor).or(matches.first())
+        } else {
+            matches.iter().rev().find(|&&m| m < cursor).or(matches.last())
+        };
+        if let Some(&cell) = found {
+            self.select_result_cell(cell);
+        }
+    }
+
+    // Move the grid cursor to a (displayed row, column) and scroll to it;
+    // the grid scrolls right by itself when the column is past the view
+    fn select_result_cell(&mut self, (row, col): (usize, usize)) {
+        self.ws.query_focus = QueryFocus::Results;
+        self.ws.result_selected_row = row;
+        self.ws.result_selected_col = col;
+        if col < self.ws.result_scroll_offset {
+            self.ws.result_scroll_offset = col;
+        }
+    }
+
+    pub fn result_col_left(&mut self) {
+        self.ws.result_selected_col = self.ws.result_selected_col.saturating_sub(1);
+        if self.ws.result_selected_col < self.ws.result_scroll_offset {
+            self.ws.result_scroll_offset = self.ws.result_selected_col;
+        }
+    }
+
+    pub fn result_col_right(&mut self) {
+        if self.ws.result_selected_col + 1 < self.result_column_count() {
+            self.ws.result_selected_col += 1;
+        }
+    }
+
+    // Show the selected column as wide as its values need, up to the whole
+    // grid, or back to its usual width
+    pub fn toggle_expanded_column(&mut self) {
+        self.ws.result_expanded = match self.ws.result_expanded {
+            Some(_) => None,
+            None => Some(0),
+        };
+    }
+
+    // Pin the columns up to the selected one so they stay on screen while
+    // the rest scroll; on a pinned column, unpin them all
+    pub fn pin_result_columns(&mut self) {
+        if self.result_column_count() == 0 {
+            return;
+        }
+        let col = self.ws.result_selected_col;
+        if col < self.ws.result_pinned {
+            self.ws.result_pinned = 0;
+            self.set_status("Columns unpinned".to_string());
+        } else {
+            self.ws.result_pinned = col + 1;
+            self.set_status(format!("Pinned {} column(s)", col + 1));
+        }
+    }
+
+    pub fn expanded_column(&self) -> bool {
+        self.ws.result_expanded.is_some()
+    }
+
+    // ←/→ in an expanded column: scroll through its values, and past either
+    // end on to the next column, which is expanded in its place
+    pub fn scroll_expanded_column(&mut self, forward: bool) {
+        let Some(offset) = self.ws.result_expanded else {
+            return;
+        };
+        let (_, scrollable) = self.ws.result_expanded_view.get();
+        let offset = offset.min(scrollable);
+        self.ws.result_expanded = Some(match forward {
+            true if offset < scrollable => (offset + EXPANDED_STEP).min(scrollable),
+            false if offset > 0 => offset.saturating_sub(EXPANDED_STEP),
+            true => {
+                self.result_col_right();
+                0
+            }
+            false => {
+                self.result_col_left();
+                0
+            }
+        });
+    }
+}
+
+// Where each grid column sits in a row as returned; repeated names pair up
+// in order
+fn column_positions(grid: &[String], returned: &[String]) -> Vec<usize> {
+    grid.iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let nth = grid[..i].iter().filter(|c| *c == name).count();
+            returned
+                .iter()
+                .enumerate()
+                .filter(|(_, c)| *c == name)
+                .map(|(j, _)| j)
+                .nth(nth)
+                .unwrap_or(0)
+        })
+        .collect()
+}
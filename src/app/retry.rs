@@ -0,0 +1,72 @@
+use std::time::Duration;
+
+use crate::app::guard::{transaction_change, write_keyword};
+use crate::app::App;
+use crate::db::Transient;
+
+// Retrying statements that failed on a transient error: serialization
+// failures, deadlocks and sessions ended by the server
+impl App {
+    // After attempt number `attempt` of `sql` failed with `error`: true when
+    // it should run again, after the backoff and, if the session was lost,
+    // a new connection. Neither a write nor anything run inside a
+    // transaction block is retried on a new connection, since the
+    // transaction it belonged to is gone.
+    pub(super) async fn retry_after(&mut self, sql: &str, error: &anyhow::Error, attempt: u32) -> bool {
+        let Some(failure) = crate::db::transient_failure(error) else {
+            return false;
+        };
+        let lost = failure == Transient::Disconnected;
+        if attempt > self.config.retry_attempts
+            || (lost && (write_keyword(sql).is_some() || self.ws.db.transaction_open()))
+        {
+            return false;
+        }
+        // 1x, 2x, 4x... the configured delay
+        let delay = self.config.retry_backoff_ms.saturating_mul(1 << (attempt - 1).min(10));
+        tokio::time::sleep(Duration::from_millis(delay)).await;
+        if !lost {
+            return true;
+        }
+        if self.ws.reconnect.is_none() {
+            self.audit.record_disconnect(&self.ws.name);
+        }
+        if self.open_new_session().await.is_err() {
+            return false;
+        }
+        self.set_warning(format!(
+            "Lost the connection to {}; running the statement again on a new session, without the temporary tables and SET values of the old one",
+            self.ws.name
+        ));
+        true
+    }
+
+    // Follow BEGIN ... COMMIT through statements that ran, to know whether
+    // a lost session took a transaction with it
+    pub(super) fn note_transaction(&mut self, sql: &str) {
+        if let Some(open) = transaction_change(sql) {
+            self.ws.db.set_transaction_open(open);
+        }
+    }
+
+    // Error message for a statement that failed after `attempts` tries
+    pub(super) fn describe_failure(&self, error: &anyhow::Error, attempts: u32) -> String {
+        match crate::db::transient_failure(error) {
+            Some(Transient::Disconnected) if self.ws.db.transaction_open() => {
+                format!("{:#} (connection lost inside a transaction, which is gone with the session; not run again)", error)
+            }
+            Some(failure) if attempts > 1 => {
+                format!("{:#} ({}; gave up after {} attempts)", error, failure.describe(), attempts)
+            }
+            Some(failure) => format!("{:#} ({}; running it again may succeed)", error, failure.describe()),
+            None => format!("{:#}", error),
+        }
+    }
+
+    // Status after a statement that needed more than one try
+    pub(super) fn report_retries(&mut self, attempts: u32) {
+        if attempts > 1 {
+            self.set_status(format!("Succeeded after {} attempts", attempts));
+        }
+    }
+}
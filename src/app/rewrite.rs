@@ -0,0 +1,78 @@
+use crate::app::App;
+use crate::syntax::{SqlHighlighter, TokenType};
+
+// Shared plumbing for editor refactorings that rewrite the statement at the
+// cursor from its tokens
+
+pub struct Tok {
+    pub text: String,
+    pub upper: String,
+    pub kind: TokenType,
+    pub start: usize,
+    pub end: usize,
+}
+
+// Replacement of `start..end` in the editor text; an insertion when empty
+pub struct Edit {
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+}
+
+// Words that can follow a table name without being its alias
+pub const NOT_ALIAS: &[&str] = &[
+    "ON", "USING", "WHERE", "JOIN", "INNER", "LEFT", "RIGHT", "FULL", "CROSS", "NATURAL", "GROUP", "ORDER",
+    "HAVING", "LIMIT", "OFFSET", "UNION", "INTERSECT", "EXCEPT", "WINDOW", "FETCH", "FOR", "TABLESAMPLE",
+    "RETURNING",
+];
+
+// Tokens of the statement at `cursor`, without whitespace and comments.
+// Same boundaries as execute-at-cursor: a `;` at or after the cursor ends
+// the statement.
+pub fn statement_at(sql: &str, cursor: usize) -> Vec<Tok> {
+    let mut tokens = Vec::new();
+    let mut offset = 0;
+    for token in SqlHighlighter::new().tokenize(sql) {
+        let end = offset + token.text.len();
+        if !matches!(token.token_type, TokenType::Whitespace | TokenType::Comment) {
+            tokens.push(Tok {
+                upper: token.text.to_uppercase(),
+                text: token.text,
+                kind: token.token_type,
+                start: offset,
+                end,
+            });
+        }
+        offset = end;
+    }
+
+    let mut statement = Vec::new();
+    for tok in tokens {
+        if tok.text != ";" {
+            statement.push(tok);
+        } else if tok.start >= cursor {
+            break;
+        } else {
+            statement.clear();
+        }
+    }
+    statement
+}
+
+impl App {
+    // Apply non-overlapping edits to the editor, keeping the cursor on the
+    // same text
+    pub fn apply_edits(&mut self, mut edits: Vec<Edit>) {
+        edits.sort_by_key(|edit| edit.start);
+        // Back to front so earlier offsets stay valid
+        for edit in edits.iter().rev() {
+            self.ws.query_input.replace_range(edit.start..edit.end, &edit.text);
+            if self.ws.query_cursor >= edit.end {
+                self.ws.query_cursor = self.ws.query_cursor - (edit.end - edit.start) + edit.text.len();
+            } else if self.ws.query_cursor > edit.start {
+                self.ws.query_cursor = edit.start;
+            }
+        }
+        self.hide_autocomplete();
+    }
+}
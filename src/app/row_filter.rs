@@ -0,0 +1,166 @@
+use regex::{Regex, RegexBuilder};
+use std::cmp::Ordering;
+
+// Results filter expressions. The input is split into whitespace-separated
+// terms (double quotes keep spaces together); every term must match:
+//   status=paid  total>=10  name~^A  email!~example  "full name"="Ann Lee"
+// A term that does not name a result column is a plain substring search
+// over all cells, which is also what the filter did before.
+
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Match(Regex),
+    NotMatch(Regex),
+}
+
+enum Term {
+    Column { index: usize, op: Op, value: String },
+    Anywhere(String),
+}
+
+pub struct RowFilter {
+    terms: Vec<Term>,
+    // First problem found while parsing, e.g. an invalid regex; the term is skipped
+    pub error: Option<String>,
+}
+
+// Longest operators first so `>=` is not read as `>`
+const OPERATORS: &[&str] = &["!=", "<>", ">=", "<=", "!~", "~*", "=", "<", ">", "~"];
+
+impl RowFilter {
+    pub fn parse(input: &str, columns: &[String]) -> Self {
+        let mut filter = Self { terms: Vec::new(), error: None };
+        for raw in split_terms(input) {
+            match filter.parse_term(&raw, columns) {
+                Ok(term) => filter.terms.push(term),
+                Err(e) => {
+                    filter.error.get_or_insert(e);
+                }
+            }
+        }
+        filter
+    }
+
+    fn parse_term(&self, raw: &str, columns: &[String]) -> Result<Term, String> {
+        let anywhere = || Term::Anywhere(unquote(raw).to_lowercase());
+        let Some((at, op)) = find_operator(raw) else {
+            return Ok(anywhere());
+        };
+        let name = unquote(&raw[..at]);
+        let value = unquote(&raw[at + op.len()..]);
+        let Some(index) = columns.iter().position(|c| c.eq_ignore_ascii_case(name)) else {
+            return Ok(anywhere());
+        };
+
+        let regex = |case_insensitive: bool| {
+            RegexBuilder::new(value)
+                .case_insensitive(case_insensitive)
+                .build()
+                .map_err(|e| format!("invalid regex for {}: {}", name, e.to_string().lines().last().unwrap_or("").trim_start_matches("error: ")))
+        };
+        let op = match op {
+            "=" => Op::Eq,
+            "!=" | "<>" => Op::Ne,
+            "<" => Op::Lt,
+            "<=" => Op::Le,
+            ">" => Op::Gt,
+            ">=" => Op::Ge,
+            "~" => Op::Match(regex(false)?),
+            "~*" => Op::Match(regex(true)?),
+            _ => Op::NotMatch(regex(false)?),
+        };
+        Ok(Term::Column { index, op, value: value.to_string() })
+    }
+
+    pub fn matches(&self, row: &[Option<String>]) -> bool {
+        self.terms.iter().all(|term| match term {
+            Term::Anywhere(text) => row.iter().flatten().any(|cell| cell.to_lowercase().contains(text)),
+            Term::Column { index, op, value } => {
+                let Some(cell) = row.get(*index).and_then(Option::as_deref) else {
+                    // As in SQL, NULL is neither greater nor smaller than
+                    // anything; `col=NULL` finds it like IS NULL would
+                    let null = value.eq_ignore_ascii_case("NULL");
+                    return match op {
+                        Op::Eq => null,
+                        Op::Ne => !null,
+                        Op::NotMatch(_) => true,
+                        _ => false,
+                    };
+                };
+                match op {
+                    Op::Eq => cells_equal(cell, value),
+                    Op::Ne => !cells_equal(cell, value),
+                    Op::Lt => compare_cells(cell, value) == Ordering::Less,
+                    Op::Le => compare_cells(cell, value) != Ordering::Greater,
+                    Op::Gt => compare_cells(cell, value) == Ordering::Greater,
+                    Op::Ge => compare_cells(cell, value) != Ordering::Less,
+                    Op::Match(re) => re.is_match(cell),
+                    Op::NotMatch(re) => !re.is_match(cell),
+                }
+            }
+        })
+    }
+}
+
+// Numbers compare by value, everything else case-insensitively
+pub fn compare_cells(a: &str, b: &str) -> Ordering {
+    match (a.parse::<f64>(), b.parse::<f64>()) {
+        (Ok(x), Ok(y)) => x.total_cmp(&y),
+        _ => a.to_lowercase().cmp(&b.to_lowercase()).then_with(|| a.cmp(b)),
+    }
+}
+
+fn cells_equal(a: &str, b: &str) -> bool {
+    match (a.parse::<f64>(), b.parse::<f64>()) {
+        (Ok(x), Ok(y)) => x == y,
+        _ => a.to_lowercase() == b.to_lowercase(),
+    }
+}
+
+// Earliest operator outside double quotes
+pub(super) fn find_operator(term: &str) -> Option<(usize, &'static str)> {
+    let mut quoted = false;
+    for (i, c) in term.char_indices() {
+        if c == '"' {
+            quoted = !quoted;
+        } else if !quoted && i > 0 {
+            if let Some(op) = OPERATORS.iter().find(|op| term[i..].starts_with(**op)) {
+                return Some((i, op));
+            }
+        }
+    }
+    None
+}
+
+fn split_terms(input: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in input.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                current.push(c);
+            }
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    terms.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        terms.push(current);
+    }
+    terms
+}
+
+pub(super) fn unquote(s: &str) -> &str {
+    s.strip_prefix('"').and_then(|s| s.strip_suffix('"')).unwrap_or(s)
+}
@@ -0,0 +1,26 @@
+use anyhow::{anyhow, Context, Result};
+
+use crate::app::commands::expand_home;
+use crate::app::{App, PromptAction};
+
+// The selected schema's DDL written to a file, read from the catalogs
+// rather than by running pg_dump
+impl App {
+    pub fn begin_schema_export(&mut self) {
+        let Some(schema) = self.browser_schema() else {
+            return;
+        };
+        let initial = format!("{}{}.sql", self.ws.file_dir(), schema);
+        self.open_prompt(&format!("Export the DDL of schema {} to", schema), &initial, PromptAction::ExportSchema);
+    }
+
+    pub async fn export_schema(&mut self, path: &str) -> Result<()> {
+        let schema = self.browser_schema().ok_or_else(|| anyhow!("No schema selected"))?;
+        let client = self.ws.db.client().ok_or_else(|| anyhow!("Not connected"))?;
+        let ddl = crate::db::schema_ddl(client, &schema).await?;
+        let path = expand_home(path);
+        std::fs::write(&path, &ddl).with_context(|| format!("Failed to write {}", path))?;
+        self.set_status(format!("Wrote the DDL of schema {} to {}", schema, path));
+        Ok(())
+    }
+}
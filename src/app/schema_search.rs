@@ -0,0 +1,188 @@
+use anyhow::Result;
+use crossterm::event::KeyCode;
+
+use crate::app::{App, AppMode, BrowserItem, FolderType, TableDetailTab};
+use crate::autocomplete::fuzzy_match;
+use crate::db::{ObjectKind, SchemaObject};
+
+// Most matches listed at once
+const MAX_MATCHES: usize = 200;
+
+// Names of every schema's objects, searched as you type, to jump the browser to
+pub struct SchemaSearch {
+    pub objects: Vec<SchemaObject>,
+    pub filter: String,
+    // Indexes into `objects`, best match first, with the characters of
+    // `search_label` the filter matched
+    pub matches: Vec<(usize, Vec<usize>)>,
+    pub selected: usize,
+}
+
+// What an object is matched and listed as: its name, or with a `.` in the
+// filter the name qualified by its schema, or a column's by its table
+pub fn search_label(object: &SchemaObject, qualified: bool) -> String {
+    match (&object.parent, qualified) {
+        (_, false) => object.name.clone(),
+        (Some((_, parent)), true) if object.kind == ObjectKind::Column => format!("{}.{}", parent, object.name),
+        _ => format!("{}.{}", object.schema, object.name),
+    }
+}
+
+impl SchemaSearch {
+    pub fn qualified(&self) -> bool {
+        self.filter.contains('.')
+    }
+
+    fn update_matches(&mut self) {
+        let qualified = self.qualified();
+        let mut matches: Vec<(i32, usize, Vec<usize>)> = self
+            .objects
+            .iter()
+            .enumerate()
+            .filter_map(|(i, object)| {
+                let (score, matched) = fuzzy_match(&search_label(object, qualified), &self.filter)?;
+                Some((score, i, matched))
+            })
+            .collect();
+        // Ties go to relations before functions, columns and indexes, then
+        // to shorter names
+        let objects = &self.objects;
+        matches.sort_by(|a, b| {
+            let (x, y) = (&objects[a.1], &objects[b.1]);
+            b.0.cmp(&a.0)
+                .then_with(|| x.kind.cmp(&y.kind))
+                .then_with(|| x.name.len().cmp(&y.name.len()))
+                .then_with(|| x.name.cmp(&y.name))
+        });
+        matches.truncate(MAX_MATCHES);
+        self.matches = matches.into_iter().map(|(_, i, matched)| (i, matched)).collect();
+        self.selected = 0;
+    }
+}
+
+impl App {
+    pub async fn open_schema_search(&mut self) {
+        let Some(client) = self.ws.db.client() else {
+            return;
+        };
+        match crate::db::search_objects(client).await {
+            Ok(objects) => {
+                let mut search = SchemaSearch {
+                    objects,
+                    filter: String::new(),
+                    matches: Vec::new(),
+                    selected: 0,
+                };
+                search.update_matches();
+                self.schema_search = Some(search);
+            }
+            Err(e) => self.set_error(format!("{:#}", e)),
+        }
+    }
+
+    pub fn close_schema_search(&mut self) {
+        self.schema_search = None;
+    }
+
+    pub fn handle_schema_search_input(&mut self, key: KeyCode) {
+        let Some(search) = &mut self.schema_search else {
+            return;
+        };
+        let last = search.matches.len().saturating_sub(1);
+        match key {
+            KeyCode::Up => search.selected = search.selected.saturating_sub(1),
+            KeyCode::Down => search.selected = (search.selected + 1).min(last),
+            KeyCode::PageUp => search.selected = search.selected.saturating_sub(10),
+            KeyCode::PageDown => search.selected = (search.selected + 10).min(last),
+            KeyCode::Char(c) => {
+                search.filter.push(c);
+                search.update_matches();
+            }
+            KeyCode::Backspace => {
+                search.filter.pop();
+                search.update_matches();
+            }
+            _ => {}
+        }
+    }
+
+    // Show the selected object in the browser, expanding its schema and
+    // folder on the way. Columns and indexes select their table with the
+    // matching details tab open.
+    pub async fn jump_to_search_result(&mut self) {
+        let Some(search) = self.schema_search.take() else {
+            return;
+        };
+        let Some(object) = search.matches.get(search.selected).map(|(i, _)| search.objects[*i].clone()) else {
+            return;
+        };
+        let (kind, name, tab) = match (&object.parent, object.kind) {
+            (Some((kind, parent)), ObjectKind::Column) => (*kind, parent.clone(), TableDetailTab::Columns),
+            (Some((kind, parent)), ObjectKind::Index) => (*kind, parent.clone(), TableDetailTab::Indexes),
+            _ => (object.kind, object.name.clone(), TableDetailTab::Columns),
+        };
+        match self.reveal_in_browser(&object.schema, kind, &name).await {
+            Ok(true) => {
+                self.ws.table_detail_tab = tab;
+                if matches!(object.kind, ObjectKind::Column | ObjectKind::Index) {
+                    self.ws.found_detail = Some(object.name.clone());
+                }
+            }
+            Ok(false) => self.set_warning(format!(
+                "{}.{} ({}) is not listed in the browser",
+                object.schema,
+                name,
+                kind.label()
+            )),
+            Err(e) => self.set_error(format!("{:#}", e)),
+        }
+    }
+
+    // Select `name` in the browser tree and load its details; false when the
+    // tree doesn't list it (e.g. for lack of privileges)
+    pub(super) async fn reveal_in_browser(&mut self, schema: &str, kind: ObjectKind, name: &str) -> Result<bool> {
+        let folder = match kind {
+            ObjectKind::View => FolderType::Views,
+            ObjectKind::MaterializedView => FolderType::MaterializedViews,
+            ObjectKind::Function => FolderType::Functions,
+            _ => FolderType::Tables,
+        };
+        self.mode = AppMode::Browser;
+        // A browser filter could hide the object
+        self.ws.filter_active = false;
+        self.ws.filter_input.clear();
+
+        let is_schema = |item: &BrowserItem| matches!(item, BrowserItem::Schema(s) if s == schema);
+        let Some(at) = self.expand_browser_node(0, is_schema).await? else {
+            return Ok(false);
+        };
+        let is_folder = |item: &BrowserItem| matches!(item, BrowserItem::Folder(s, f) if s == schema && *f == folder);
+        let Some(at) = self.expand_browser_node(at, is_folder).await? else {
+            return Ok(false);
+        };
+        let found = self.ws.browser_items[at..].iter().position(|item| match (item, kind) {
+            (BrowserItem::Table(s, n), ObjectKind::Table)
+            | (BrowserItem::View(s, n), ObjectKind::View)
+            | (BrowserItem::MaterializedView(s, n), ObjectKind::MaterializedView)
+            | (BrowserItem::Function(s, n), ObjectKind::Function) => s == schema && n == name,
+            _ => false,
+        });
+        let Some(offset) = found else {
+            return Ok(false);
+        };
+        self.ws.browser_selected = at + offset;
+        self.browser_select().await?;
+        Ok(true)
+    }
+
+    // The first node from `from` on that `is_node` accepts, expanded unless
+    // it already was
+    async fn expand_browser_node(&mut self, from: usize, is_node: impl Fn(&BrowserItem) -> bool) -> Result<Option<usize>> {
+        let Some(offset) = self.ws.browser_items[from..].iter().position(is_node) else {
+            return Ok(None);
+        };
+        self.ws.browser_selected = from + offset;
+        self.expand_row(from + offset).await?;
+        Ok(Some(from + offset))
+    }
+}
@@ -0,0 +1,326 @@
+use anyhow::{anyhow, Result};
+use std::time::Instant;
+
+use crate::app::{hooks, App, GuardedRun, NotificationLevel, StatementOutcome, StatementStatus};
+
+// Whole-buffer script execution
+impl App {
+    pub async fn execute_script(&mut self) -> Result<()> {
+        let script = self.ws.query_input.clone();
+        self.refuse_on_read_only(&script)?;
+        if self.hold_destructive(&script, GuardedRun::Script) || self.hold_write(&script, GuardedRun::Script) {
+            return Ok(());
+        }
+        if !self.ws.db.is_connected() {
+            return Ok(());
+        }
+
+        let statements = split_statements(&self.ws.query_input);
+        if statements.is_empty() {
+            return Ok(());
+        }
+
+        // Toasts of earlier runs go; ones from this run stay
+        self.clear_notifications();
+        self.ws.watch = None;
+        let stop_on_error = self.config.stop_script_on_error;
+        let mut outcomes = Vec::with_capacity(statements.len());
+        let mut last_result = None;
+        let mut failed = 0;
+        // Extra attempts made for statements that failed transiently
+        let mut retried = 0;
+        let mut hook_error = None;
+        // Every failure, for the message log
+        let mut errors = Vec::new();
+        let began = Instant::now();
+        self.discard_notices();
+
+        for sql in statements {
+            if stop_on_error && failed > 0 {
+                outcomes.push(StatementOutcome {
+                    sql,
+                    status: StatementStatus::Skipped,
+                    row_count: 0,
+                    elapsed: Default::default(),
+                });
+                continue;
+            }
+
+            if !self.ws.db.is_connected() {
+                break;
+            }
+            let started = Instant::now();
+            if let Err(e) = hooks::run_pre_query(&self.ws.db, &self.ws.hooks, &sql).await {
+                failed += 1;
+                let error = format!("{:#}", e);
+                errors.push(format!("Statement {}: {}", outcomes.len() + 1, error));
+                outcomes.push(StatementOutcome {
+                    sql,
+                    status: StatementStatus::Failed(error),
+                    row_count: 0,
+                    elapsed: started.elapsed(),
+                });
+                continue;
+            }
+            let executed = Instant::now();
+            let mut attempts = 0;
+            let result = loop {
+                attempts += 1;
+                let Some(client) = self.ws.db.client() else {
+                    break Err(anyhow!("Not connected"));
+                };
+                match crate::db::execute_query(client, &sql).await {
+                    Err(e) if self.retry_after(&sql, &e, attempts).await => continue,
+                    result => break result,
+                }
+            };
+            let rows = result.as_ref().ok().map(|r| r.row_count);
+            self.stats.record_query(&sql, rows);
+            let outcome = result.as_ref().map(|r| r.row_count).map_err(|e| format!("{:#}", e));
+            self.audit.record_statement(&self.ws.name, &sql, executed.elapsed(), outcome);
+            retried += attempts - 1;
+            if !self.ws.db.is_connected() {
+                break;
+            }
+            if let Err(e) = hooks::run_post_query(&self.ws.db, &self.ws.hooks, &sql, rows).await {
+                let error = format!("{:#}", e);
+                errors.push(format!("Statement {}: {}", outcomes.len() + 1, error));
+                hook_error = Some(error);
+            }
+            match result {
+                Ok(result) => {
+                    self.note_transaction(&sql);
+                    outcomes.push(StatementOutcome {
+                        sql: sql.clone(),
+                        status: StatementStatus::Ok,
+                        row_count: result.row_count,
+                        elapsed: started.elapsed(),
+                    });
+                    last_result = Some((sql, result));
+                }
+                Err(e) => {
+                    failed += 1;
+                    let error = self.describe_failure(&e, attempts);
+                    errors.push(format!("Statement {}: {}", outcomes.len() + 1, error));
+                    outcomes.push(StatementOutcome {
+                        sql,
+                        status: StatementStatus::Failed(error),
+                        row_count: 0,
+                        elapsed: started.elapsed(),
+                    });
+                }
+            }
+        }
+
+        // Notices of every statement go with the script's results
+        let notices = self.collect_notices();
+        if let Some((sql, result)) = last_result {
+            self.ws.query_result = Some(result);
+            self.ws.result_tag = None;
+            // The whole script's time; the outcomes list each statement's
+            self.ws.result_elapsed = Some(began.elapsed());
+            self.ws.result_sql = Some(sql);
+            self.ws.result_params = Vec::new();
+            self.ws.auto_limited = None;
+            self.ws.result_notices = notices;
+        }
+
+        for error in errors {
+            self.log_message(NotificationLevel::Error, error);
+        }
+        if failed > 0 {
+            let messages = self.keymap.label(crate::keymap::Action::ShowMessages);
+            self.set_error(format!("Script finished with {} failed statement(s); {} lists the errors", failed, messages));
+        } else if let Some(e) = hook_error {
+            self.set_error(e);
+        } else if retried > 0 {
+            self.set_status(format!("Script finished; {} statement run(s) were retried", retried));
+        }
+        self.ws.script_outcomes = Some(outcomes);
+        Ok(())
+    }
+}
+
+/// Split a SQL buffer into individual statements on top-level semicolons.
+///
+/// Semicolons inside string literals (E'' ones with backslash escapes),
+/// quoted identifiers, comments (nested block comments too) and
+/// dollar-quoted bodies are not treated as separators.
+pub fn split_statements(sql: &str) -> Vec<String> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let ch = chars[i];
+        match ch {
+            '\'' | '"' => {
+                // Quoted literal/identifier; doubled quotes stay inside, and
+                // so does a backslash-escaped quote in an E'' literal
+                let escapes = ch == '\''
+                    && i > 0
+                    && matches!(chars[i - 1], 'E' | 'e')
+                    && (i < 2 || !is_identifier_char(chars[i - 2]));
+                current.push(ch);
+                i += 1;
+                while i < chars.len() {
+                    current.push(chars[i]);
+                    if escapes && chars[i] == '\\' {
+                        if let Some(&escaped) = chars.get(i + 1) {
+                            current.push(escaped);
+                        }
+                        i += 2;
+                        continue;
+                    }
+                    if chars[i] == ch {
+                        if chars.get(i + 1) == Some(&ch) {
+                            current.push(ch);
+                            i += 1;
+                        } else {
+                            break;
+                        }
+                    }
+                    i += 1;
+                }
+                i += 1;
+            }
+            '-' if chars.get(i + 1) == Some(&'-') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    current.push(chars[i]);
+                    i += 1;
+                }
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                // Block comments nest
+                let mut depth = 0;
+                while i < chars.len() {
+                    if chars[i] == '/' && chars.get(i + 1) == Some(&'*') {
+                        depth += 1;
+                    } else if chars[i] == '*' && chars.get(i + 1) == Some(&'/') {
+                        depth -= 1;
+                    } else {
+                        current.push(chars[i]);
+                        i += 1;
+                        continue;
+                    }
+                    current.push(chars[i]);
+                    current.push(chars[i + 1]);
+                    i += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+            }
+            '$' => {
+                if let Some(tag) = dollar_tag(&chars, i) {
+                    let tag_chars: Vec<char> = tag.chars().collect();
+                    current.push_str(&tag);
+                    i += tag_chars.len();
+                    while i < chars.len() {
+                        if chars[i..].starts_with(&tag_chars) {
+                            current.push_str(&tag);
+                            i += tag_chars.len();
+                            break;
+                        }
+                        current.push(chars[i]);
+                        i += 1;
+                    }
+                } else {
+                    current.push(ch);
+                    i += 1;
+                }
+            }
+            ';' => {
+                push_statement(&mut statements, &current);
+                current.clear();
+                i += 1;
+            }
+            _ => {
+                current.push(ch);
+                i += 1;
+            }
+        }
+    }
+    push_statement(&mut statements, &current);
+
+    statements
+}
+
+// Returns the full `$tag$` opener starting at `start`, if there is one
+pub(super) fn dollar_tag(chars: &[char], start: usize) -> Option<String> {
+    // A `$` inside an identifier (`a$b$`) opens nothing
+    if start > 0 && is_identifier_char(chars[start - 1]) {
+        return None;
+    }
+    let mut end = start + 1;
+    while end < chars.len() && (chars[end].is_alphanumeric() || chars[end] == '_') {
+        end += 1;
+    }
+    if chars.get(end) != Some(&'$') {
+        return None;
+    }
+    // `$1` is a positional parameter, not a tag
+    if chars.get(start + 1).is_some_and(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some(chars[start..=end].iter().collect())
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+fn push_statement(statements: &mut Vec<String>, text: &str) {
+    let trimmed = text.trim();
+    let only_comments = trimmed
+        .lines()
+        .all(|line| line.trim().is_empty() || line.trim().starts_with("--"));
+    if !trimmed.is_empty() && !only_comments {
+        statements.push(trimmed.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_on_top_level_semicolons() {
+        assert_eq!(split_statements("SELECT 1; SELECT 2;\n-- done"), ["SELECT 1", "SELECT 2"]);
+        assert_eq!(split_statements("SELECT ';', \"a;b\" FROM t; SELECT 2"), ["SELECT ';', \"a;b\" FROM t", "SELECT 2"]);
+        assert_eq!(split_statements("SELECT 'it''s; fine'"), ["SELECT 'it''s; fine'"]);
+    }
+
+    #[test]
+    fn escape_strings_keep_backslashed_quotes() {
+        assert_eq!(split_statements(r"SELECT E'a\'; b'; SELECT 2"), [r"SELECT E'a\'; b'", "SELECT 2"]);
+        assert_eq!(split_statements(r"SELECT e'\\'; SELECT 2"), [r"SELECT e'\\'", "SELECT 2"]);
+        // Not an E'' literal: a name ending in e, then a plain one
+        assert_eq!(split_statements(r"SELECT name'a\'; SELECT 2"), [r"SELECT name'a\'", "SELECT 2"]);
+    }
+
+    #[test]
+    fn dollar_quotes_hide_semicolons() {
+        assert_eq!(
+            split_statements("CREATE FUNCTION f() RETURNS int AS $body$ SELECT 1; $body$ LANGUAGE sql; SELECT 2"),
+            ["CREATE FUNCTION f() RETURNS int AS $body$ SELECT 1; $body$ LANGUAGE sql", "SELECT 2"]
+        );
+        assert_eq!(split_statements("SELECT $1; SELECT $$;$$"), ["SELECT $1", "SELECT $$;$$"]);
+    }
+
+    #[test]
+    fn dollars_inside_identifiers_open_nothing() {
+        assert_eq!(split_statements("SELECT a$b$ FROM t; SELECT 2"), ["SELECT a$b$ FROM t", "SELECT 2"]);
+        assert_eq!(split_statements("SELECT x$$; SELECT $$"), ["SELECT x$$", "SELECT $$"]);
+    }
+
+    #[test]
+    fn block_comments_nest() {
+        assert_eq!(
+            split_statements("SELECT 1 /* outer /* inner; */ still; */; SELECT 2"),
+            ["SELECT 1 /* outer /* inner; */ still; */", "SELECT 2"]
+        );
+        assert_eq!(split_statements("/* only; a comment */"), ["/* only; a comment */"]);
+    }
+}
@@ -0,0 +1,255 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use rhai::{Array, Dynamic, Engine, EvalAltResult, Map};
+use tokio::runtime::Handle;
+use tokio_postgres::Client;
+
+use crate::app::commands::{expand_home, to_csv};
+use crate::app::destructive::destructive_action;
+use crate::app::guard::write_keyword;
+use crate::app::hooks;
+use crate::app::{App, ResultPopup};
+use crate::config::{Environment, QueryHooks, UserCommand};
+use crate::db::QueryResult;
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+// Rhai operations a script may run before it is stopped, so a runaway loop
+// ends in an error instead of a frozen screen. Time spent waiting on the
+// server does not count.
+const MAX_OPERATIONS: u64 = 10_000_000;
+
+// What a script did that the app shows or logs once it has finished
+#[derive(Default)]
+struct ScriptEffects {
+    // (sql, elapsed, rows or error) for the session log
+    statements: Vec<(String, Duration, Result<usize, String>)>,
+    popup: Option<(String, QueryResult)>,
+    messages: Vec<String>,
+    // (path, rows)
+    exports: Vec<(String, usize)>,
+}
+
+// Things a script can see but not change
+struct ScriptContext {
+    client: Arc<Client>,
+    // Writes are refused on production connections, which ask before
+    // every write made from the editor, and on read-only ones
+    read_only: bool,
+    // The connection's pre/post query hooks, run around each statement
+    hooks: QueryHooks,
+    results: Option<QueryResult>,
+    selected_table: Option<(String, String)>,
+}
+
+// User commands backed by a Rhai script file instead of SQL. The script runs
+// on this task, blocking the UI like a query from the editor does; its
+// database calls wait for the server in place.
+impl App {
+    pub(super) async fn run_script_command(&mut self, command: &UserCommand, path: &str) -> Result<()> {
+        let path = expand_home(path);
+        let source = std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path))?;
+        let client = self.ws.db.shared_client().ok_or_else(|| anyhow!("Not connected"))?;
+        let context = Rc::new(ScriptContext {
+            client,
+            read_only: self.ws.read_only || self.ws.environment == Some(Environment::Prod),
+            hooks: self.ws.hooks.clone(),
+            results: self.ws.query_result.clone(),
+            selected_table: self.ws.selected_table.clone(),
+        });
+        let effects = Rc::new(RefCell::new(ScriptEffects::default()));
+        let engine = script_engine(context, effects.clone());
+        let outcome = tokio::task::block_in_place(|| engine.run(&source));
+
+        // Whatever ran before a failure still goes into the log
+        let effects = effects.take();
+        for (sql, elapsed, rows) in effects.statements {
+            if let Ok(rows) = rows {
+                self.stats.record_query(&sql, Some(rows));
+            }
+            self.audit.record_statement(&self.ws.name, &sql, elapsed, rows);
+        }
+        for (path, rows) in &effects.exports {
+            self.audit.record_export(path, *rows);
+        }
+        outcome.map_err(|e| anyhow!("Script '{}' failed: {}", command.name, e))?;
+
+        if let Some((title, result)) = effects.popup {
+            self.popup = Some(ResultPopup { title, result, scroll: 0 });
+        }
+        match effects.messages.last() {
+            Some(message) => self.set_status(message.clone()),
+            None => self.set_status(format!("{}: done", command.name)),
+        }
+        Ok(())
+    }
+}
+
+fn script_engine(context: Rc<ScriptContext>, effects: Rc<RefCell<ScriptEffects>>) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+
+    let (ctx, fx) = (context.clone(), effects.clone());
+    engine.register_fn("query", move |sql: &str| -> ScriptResult<Map> {
+        let (_, result) = run_sql(&ctx, &fx, sql, |client, sql| async move {
+            let result = crate::db::execute_query(&client, &sql).await?;
+            Ok((result.row_count, Some(result)))
+        })?;
+        Ok(result_to_map(&result.unwrap_or_else(empty_result)))
+    });
+
+    let (ctx, fx) = (context.clone(), effects.clone());
+    engine.register_fn("execute", move |sql: &str| -> ScriptResult<i64> {
+        let (rows, _) = run_sql(&ctx, &fx, sql, |client, sql| async move {
+            let rows = crate::db::execute_statement(&client, &sql).await?;
+            Ok((rows as usize, None))
+        })?;
+        Ok(rows as i64)
+    });
+
+    let ctx = context.clone();
+    engine.register_fn("results", move || -> Dynamic {
+        match &ctx.results {
+            Some(result) => Dynamic::from_map(result_to_map(result)),
+            None => Dynamic::UNIT,
+        }
+    });
+
+    let ctx = context.clone();
+    engine.register_fn("selected_table", move || -> Dynamic {
+        match &ctx.selected_table {
+            Some((schema, table)) => {
+                let mut map = Map::new();
+                map.insert("schema".into(), schema.clone().into());
+                map.insert("table".into(), table.clone().into());
+                Dynamic::from_map(map)
+            }
+            None => Dynamic::UNIT,
+        }
+    });
+
+    let fx = effects.clone();
+    engine.register_fn("popup", move |title: &str, result: Map| -> ScriptResult<()> {
+        fx.borrow_mut().popup = Some((title.to_string(), map_to_result(result)?));
+        Ok(())
+    });
+
+    let fx = effects.clone();
+    engine.register_fn("write_file", move |path: &str, text: &str| -> ScriptResult<()> {
+        let path = expand_home(path);
+        std::fs::write(&path, text).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+        fx.borrow_mut().exports.push((path, text.lines().count()));
+        Ok(())
+    });
+
+    engine.register_fn("to_csv", |result: Map| -> ScriptResult<String> { Ok(to_csv(&map_to_result(result)?)) });
+    engine.register_fn("quote_ident", |name: &str| crate::db::quote_ident(name));
+    engine.register_fn("quote_literal", |value: &str| crate::db::quote_literal(value));
+
+    let fx = effects.clone();
+    engine.register_fn("status", move |message: &str| fx.borrow_mut().messages.push(message.to_string()));
+    let fx = effects;
+    engine.on_print(move |message| fx.borrow_mut().messages.push(message.to_string()));
+
+    engine
+}
+
+// Run one statement for a script, with the connection's hooks, and log it.
+// A script cannot stop to ask, so what the editor would confirm is refused:
+// destructive statements, and writes on a read-only (production)
+// connection.
+fn run_sql<F, Fut>(
+    context: &ScriptContext,
+    effects: &RefCell<ScriptEffects>,
+    sql: &str,
+    run: F,
+) -> ScriptResult<(usize, Option<QueryResult>)>
+where
+    F: FnOnce(Arc<Client>, String) -> Fut,
+    Fut: std::future::Future<Output = Result<(usize, Option<QueryResult>)>>,
+{
+    if let Some(action) = destructive_action(sql) {
+        return Err(format!("{} refused: scripts cannot run destructive statements; run it from the editor", action).into());
+    }
+    if context.read_only {
+        if let Some(word) = write_keyword(sql) {
+            return Err(format!("{} refused: scripts cannot write on a production or read-only connection", word).into());
+        }
+    }
+    let client = context.client.clone();
+    let hooks = &context.hooks;
+    Handle::current()
+        .block_on(hooks::run_pre_query_on(&client, hooks, sql))
+        .map_err(|e| format!("{:#}", e))?;
+    let started = Instant::now();
+    let outcome = Handle::current().block_on(run(client.clone(), sql.to_string()));
+    let logged = outcome.as_ref().map(|(rows, _)| *rows).map_err(|e| format!("{:#}", e));
+    effects.borrow_mut().statements.push((sql.to_string(), started.elapsed(), logged));
+    let rows = outcome.as_ref().ok().map(|(rows, _)| *rows);
+    let post_hooks = Handle::current().block_on(hooks::run_post_query_on(&client, hooks, sql, rows));
+    // The statement's own error comes first
+    let outcome = outcome.map_err(|e| format!("{:#}", e))?;
+    post_hooks.map_err(|e| format!("{:#}", e))?;
+    Ok(outcome)
+}
+
+fn empty_result() -> QueryResult {
+    QueryResult {
+        columns: Vec::new(),
+        rows: Vec::new(),
+        types: Vec::new(),
+        row_count: 0,
+        source: None,
+    }
+}
+
+// #{columns: [..], rows: [[..], ..], row_count: n}; NULL cells are ()
+fn result_to_map(result: &QueryResult) -> Map {
+    let columns: Array = result.columns.iter().map(|c| Dynamic::from(c.clone())).collect();
+    let rows: Array = result
+        .rows
+        .iter()
+        .map(|row| {
+            let cells: Array = row
+                .iter()
+                .map(|value| value.clone().map_or(Dynamic::UNIT, Dynamic::from))
+                .collect();
+            Dynamic::from_array(cells)
+        })
+        .collect();
+    let mut map = Map::new();
+    map.insert("columns".into(), Dynamic::from_array(columns));
+    map.insert("rows".into(), Dynamic::from_array(rows));
+    map.insert("row_count".into(), Dynamic::from(result.rows.len() as i64));
+    map
+}
+
+fn map_to_result(mut map: Map) -> ScriptResult<QueryResult> {
+    let mut list = |key: &str| -> ScriptResult<Array> {
+        map.remove(key)
+            .and_then(|value| value.try_cast::<Array>())
+            .ok_or_else(|| format!("Expected a map with a '{}' array, like the ones query() returns", key).into())
+    };
+    let columns: Vec<String> = list("columns")?.into_iter().map(|c| c.to_string()).collect();
+    let rows = list("rows")?
+        .into_iter()
+        .map(|row| {
+            let cells = row.try_cast::<Array>().ok_or("Each row has to be an array of cells")?;
+            Ok(cells
+                .into_iter()
+                .map(|cell| (!cell.is_unit()).then(|| cell.to_string()))
+                .collect())
+        })
+        .collect::<ScriptResult<Vec<Vec<Option<String>>>>>()?;
+    Ok(QueryResult {
+        columns,
+        row_count: rows.len(),
+        rows,
+        types: Vec::new(),
+        source: None,
+    })
+}
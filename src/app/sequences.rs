@@ -0,0 +1,39 @@
+use std::time::Instant;
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::app::{App, PromptAction};
+
+// Restarting the sequence selected in the browser
+impl App {
+    pub fn begin_restart_sequence(&mut self) {
+        let (Some((schema, name)), Some(details)) = (&self.ws.selected_sequence, &self.ws.sequence_details) else {
+            return;
+        };
+        let last = details.last_value.map(|v| v.to_string()).unwrap_or_else(|| "unused".to_string());
+        let title = format!("Restart sequence {}.{} (last value {}) - next value, Enter to confirm", schema, name, last);
+        let start = details.start_value.to_string();
+        self.open_prompt(&title, &start, PromptAction::RestartSequence);
+    }
+
+    pub async fn restart_sequence(&mut self, input: &str) -> Result<()> {
+        let (Some((schema, name)), Some(details)) = (self.ws.selected_sequence.clone(), &self.ws.sequence_details) else {
+            return Ok(());
+        };
+        let value: i64 = input.parse().map_err(|_| anyhow!("Not a number: {}", input))?;
+        if value < details.min_value || value > details.max_value {
+            bail!("{} is outside the sequence's range {}..{}", value, details.min_value, details.max_value);
+        }
+        let client = self.ws.db.client().ok_or_else(|| anyhow!("Not connected"))?;
+        let started = Instant::now();
+        let restarted = crate::db::restart_sequence(client, &schema, &name, value).await;
+        let outcome = restarted.as_ref().map(|_| 0).map_err(|e| format!("{:#}", e));
+        let sql = format!("ALTER SEQUENCE {} RESTART WITH {}", crate::db::qualified_name(&schema, &name), value);
+        self.audit.record_statement(&self.ws.name, &sql, started.elapsed(), outcome);
+        restarted?;
+
+        self.ws.sequence_details = crate::db::get_sequence_details(client, &schema, &name).await?;
+        self.set_status(format!("Sequence {}.{} restarted; nextval() returns {}", schema, name, value));
+        Ok(())
+    }
+}
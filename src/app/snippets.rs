@@ -0,0 +1,178 @@
+use std::ops::Range;
+
+use crossterm::event::KeyCode;
+
+use crate::app::App;
+
+// Placeholders of an expanded snippet still being filled in
+#[derive(Debug, Clone)]
+pub struct SnippetSession {
+    // Byte ranges in the editor text, in Tab order; the last one is where
+    // the cursor ends up
+    stops: Vec<Range<usize>>,
+    current: usize,
+    // The current placeholder still holds its default text, which the
+    // first key typed replaces
+    fresh: bool,
+}
+
+impl SnippetSession {
+    // Placeholder text to highlight in the editor
+    pub fn highlighted(&self) -> Option<Range<usize>> {
+        let stop = self.stops.get(self.current)?;
+        (self.fresh && !stop.is_empty()).then(|| stop.clone())
+    }
+
+    // Move the placeholders after an edit at `at` that changed the text's
+    // length by `delta`. The current one grows or shrinks with edits inside
+    // or at either end of it; others only move.
+    fn shift(&mut self, at: usize, delta: isize) {
+        let moved = |pos: usize| pos.saturating_add_signed(delta).max(at);
+        for (i, stop) in self.stops.iter_mut().enumerate() {
+            if i == self.current && (stop.start..=stop.end).contains(&at) {
+                stop.end = moved(stop.end);
+            } else if stop.start >= at {
+                *stop = moved(stop.start)..moved(stop.end);
+            } else if stop.end > at {
+                stop.end = moved(stop.end);
+            }
+        }
+    }
+}
+
+impl App {
+    // Put the snippet in place of the word `range` covers and go to its first
+    // placeholder. Lines after the first get the current line's indentation.
+    pub(super) fn expand_snippet(&mut self, range: Range<usize>, template: &str) {
+        let line_start = self.ws.line_start(range.start);
+        let indent: String = self.ws.query_input[line_start..]
+            .chars()
+            .take_while(|c| *c == ' ' || *c == '\t')
+            .collect();
+        let template = template.replace('\n', &format!("\n{}", indent));
+        let (text, stops) = parse_template(&template);
+
+        self.ws.query_input.replace_range(range.clone(), &text);
+        let stops = stops.into_iter().map(|r| r.start + range.start..r.end + range.start).collect();
+        let mut session = SnippetSession {
+            stops,
+            current: 0,
+            fresh: false,
+        };
+        self.ws.query_cursor = range.start + text.len();
+        self.enter_stop(&mut session);
+        if session.current + 1 < session.stops.len() {
+            self.ws.snippet = Some(session);
+        }
+    }
+
+    pub fn in_snippet(&self) -> bool {
+        self.ws.snippet.is_some()
+    }
+
+    // Tab / Shift+Tab: the next or previous placeholder; past the last one
+    // the snippet is done
+    pub fn snippet_jump(&mut self, forward: bool) {
+        let Some(mut session) = self.ws.snippet.take() else {
+            return;
+        };
+        if forward {
+            session.current += 1;
+        } else {
+            session.current = session.current.saturating_sub(1);
+        }
+        self.enter_stop(&mut session);
+        if session.current + 1 < session.stops.len() {
+            self.ws.snippet = Some(session);
+        }
+    }
+
+    pub fn end_snippet(&mut self) {
+        self.ws.snippet = None;
+    }
+
+    // An editor key while placeholders are being filled in: typing over a
+    // fresh placeholder replaces it, and every edit moves the ones after it
+    pub fn snippet_editor_key(&mut self, key: KeyCode) {
+        let Some(mut session) = self.ws.snippet.take() else {
+            self.editor_key(key);
+            return;
+        };
+        let typing = matches!(key, KeyCode::Char(_) | KeyCode::Backspace | KeyCode::Delete);
+        if session.fresh && typing && self.editor_inserting() {
+            let stop = session.stops[session.current].clone();
+            self.ws.query_input.replace_range(stop.clone(), "");
+            self.ws.query_cursor = stop.start;
+            session.shift(stop.start, -(stop.len() as isize));
+            session.fresh = false;
+            if !matches!(key, KeyCode::Char(_)) {
+                self.ws.snippet = Some(session);
+                return;
+            }
+        }
+
+        let (length, cursor) = (self.ws.query_input.len(), self.ws.query_cursor);
+        self.editor_key(key);
+        self.ws.snippet = Some(session);
+        self.snippet_edited(cursor.min(self.ws.query_cursor), length);
+    }
+
+    // Keep the placeholders in place after an edit at `at` changed the text
+    // from `old_length` bytes
+    pub(super) fn snippet_edited(&mut self, at: usize, old_length: usize) {
+        let delta = self.ws.query_input.len() as isize - old_length as isize;
+        if let Some(session) = self.ws.snippet.as_mut().filter(|_| delta != 0) {
+            session.shift(at, delta);
+            session.fresh = false;
+            // A placeholder past the end means the text was replaced wholesale
+            if session.stops.iter().any(|stop| stop.end > self.ws.query_input.len()) {
+                self.ws.snippet = None;
+            }
+        }
+    }
+
+    fn enter_stop(&mut self, session: &mut SnippetSession) {
+        let Some(stop) = session.stops.get(session.current) else {
+            return;
+        };
+        self.ws.query_cursor = stop.start;
+        session.fresh = !stop.is_empty();
+    }
+}
+
+// Template text without its markers, and the placeholders' ranges in it in
+// Tab order, the `$0` one (or the end of the text) last
+fn parse_template(template: &str) -> (String, Vec<Range<usize>>) {
+    let mut text = String::new();
+    let mut numbered: Vec<(u32, Range<usize>)> = Vec::new();
+    let mut rest = template;
+    while let Some(at) = rest.find('$') {
+        text.push_str(&rest[..at]);
+        rest = &rest[at + 1..];
+        if let Some(inner) = rest.strip_prefix('{') {
+            let Some(end) = inner.find('}') else {
+                text.push('$');
+                continue;
+            };
+            let (number, default) = inner[..end].split_once(':').unwrap_or((&inner[..end], ""));
+            let start = text.len();
+            text.push_str(default);
+            numbered.push((number.parse().unwrap_or(u32::MAX), start..text.len()));
+            rest = &inner[end + 1..];
+        } else if let Some(after) = rest.strip_prefix('0') {
+            numbered.push((0, text.len()..text.len()));
+            rest = after;
+        } else {
+            text.push('$');
+        }
+    }
+    text.push_str(rest);
+
+    // $0 sorts after every other number
+    numbered.sort_by_key(|(number, _)| number.wrapping_sub(1));
+    let mut stops: Vec<Range<usize>> = numbered.into_iter().map(|(_, range)| range).collect();
+    if stops.last().is_none_or(|last| !last.is_empty()) {
+        stops.push(text.len()..text.len());
+    }
+    (text, stops)
+}
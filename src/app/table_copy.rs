@@ -0,0 +1,82 @@
+use anyhow::{anyhow, bail, Result};
+
+use crate::app::App;
+
+// Types lined up on the right in a Markdown table
+const NUMERIC_TYPES: &[&str] = &["int2", "int4", "int8", "float4", "float8", "numeric", "oid", "money"];
+
+// The grid as a text table to paste into a pull request or notes: the rows
+// the filter leaves, in the grid's order, and the columns it shows
+impl App {
+    pub fn copy_results_table(&mut self, org: bool) {
+        match self.results_table(org) {
+            Ok((text, rows)) => match crate::clipboard::copy(&text) {
+                Ok(()) => {
+                    let format = if org { "an org" } else { "a Markdown" };
+                    self.set_status(format!("Copied {} row(s) as {} table", rows, format));
+                }
+                Err(e) => self.set_error(format!("{:#}", e)),
+            },
+            Err(e) => self.set_error(format!("{:#}", e)),
+        }
+    }
+
+    fn results_table(&self, org: bool) -> Result<(String, usize)> {
+        let result = self.ws.query_result.as_ref().ok_or_else(|| anyhow!("No results"))?;
+        if result.columns.is_empty() {
+            bail!("The results have no columns");
+        }
+        let rows = self.ws.filtered_rows().unwrap_or_else(|| (0..result.rows.len()).collect());
+        let null_text = &self.config.theme.grid.null_text;
+        let cell = |text: &str| match org {
+            // Org has no escape for a bar inside a cell
+            true => text.replace(['\n', '\r'], " ").replace('|', "\\vert{}"),
+            false => text.replace("\r\n", "<br>").replace(['\n', '\r'], "<br>").replace('|', "\\|"),
+        };
+        let mut lines: Vec<Vec<String>> = vec![result.columns.iter().map(|name| cell(name)).collect()];
+        lines.extend(rows.iter().map(|&row| {
+            result.rows[row]
+                .iter()
+                .map(|value| cell(value.as_deref().unwrap_or(null_text)))
+                .collect()
+        }));
+        let widths: Vec<usize> = (0..result.columns.len())
+            .map(|col| lines.iter().map(|line| line[col].chars().count()).max().unwrap_or(0).max(3))
+            .collect();
+        let right: Vec<bool> = (0..result.columns.len())
+            .map(|col| result.column_type(col).is_some_and(|kind| NUMERIC_TYPES.contains(&kind)))
+            .collect();
+
+        let row_text = |line: &[String]| {
+            let cells: Vec<String> = line
+                .iter()
+                .enumerate()
+                .map(|(col, text)| match right[col] {
+                    true => format!("{:>width$}", text, width = widths[col]),
+                    false => format!("{:<width$}", text, width = widths[col]),
+                })
+                .collect();
+            format!("| {} |", cells.join(" | "))
+        };
+        let separator = match org {
+            true => {
+                let dashes: Vec<String> = widths.iter().map(|&width| "-".repeat(width + 2)).collect();
+                format!("|{}|", dashes.join("+"))
+            }
+            false => {
+                let dashes: Vec<String> = widths
+                    .iter()
+                    .zip(&right)
+                    .map(|(&width, &right)| match right {
+                        true => format!("{}:", "-".repeat(width - 1)),
+                        false => "-".repeat(width),
+                    })
+                    .collect();
+                format!("| {} |", dashes.join(" | "))
+            }
+        };
+        let mut out = vec![row_text(&lines[0]), separator];
+        out.extend(lines[1..].iter().map(|line| row_text(line)));
+        Ok((out.join("\n") + "\n", rows.len()))
+    }
+}
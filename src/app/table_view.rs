@@ -0,0 +1,303 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use unicode_width::UnicodeWidthStr;
+
+use crate::app::{App, SortDirection};
+use crate::config::TableView;
+use crate::db::QueryResult;
+
+// Widest a column gets from its content alone, and the range it can be
+// resized to by hand
+pub const AUTO_COLUMN_WIDTH: usize = 30;
+const MIN_COLUMN_WIDTH: usize = 3;
+const MAX_COLUMN_WIDTH: usize = 200;
+
+// Resizing is a run of keypresses; the widths are saved once they stop
+const RESIZE_SAVE_DELAY: Duration = Duration::from_millis(800);
+
+// Saved grid layouts: column order, hidden columns and default sort for
+// results that come from a single table, stored per connection profile
+impl App {
+    // "schema.table" of the table behind the current results
+    fn result_table_key(&self) -> Option<String> {
+        let source = self.ws.query_result.as_ref()?.source.as_ref()?;
+        Some(format!("{}.{}", source.schema, source.table))
+    }
+
+    // A layout picked for the query's text wins over its table's view
+    fn saved_table_view(&self) -> Option<&TableView> {
+        let by_query = self.ws.result_sql.as_deref().map(query_signature);
+        if let Some(view) = by_query.and_then(|key| self.config.query_layouts.get(&key)) {
+            return Some(view);
+        }
+        let key = self.result_table_key()?;
+        self.config
+            .connections
+            .iter()
+            .find(|p| p.keyring_account() == self.ws.profile)?
+            .table_views
+            .get(&key)
+    }
+
+    // Lay out fresh results by their table's saved view, including its sort,
+    // and the column widths saved for their column list
+    pub fn apply_table_view(&mut self) {
+        self.ws.result_hidden.clear();
+        self.ws.result_signature = self.ws.query_result.as_ref().map(|r| column_signature(&r.columns));
+        self.ws.result_col_widths = self
+            .ws
+            .result_signature
+            .as_ref()
+            .and_then(|signature| self.config.column_widths.get(signature))
+            .map(|widths| widths.iter().map(|(name, &width)| (name.clone(), width)).collect())
+            .unwrap_or_default();
+        let Some(view) = self.saved_table_view().cloned() else {
+            return;
+        };
+        let Some(result) = self.ws.query_result.as_mut() else {
+            return;
+        };
+        arrange_columns(result, &view.column_order, &view.hidden_columns);
+        self.ws.result_sort = view
+            .sort_column
+            .and_then(|name| result.columns.iter().position(|c| *c == name))
+            .map(|col| (col, if view.sort_descending { SortDirection::Descending } else { SortDirection::Ascending }));
+        self.ws.result_hidden = view.hidden_columns;
+        self.ws.result_pinned = view.pinned_columns.min(result.columns.len());
+    }
+
+    // Re-run results (e.g. after an edit) keep the layout they had
+    pub fn keep_result_layout(&mut self, previous_columns: &[String]) {
+        if let Some(result) = self.ws.query_result.as_mut() {
+            arrange_columns(result, previous_columns, &self.ws.result_hidden);
+        }
+    }
+
+    pub fn hide_result_column(&mut self) {
+        let Some(result) = self.ws.query_result.as_mut() else {
+            return;
+        };
+        let col = self.ws.result_selected_col;
+        if result.columns.len() <= 1 || col >= result.columns.len() {
+            return;
+        }
+        self.ws.result_hidden.push(result.columns[col].clone());
+        let keep: Vec<usize> = (0..result.columns.len()).filter(|&i| i != col).collect();
+        select_columns(result, &keep);
+        self.ws.result_sort = match self.ws.result_sort {
+            Some((c, _)) if c == col => None,
+            Some((c, direction)) if c > col => Some((c - 1, direction)),
+            sort => sort,
+        };
+        if col < self.ws.result_pinned {
+            self.ws.result_pinned -= 1;
+        }
+        self.ws.result_selected_col = col.min(result.columns.len() - 1);
+    }
+
+    // Swap the selected column with its neighbour; the cursor moves along
+    pub fn move_result_column(&mut self, right: bool) {
+        let Some(result) = self.ws.query_result.as_mut() else {
+            return;
+        };
+        let col = self.ws.result_selected_col;
+        let other = if right { col + 1 } else { col.wrapping_sub(1) };
+        if other >= result.columns.len() || col >= result.columns.len() {
+            return;
+        }
+        let mut keep: Vec<usize> = (0..result.columns.len()).collect();
+        keep.swap(col, other);
+        select_columns(result, &keep);
+        self.ws.result_sort = self.ws.result_sort.map(|(c, direction)| match c {
+            c if c == col => (other, direction),
+            c if c == other => (col, direction),
+            c => (c, direction),
+        });
+        self.ws.result_selected_col = other;
+        if other < self.ws.result_scroll_offset {
+            self.ws.result_scroll_offset = other;
+        }
+    }
+
+    // Make the selected column `delta` characters wider or narrower, and
+    // remember it for every result with the same columns
+    pub fn resize_result_column(&mut self, delta: isize) {
+        let (Some(result), Some(signature)) = (&self.ws.query_result, self.ws.result_signature.clone()) else {
+            return;
+        };
+        let Some(name) = result.columns.get(self.ws.result_selected_col).cloned() else {
+            return;
+        };
+        let current = match self.ws.result_col_widths.get(&name) {
+            Some(&width) => width,
+            None => natural_width(result, self.ws.result_selected_col, &self.ws.result_fitted_widths),
+        };
+        let width = current.saturating_add_signed(delta).clamp(MIN_COLUMN_WIDTH, MAX_COLUMN_WIDTH);
+        self.ws.result_col_widths.insert(name.clone(), width);
+        self.config.column_widths.entry(signature).or_default().insert(name, width);
+        self.columns_resized = Some(Instant::now());
+    }
+
+    // Called on every pass of the event loop; saves resized widths once the
+    // resize keys have been quiet for a moment
+    pub fn poll_column_widths(&mut self) {
+        if self.columns_resized.is_some_and(|at| at.elapsed() >= RESIZE_SAVE_DELAY) {
+            if let Err(e) = self.save_column_widths() {
+                self.set_error(format!("{:#}", e));
+            }
+        }
+    }
+
+    // Save widths resized since the last save, e.g. on the way out
+    pub fn save_column_widths(&mut self) -> Result<()> {
+        if self.columns_resized.take().is_some() {
+            self.config.save()?;
+        }
+        Ok(())
+    }
+
+    // Remember the current column order, hidden columns and sort for the
+    // results' table
+    pub fn save_table_view(&mut self) {
+        if let Err(e) = self.try_save_table_view() {
+            self.set_error(format!("{:#}", e));
+        }
+    }
+
+    // The grid's layout as it is now
+    pub(super) fn current_table_view(&self) -> Option<TableView> {
+        let result = self.ws.query_result.as_ref()?;
+        Some(TableView {
+            sort_column: self.ws.result_sort.map(|(col, _)| result.columns[col].clone()),
+            sort_descending: self.ws.result_sort.is_some_and(|(_, d)| d == SortDirection::Descending),
+            hidden_columns: self.ws.result_hidden.clone(),
+            column_order: result.columns.clone(),
+            pinned_columns: self.ws.result_pinned,
+        })
+    }
+
+    fn try_save_table_view(&mut self) -> Result<()> {
+        let key = self
+            .result_table_key()
+            .ok_or_else(|| anyhow!("Only results from a single table can have a saved view"))?;
+        let view = self.current_table_view().ok_or_else(|| anyhow!("No results"))?;
+        let profile = self
+            .config
+            .connections
+            .iter_mut()
+            .find(|p| p.keyring_account() == self.ws.profile)
+            .ok_or_else(|| anyhow!("This connection has no saved profile"))?;
+        profile.table_views.insert(key.clone(), view);
+        self.config.save()?;
+        self.set_status(format!("Saved the grid layout for {}", key));
+        Ok(())
+    }
+
+    // Drop the saved view and the query's layout, and re-run the query so
+    // every column is back
+    pub async fn reset_table_view(&mut self) -> Result<()> {
+        let table_key = self.result_table_key();
+        let query_key = self.ws.result_sql.as_deref().map(query_signature);
+        if table_key.is_none() && query_key.is_none() {
+            return Ok(());
+        }
+        let mut removed = query_key.is_some_and(|key| self.config.query_layouts.remove(&key).is_some());
+        if let (Some(key), Some(profile)) = (
+            &table_key,
+            self.config.connections.iter_mut().find(|p| p.keyring_account() == self.ws.profile),
+        ) {
+            removed |= profile.table_views.remove(key).is_some();
+        }
+        if removed {
+            if let Err(e) = self.config.save() {
+                self.set_error(format!("{:#}", e));
+                return Ok(());
+            }
+        }
+        if let Some(sql) = self.ws.result_sql.clone() {
+            let params = &self.ws.result_params;
+            let (result, capped, _) =
+                super::results::load_result(&self.ws.db, &sql, params, self.config.max_result_rows, 0).await?;
+            self.ws.query_result = Some(result);
+            self.ws.result_capped = capped;
+        }
+        self.ws.result_hidden.clear();
+        self.ws.result_sort = None;
+        self.ws.result_pinned = 0;
+        self.ws.result_selected_col = 0;
+        self.ws.result_scroll_offset = 0;
+        let key = table_key.unwrap_or_else(|| "this query".to_string());
+        self.set_status(format!("Reset the grid layout for {}", key));
+        Ok(())
+    }
+}
+
+// Key of the widths saved for a column list
+fn column_signature(columns: &[String]) -> String {
+    fnv_hex(&columns.join("\u{1f}"))
+}
+
+// Key of a query's column layout: its text with whitespace runs as one
+// space, so reformatting it keeps the layout
+pub(super) fn query_signature(sql: &str) -> String {
+    fnv_hex(&sql.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+// Hex FNV-1a hash; stable across runs and builds, unlike the standard
+// library's hasher
+fn fnv_hex(text: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in text.bytes() {
+        hash = (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+// Width the grid gives a column without a manual one: its header, a fitted
+// width and the first rows, capped
+fn natural_width(result: &QueryResult, col: usize, fitted: &HashMap<String, usize>) -> usize {
+    let name = &result.columns[col];
+    let widest = result.rows.iter().take(10).filter_map(|row| row.get(col)?.as_ref()).map(|cell| cell.width()).max();
+    name.width()
+        .max(fitted.get(name).copied().unwrap_or(0))
+        .max(widest.unwrap_or(0))
+        .min(AUTO_COLUMN_WIDTH)
+}
+
+// Listed columns first in `order`, the rest as returned, without `hidden`
+pub(super) fn arrange_columns(result: &mut QueryResult, order: &[String], hidden: &[String]) {
+    let position = |name: &String| order.iter().position(|c| c == name).unwrap_or(usize::MAX);
+    let mut keep: Vec<usize> = (0..result.columns.len())
+        .filter(|&i| !hidden.contains(&result.columns[i]))
+        .collect();
+    // Hiding every column would leave nothing to show
+    if keep.is_empty() {
+        keep = (0..result.columns.len()).collect();
+    }
+    keep.sort_by_key(|&i| position(&result.columns[i]));
+    select_columns(result, &keep);
+}
+
+// Keep the columns at `keep`, in that order, along with what the edit
+// support knows about them
+fn select_columns(result: &mut QueryResult, keep: &[usize]) {
+    result.columns = keep.iter().map(|&i| result.columns[i].clone()).collect();
+    if !result.types.is_empty() {
+        result.types = keep.iter().map(|&i| result.types[i].clone()).collect();
+    }
+    for row in &mut result.rows {
+        *row = keep.iter().map(|&i| row[i].clone()).collect();
+    }
+    if let Some(source) = &mut result.source {
+        source.column_names = keep.iter().map(|&i| source.column_names[i].clone()).collect();
+        // A hidden key column makes the rows unidentifiable
+        let pk_columns: Option<Vec<usize>> = source
+            .pk_columns
+            .iter()
+            .map(|pk| keep.iter().position(|i| i == pk))
+            .collect();
+        source.pk_columns = pk_columns.unwrap_or_default();
+    }
+}
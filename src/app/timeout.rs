@@ -0,0 +1,30 @@
+use anyhow::Result;
+
+use crate::app::{App, PromptAction};
+
+// statement_timeout of the current connection's session
+impl App {
+    pub fn begin_statement_timeout(&mut self) {
+        if !self.ws.db.is_connected() {
+            return;
+        }
+        let current = self.ws.db.statement_timeout().unwrap_or("0").to_string();
+        self.open_prompt(
+            "Statement timeout for this session (e.g. 30s, 5min; 0 for none)",
+            &current,
+            PromptAction::StatementTimeout,
+        );
+    }
+
+    // Applies to this connection until it closes, reconnects included; the
+    // profile's `statement_timeout` is what the next one starts with
+    pub async fn set_statement_timeout(&mut self, value: &str) -> Result<()> {
+        let value = if value.is_empty() { "0" } else { value };
+        self.ws.db.set_statement_timeout(value).await?;
+        match self.ws.db.statement_timeout() {
+            Some(timeout) => self.set_status(format!("Statements now time out after {}", timeout)),
+            None => self.set_status("Statements no longer time out".to_string()),
+        }
+        Ok(())
+    }
+}
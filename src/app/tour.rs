@@ -0,0 +1,127 @@
+use crossterm::event::KeyCode;
+
+use crate::app::App;
+use crate::keymap::{Action, Keymap};
+
+// Part of the screen a tour step talks about; its box is placed next to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TourAnchor {
+    Center,
+    Browser,
+    Editor,
+    Results,
+}
+
+pub struct TourStep {
+    pub title: &'static str,
+    pub text: String,
+    pub anchor: TourAnchor,
+}
+
+// connect -> browse -> query -> export, with the keys as currently bound
+pub fn tour_steps(keymap: &Keymap) -> Vec<TourStep> {
+    let key = |action| keymap.label(action);
+    vec![
+        TourStep {
+            title: "Welcome to pg-tui",
+            text: format!(
+                "A short walkthrough of the main screens: connect, browse, query and export.\n\n\
+                 Enter/→: next   ←: back   Esc: close\n\n\
+                 Reopen it any time with {}.",
+                key(Action::ShowTour)
+            ),
+            anchor: TourAnchor::Center,
+        },
+        TourStep {
+            title: "1. Connect",
+            text: "The first screen lists saved connection profiles.\n\n\
+                   n: new profile (Tab moves between fields, Enter connects)\n\
+                   Enter: connect with the selected profile\n\
+                   d: delete a profile\n\n\
+                   Passwords are never written to the config file; Space on the last field \
+                   keeps one in the OS keyring."
+                .to_string(),
+            anchor: TourAnchor::Center,
+        },
+        TourStep {
+            title: "2. Browse",
+            text: format!(
+                "Once connected, schemas and their tables, views and functions are listed here; \
+                 Enter expands a schema or opens an object's details.\n\n\
+                 {}: filter   {}: DDL   {}: insert a row   {}: import a CSV\n\
+                 {}: another connection   Tab: query editor",
+                key(Action::FilterBrowser),
+                key(Action::ShowDdl),
+                key(Action::InsertRow),
+                key(Action::ImportCsv),
+                key(Action::NewConnection)
+            ),
+            anchor: TourAnchor::Browser,
+        },
+        TourStep {
+            title: "3. Query",
+            text: format!(
+                "Write SQL in the editor; Tab completes tables, columns and keywords.\n\n\
+                 {}: run the statement under the cursor\n\
+                 {}: run the whole script\n\
+                 {}: format   {}: open a .sql file   {}: save it",
+                key(Action::ExecuteQuery),
+                key(Action::RunScript),
+                key(Action::FormatQuery),
+                key(Action::OpenFile),
+                key(Action::SaveFile)
+            ),
+            anchor: TourAnchor::Editor,
+        },
+        TourStep {
+            title: "4. Results and export",
+            text: format!(
+                "{} moves between the editor and the results grid.\n\n\
+                 {}: filter   {}: search   {}: sort   {}: edit a cell   {}: row details (y copies it)\n\
+                 {}: commands from the config, including exports to CSV files\n\
+                 {}: write a report of this session",
+                key(Action::ToggleResultsFocus),
+                key(Action::FilterResults),
+                key(Action::SearchResults),
+                key(Action::SortColumn),
+                key(Action::EditCell),
+                key(Action::ShowRecord),
+                key(Action::OpenPalette),
+                key(Action::ExportSession)
+            ),
+            anchor: TourAnchor::Results,
+        },
+    ]
+}
+
+// Walkthrough overlay, shown on first launch and on request
+impl App {
+    pub fn start_tour(&mut self) {
+        self.tour = Some(0);
+    }
+
+    pub fn handle_tour_input(&mut self, key: KeyCode) {
+        let Some(step) = self.tour else {
+            return;
+        };
+        let last = tour_steps(&self.keymap).len() - 1;
+        match key {
+            KeyCode::Enter | KeyCode::Right | KeyCode::Char(' ') if step < last => self.tour = Some(step + 1),
+            KeyCode::Enter | KeyCode::Right | KeyCode::Char(' ') | KeyCode::Esc | KeyCode::Char('q') => {
+                self.end_tour()
+            }
+            KeyCode::Left | KeyCode::Backspace => self.tour = Some(step.saturating_sub(1)),
+            _ => {}
+        }
+    }
+
+    // The tour runs once: the config file it is keyed on is created on the way out
+    fn end_tour(&mut self) {
+        self.tour = None;
+        if !crate::config::Config::exists() {
+            if let Err(e) = self.config.save() {
+                self.set_error(format!("Failed to save config: {:#}", e));
+            }
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use std::time::{Duration, Instant};
+
+use crate::app::guard::write_keyword;
+use crate::app::{format_duration, App};
+
+// The query behind the grid, run again every few seconds like psql's \watch
+pub struct Watch {
+    sql: String,
+    params: Vec<Option<String>>,
+    pub interval: Duration,
+    last_run: Instant,
+    // When the connection dropped under the watch, e.g. in a failover.
+    // Runs wait until the connection's reconnect gets it back.
+    lost_since: Option<Instant>,
+}
+
+impl App {
+    // Start running the query behind the grid again every
+    // `watch_interval_secs`, or stop
+    pub fn toggle_watch(&mut self) {
+        if self.ws.watch.take().is_some() {
+            self.set_status("Stopped watching the query".to_string());
+            return;
+        }
+        let Some(sql) = self.ws.result_sql.clone() else {
+            self.set_status("Run a query first, then watch it".to_string());
+            return;
+        };
+        if let Some(verb) = write_keyword(&sql) {
+            self.set_warning(format!("Only queries can be watched; this statement runs {}", verb));
+            return;
+        }
+        let interval = Duration::from_secs(self.config.watch_interval_secs.max(1));
+        self.ws.watch = Some(Watch {
+            sql,
+            params: self.ws.result_params.clone(),
+            interval,
+            last_run: Instant::now(),
+            lost_since: None,
+        });
+        let stop = self.keymap.label(crate::keymap::Action::WatchQuery);
+        self.set_status(format!("Watching the query every {}s; {} stops", interval.as_secs(), stop));
+    }
+
+    // Called on every pass of the event loop; runs the watched query once
+    // per interval. Without a connection it waits for the reconnect, then
+    // picks up again and reports the gap.
+    pub async fn poll_watch(&mut self) {
+        let reconnecting = self.ws.reconnect_status().is_some();
+        let Some(watch) = &mut self.ws.watch else {
+            return;
+        };
+        if watch.last_run.elapsed() < watch.interval {
+            return;
+        }
+        watch.last_run = Instant::now();
+        if reconnecting {
+            watch.lost_since.get_or_insert_with(Instant::now);
+            return;
+        }
+        let gap = watch.lost_since.take().map(|since| format_duration(since.elapsed().as_secs_f64()));
+        let (sql, params) = (watch.sql.clone(), watch.params.clone());
+
+        if let Err(e) = self.run_interactive_query(sql, params, false).await {
+            self.set_error(format!("{:#}", e));
+        }
+        if let (Some(watch), Some(_)) = (&mut self.ws.watch, self.ws.db.lost()) {
+            watch.lost_since = Some(Instant::now());
+            return;
+        }
+        if let Some(gap) = gap {
+            self.set_warning(format!(
+                "Back on {} after {} without a connection; the watched query resumed",
+                self.ws.name, gap
+            ));
+        }
+    }
+}
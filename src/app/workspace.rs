@@ -0,0 +1,448 @@
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::app::{App, AppMode, BrowserItem, PromptAction, QueryFocus, TreeNode, Reconnect, SnippetSession, SortDirection, StatementOutcome, TableDetailTab, Watch};
+use crate::app::catalog::CatalogJob;
+use crate::app::keepalive::KeepaliveJob;
+use crate::app::editor::VimState;
+use crate::app::row_filter::{compare_cells, RowFilter};
+use crate::autocomplete::{AutocompleteEngine, Suggestion};
+use crate::db::{Capabilities, Column, Constraint, DatabaseDetails, DbConnection, ForeignKey, FunctionDetails, Index, ObjectKind, QueryResult, Schema, SequenceDetails, ServerNotice, Table, Trigger};
+use crate::lint::Diagnostic;
+use crate::lsp::LspClient;
+
+// Everything that belongs to one open connection: the client itself plus
+// its browser, details and query state.
+pub struct Workspace {
+    // Tab label, e.g. "postgres@localhost/app"
+    pub name: String,
+    
+    // Pre/post query hooks of the connected profile
+    pub hooks: crate::config::QueryHooks,
+    // Keyring account of the connected profile, which identifies it
+    pub profile: String,
+    // Environment tag of the connected profile and its banner color
+    pub environment: Option<crate::config::Environment>,
+    pub environment_color: String,
+    // The profile is read-only: the server refuses writes and destructive
+    // statements are refused before they are sent
+    pub read_only: bool,
+    
+    // Database connection
+    pub db: DbConnection,
+    // Set while the connection is lost and being re-opened
+    pub reconnect: Option<Reconnect>,
+    // The keepalive ping under way, and when the last one started
+    pub keepalive: Option<KeepaliveJob>,
+    pub last_ping: Instant,
+    pub capabilities: Option<Capabilities>,
+    
+    // Browser state
+    pub schemas: Vec<Schema>,
+    pub tables: Vec<Table>,
+    pub columns: Vec<Column>,
+    // Top-level nodes of the browser tree, and the rows it is shown as
+    // with each row's path of child indexes from the top
+    pub browser_tree: Vec<TreeNode>,
+    pub browser_items: Vec<BrowserItem>,
+    pub browser_paths: Vec<Vec<usize>>,
+    pub browser_selected: usize,
+    pub browser_scroll_offset: usize,
+    
+    // Table details tab state
+    pub table_detail_tab: TableDetailTab,
+    pub selected_table: Option<(String, String)>, // (schema, table_name)
+    pub constraints: Vec<Constraint>,
+    pub indexes: Vec<Index>,
+    pub triggers: Vec<Trigger>,
+    pub foreign_keys: Vec<ForeignKey>,
+    pub table_ddl: Option<String>,
+    pub table_stats: Option<crate::db::TableStats>,
+    pub view_definition: Option<String>,
+    // What the selected relation uses and what uses it
+    pub dependencies: Vec<crate::db::Dependency>,
+    // Grants on the selected relation and its schema
+    pub privileges: Vec<crate::db::Privilege>,
+    pub selected_is_view: bool,
+    pub selected_is_matview: bool,
+    // Panes that could not be loaded for lack of privileges, with a note to show instead
+    pub detail_notes: HashMap<TableDetailTab, String>,
+    // Overloads of the selected function, when a function is selected
+    pub selected_function: Option<(String, String)>,
+    pub function_details: Vec<FunctionDetails>,
+    pub selected_sequence: Option<(String, String)>,
+    pub sequence_details: Option<SequenceDetails>,
+    // Shown while the database node is selected
+    pub database_details: Option<DatabaseDetails>,
+    pub details_scroll: u16,
+    // Column or index the schema search jumped to, highlighted in its tab
+    pub found_detail: Option<String>,
+    // Tables, views and functions opened in the browser, most recent first
+    pub recent_objects: Vec<(ObjectKind, String, String)>,
+    
+    // Query state
+    pub query_input: String,
+    // File the editor was loaded from or saved to, and its text at that point
+    pub file_path: Option<String>,
+    pub saved_input: String,
+    pub query_result: Option<QueryResult>,
+    pub query_cursor: usize,
+    pub query_scroll_offset: usize,
+    // Column Up/Down aim for while moving through shorter lines
+    pub goal_column: Option<usize>,
+    // Vim-style editing state, used when `vim_mode` is on
+    pub vim: VimState,
+    pub result_scroll_offset: usize,
+    pub query_focus: QueryFocus,
+    // Grid cursor; the row indexes the displayed (filtered) rows
+    pub result_selected_row: usize,
+    pub result_selected_col: usize,
+    pub script_outcomes: Option<Vec<StatementOutcome>>,
+    // Statement that produced `query_result`, re-run to refresh the grid
+    pub result_sql: Option<String>,
+    
+    // Filter state (browser)
+    pub filter_input: String,
+    pub filter_active: bool,
+    
+    // Filter state (results)
+    pub results_filter_input: String,
+    pub results_filter_active: bool,
+    // Client-side sort of the results grid: column index and direction
+    pub result_sort: Option<(usize, SortDirection)>,
+    // Text searched for in the results grid, lowercased; matching cells
+    // are highlighted
+    pub result_search: Option<String>,
+    // Columns taken out of the grid, kept for saving the table's view
+    pub result_hidden: Vec<String>,
+    // Column name -> widest value over every fetched row, once fitted
+    pub result_fitted_widths: HashMap<String, usize>,
+    // Widths set by hand, and the signature of the column list they are
+    // saved under
+    pub result_col_widths: HashMap<String, usize>,
+    pub result_signature: Option<String>,
+    // Set while the selected column is shown at full width: how many
+    // characters of its values are scrolled past
+    pub result_expanded: Option<usize>,
+    // The expanded column as last drawn: its width and how far it can
+    // scroll before its longest value ends
+    pub result_expanded_view: Cell<(usize, usize)>,
+    // How many leading columns stay on screen while the rest scroll
+    pub result_pinned: usize,
+    // The results stopped at `max_result_rows` with rows left unread
+    pub result_capped: bool,
+    // Query as written when the grid shows it with an automatic LIMIT
+    pub auto_limited: Option<String>,
+    // Server notices of the statement (or script) behind the results
+    pub result_notices: Vec<ServerNotice>,
+    // Command tag of the statement behind the results ("UPDATE 42"), when
+    // the server reported one
+    pub result_tag: Option<String>,
+    // How long that statement took
+    pub result_elapsed: Option<Duration>,
+    // Values bound to the $n placeholders of `result_sql`
+    pub result_params: Vec<Option<String>>,
+    // `result_sql` running again every few seconds
+    pub watch: Option<Watch>,
+    // Last value entered for each placeholder, to offer again
+    pub param_values: HashMap<String, String>,
+    
+    // Autocomplete
+    pub autocomplete_engine: AutocompleteEngine,
+    pub suggestions: Vec<Suggestion>,
+    pub suggestion_selected: usize,
+    pub show_autocomplete: bool,
+    pub autocomplete_schema_loaded: bool,
+    // Catalog of names loading in the background
+    pub catalog_job: Option<CatalogJob>,
+    // Expanded snippet whose placeholders Tab moves through
+    pub snippet: Option<SnippetSession>,
+
+    // Lint warnings for `linted_input`, the editor text they were computed on
+    pub lint_diagnostics: Vec<Diagnostic>,
+    pub linted_input: String,
+
+    // Language server for this connection, when one is configured
+    pub lsp: Option<LspClient>,
+    pub lsp_diagnostics: Vec<Diagnostic>,
+}
+
+impl Workspace {
+    pub fn new() -> Self {
+        Self {
+            name: String::new(),
+            hooks: crate::config::QueryHooks::default(),
+            profile: String::new(),
+            environment: None,
+            environment_color: String::new(),
+            read_only: false,
+            db: DbConnection::new(),
+            reconnect: None,
+            keepalive: None,
+            last_ping: Instant::now(),
+            capabilities: None,
+            schemas: Vec::new(),
+            tables: Vec::new(),
+            columns: Vec::new(),
+            browser_tree: Vec::new(),
+            browser_items: Vec::new(),
+            browser_paths: Vec::new(),
+            browser_selected: 0,
+            browser_scroll_offset: 0,
+            table_detail_tab: TableDetailTab::Columns,
+            selected_table: None,
+            constraints: Vec::new(),
+            indexes: Vec::new(),
+            triggers: Vec::new(),
+            foreign_keys: Vec::new(),
+            table_ddl: None,
+            table_stats: None,
+            view_definition: None,
+            dependencies: Vec::new(),
+            privileges: Vec::new(),
+            selected_is_view: false,
+            selected_is_matview: false,
+            detail_notes: HashMap::new(),
+            selected_function: None,
+            function_details: Vec::new(),
+            selected_sequence: None,
+            sequence_details: None,
+            database_details: None,
+            details_scroll: 0,
+            found_detail: None,
+            recent_objects: Vec::new(),
+            query_input: String::new(),
+            file_path: None,
+            saved_input: String::new(),
+            query_result: None,
+            query_cursor: 0,
+            query_scroll_offset: 0,
+            goal_column: None,
+            vim: VimState::default(),
+            result_scroll_offset: 0,
+            query_focus: QueryFocus::Editor,
+            result_selected_row: 0,
+            result_selected_col: 0,
+            script_outcomes: None,
+            result_sql: None,
+            filter_input: String::new(),
+            filter_active: false,
+            results_filter_input: String::new(),
+            results_filter_active: false,
+            result_sort: None,
+            result_hidden: Vec::new(),
+            result_fitted_widths: HashMap::new(),
+            result_col_widths: HashMap::new(),
+            result_expanded: None,
+            result_expanded_view: Cell::new((0, 0)),
+            result_pinned: 0,
+            result_signature: None,
+            result_search: None,
+            result_capped: false,
+            auto_limited: None,
+            result_tag: None,
+            result_elapsed: None,
+            result_params: Vec::new(),
+            watch: None,
+            param_values: HashMap::new(),
+            result_notices: Vec::new(),
+            autocomplete_engine: AutocompleteEngine::new(),
+            suggestions: Vec::new(),
+            suggestion_selected: 0,
+            show_autocomplete: false,
+            autocomplete_schema_loaded: false,
+            catalog_job: None,
+            snippet: None,
+            lint_diagnostics: Vec::new(),
+            linted_input: String::new(),
+            lsp: None,
+            lsp_diagnostics: Vec::new(),
+        }
+    }
+
+    // Rows shown in the results grid, as indices into `query_result.rows`
+    // after filtering and sorting; `None` when they are shown as returned
+    pub fn filtered_rows(&self) -> Option<Vec<usize>> {
+        let result = self.query_result.as_ref()?;
+        let filter = self.results_filter();
+        if filter.is_none() && self.result_sort.is_none() {
+            return None;
+        }
+
+        let mut indices: Vec<usize> = (0..result.rows.len())
+            .filter(|&i| filter.as_ref().is_none_or(|f| f.matches(&result.rows[i])))
+            .collect();
+
+        if let Some((col, direction)) = self.result_sort {
+            indices.sort_by(|&a, &b| {
+                // NULLs go last either way
+                match (result.rows[a][col].as_deref(), result.rows[b][col].as_deref()) {
+                    (None, None) => Ordering::Equal,
+                    (None, Some(_)) => Ordering::Greater,
+                    (Some(_), None) => Ordering::Less,
+                    (Some(a), Some(b)) if direction == SortDirection::Descending => compare_cells(b, a),
+                    (Some(a), Some(b)) => compare_cells(a, b),
+                }
+            });
+        }
+        Some(indices)
+    }
+
+    pub fn cell_matches_search(&self, cell: &str) -> bool {
+        self.result_search.as_ref().is_some_and(|term| cell.to_lowercase().contains(term.as_str()))
+    }
+
+    // Cells matching the results search as (displayed row, column), in
+    // reading order
+    pub fn search_matches(&self) -> Vec<(usize, usize)> {
+        let Some(result) = &self.query_result else {
+            return Vec::new();
+        };
+        if self.result_search.is_none() {
+            return Vec::new();
+        }
+        let rows: Vec<usize> = self.filtered_rows().unwrap_or_else(|| (0..result.rows.len()).collect());
+        let mut matches = Vec::new();
+        for (displayed, &row) in rows.iter().enumerate() {
+            for (col, cell) in result.rows[row].iter().enumerate() {
+                if cell.as_deref().is_some_and(|cell| self.cell_matches_search(cell)) {
+                    matches.push((displayed, col));
+                }
+            }
+        }
+        matches
+    }
+
+    // The parsed results filter, when one is being applied
+    pub fn results_filter(&self) -> Option<RowFilter> {
+        let result = self.query_result.as_ref()?;
+        if !self.results_filter_active || self.results_filter_input.is_empty() {
+            return None;
+        }
+        Some(RowFilter::parse(&self.results_filter_input, &result.columns))
+    }
+}
+
+// Switching between open connections
+impl App {
+    pub fn has_workspaces(&self) -> bool {
+        !self.workspaces.is_empty()
+    }
+
+    // Tab labels in order
+    pub fn workspace_names(&self) -> Vec<&str> {
+        self.workspaces
+            .iter()
+            .enumerate()
+            .map(|(i, ws)| if i == self.active_workspace { self.ws.name.as_str() } else { ws.name.as_str() })
+            .collect()
+    }
+
+    // Make `ws` the active workspace in a new tab after the existing ones
+    pub fn open_workspace(&mut self, ws: Workspace) {
+        let previous = std::mem::replace(&mut self.ws, ws);
+        if self.has_workspaces() {
+            self.workspaces[self.active_workspace] = previous;
+        }
+        self.workspaces.push(Workspace::new());
+        self.active_workspace = self.workspaces.len() - 1;
+    }
+
+    pub fn switch_workspace(&mut self, index: usize) {
+        if index == self.active_workspace || index >= self.workspaces.len() {
+            return;
+        }
+        std::mem::swap(&mut self.ws, &mut self.workspaces[self.active_workspace]);
+        std::mem::swap(&mut self.ws, &mut self.workspaces[index]);
+        // Selecting the split partner trades places with it
+        if self.split_partner == Some(index) {
+            self.split_partner = Some(self.active_workspace);
+        }
+        self.active_workspace = index;
+        self.clear_notifications();
+    }
+
+    // Close the active connection, asking first when that would roll back
+    // an open transaction, or one it cannot rule out, or lose unsaved
+    // editor text
+    pub async fn request_close_workspace(&mut self) {
+        if !self.has_workspaces() {
+            return;
+        }
+        let mut losses = Vec::new();
+        match self.ws.db.in_transaction().await {
+            Some(true) => losses.push("an open transaction"),
+            Some(false) => {}
+            None => losses.push("a transaction that may be open"),
+        }
+        if self.ws.has_unsaved_text() {
+            losses.push("unsaved edits");
+        }
+        if losses.is_empty() {
+            self.close_workspace();
+            return;
+        }
+        let title = format!("Close {} with {}? (y/n)", self.ws.name, losses.join(" and "));
+        self.open_prompt(&title, "", PromptAction::CloseConnection);
+    }
+
+    pub fn answer_close_workspace(&mut self, answer: &str) {
+        if answer.eq_ignore_ascii_case("y") || answer.eq_ignore_ascii_case("yes") {
+            self.close_workspace();
+        }
+    }
+
+    // Drop the active connection and show its neighbour, or go back to the
+    // connection manager when it was the last one
+    pub fn close_workspace(&mut self) {
+        if !self.has_workspaces() {
+            return;
+        }
+        let closed = self.active_workspace;
+        self.audit.record_disconnect(&self.ws.name);
+        self.workspaces.remove(closed);
+        // The split partner, if any, takes over and the split ends
+        if let Some(partner) = self.split_partner.take() {
+            self.active_workspace = if partner > closed { partner - 1 } else { partner };
+        }
+        if self.workspaces.is_empty() {
+            self.ws = Workspace::new();
+            self.active_workspace = 0;
+            self.stats.record_disconnect();
+            self.mode = AppMode::ConnectionSelector;
+        } else {
+            self.active_workspace = self.active_workspace.min(self.workspaces.len() - 1);
+            self.ws = std::mem::replace(&mut self.workspaces[self.active_workspace], Workspace::new());
+        }
+        self.clear_notifications();
+    }
+
+    // Back to the connection manager to open another connection
+    pub fn add_workspace(&mut self) {
+        self.clear_notifications();
+        self.mode = AppMode::ConnectionSelector;
+    }
+
+    // Show the active workspace side by side with the next one
+    pub fn toggle_split(&mut self) {
+        if self.split_partner.take().is_some() {
+            return;
+        }
+        if self.workspaces.len() < 2 {
+            let new_connection = self.keymap.label(crate::keymap::Action::NewConnection);
+            self.set_error(format!("Open a second connection ({}) to compare side by side", new_connection));
+            return;
+        }
+        self.split_partner = Some((self.active_workspace + 1) % self.workspaces.len());
+        self.mode = AppMode::Query;
+        self.clear_notifications();
+    }
+
+    pub fn focus_split_partner(&mut self) {
+        if let Some(partner) = self.split_partner {
+            self.switch_workspace(partner);
+        }
+    }
+}
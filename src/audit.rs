@@ -0,0 +1,202 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+// Everything done this session, for change-management evidence after work
+// on production: connections, every statement with its timing and outcome,
+// and files written. Kept in memory and only written out on request.
+#[derive(Debug, Serialize)]
+pub struct SessionLog {
+    started: String,
+    events: Vec<AuditEvent>,
+}
+
+#[derive(Debug, Serialize)]
+struct AuditEvent {
+    at: String,
+    #[serde(flatten)]
+    kind: AuditKind,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum AuditKind {
+    Connected {
+        connection: String,
+    },
+    Disconnected {
+        connection: String,
+    },
+    Statement {
+        connection: String,
+        sql: String,
+        // Schema changes and privilege grants, listed separately in reports
+        ddl: bool,
+        elapsed_ms: u128,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        rows: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    Export {
+        path: String,
+        rows: usize,
+    },
+}
+
+impl Default for SessionLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SessionLog {
+    pub fn new() -> Self {
+        Self {
+            started: timestamp(SystemTime::now()),
+            events: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, kind: AuditKind) {
+        self.events.push(AuditEvent { at: timestamp(SystemTime::now()), kind });
+    }
+
+    pub fn record_connect(&mut self, connection: &str) {
+        self.push(AuditKind::Connected { connection: connection.to_string() });
+    }
+
+    pub fn record_disconnect(&mut self, connection: &str) {
+        self.push(AuditKind::Disconnected { connection: connection.to_string() });
+    }
+
+    // `outcome` is the row count, or the error message of a failed statement
+    pub fn record_statement(&mut self, connection: &str, sql: &str, elapsed: Duration, outcome: Result<usize, String>) {
+        let (rows, error) = match outcome {
+            Ok(rows) => (Some(rows), None),
+            Err(e) => (None, Some(e)),
+        };
+        self.push(AuditKind::Statement {
+            connection: connection.to_string(),
+            sql: sql.trim().to_string(),
+            ddl: is_ddl(sql),
+            elapsed_ms: elapsed.as_millis(),
+            rows,
+            error,
+        });
+    }
+
+    pub fn record_export(&mut self, path: &str, rows: usize) {
+        self.push(AuditKind::Export { path: path.to_string(), rows });
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!("# Session report\n\nStarted {}, exported {}.\n", self.started, timestamp(SystemTime::now()));
+
+        md.push_str("\n## Connections\n\n");
+        let mut any = false;
+        for event in &self.events {
+            match &event.kind {
+                AuditKind::Connected { connection } => md.push_str(&format!("- {} connected to `{}`\n", event.at, connection)),
+                AuditKind::Disconnected { connection } => {
+                    md.push_str(&format!("- {} disconnected from `{}`\n", event.at, connection))
+                }
+                _ => continue,
+            }
+            any = true;
+        }
+        if !any {
+            md.push_str("None\n");
+        }
+
+        let statements: Vec<&AuditEvent> =
+            self.events.iter().filter(|e| matches!(e.kind, AuditKind::Statement { .. })).collect();
+        let ddl: Vec<&AuditEvent> = statements
+            .iter()
+            .copied()
+            .filter(|e| matches!(e.kind, AuditKind::Statement { ddl: true, .. }))
+            .collect();
+        md.push_str(&format!("\n## DDL ({})\n\n", ddl.len()));
+        push_statements(&mut md, &ddl);
+        md.push_str(&format!("\n## All statements ({})\n\n", statements.len()));
+        push_statements(&mut md, &statements);
+
+        md.push_str("\n## Exports\n\n");
+        let mut any = false;
+        for event in &self.events {
+            if let AuditKind::Export { path, rows } = &event.kind {
+                md.push_str(&format!("- {} wrote {} row(s) to `{}`\n", event.at, rows, path));
+                any = true;
+            }
+        }
+        if !any {
+            md.push_str("None\n");
+        }
+        md
+    }
+}
+
+fn push_statements(md: &mut String, events: &[&AuditEvent]) {
+    if events.is_empty() {
+        md.push_str("None\n");
+    }
+    for event in events {
+        let AuditKind::Statement { connection, sql, elapsed_ms, rows, error, .. } = &event.kind else {
+            continue;
+        };
+        let outcome = match (rows, error) {
+            (_, Some(error)) => format!("failed: {}", error),
+            (Some(rows), None) => format!("{} row(s)", rows),
+            (None, None) => "ok".to_string(),
+        };
+        md.push_str(&format!(
+            "- {} on `{}`, {} ms, {}\n\n  ```sql\n  {}\n  ```\n\n",
+            event.at,
+            connection,
+            elapsed_ms,
+            outcome,
+            sql.replace('\n', "\n  ")
+        ));
+    }
+}
+
+// Statements that change schema objects or privileges
+fn is_ddl(sql: &str) -> bool {
+    let first = sql
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with("--"))
+        .and_then(|line| line.split_whitespace().next())
+        .unwrap_or("")
+        .to_uppercase();
+    matches!(first.as_str(), "CREATE" | "ALTER" | "DROP" | "TRUNCATE" | "GRANT" | "REVOKE" | "COMMENT" | "REINDEX")
+}
+
+// "2024-05-01T14:03:27Z"
+fn timestamp(at: SystemTime) -> String {
+    let secs = at.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let (days, rest) = ((secs / 86_400) as i64, secs % 86_400);
+    // Days since the epoch to a civil date (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rest / 3600,
+        rest % 3600 / 60,
+        rest % 60
+    )
+}
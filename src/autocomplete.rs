@@ -1,4 +1,6 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+use crate::db::{Routine, Setting};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SuggestionType {
@@ -6,6 +8,85 @@ pub enum SuggestionType {
     Table,
     Column,
     Function,
+    Setting,
+    Value,
+    Role,
+    Schema,
+    Database,
+    Snippet,
+}
+
+// Most suggestions shown, and how many accepted names are remembered
+const MAX_SUGGESTIONS: usize = 10;
+const RECENT_NAMES: usize = 20;
+
+// Fuzzy match scoring: per matched character, at the start of the name or
+// of a word in it, right after the previous match, and per skipped character
+const SCORE_MATCH: i32 = 16;
+const BONUS_START: i32 = 24;
+const BONUS_WORD: i32 = 12;
+const BONUS_CONSECUTIVE: i32 = 12;
+const PENALTY_GAP: i32 = 2;
+const NO_MATCH: i32 = i32::MIN / 2;
+
+// Statement and clause skeletons: trigger, what it expands to, and the
+// template. `${n:text}` are the placeholders Tab moves through in order,
+// `$0` is where the cursor ends up.
+const SNIPPETS: &[(&str, &str, &str)] = &[
+    ("sel", "SELECT … FROM … WHERE …", "SELECT ${1:*}\nFROM ${2:table}\nWHERE ${3:condition}$0"),
+    ("selc", "SELECT count(*) FROM … WHERE …", "SELECT count(*)\nFROM ${1:table}\nWHERE ${2:condition}$0"),
+    ("selg", "SELECT …, count(*) FROM … GROUP BY …", "SELECT ${1:column}, count(*)\nFROM ${2:table}\nGROUP BY 1\nORDER BY 2 DESC$0"),
+    ("selj", "SELECT … FROM … JOIN … ON …", "SELECT ${1:*}\nFROM ${2:table} a\nJOIN ${3:other} b ON b.${4:column} = a.${5:column}\nWHERE ${6:condition}$0"),
+    ("ins", "INSERT INTO … (…) VALUES (…)", "INSERT INTO ${1:table} (${2:columns})\nVALUES (${3:values})$0"),
+    ("inss", "INSERT INTO … SELECT …", "INSERT INTO ${1:table} (${2:columns})\nSELECT ${3:columns}\nFROM ${4:source}$0"),
+    ("upd", "UPDATE … SET … WHERE …", "UPDATE ${1:table}\nSET ${2:column} = ${3:value}\nWHERE ${4:condition}$0"),
+    ("del", "DELETE FROM … WHERE …", "DELETE FROM ${1:table}\nWHERE ${2:condition}$0"),
+    ("with", "WITH … AS (…) SELECT …", "WITH ${1:name} AS (\n    ${2:query}\n)\nSELECT ${3:*}\nFROM ${4:name}$0"),
+    ("join", "JOIN … ON …", "JOIN ${1:table} ON ${2:left} = ${3:right}$0"),
+    ("ljoin", "LEFT JOIN … ON …", "LEFT JOIN ${1:table} ON ${2:left} = ${3:right}$0"),
+    ("case", "CASE WHEN … THEN … ELSE … END", "CASE WHEN ${1:condition} THEN ${2:value} ELSE ${3:other} END$0"),
+    ("exists", "EXISTS (SELECT 1 FROM … WHERE …)", "EXISTS (SELECT 1 FROM ${1:table} WHERE ${2:condition})$0"),
+];
+
+// Snippets that start a statement; the rest go inside one
+const STATEMENT_SNIPPETS: &[&str] = &["sel", "selc", "selg", "selj", "ins", "inss", "upd", "del", "with"];
+
+// Built-in functions rank below user-defined ones of the same match
+const BUILTIN_PENALTY: i32 = 5;
+
+// A function by name, with its overloads
+struct FunctionEntry {
+    schema: String,
+    name: String,
+    kind: String,
+    signatures: Vec<Signature>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Signature {
+    // "a integer", "b text DEFAULT 'x'::text"
+    pub arguments: Vec<String>,
+    pub result: String,
+}
+
+impl Signature {
+    // Number of arguments the call can have at most, None for VARIADIC
+    fn max_arguments(&self) -> Option<usize> {
+        match self.arguments.last() {
+            Some(last) if last.starts_with("VARIADIC ") => None,
+            _ => Some(self.arguments.len()),
+        }
+    }
+}
+
+// The function call the editor cursor is in
+#[derive(Debug, Clone)]
+pub struct SignatureHint {
+    pub name: String,
+    // Overloads that take at least as many arguments as typed so far first
+    pub signatures: Vec<Signature>,
+    // 0-based argument the cursor is on
+    pub argument: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -13,6 +94,8 @@ pub struct Suggestion {
     pub suggestion_type: SuggestionType,
     pub text: String,
     pub description: Option<String>,
+    // Char positions in `text` of the typed characters, for highlighting
+    pub matched: Vec<usize>,
 }
 
 impl Suggestion {
@@ -21,8 +104,78 @@ impl Suggestion {
             suggestion_type,
             text,
             description,
+            matched: Vec::new(),
+        }
+    }
+
+    fn describe(mut self, description: String) -> Self {
+        self.description = Some(description);
+        self
+    }
+}
+
+/// Match `pattern` against `candidate` the way fzf does: its characters have
+/// to appear in order, in any case, with anything between them. Returns a
+/// score, higher when matches start the name or a word of it (`usrnm` on
+/// `user_name`) or follow each other, and the char positions matched.
+pub fn fuzzy_match(candidate: &str, pattern: &str) -> Option<(i32, Vec<usize>)> {
+    let text: Vec<char> = candidate.chars().collect();
+    let pattern: Vec<char> = pattern.chars().flat_map(char::to_lowercase).collect();
+    let (n, m) = (pattern.len(), text.len());
+    if n == 0 {
+        return Some((0, Vec::new()));
+    }
+    if n > m {
+        return None;
+    }
+    let lower: Vec<char> = text.iter().map(|c| c.to_lowercase().next().unwrap_or(*c)).collect();
+    let bonus = |j: usize| match j {
+        0 => BONUS_START,
+        _ if !text[j - 1].is_alphanumeric() || (text[j - 1].is_lowercase() && text[j].is_uppercase()) => BONUS_WORD,
+        _ => 0,
+    };
+
+    // score[i][j]: best match of pattern[..=i] with pattern[i] at text[j];
+    // from[i][j]: where pattern[i - 1] was in it
+    let mut score = vec![vec![NO_MATCH; m]; n];
+    let mut from = vec![vec![0usize; m]; n];
+    for i in 0..n {
+        // Best match of pattern[..i] ending two or more characters back,
+        // less the gap to the current column
+        let (mut gapped, mut gapped_at) = (NO_MATCH, 0);
+        for j in 0..m {
+            if i > 0 && j >= 2 && score[i - 1][j - 2] - PENALTY_GAP > gapped - PENALTY_GAP {
+                (gapped, gapped_at) = (score[i - 1][j - 2] - PENALTY_GAP, j - 2);
+            } else {
+                gapped -= PENALTY_GAP;
+            }
+            if lower[j] != pattern[i] {
+                continue;
+            }
+            if i == 0 {
+                // Earlier starts rank higher
+                score[i][j] = SCORE_MATCH + bonus(j) - j as i32;
+                continue;
+            }
+            let consecutive = if j >= 1 { score[i - 1][j - 1] + BONUS_CONSECUTIVE } else { NO_MATCH };
+            let (best, at) = if consecutive >= gapped { (consecutive, j.saturating_sub(1)) } else { (gapped, gapped_at) };
+            if best > NO_MATCH / 2 {
+                score[i][j] = best + SCORE_MATCH + bonus(j);
+                from[i][j] = at;
+            }
         }
     }
+
+    let (end, &best) = score[n - 1].iter().enumerate().max_by_key(|&(_, s)| *s)?;
+    if best <= NO_MATCH / 2 {
+        return None;
+    }
+    let mut matched = vec![end; n];
+    for i in (1..n).rev() {
+        matched[i - 1] = from[i][matched[i]];
+    }
+    // Of two equally good matches the shorter name wins
+    Some((best - (m - n) as i32 / 4, matched))
 }
 
 pub struct AutocompleteEngine {
@@ -30,6 +183,18 @@ pub struct AutocompleteEngine {
     tables: Vec<String>,
     // Map of table name to list of column names
     columns: HashMap<String, Vec<String>>,
+    // User-defined and built-in functions with their overloads
+    functions: Vec<FunctionEntry>,
+    // Lowercase names of built-in functions, not offered again as keywords
+    builtins: HashSet<String>,
+    // Server settings for SET/SHOW, with allowed values where enumerable
+    settings: Vec<Setting>,
+    // Object names for GRANT/REVOKE/ALTER/DROP
+    roles: Vec<String>,
+    schemas: Vec<String>,
+    databases: Vec<String>,
+    // Names accepted from the list, most recent last; they rank higher
+    recent: Vec<String>,
 }
 
 impl AutocompleteEngine {
@@ -70,6 +235,13 @@ impl AutocompleteEngine {
             keywords,
             tables: Vec::new(),
             columns: HashMap::new(),
+            functions: Vec::new(),
+            builtins: HashSet::new(),
+            settings: Vec::new(),
+            roles: Vec::new(),
+            schemas: Vec::new(),
+            databases: Vec::new(),
+            recent: Vec::new(),
         }
     }
 
@@ -83,118 +255,157 @@ impl AutocompleteEngine {
         }
     }
 
+    // Routines come ordered by name and schema, so the overloads of each
+    // function are next to each other
+    pub fn update_functions(&mut self, routines: Vec<Routine>) {
+        self.functions.clear();
+        for routine in routines {
+            let signature = Signature {
+                arguments: split_arguments(&routine.arguments),
+                result: routine.result,
+            };
+            match self.functions.last_mut() {
+                Some(entry) if entry.name == routine.name && entry.schema == routine.schema => {
+                    entry.signatures.push(signature)
+                }
+                _ => self.functions.push(FunctionEntry {
+                    schema: routine.schema,
+                    name: routine.name,
+                    kind: routine.kind,
+                    signatures: vec![signature],
+                }),
+            }
+        }
+        self.builtins = self
+            .functions
+            .iter()
+            .filter(|f| f.schema == "pg_catalog")
+            .map(|f| f.name.to_lowercase())
+            .collect();
+    }
+
+    pub fn update_settings(&mut self, settings: Vec<Setting>) {
+        self.settings = settings;
+    }
+
+    pub fn update_catalog(&mut self, roles: Vec<String>, schemas: Vec<String>, databases: Vec<String>) {
+        self.roles = roles;
+        self.schemas = schemas;
+        self.databases = databases;
+    }
+
+    // Remember a name taken from the list so it ranks higher next time
+    pub fn note_used(&mut self, text: &str) {
+        self.recent.retain(|name| name != text);
+        self.recent.push(text.to_string());
+        if self.recent.len() > RECENT_NAMES {
+            self.recent.remove(0);
+        }
+    }
+
+    // `name` as a completion of `typed` with its score: how well it matches,
+    // plus `bonus` for what the context expects and more for recent picks
+    fn rank(&self, suggestion_type: SuggestionType, name: &str, typed: &str, bonus: i32) -> Option<(i32, Suggestion)> {
+        let (score, matched) = fuzzy_match(name, typed)?;
+        let recency = self
+            .recent
+            .iter()
+            .rev()
+            .position(|recent| recent == name)
+            .map_or(0, |age| (RECENT_NAMES - age) as i32 * 2);
+        let mut suggestion = Suggestion::new(suggestion_type, name.to_string(), None);
+        suggestion.matched = matched;
+        Some((score + bonus + recency, suggestion))
+    }
+
+    // Cached table names, empty until the schema is loaded
+    pub fn table_names(&self) -> &[String] {
+        &self.tables
+    }
+
+    // Cached column names of `table`, matched case-insensitively as a fallback
+    pub fn table_columns(&self, table: &str) -> Option<&[String]> {
+        self.columns
+            .get(table)
+            .or_else(|| {
+                self.columns
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(table))
+                    .map(|(_, columns)| columns)
+            })
+            .map(Vec::as_slice)
+    }
+
     pub fn get_suggestions(&self, query: &str, cursor_pos: usize) -> Vec<Suggestion> {
         // Extract the word being typed at cursor position
         let (current_word, word_start) = self.extract_current_word(query, cursor_pos);
+
+        if let Some(suggestions) = self.match_settings(query, word_start, &current_word) {
+            return suggestions;
+        }
+        if let Some(suggestions) = self.match_catalog_objects(query, word_start, &current_word) {
+            return suggestions;
+        }
         
         if current_word.is_empty() {
             return Vec::new();
         }
 
-        let mut suggestions = Vec::new();
-        let current_word_upper = current_word.to_uppercase();
-        
         // Check if user is typing table.column pattern (e.g., users.id)
         if let Some(table_name) = self.extract_table_before_dot(query, word_start) {
             // Show ONLY columns from this specific table
+            let mut ranked = Vec::new();
             if let Some(columns) = self.columns.get(&table_name) {
-                suggestions = columns
+                ranked = columns
                     .iter()
-                    .filter(|col| current_word.is_empty() || col.to_uppercase().starts_with(&current_word_upper))
-                    .map(|col| Suggestion::new(
-                        SuggestionType::Column,
-                        col.clone(),
-                        Some(format!("Column in {}", table_name)),
-                    ))
+                    .filter_map(|col| self.rank(SuggestionType::Column, col, &current_word, 0))
+                    .map(|(score, s)| (score, s.describe(format!("Column in {}", table_name))))
                     .collect();
             }
-            suggestions.truncate(10);
-            return suggestions;
+            return best(ranked);
         }
 
-        // Determine context to prioritize suggestions
+        // The context decides which kinds of names are worth more; how well
+        // each one matches decides within and across them
         let context = self.analyze_context(query, word_start);
-
+        let mut ranked = self.match_snippets(query, word_start, &current_word);
         match context {
             Context::TableName => {
-                // Prioritize table suggestions
-                suggestions.extend(self.match_tables(&current_word_upper));
-                suggestions.extend(self.match_keywords(&current_word_upper));
+                ranked.extend(self.match_tables(&current_word, 30));
+                ranked.extend(self.match_keywords(&current_word, 0));
             }
             Context::ColumnName => {
-                // Prioritize column suggestions
-                suggestions.extend(self.match_columns(&current_word_upper, query, word_start));
-                suggestions.extend(self.match_keywords(&current_word_upper));
+                ranked.extend(self.match_columns(&current_word, query, 30));
+                ranked.extend(self.match_functions(&current_word, 15));
+                ranked.extend(self.match_keywords(&current_word, 0));
             }
             Context::General => {
-                // General context: keywords first, then tables, then columns
-                suggestions.extend(self.match_keywords(&current_word_upper));
-                suggestions.extend(self.match_tables(&current_word_upper));
-                suggestions.extend(self.match_all_columns(&current_word_upper));
+                ranked.extend(self.match_keywords(&current_word, 20));
+                ranked.extend(self.match_tables(&current_word, 15));
+                ranked.extend(self.match_functions(&current_word, 10));
+                ranked.extend(self.match_all_columns(&current_word, 5));
             }
         }
-
-        // Limit to top 10 suggestions
-        suggestions.truncate(10);
-        suggestions
+        best(ranked)
     }
 
     fn extract_current_word(&self, text: &str, cursor_pos: usize) -> (String, usize) {
-        if text.is_empty() || cursor_pos == 0 {
-            return (String::new(), 0);
-        }
-
         let safe_pos = cursor_pos.min(text.len());
-        
-        // Find word boundaries (alphanumeric + underscore)
-        let mut word_start = safe_pos;
-        let chars: Vec<char> = text.chars().collect();
-        
-        // Move back to find start of word
-        while word_start > 0 {
-            let prev_char = chars[word_start - 1];
-            if prev_char.is_alphanumeric() || prev_char == '_' {
-                word_start -= 1;
-            } else {
-                break;
-            }
-        }
-
-        // Extract the word from start to cursor
-        let word: String = chars[word_start..safe_pos].iter().collect();
-        (word, word_start)
+        // Find word boundaries (alphanumeric + underscore), in bytes
+        let word_start = word_start(&text[..safe_pos]);
+        (text[word_start..safe_pos].to_string(), word_start)
     }
     
-    // Helper to check if there's a table.column pattern
     fn extract_table_before_dot(&self, text: &str, word_start: usize) -> Option<String> {
-        if word_start == 0 {
-            return None;
-        }
-        
-        let chars: Vec<char> = text.chars().collect();
-        
         // Check if there's a dot right before the word
-        if word_start > 0 && chars.get(word_start - 1) == Some(&'.') {
-            // Find the table name before the dot
-            let mut table_end = word_start - 1;
-            let mut table_start = table_end;
-            
-            while table_start > 0 {
-                let prev_char = chars[table_start - 1];
-                if prev_char.is_alphanumeric() || prev_char == '_' {
-                    table_start -= 1;
-                } else {
-                    break;
-                }
-            }
-            
-            let table_name: String = chars[table_start..table_end].iter().collect();
-            if !table_name.is_empty() {
-                return Some(table_name);
-            }
+        let table_end = text[..word_start].strip_suffix('.')?.len();
+        let table_start = self::word_start(&text[..table_end]);
+        let table_name = &text[table_start..table_end];
+        if table_name.is_empty() {
+            None
+        } else {
+            Some(table_name.to_string())
         }
-        
-        None
     }
 
     fn analyze_context(&self, query: &str, cursor_pos: usize) -> Context {
@@ -217,31 +428,205 @@ impl AutocompleteEngine {
         Context::General
     }
 
-    fn match_keywords(&self, prefix: &str) -> Vec<Suggestion> {
+    fn match_keywords(&self, typed: &str, bonus: i32) -> Vec<(i32, Suggestion)> {
         self.keywords
             .iter()
-            .filter(|kw| kw.starts_with(prefix))
-            .map(|kw| Suggestion::new(
-                SuggestionType::Keyword,
-                kw.clone(),
-                Some("SQL Keyword".to_string()),
-            ))
+            .filter(|kw| !self.builtins.contains(&kw.to_lowercase()))
+            .filter_map(|kw| self.rank(SuggestionType::Keyword, kw, typed, bonus))
+            .map(|(score, s)| (score, s.describe("SQL Keyword".to_string())))
             .collect()
     }
 
-    fn match_tables(&self, prefix: &str) -> Vec<Suggestion> {
+    fn match_tables(&self, typed: &str, bonus: i32) -> Vec<(i32, Suggestion)> {
         self.tables
             .iter()
-            .filter(|table| table.to_uppercase().starts_with(prefix))
-            .map(|table| Suggestion::new(
-                SuggestionType::Table,
-                table.clone(),
-                Some("Table".to_string()),
-            ))
+            .filter_map(|table| self.rank(SuggestionType::Table, table, typed, bonus))
+            .map(|(score, s)| (score, s.describe("Table".to_string())))
+            .collect()
+    }
+
+    // Functions outside `public` and `pg_catalog` are inserted
+    // schema-qualified. The description is the first overload's signature.
+    fn match_functions(&self, typed: &str, bonus: i32) -> Vec<(i32, Suggestion)> {
+        self.functions
+            .iter()
+            .filter_map(|entry| {
+                let builtin = entry.schema == "pg_catalog";
+                let text = if builtin || entry.schema == "public" {
+                    entry.name.clone()
+                } else {
+                    format!("{}.{}", entry.schema, entry.name)
+                };
+                let bonus = if builtin { bonus - BUILTIN_PENALTY } else { bonus };
+                let (score, s) = self.rank(SuggestionType::Function, &text, typed, bonus)?;
+                let first = &entry.signatures[0];
+                let mut description = format!("({}) → {}", first.arguments.join(", "), first.result);
+                if entry.kind != "function" {
+                    description.push_str(&format!(", {}", entry.kind));
+                }
+                if entry.signatures.len() > 1 {
+                    description.push_str(&format!(" +{} overloads", entry.signatures.len() - 1));
+                }
+                Some((score, s.describe(description)))
+            })
             .collect()
     }
 
-    fn match_columns(&self, prefix: &str, query: &str, _word_start: usize) -> Vec<Suggestion> {
+    /// The known function whose argument list `cursor` is in, looking back
+    /// to the start of the statement; parentheses and commas inside string
+    /// literals and quoted names are skipped.
+    pub fn signature_hint(&self, query: &str, cursor: usize) -> Option<SignatureHint> {
+        let before = &query[..cursor.min(query.len())];
+        // Byte offset of each '(' still open, with the commas seen in it
+        let mut open: Vec<(usize, usize)> = Vec::new();
+        let mut quote: Option<char> = None;
+        for (i, c) in before.char_indices() {
+            match (quote, c) {
+                (Some(q), c) if c == q => quote = None,
+                (Some(_), _) => {}
+                (None, '\'' | '"') => quote = Some(c),
+                (None, ';') => open.clear(),
+                (None, '(') => open.push((i, 0)),
+                (None, ')') => {
+                    open.pop();
+                }
+                (None, ',') => {
+                    if let Some((_, commas)) = open.last_mut() {
+                        *commas += 1;
+                    }
+                }
+                _ => {}
+            }
+        }
+        let &(paren, argument) = open.last()?;
+
+        // schema.name or name right before the parenthesis
+        let head = before[..paren].trim_end();
+        let start = head
+            .char_indices()
+            .rev()
+            .find(|&(_, c)| !(c.is_alphanumeric() || c == '_' || c == '.'))
+            .map_or(0, |(i, c)| i + c.len_utf8());
+        let called = &head[start..];
+        let (schema, name) = match called.rsplit_once('.') {
+            Some((schema, name)) => (Some(schema), name),
+            None => (None, called),
+        };
+        if name.is_empty() {
+            return None;
+        }
+        let entry = self
+            .functions
+            .iter()
+            .filter(|f| f.name.eq_ignore_ascii_case(name))
+            .filter(|f| schema.is_none_or(|s| f.schema.eq_ignore_ascii_case(s)))
+            // Unqualified calls resolve through the search path
+            .min_by_key(|f| match f.schema.as_str() {
+                _ if schema.is_some() => 0,
+                "pg_catalog" => 0,
+                "public" => 1,
+                _ => 2,
+            })?;
+
+        let mut signatures = entry.signatures.clone();
+        signatures.sort_by_key(|s| s.max_arguments().is_some_and(|max| max <= argument));
+        Some(SignatureHint {
+            name: entry.name.clone(),
+            signatures,
+            argument,
+        })
+    }
+
+    // Statement snippets where a statement starts, the others elsewhere
+    fn match_snippets(&self, query: &str, word_start: usize, typed: &str) -> Vec<(i32, Suggestion)> {
+        let statement_start = query[..word_start].rsplit(';').next().unwrap_or("").trim().is_empty();
+        SNIPPETS
+            .iter()
+            .filter(|(trigger, _, _)| STATEMENT_SNIPPETS.contains(trigger) == statement_start)
+            .filter_map(|(trigger, label, _)| {
+                let (score, s) = self.rank(SuggestionType::Snippet, trigger, typed, 0)?;
+                Some((score, s.describe(label.to_string())))
+            })
+            .collect()
+    }
+
+    // Parameter names after SET/SHOW, and allowed values after `SET name TO`.
+    // None when the statement is not one of those.
+    fn match_settings(&self, query: &str, word_start: usize, current_word: &str) -> Option<Vec<Suggestion>> {
+        let before = &query[..word_start];
+        let statement = before.rsplit(';').next().unwrap_or("").replace('=', " = ");
+        let words: Vec<String> = statement.split_whitespace().map(str::to_uppercase).collect();
+        let mut words: Vec<&str> = words.iter().map(String::as_str).collect();
+        if words.len() > 1 && matches!(words[1], "LOCAL" | "SESSION") {
+            words.remove(1);
+        }
+        let ranked: Vec<(i32, Suggestion)> = match words.as_slice() {
+            ["SET"] | ["SHOW"] | ["RESET"] => self
+                .settings
+                .iter()
+                .filter_map(|s| self.rank(SuggestionType::Setting, &s.name, current_word, 0))
+                .map(|(score, s)| (score, s.describe("Setting".to_string())))
+                .collect(),
+            ["SET", name, "TO" | "="] => {
+                let name = name.to_lowercase();
+                self.settings
+                    .iter()
+                    .find(|s| s.name.to_lowercase() == name)?
+                    .values
+                    .iter()
+                    .filter_map(|v| self.rank(SuggestionType::Value, v, current_word, 0))
+                    .map(|(score, s)| (score, s.describe(format!("Value for {}", name))))
+                    .collect()
+            }
+            _ => return None,
+        };
+        Some(best(ranked))
+    }
+
+    // Roles, schemas and databases where DDL/DCL expects one, e.g.
+    // `OWNER TO`, `GRANT ... TO`, `ON SCHEMA`, `ALTER DATABASE`.
+    // None when the cursor is not in such a spot.
+    fn match_catalog_objects(&self, query: &str, word_start: usize, current_word: &str) -> Option<Vec<Suggestion>> {
+        let before = &query[..word_start];
+        let statement = before.rsplit(';').next().unwrap_or("").replace(',', " , ");
+        let words: Vec<String> = statement.split_whitespace().map(str::to_uppercase).collect();
+        let first = words.first()?.as_str();
+        let last = words.last()?.as_str();
+        let prev = words.len().checked_sub(2).map(|i| words[i].as_str()).unwrap_or("");
+
+        // Role lists after GRANT ... TO / REVOKE ... FROM, commas included
+        let grantee_list = |keyword: &str| {
+            words
+                .iter()
+                .rposition(|w| w == keyword)
+                .is_some_and(|at| words[at + 1..].iter().enumerate().all(|(i, w)| (i % 2 == 1) == (w == ",")))
+        };
+
+        let (kind, names) = match (first, prev, last) {
+            (_, "OWNER", "TO") | (_, _, "AUTHORIZATION") => (SuggestionType::Role, &self.roles),
+            ("ALTER" | "DROP" | "SET", _, "ROLE" | "USER") => (SuggestionType::Role, &self.roles),
+            ("GRANT", _, _) if grantee_list("TO") => (SuggestionType::Role, &self.roles),
+            ("REVOKE", _, _) if grantee_list("FROM") => (SuggestionType::Role, &self.roles),
+            ("CREATE", _, _) => return None,
+            (_, _, "SCHEMA") => (SuggestionType::Schema, &self.schemas),
+            (_, _, "DATABASE") => (SuggestionType::Database, &self.databases),
+            _ => return None,
+        };
+        let description = match kind {
+            SuggestionType::Role => "Role",
+            SuggestionType::Schema => "Schema",
+            _ => "Database",
+        };
+        Some(best(
+            names
+                .iter()
+                .filter_map(|name| self.rank(kind.clone(), name, current_word, 0))
+                .map(|(score, s)| (score, s.describe(description.to_string())))
+                .collect(),
+        ))
+    }
+
+    fn match_columns(&self, typed: &str, query: &str, bonus: i32) -> Vec<(i32, Suggestion)> {
         // Try to find the table in the query context
         let table_name = self.extract_table_from_query(query);
         
@@ -249,30 +634,22 @@ impl AutocompleteEngine {
             if let Some(columns) = self.columns.get(&table) {
                 return columns
                     .iter()
-                    .filter(|col| col.to_uppercase().starts_with(prefix))
-                    .map(|col| Suggestion::new(
-                        SuggestionType::Column,
-                        col.clone(),
-                        Some(format!("Column in {}", table)),
-                    ))
+                    .filter_map(|col| self.rank(SuggestionType::Column, col, typed, bonus))
+                    .map(|(score, s)| (score, s.describe(format!("Column in {}", table))))
                     .collect();
             }
         }
 
         // Fall back to all columns
-        self.match_all_columns(prefix)
+        self.match_all_columns(typed, bonus)
     }
 
-    fn match_all_columns(&self, prefix: &str) -> Vec<Suggestion> {
+    fn match_all_columns(&self, typed: &str, bonus: i32) -> Vec<(i32, Suggestion)> {
         let mut results = Vec::new();
         for (table, columns) in &self.columns {
             for col in columns {
-                if col.to_uppercase().starts_with(prefix) {
-                    results.push(Suggestion::new(
-                        SuggestionType::Column,
-                        col.clone(),
-                        Some(format!("Column in {}", table)),
-                    ));
+                if let Some((score, s)) = self.rank(SuggestionType::Column, col, typed, bonus) {
+                    results.push((score, s.describe(format!("Column in {}", table))));
                 }
             }
         }
@@ -308,3 +685,46 @@ impl Default for AutocompleteEngine {
         Self::new()
     }
 }
+
+// The best scoring suggestions, best first; equal scores keep their order
+fn best(mut ranked: Vec<(i32, Suggestion)>) -> Vec<Suggestion> {
+    ranked.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+    ranked.into_iter().take(MAX_SUGGESTIONS).map(|(_, s)| s).collect()
+}
+
+// Template of the snippet `trigger`
+pub fn snippet_template(trigger: &str) -> Option<&'static str> {
+    SNIPPETS.iter().find(|(t, _, _)| *t == trigger).map(|(_, _, template)| *template)
+}
+
+// "a integer, b text DEFAULT 'x, y'::text" split at the commas between
+// arguments
+fn split_arguments(arguments: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let (mut depth, mut quoted, mut start) = (0i32, false, 0);
+    for (i, c) in arguments.char_indices() {
+        match c {
+            '\'' => quoted = !quoted,
+            '(' if !quoted => depth += 1,
+            ')' if !quoted => depth -= 1,
+            ',' if !quoted && depth == 0 => {
+                parts.push(arguments[start..i].trim().to_string());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    let last = arguments[start..].trim();
+    if !last.is_empty() {
+        parts.push(last.to_string());
+    }
+    parts
+}
+
+// Byte offset where the identifier-like word ending `text` starts
+fn word_start(text: &str) -> usize {
+    text.char_indices()
+        .rev()
+        .find(|&(_, c)| !(c.is_alphanumeric() || c == '_'))
+        .map_or(0, |(i, c)| i + c.len_utf8())
+}
@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use anyhow::Result;
 
+use crate::theme::Theme;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConnectionProfile {
     pub name: String,
@@ -9,24 +12,409 @@ pub struct ConnectionProfile {
     pub port: String,
     pub database: String,
     pub user: String,
-    // Note: password is not saved for security reasons
+    // The password itself never goes in this file; when set it lives in the OS keyring
+    #[serde(default)]
+    pub save_password: bool,
+    #[serde(default, skip_serializing_if = "QueryHooks::is_empty")]
+    pub hooks: QueryHooks,
+    // Saved results grid layout per table, keyed by "schema.table"
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub table_views: BTreeMap<String, TableView>,
+    // What the server is used for; shown as a banner while connected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<Environment>,
+    // Banner color instead of the environment's own
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    // Connect to this profile at startup instead of showing the selector
+    #[serde(default)]
+    pub auto_connect: bool,
+    // Sessions start with default_transaction_read_only on, and UPDATE,
+    // DELETE, DROP, TRUNCATE and ALTER are refused before reaching the server
+    #[serde(default)]
+    pub read_only: bool,
+    // Sessions start with this statement_timeout ("30s", "5min"), so a
+    // runaway query fails instead of holding the results panel
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub statement_timeout: Option<String>,
+    // With several hosts ("db1,db2"), the kind of server a session needs:
+    // "read-write" finds the primary again after a failover. PGTARGETSESSIONATTRS
+    // when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_session_attrs: Option<String>,
+    // Starred objects and saved queries, listed atop the browser tree
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub favorites: Vec<Favorite>,
+}
+
+// Environment tag of a profile. Statements that write ask for an extra
+// confirmation on prod connections.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Environment {
+    Dev,
+    Staging,
+    Prod,
+}
+
+impl Environment {
+    pub fn label(self) -> &'static str {
+        match self {
+            Environment::Dev => "dev",
+            Environment::Staging => "staging",
+            Environment::Prod => "prod",
+        }
+    }
+
+    pub fn banner(self) -> &'static str {
+        match self {
+            Environment::Dev => "DEVELOPMENT",
+            Environment::Staging => "STAGING",
+            Environment::Prod => "PRODUCTION",
+        }
+    }
+
+    fn default_color(self) -> &'static str {
+        match self {
+            Environment::Dev => "green",
+            Environment::Staging => "yellow",
+            Environment::Prod => "red",
+        }
+    }
+
+    // Tagging cycles untagged -> dev -> staging -> prod -> untagged
+    pub fn cycle(current: Option<Environment>) -> Option<Environment> {
+        match current {
+            None => Some(Environment::Dev),
+            Some(Environment::Dev) => Some(Environment::Staging),
+            Some(Environment::Staging) => Some(Environment::Prod),
+            Some(Environment::Prod) => None,
+        }
+    }
+}
+
+// How results from one table are shown: columns in `column_order` come
+// first in that order, `hidden_columns` are left out, and the grid starts
+// sorted by `sort_column`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TableView {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort_column: Option<String>,
+    #[serde(default)]
+    pub sort_descending: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hidden_columns: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub column_order: Vec<String>,
+    // Leading columns kept on screen while the rest scroll
+    #[serde(default)]
+    pub pinned_columns: usize,
+}
+
+// A starred browser object, written as `{"table": {"schema": "public",
+// "name": "users"}}`, or a named query, `{"query": {"name": ..., "sql": ...}}`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Favorite {
+    Table { schema: String, name: String },
+    View { schema: String, name: String },
+    MaterializedView { schema: String, name: String },
+    Function { schema: String, name: String },
+    Query { name: String, sql: String },
+}
+
+impl Favorite {
+    pub fn label(&self) -> String {
+        match self {
+            Favorite::Table { schema, name }
+            | Favorite::View { schema, name }
+            | Favorite::MaterializedView { schema, name }
+            | Favorite::Function { schema, name } => format!("{}.{}", schema, name),
+            Favorite::Query { name, .. } => name.clone(),
+        }
+    }
+
+    pub fn kind_label(&self) -> &'static str {
+        match self {
+            Favorite::Table { .. } => "table",
+            Favorite::View { .. } => "view",
+            Favorite::MaterializedView { .. } => "materialized view",
+            Favorite::Function { .. } => "function",
+            Favorite::Query { .. } => "query",
+        }
+    }
+}
+
+// A hook is either SQL run on the profile's connection or a shell command,
+// written as `{"sql": "..."}` / `{"shell": "..."}` in the config file.
+// SQL hooks may use `{query}`, `{status}` and `{rows}` placeholders; shell
+// hooks get the same values as PGTUI_QUERY, PGTUI_STATUS and PGTUI_ROWS.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Hook {
+    Sql(String),
+    Shell(String),
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QueryHooks {
+    #[serde(default)]
+    pub pre_query: Vec<Hook>,
+    #[serde(default)]
+    pub post_query: Vec<Hook>,
+}
+
+impl QueryHooks {
+    pub fn is_empty(&self) -> bool {
+        self.pre_query.is_empty() && self.post_query.is_empty()
+    }
 }
 
 impl ConnectionProfile {
-    pub fn new(name: String) -> Self {
+    // Account name used for this profile's keyring entry; profiles for the
+    // same user and database share it
+    pub fn keyring_account(&self) -> String {
+        format!("{}@{}:{}/{}", self.user, self.host, self.port, self.database)
+    }
+
+    // Banner color of a tagged profile, as written in the config file
+    pub fn environment_color(&self) -> Option<String> {
+        let environment = self.environment?;
+        Some(self.color.clone().unwrap_or_else(|| environment.default_color().to_string()))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayoutProfile {
+    pub name: String,
+    // Browser pane width as a percentage of the screen
+    pub browser_width: u16,
+    // Query editor height in rows (including borders)
+    pub editor_height: u16,
+    pub show_details: bool,
+    pub show_status_bar: bool,
+}
+
+impl LayoutProfile {
+    pub fn exploration() -> Self {
+        Self {
+            name: "exploration".to_string(),
+            browser_width: 30,
+            editor_height: 10,
+            show_details: true,
+            show_status_bar: true,
+        }
+    }
+
+    pub fn monitoring() -> Self {
         Self {
-            name,
-            host: "localhost".to_string(),
-            port: "5432".to_string(),
-            database: "postgres".to_string(),
-            user: "postgres".to_string(),
+            name: "monitoring".to_string(),
+            browser_width: 20,
+            editor_height: 6,
+            show_details: true,
+            show_status_bar: true,
         }
     }
 }
 
+// A user-defined command run from the command palette. `sql` may use
+// `{schema}` and `{table}` (the selected browser table, as quoted identifiers)
+// and `{selection}` (the selected results cell, as a literal). With `script`
+// set, the Rhai script in that file runs instead.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserCommand {
+    pub name: String,
+    #[serde(default)]
+    pub sql: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script: Option<String>,
+    #[serde(default)]
+    pub target: CommandTarget,
+}
+
+// Where a user command's result goes: `"results"`, `"popup"` or
+// `{"file": "/path/out.csv"}`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommandTarget {
+    #[default]
+    Results,
+    Popup,
+    File(String),
+}
+
+// External SQL language server started for each connection, e.g.
+// `{"command": "sqls"}`. It gets the connection as PG* environment variables.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LspConfig {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub initialization_options: serde_json::Value,
+}
+
+// Keys for one action in the `keybindings` section: `"ctrl+k"` or a list
+// like `["ctrl+enter", "f5"]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KeySpec {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeySpec {
+    pub fn keys(&self) -> Vec<&str> {
+        match self {
+            KeySpec::One(key) => vec![key.as_str()],
+            KeySpec::Many(keys) => keys.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+fn default_layouts() -> Vec<LayoutProfile> {
+    vec![LayoutProfile::exploration(), LayoutProfile::monitoring()]
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
     pub connections: Vec<ConnectionProfile>,
+    // Abort "run script" at the first failing statement
+    #[serde(default = "default_true")]
+    pub stop_script_on_error: bool,
+    // Master switch for storing profile passwords in the OS keyring
+    #[serde(default = "default_true")]
+    pub use_keyring: bool,
+    // Print local usage stats (queries, rows, tables) when the app exits
+    #[serde(default = "default_true")]
+    pub session_summary: bool,
+    // Underline lint warnings in the query editor once typing pauses
+    #[serde(default = "default_true")]
+    pub lint: bool,
+    // How often the UI checks for input and redraws, in milliseconds
+    #[serde(default = "default_poll_interval")]
+    pub poll_interval_ms: u64,
+    // Check less often while nothing on screen is changing
+    #[serde(default)]
+    pub low_power: bool,
+    // Vim-style normal/insert/visual modes in the query editor
+    #[serde(default)]
+    pub vim_mode: bool,
+    // How often unsaved editor text is snapshotted for crash recovery, in
+    // seconds; 0 turns it off
+    #[serde(default = "default_autosave_interval")]
+    pub autosave_interval_secs: u64,
+    // Most rows read into the results grid for one query; 0 means no limit
+    #[serde(default = "default_max_result_rows")]
+    pub max_result_rows: usize,
+    // LIMIT added to SELECTs without one when run from the editor; 0 is off
+    #[serde(default)]
+    pub auto_limit: usize,
+    // Queries run from the editor are explained first and held for a
+    // confirmation when the planner expects more than this cost or these
+    // rows; unset checks nothing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preflight_max_cost: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preflight_max_rows: Option<f64>,
+    // Default number of runs for "benchmark query", and whether an extra
+    // first run warms the cache without being counted
+    #[serde(default = "default_benchmark_runs")]
+    pub benchmark_runs: usize,
+    #[serde(default = "default_true")]
+    pub benchmark_warmup: bool,
+    // Extra tries for a statement failing with a serialization failure,
+    // deadlock or dropped connection, waiting `retry_backoff_ms` before the
+    // first and twice as long before each next one; 0 turns retrying off
+    #[serde(default = "default_retry_attempts")]
+    pub retry_attempts: u32,
+    #[serde(default = "default_retry_backoff")]
+    pub retry_backoff_ms: u64,
+    // Size result columns by the rows around the cursor rather than the
+    // first ten, so widths follow the grid as it scrolls
+    #[serde(default)]
+    pub auto_fit_on_scroll: bool,
+    // How often the activity monitor reloads pg_stat_activity, in seconds
+    #[serde(default = "default_monitor_refresh")]
+    pub monitor_refresh_secs: u64,
+    // How often a watched query runs again, in seconds
+    #[serde(default = "default_watch_interval")]
+    pub watch_interval_secs: u64,
+    // How many recent results of each connection are kept on disk to look
+    // at again without running their queries; 0 keeps none
+    #[serde(default)]
+    pub results_history: usize,
+    // How often every open connection runs `SELECT 1`, so NAT and firewall
+    // sessions aren't dropped while idle, in seconds; 0 turns it off
+    #[serde(default = "default_keepalive")]
+    pub keepalive_secs: u64,
+    // Estimated row counts (pg_class.reltuples) after table names in the
+    // browser
+    #[serde(default = "default_true")]
+    pub browser_row_counts: bool,
+    // Completion, hover and diagnostics from a language server; the built-in
+    // engine is used when this is unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lsp: Option<LspConfig>,
+    #[serde(default)]
+    pub theme: Theme,
+    #[serde(default = "default_layouts")]
+    pub layouts: Vec<LayoutProfile>,
+    #[serde(default)]
+    pub active_layout: usize,
+    #[serde(default)]
+    pub commands: Vec<UserCommand>,
+    // Column widths set by hand in the results grid: signature of a
+    // result's column list -> column name -> width
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub column_widths: BTreeMap<String, BTreeMap<String, usize>>,
+    // Column layouts picked in the column chooser: hash of the query's text
+    // -> layout, used before its table's saved view
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub query_layouts: BTreeMap<String, TableView>,
+    // Action name -> keys, replacing that action's default keys
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub keybindings: BTreeMap<String, KeySpec>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_poll_interval() -> u64 {
+    100
+}
+
+fn default_autosave_interval() -> u64 {
+    10
+}
+
+fn default_max_result_rows() -> usize {
+    50_000
+}
+
+fn default_benchmark_runs() -> usize {
+    10
+}
+
+fn default_monitor_refresh() -> u64 {
+    2
+}
+
+fn default_watch_interval() -> u64 {
+    2
+}
+
+fn default_retry_attempts() -> u32 {
+    3
+}
+
+fn default_retry_backoff() -> u64 {
+    200
+}
+
+fn default_keepalive() -> u64 {
+    120
 }
 
 impl Config {
@@ -42,6 +430,21 @@ impl Config {
         Ok(config)
     }
 
+    // Whether a profile other than the one at `index` keeps its password in
+    // the same keyring entry, which then has to stay
+    pub fn keyring_shared(&self, index: usize) -> bool {
+        let account = self.connections[index].keyring_account();
+        self.connections
+            .iter()
+            .enumerate()
+            .any(|(i, p)| i != index && p.save_password && p.keyring_account() == account)
+    }
+
+    // False until something has been saved, i.e. on first launch
+    pub fn exists() -> bool {
+        Self::config_path().is_ok_and(|path| path.exists())
+    }
+
     pub fn save(&self) -> Result<()> {
         let config_path = Self::config_path()?;
         
@@ -67,6 +470,36 @@ impl Default for Config {
     fn default() -> Self {
         Self {
             connections: vec![],
+            stop_script_on_error: true,
+            use_keyring: true,
+            session_summary: true,
+            lint: true,
+            poll_interval_ms: default_poll_interval(),
+            low_power: false,
+            vim_mode: false,
+            autosave_interval_secs: default_autosave_interval(),
+            max_result_rows: default_max_result_rows(),
+            auto_limit: 0,
+            preflight_max_cost: None,
+            preflight_max_rows: None,
+            benchmark_runs: default_benchmark_runs(),
+            benchmark_warmup: true,
+            retry_attempts: default_retry_attempts(),
+            retry_backoff_ms: default_retry_backoff(),
+            auto_fit_on_scroll: false,
+            monitor_refresh_secs: default_monitor_refresh(),
+            watch_interval_secs: default_watch_interval(),
+            keepalive_secs: default_keepalive(),
+            browser_row_counts: true,
+            results_history: 0,
+            lsp: None,
+            theme: Theme::default(),
+            layouts: default_layouts(),
+            active_layout: 0,
+            commands: vec![],
+            column_widths: BTreeMap::new(),
+            query_layouts: BTreeMap::new(),
+            keybindings: BTreeMap::new(),
         }
     }
 }
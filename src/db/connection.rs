@@ -1,13 +1,60 @@
-use anyhow::{Context, Result};
-use tokio_postgres::{Client, NoTls};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use futures_util::StreamExt;
+use tokio_postgres::error::SqlState;
+use tokio_postgres::config::TargetSessionAttrs;
+use tokio_postgres::{AsyncMessage, Client, NoTls, SimpleQueryMessage};
+
+use super::{PendingRows, ServerNotice};
+
+// How long closing a connection waits to learn whether it is in a
+// transaction
+const TRANSACTION_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
 
 pub struct DbConnection {
-    client: Option<Client>,
+    // Shared so long-running work like COPY can run in a background task
+    client: Option<Arc<Client>>,
+    // Unread rows of the last query, fetched as the grid asks for them
+    pending_rows: Mutex<Option<PendingRows>>,
+    // How the connection was made, to make it again after it was dropped
+    config: Option<tokio_postgres::Config>,
+    // NOTICE/WARNING messages received and not yet taken; the connection
+    // task adds to it
+    notices: Arc<Mutex<Vec<ServerNotice>>>,
+    // Sent at startup, kept to send again when one changes
+    settings: SessionSettings,
+    // Why the current session's connection ended, once it has; the
+    // connection task sets it
+    lost: Arc<Mutex<Option<String>>>,
+    // A transaction block was begun on this session and not ended, going by
+    // the statements run on it
+    transaction: bool,
+}
+
+// Session settings of a connection profile, applied as the session starts
+#[derive(Debug, Clone, Default)]
+pub struct SessionSettings {
+    pub read_only: bool,
+    // "30s", "5min"; None leaves the server's default
+    pub statement_timeout: Option<String>,
+    // "any", "read-write" or "read-only": which of several hosts to settle
+    // on, at connect and on every reconnect
+    pub target_session_attrs: Option<String>,
 }
 
 impl DbConnection {
     pub fn new() -> Self {
-        Self { client: None }
+        Self {
+            client: None,
+            pending_rows: Mutex::new(None),
+            config: None,
+            notices: Arc::new(Mutex::new(Vec::new())),
+            settings: SessionSettings::default(),
+            lost: Arc::new(Mutex::new(None)),
+            transaction: false,
+        }
     }
 
     pub async fn connect(
@@ -17,37 +64,196 @@ impl DbConnection {
         database: &str,
         user: &str,
         password: &str,
+        settings: SessionSettings,
     ) -> Result<()> {
-        let config = format!(
-            "host={} port={} dbname={} user={} password={}",
-            host, port, database, user, password
-        );
+        // Built field by field so passwords with spaces or quotes (e.g. from
+        // ~/.pgpass) survive, and an empty one means "no password"
+        let mut config = tokio_postgres::Config::new();
+        // "db1,db2" are tried in order, like libpq does
+        for host in host.split(',') {
+            config.host(host.trim());
+        }
+        config.port(port).dbname(database).user(user);
+        if !password.is_empty() {
+            config.password(password);
+        }
+        if let Some(attrs) = &settings.target_session_attrs {
+            config.target_session_attrs(match attrs.as_str() {
+                "any" => TargetSessionAttrs::Any,
+                "read-write" => TargetSessionAttrs::ReadWrite,
+                "read-only" => TargetSessionAttrs::ReadOnly,
+                other => bail!("Unknown target_session_attrs \"{}\"; use any, read-write or read-only", other),
+            });
+        }
+        // Part of the startup packet, so reconnects and database switches
+        // keep them
+        self.settings = settings;
+        let options = self.startup_options();
+        if !options.is_empty() {
+            config.options(&options);
+        }
+        self.open(config).await
+    }
+
+    // The session's statement_timeout as set at connect or since, if any
+    pub fn statement_timeout(&self) -> Option<&str> {
+        self.settings.statement_timeout.as_deref()
+    }
+
+    // Change statement_timeout for this session ("30s", "5min", "0" for
+    // none), and for the sessions a reconnect opens
+    pub async fn set_statement_timeout(&mut self, value: &str) -> Result<()> {
+        let client = self.client().context("Not connected")?;
+        client
+            .execute("SELECT set_config('statement_timeout', $1, false)", &[&value])
+            .await
+            .context("Failed to set statement_timeout")?;
+        self.settings.statement_timeout = Some(value.to_string()).filter(|value| value != "0");
+        let options = self.startup_options();
+        if let Some(config) = &mut self.config {
+            config.options(&options);
+        }
+        Ok(())
+    }
+
+    // "-c name=value" settings for the startup packet; spaces in values are
+    // escaped with a backslash
+    fn startup_options(&self) -> String {
+        let mut options = Vec::new();
+        if self.settings.read_only {
+            options.push("-c default_transaction_read_only=on".to_string());
+        }
+        if let Some(timeout) = &self.settings.statement_timeout {
+            let escaped = timeout.replace('\\', "\\\\").replace(' ', "\\ ");
+            options.push(format!("-c statement_timeout={}", escaped));
+        }
+        options.join(" ")
+    }
+
+    // Connect again with the same settings, e.g. after the server ended the session
+    pub async fn reconnect(&mut self) -> Result<()> {
+        let config = self.config.clone().context("Not connected")?;
+        self.release_rows();
+        self.open(config).await
+    }
+
+    // Connect to another database of the same server as the same user; the
+    // current session stays open if that fails
+    pub async fn switch_database(&mut self, database: &str) -> Result<()> {
+        let mut config = self.config.clone().context("Not connected")?;
+        config.dbname(database);
+        self.release_rows();
+        self.open(config).await
+    }
 
-        let (client, connection) = tokio_postgres::connect(&config, NoTls)
+    async fn open(&mut self, config: tokio_postgres::Config) -> Result<()> {
+        let (client, mut connection) = config
+            .connect(NoTls)
             .await
             .context("Failed to connect to database")?;
 
-        // Spawn connection handler
+        // Spawn connection handler; driving it message by message keeps the
+        // notices a plain `connection.await` would drop
+        let notices = self.notices.clone();
+        let lost = Arc::new(Mutex::new(None));
+        let session_lost = lost.clone();
         tokio::spawn(async move {
-            if let Err(e) = connection.await {
-                eprintln!("Connection error: {}", e);
+            let mut messages = futures_util::stream::poll_fn(move |cx| connection.poll_message(cx));
+            let mut reason = "the server closed the connection".to_string();
+            while let Some(message) = messages.next().await {
+                match message {
+                    Ok(AsyncMessage::Notice(notice)) => notices.lock().unwrap().push(ServerNotice::from(&notice)),
+                    Ok(_) => {}
+                    Err(e) => {
+                        reason = e.to_string();
+                        break;
+                    }
+                }
             }
+            *session_lost.lock().unwrap() = Some(reason);
         });
 
-        self.client = Some(client);
+        self.lost = lost;
+        self.transaction = false;
+        self.client = Some(Arc::new(client));
+        self.config = Some(config);
         Ok(())
     }
 
+    // Any other use of the connection drops the unread rows first: nothing
+    // else gets through until they are read or discarded
     pub fn client(&self) -> Option<&Client> {
-        self.client.as_ref()
+        self.release_rows();
+        self.client.as_deref()
+    }
+
+    pub fn shared_client(&self) -> Option<Arc<Client>> {
+        self.release_rows();
+        self.client.clone()
+    }
+
+    pub fn park_rows(&self, rows: Option<PendingRows>) {
+        *self.pending_rows.lock().unwrap() = rows;
+    }
+
+    pub fn take_rows(&self) -> Option<PendingRows> {
+        self.pending_rows.lock().unwrap().take()
+    }
+
+    // Messages received since the last call
+    pub fn take_notices(&self) -> Vec<ServerNotice> {
+        std::mem::take(&mut *self.notices.lock().unwrap())
+    }
+
+    pub fn transaction_open(&self) -> bool {
+        self.transaction
+    }
+
+    pub fn set_transaction_open(&mut self, open: bool) {
+        self.transaction = open;
+    }
+
+    pub fn has_pending_rows(&self) -> bool {
+        self.pending_rows.lock().unwrap().is_some()
+    }
+
+    fn release_rows(&self) {
+        self.pending_rows.lock().unwrap().take();
+    }
+
+    // Whether a transaction block is open: now() stays at the time of
+    // BEGIN while statement_timestamp() moves on, and a failed block refuses
+    // the query. A simple query, since the extended protocol stamps its
+    // messages apart. None when it cannot be told: the connection is lost,
+    // rows are pending (asking would discard them, and their portal lives in
+    // a transaction), or the server does not answer within a few seconds.
+    pub async fn in_transaction(&self) -> Option<bool> {
+        if self.lost().is_some() || self.has_pending_rows() {
+            return None;
+        }
+        let Some(client) = self.client.as_deref() else {
+            return Some(false);
+        };
+        let query = client.simple_query("SELECT now() <> statement_timestamp()");
+        match tokio::time::timeout(TRANSACTION_CHECK_TIMEOUT, query).await.ok()? {
+            Ok(messages) => Some(messages.iter().any(|message| match message {
+                SimpleQueryMessage::Row(row) => row.get(0) == Some("t"),
+                _ => false,
+            })),
+            Err(e) if e.code() == Some(&SqlState::IN_FAILED_SQL_TRANSACTION) => Some(true),
+            Err(_) => None,
+        }
     }
 
     pub fn is_connected(&self) -> bool {
         self.client.is_some()
     }
 
-    pub fn disconnect(&mut self) {
-        self.client = None;
+    // Why the connection was lost, when it was: a server restart, a network
+    // drop. The client stays in place for `reconnect`.
+    pub fn lost(&self) -> Option<String> {
+        self.client.as_ref()?;
+        self.lost.lock().unwrap().clone()
     }
 }
 
@@ -0,0 +1,143 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use anyhow::{bail, Context, Result};
+use bytes::Bytes;
+use futures_util::SinkExt;
+use tokio_postgres::Client;
+
+// Chunk size for streaming a file into COPY
+const CHUNK: usize = 64 * 1024;
+
+/// Header and up to `samples` data records of a CSV file.
+pub fn read_csv_preview(path: &str, samples: usize) -> Result<(Vec<String>, Vec<Vec<String>>)> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path))?;
+    let mut reader = BufReader::new(file);
+    let Some(header) = read_record(&mut reader)? else {
+        bail!("{} is empty", path);
+    };
+    let mut rows = Vec::new();
+    while rows.len() < samples {
+        match read_record(&mut reader)? {
+            Some(row) => rows.push(row),
+            None => break,
+        }
+    }
+    Ok((header, rows))
+}
+
+/// `COPY` statement loading CSV with a header line into `columns` of `target`.
+pub fn copy_csv_sql(target: &str, columns: &[String]) -> String {
+    format!("COPY {} ({}) FROM STDIN WITH (FORMAT csv, HEADER true)", target, columns.join(", "))
+}
+
+/// Load a CSV file with `sql` from `copy_csv_sql`, whose columns take the
+/// CSV fields at `fields`. When every field is loaded the file is streamed
+/// as-is, otherwise each record is re-encoded without the skipped ones.
+/// `progress` counts the bytes of the file read so far. Returns the number
+/// of rows loaded.
+pub async fn copy_csv_in(
+    client: Arc<Client>,
+    sql: String,
+    fields: Vec<usize>,
+    field_count: usize,
+    path: String,
+    progress: Arc<AtomicU64>,
+) -> Result<u64> {
+    let file = File::open(&path).with_context(|| format!("Failed to open {}", path))?;
+    let mut reader = BufReader::new(file);
+
+    let sink = client.copy_in::<_, Bytes>(sql.as_str()).await.context("COPY failed to start")?;
+    futures_util::pin_mut!(sink);
+
+    let passthrough = fields.len() == field_count && fields.iter().enumerate().all(|(i, &f)| i == f);
+    if passthrough {
+        let mut buf = vec![0; CHUNK];
+        loop {
+            let n = reader.read(&mut buf).with_context(|| format!("Failed to read {}", path))?;
+            if n == 0 {
+                break;
+            }
+            sink.send(Bytes::copy_from_slice(&buf[..n])).await.context("COPY failed")?;
+            progress.fetch_add(n as u64, Ordering::Relaxed);
+        }
+    } else {
+        let mut out = String::new();
+        let mut read = 0;
+        while let Some((record, len)) = read_record_len(&mut reader)? {
+            let kept: Vec<String> = fields
+                .iter()
+                .map(|&f| encode_field(record.get(f).map(String::as_str).unwrap_or("")))
+                .collect();
+            out.push_str(&kept.join(","));
+            out.push('\n');
+            read += len as u64;
+            if out.len() >= CHUNK {
+                sink.send(Bytes::from(std::mem::take(&mut out))).await.context("COPY failed")?;
+                progress.store(read, Ordering::Relaxed);
+            }
+        }
+        if !out.is_empty() {
+            sink.send(Bytes::from(out)).await.context("COPY failed")?;
+        }
+        progress.store(read, Ordering::Relaxed);
+    }
+
+    let rows = sink.finish().await.context("COPY failed")?;
+    Ok(rows)
+}
+
+fn read_record(reader: &mut impl BufRead) -> Result<Option<Vec<String>>> {
+    Ok(read_record_len(reader)?.map(|(record, _)| record))
+}
+
+// One CSV record, which may span lines inside quotes, and its length in bytes
+fn read_record_len(reader: &mut impl BufRead) -> Result<Option<(Vec<String>, usize)>> {
+    let mut text = String::new();
+    loop {
+        let n = reader.read_line(&mut text).context("Failed to read CSV")?;
+        if n == 0 {
+            break;
+        }
+        // An odd number of quotes so far means a quoted field goes on
+        if text.matches('"').count().is_multiple_of(2) {
+            break;
+        }
+    }
+    if text.is_empty() {
+        return Ok(None);
+    }
+    let len = text.len();
+    Ok(Some((parse_record(text.trim_end_matches(['\r', '\n'])), len)))
+}
+
+fn parse_record(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+// Quote a field when it needs it; an empty unquoted field is NULL to COPY
+fn encode_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,149 @@
+use anyhow::{Context, Result};
+use tokio_postgres::Client;
+
+// What a relation reads and references, then what reads or references it,
+// from pg_depend, pg_rewrite, pg_constraint, pg_inherits and pg_trigger.
+// Functions and types from pg_catalog are left out as noise.
+const DEPENDENCIES_SQL: &str = "
+    WITH target AS (
+        SELECT c.oid, c.reltype, c.relispartition
+        FROM pg_catalog.pg_class c
+        JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+        WHERE n.nspname = $1 AND c.relname = $2
+    ),
+    kinds (relkind, label) AS (
+        VALUES ('r'::\"char\", 'table'), ('p', 'partitioned table'), ('v', 'view'),
+            ('m', 'materialized view'), ('f', 'foreign table'), ('S', 'sequence')
+    ),
+    relations AS (
+        SELECT c.oid, k.label, format('%I.%I', n.nspname, c.relname) AS name
+        FROM pg_catalog.pg_class c
+        JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+        JOIN kinds k ON k.relkind = c.relkind
+    ),
+    functions AS (
+        SELECT p.oid, format('%I.%I(%s)', n.nspname, p.proname, pg_catalog.pg_get_function_identity_arguments(p.oid)) AS name
+        FROM pg_catalog.pg_proc p
+        JOIN pg_catalog.pg_namespace n ON n.oid = p.pronamespace
+        WHERE n.nspname <> 'pg_catalog'
+    ),
+    found (used_by, kind, name, via) AS (
+        -- Relations and functions a view's query reads
+        SELECT false, r.label, r.name, 'query'
+        FROM target t
+        JOIN pg_catalog.pg_rewrite w ON w.ev_class = t.oid
+        JOIN pg_catalog.pg_depend d ON d.classid = 'pg_catalog.pg_rewrite'::regclass AND d.objid = w.oid
+            AND d.refclassid = 'pg_catalog.pg_class'::regclass AND d.refobjid <> t.oid
+        JOIN relations r ON r.oid = d.refobjid
+        UNION
+        SELECT false, 'function', f.name, 'query'
+        FROM target t
+        JOIN pg_catalog.pg_rewrite w ON w.ev_class = t.oid
+        JOIN pg_catalog.pg_depend d ON d.classid = 'pg_catalog.pg_rewrite'::regclass AND d.objid = w.oid
+            AND d.refclassid = 'pg_catalog.pg_proc'::regclass
+        JOIN functions f ON f.oid = d.refobjid
+        UNION
+        -- Tables its foreign keys reference
+        SELECT false, r.label, r.name, format('foreign key %I', con.conname)
+        FROM target t
+        JOIN pg_catalog.pg_constraint con ON con.conrelid = t.oid AND con.contype = 'f'
+        JOIN relations r ON r.oid = con.confrelid
+        UNION
+        -- Its parent, when it inherits or is a partition
+        SELECT false, r.label, r.name, CASE WHEN t.relispartition THEN 'partition of' ELSE 'inherits' END
+        FROM target t
+        JOIN pg_catalog.pg_inherits i ON i.inhrelid = t.oid
+        JOIN relations r ON r.oid = i.inhparent
+        UNION
+        -- Functions its triggers run
+        SELECT false, 'function', f.name, format('trigger %I', tg.tgname)
+        FROM target t
+        JOIN pg_catalog.pg_trigger tg ON tg.tgrelid = t.oid AND NOT tg.tgisinternal
+        JOIN functions f ON f.oid = tg.tgfoid
+        UNION
+        -- Sequences and functions column defaults and generated columns use
+        SELECT false, coalesce(r.label, 'function'), coalesce(r.name, f.name), format('default of %I', a.attname)
+        FROM target t
+        JOIN pg_catalog.pg_attrdef ad ON ad.adrelid = t.oid
+        JOIN pg_catalog.pg_attribute a ON a.attrelid = t.oid AND a.attnum = ad.adnum
+        JOIN pg_catalog.pg_depend d ON d.classid = 'pg_catalog.pg_attrdef'::regclass AND d.objid = ad.oid
+        LEFT JOIN relations r ON d.refclassid = 'pg_catalog.pg_class'::regclass AND r.oid = d.refobjid AND r.oid <> t.oid
+        LEFT JOIN functions f ON d.refclassid = 'pg_catalog.pg_proc'::regclass AND f.oid = d.refobjid
+        WHERE r.oid IS NOT NULL OR f.oid IS NOT NULL
+        UNION
+        -- Enums, domains and composite types of its columns
+        SELECT false,
+            CASE ty.typtype WHEN 'e' THEN 'enum' WHEN 'd' THEN 'domain' WHEN 'c' THEN 'composite type' ELSE 'type' END,
+            format('%I.%I', tn.nspname, ty.typname), format('column %I', a.attname)
+        FROM target t
+        JOIN pg_catalog.pg_attribute a ON a.attrelid = t.oid AND a.attnum > 0 AND NOT a.attisdropped
+        JOIN pg_catalog.pg_type ty ON ty.oid = a.atttypid
+        JOIN pg_catalog.pg_namespace tn ON tn.oid = ty.typnamespace
+        WHERE tn.nspname NOT IN ('pg_catalog', 'information_schema')
+        UNION
+        -- Views and materialized views whose query reads it
+        SELECT true, r.label, r.name, 'query'
+        FROM target t
+        JOIN pg_catalog.pg_depend d ON d.classid = 'pg_catalog.pg_rewrite'::regclass
+            AND d.refclassid = 'pg_catalog.pg_class'::regclass AND d.refobjid = t.oid
+        JOIN pg_catalog.pg_rewrite w ON w.oid = d.objid AND w.ev_class <> t.oid
+        JOIN relations r ON r.oid = w.ev_class
+        UNION
+        -- Tables with foreign keys referencing it
+        SELECT true, r.label, r.name, format('foreign key %I', con.conname)
+        FROM target t
+        JOIN pg_catalog.pg_constraint con ON con.confrelid = t.oid AND con.contype = 'f'
+        JOIN relations r ON r.oid = con.conrelid
+        UNION
+        -- Its children and partitions
+        SELECT true, r.label, r.name, CASE WHEN c.relispartition THEN 'partition' ELSE 'inherits from it' END
+        FROM target t
+        JOIN pg_catalog.pg_inherits i ON i.inhparent = t.oid
+        JOIN pg_catalog.pg_class c ON c.oid = i.inhrelid
+        JOIN relations r ON r.oid = c.oid
+        UNION
+        -- Functions taking or returning its row type, or whose SQL body reads it
+        SELECT true, 'function', f.name,
+            CASE WHEN d.refclassid = 'pg_catalog.pg_type'::regclass THEN 'row type' ELSE 'body' END
+        FROM target t
+        JOIN pg_catalog.pg_depend d ON d.classid = 'pg_catalog.pg_proc'::regclass
+            AND ((d.refclassid = 'pg_catalog.pg_class'::regclass AND d.refobjid = t.oid)
+                OR (d.refclassid = 'pg_catalog.pg_type'::regclass AND d.refobjid = t.reltype))
+        JOIN functions f ON f.oid = d.objid
+        UNION
+        -- Sequences owned by its columns, which a DROP takes along
+        SELECT true, r.label, r.name, format('owned by column %I', a.attname)
+        FROM target t
+        JOIN pg_catalog.pg_depend d ON d.classid = 'pg_catalog.pg_class'::regclass
+            AND d.refclassid = 'pg_catalog.pg_class'::regclass AND d.refobjid = t.oid AND d.deptype IN ('a', 'i')
+        JOIN pg_catalog.pg_attribute a ON a.attrelid = t.oid AND a.attnum = d.refobjsubid
+        JOIN relations r ON r.oid = d.objid AND r.label = 'sequence'
+    )
+    SELECT used_by, kind, name, via FROM found ORDER BY used_by, kind, name, via";
+
+// Something the selected relation depends on, or that depends on it
+#[derive(Debug, Clone)]
+pub struct Dependency {
+    // True when `name` depends on the selected relation
+    pub used_by: bool,
+    pub kind: String,
+    pub name: String,
+    // How: "query", "foreign key orders_customer_fkey", "trigger audit", ...
+    pub via: String,
+}
+
+pub async fn object_dependencies(client: &Client, schema: &str, name: &str) -> Result<Vec<Dependency>> {
+    let rows = client
+        .query(DEPENDENCIES_SQL, &[&schema, &name])
+        .await
+        .context("Failed to load dependencies")?;
+    Ok(rows
+        .iter()
+        .map(|row| Dependency {
+            used_by: row.get(0),
+            kind: row.get(1),
+            name: row.get(2),
+            via: row.get(3),
+        })
+        .collect())
+}
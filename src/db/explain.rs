@@ -0,0 +1,105 @@
+use anyhow::{anyhow, Context, Result};
+use tokio_postgres::types::ToSql;
+use tokio_postgres::Client;
+
+use super::values::TextParam;
+
+// Settings the planner's choices depend on, captured with every plan so
+// plans from different servers can be told apart by more than their shape
+const PLANNER_SETTINGS_SQL: &str = "
+    SELECT name, setting || COALESCE(unit, ''), source <> 'default'
+    FROM pg_catalog.pg_settings
+    WHERE name LIKE 'enable\\_%'
+       OR name IN ('work_mem', 'random_page_cost', 'seq_page_cost', 'effective_cache_size',
+                   'cpu_tuple_cost', 'cpu_index_tuple_cost', 'cpu_operator_cost',
+                   'effective_io_concurrency', 'default_statistics_target', 'jit',
+                   'max_parallel_workers_per_gather', 'from_collapse_limit', 'join_collapse_limit')
+    ORDER BY name";
+
+// A planner setting as the plan was made with it
+#[derive(Debug, Clone)]
+pub struct PlannerSetting {
+    pub name: String,
+    pub value: String,
+    // Set in the config file, for the role or in the session
+    pub changed: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Plan {
+    pub lines: Vec<String>,
+    pub settings: Vec<PlannerSetting>,
+    // Whether the statement ran, giving actual times and buffer counts
+    pub analyzed: bool,
+}
+
+/// The plan of `sql` with the planner settings in effect. With `analyze`
+/// the statement is executed and the plan carries actual timings and
+/// buffer usage.
+pub async fn explain(client: &Client, sql: &str, analyze: bool) -> Result<Plan> {
+    let settings = client
+        .query(PLANNER_SETTINGS_SQL, &[])
+        .await
+        .context("Failed to read the planner settings")?
+        .iter()
+        .map(|row| PlannerSetting {
+            name: row.get(0),
+            value: row.get(1),
+            changed: row.get(2),
+        })
+        .collect();
+
+    let options = if analyze { "(ANALYZE, BUFFERS)" } else { "" };
+    let statement = sql.trim().trim_end_matches(';');
+    let lines = client
+        .query(&format!("EXPLAIN {} {}", options, statement), &[])
+        .await
+        .context("EXPLAIN failed")?
+        .iter()
+        .map(|row| row.get(0))
+        .collect();
+
+    Ok(Plan {
+        lines,
+        settings,
+        analyzed: analyze,
+    })
+}
+
+// What the planner expects of a statement, from a plan it didn't run
+#[derive(Debug, Clone, Copy)]
+pub struct PlanEstimate {
+    // Total cost of the top plan node
+    pub cost: f64,
+    // Rows of the first node expected to produce any: the result of a
+    // query, or the rows a write goes through
+    pub rows: f64,
+}
+
+/// The planner's estimate for `sql`, bound to `params` like the run itself,
+/// from a plain EXPLAIN that doesn't execute it.
+pub async fn estimate(client: &Client, sql: &str, params: &[Option<String>]) -> Result<PlanEstimate> {
+    let statement = sql.trim().trim_end_matches(';');
+    let params: Vec<Option<TextParam>> = params.iter().map(|value| value.as_deref().map(TextParam)).collect();
+    let params: Vec<&(dyn ToSql + Sync)> = params.iter().map(|param| param as &(dyn ToSql + Sync)).collect();
+    let lines: Vec<String> = client
+        .query(&format!("EXPLAIN {}", statement), &params)
+        .await
+        .context("EXPLAIN failed")?
+        .iter()
+        .map(|row| row.get(0))
+        .collect();
+    let nodes: Vec<(f64, f64)> = lines.iter().filter_map(|line| node_estimate(line)).collect();
+    let &(cost, _) = nodes.first().ok_or_else(|| anyhow!("EXPLAIN gave no plan"))?;
+    let rows = nodes.iter().map(|&(_, rows)| rows).find(|&rows| rows > 0.0).unwrap_or(0.0);
+    Ok(PlanEstimate { cost, rows })
+}
+
+// Total cost and rows from "Seq Scan on t  (cost=0.00..35.50 rows=2550 width=4)"
+fn node_estimate(line: &str) -> Option<(f64, f64)> {
+    let rest = &line[line.find("(cost=")? + "(cost=".len()..];
+    let (_, rest) = rest.split_once("..")?;
+    let (cost, rest) = rest.split_once(' ')?;
+    let rows = rest.strip_prefix("rows=")?.split(' ').next()?;
+    Some((cost.parse().ok()?, rows.parse().ok()?))
+}
@@ -1,8 +1,122 @@
 mod connection;
+mod copy;
+mod dependencies;
+mod explain;
+mod preview;
+mod privileges;
 mod queries;
+mod schema_export;
+mod values;
 
-pub use connection::DbConnection;
+pub use connection::{DbConnection, SessionSettings};
+pub use copy::{copy_csv_in, copy_csv_sql, read_csv_preview};
+pub use dependencies::{object_dependencies, Dependency};
+pub use explain::{estimate, explain, Plan, PlanEstimate};
+pub use preview::{preview_ddl, ChangeKind, SchemaChange};
+pub use privileges::{object_privileges, Privilege};
 pub use queries::*;
+pub use schema_export::schema_ddl;
+
+// Keywords that can't be used as a bare column or table name (every
+// category of `pg_get_keywords()` but unreserved), sorted for binary search
+const RESERVED: &[&str] = &[
+    "all", "analyse", "analyze", "and", "any", "array", "as", "asc", "asymmetric", "authorization",
+    "between", "bigint", "binary", "bit", "boolean", "both", "case", "cast", "char", "character",
+    "check", "coalesce", "collate", "collation", "column", "concurrently", "constraint", "create",
+    "cross", "current_catalog", "current_date", "current_role", "current_schema", "current_time",
+    "current_timestamp", "current_user", "dec", "decimal", "default", "deferrable", "desc",
+    "distinct", "do", "else", "end", "except", "exists", "extract", "false", "fetch", "float",
+    "for", "foreign", "freeze", "from", "full", "grant", "greatest", "group", "grouping", "having",
+    "ilike", "in", "initially", "inner", "inout", "int", "integer", "intersect", "interval", "into",
+    "is", "isnull", "join", "json", "json_array", "json_arrayagg", "json_exists", "json_object",
+    "json_objectagg", "json_query", "json_scalar", "json_serialize", "json_table", "json_value",
+    "lateral", "leading", "least", "left", "like", "limit", "localtime", "localtimestamp",
+    "merge_action", "national", "natural", "nchar", "none", "normalize", "not", "notnull", "null",
+    "nullif", "numeric", "offset", "on", "only", "or", "order", "out", "outer", "overlaps",
+    "overlay", "placing", "position", "precision", "primary", "real", "references", "returning",
+    "right", "row", "select", "session_user", "setof", "similar", "smallint", "some", "substring",
+    "symmetric", "system_user", "table", "tablesample", "then", "time", "timestamp", "to",
+    "trailing", "treat", "trim", "true", "union", "unique", "user", "using", "values", "varchar",
+    "variadic", "verbose", "when", "where", "window", "with", "xmlattributes", "xmlconcat",
+    "xmlelement", "xmlexists", "xmlforest", "xmlnamespaces", "xmlparse", "xmlpi", "xmlroot",
+    "xmlserialize", "xmltable",
+];
+
+/// Quote an identifier the way `quote_ident()` would: plain lowercase names
+/// stay as they are unless they are reserved keywords like `user` or `order`.
+pub fn quote_ident(name: &str) -> String {
+    let is_plain = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        && RESERVED.binary_search(&name).is_err();
+
+    if is_plain {
+        name.to_string()
+    } else {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+}
+
+/// Quote a string as a SQL literal, like `quote_literal()`.
+pub fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// True when the error (or its cause) is a Postgres "insufficient privilege" error.
+pub fn is_permission_denied(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        cause
+            .downcast_ref::<tokio_postgres::Error>()
+            .and_then(|e| e.code())
+            .is_some_and(|code| *code == tokio_postgres::error::SqlState::INSUFFICIENT_PRIVILEGE)
+    })
+}
+
+/// Failures that running the same statement again can get past.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transient {
+    SerializationFailure,
+    Deadlock,
+    // The server ended the session, e.g. pg_terminate_backend or a restart
+    Disconnected,
+}
+
+impl Transient {
+    pub fn describe(self) -> &'static str {
+        match self {
+            Transient::SerializationFailure => "serialization failure",
+            Transient::Deadlock => "deadlock",
+            Transient::Disconnected => "connection lost",
+        }
+    }
+}
+
+/// The transient failure behind the error (or its cause), if it is one.
+pub fn transient_failure(err: &anyhow::Error) -> Option<Transient> {
+    use tokio_postgres::error::SqlState;
+    err.chain().find_map(|cause| {
+        let err = cause.downcast_ref::<tokio_postgres::Error>()?;
+        if err.is_closed() {
+            return Some(Transient::Disconnected);
+        }
+        match err.code()? {
+            code if *code == SqlState::T_R_SERIALIZATION_FAILURE => Some(Transient::SerializationFailure),
+            code if *code == SqlState::T_R_DEADLOCK_DETECTED => Some(Transient::Deadlock),
+            code if [SqlState::ADMIN_SHUTDOWN, SqlState::CRASH_SHUTDOWN, SqlState::CANNOT_CONNECT_NOW].contains(code) => {
+                Some(Transient::Disconnected)
+            }
+            _ => None,
+        }
+    })
+}
+
+pub fn qualified_name(schema: &str, name: &str) -> String {
+    format!("{}.{}", quote_ident(schema), quote_ident(name))
+}
 
 #[derive(Debug, Clone)]
 pub struct Database {
@@ -10,6 +124,22 @@ pub struct Database {
     pub owner: String,
 }
 
+// A database as described by pg_database, with its settings
+#[derive(Debug, Clone)]
+pub struct DatabaseDetails {
+    pub name: String,
+    pub owner: String,
+    pub encoding: String,
+    pub collation: String,
+    pub ctype: String,
+    // None without CONNECT privilege on it
+    pub size: Option<String>,
+    // -1 for no limit
+    pub connection_limit: i32,
+    // ALTER DATABASE/ROLE ... SET values as (role or "all roles", "name=value")
+    pub settings: Vec<(String, String)>,
+}
+
 #[derive(Debug, Clone)]
 pub struct Schema {
     pub name: String,
@@ -17,7 +147,6 @@ pub struct Schema {
 
 #[derive(Debug, Clone)]
 pub struct Table {
-    pub schema: String,
     pub name: String,
     pub row_count: Option<i64>,
 }
@@ -30,24 +159,244 @@ pub struct Column {
     pub column_default: Option<String>,
 }
 
+impl Column {
+    // NOT NULL without a default: an INSERT has to provide it
+    pub fn is_required(&self) -> bool {
+        self.is_nullable == "NO" && self.column_default.is_none()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct View {
-    pub schema: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct MaterializedView {
     pub name: String,
 }
 
 #[derive(Debug, Clone)]
 pub struct Function {
+    pub name: String,
+}
+
+// A NOTICE, WARNING or other message the server sent while a statement
+// ran, e.g. from RAISE NOTICE in a function
+#[derive(Debug, Clone)]
+pub struct ServerNotice {
+    pub severity: String,
+    pub message: String,
+    pub detail: Option<String>,
+    pub hint: Option<String>,
+}
+
+impl ServerNotice {
+    pub fn is_warning(&self) -> bool {
+        self.severity == "WARNING"
+    }
+
+    // "NOTICE: message (DETAIL: ...; HINT: ...)"
+    pub fn describe(&self) -> String {
+        let extra: Vec<String> = [("DETAIL", &self.detail), ("HINT", &self.hint)]
+            .into_iter()
+            .filter_map(|(label, text)| text.as_ref().map(|text| format!("{}: {}", label, text)))
+            .collect();
+        if extra.is_empty() {
+            format!("{}: {}", self.severity, self.message)
+        } else {
+            format!("{}: {} ({})", self.severity, self.message, extra.join("; "))
+        }
+    }
+}
+
+impl From<&tokio_postgres::error::DbError> for ServerNotice {
+    fn from(notice: &tokio_postgres::error::DbError) -> Self {
+        Self {
+            severity: notice.severity().to_string(),
+            message: notice.message().to_string(),
+            detail: notice.detail().map(str::to_string),
+            hint: notice.hint().map(str::to_string),
+        }
+    }
+}
+
+// Names the completion engine offers, loaded in one go per connection
+#[derive(Debug, Clone)]
+pub struct CompletionCatalog {
+    // Tables and views with their columns in order
+    pub tables: Vec<(String, Vec<String>)>,
+    pub roles: Vec<String>,
+    pub schemas: Vec<String>,
+    pub databases: Vec<String>,
+    pub settings: Vec<Setting>,
+    pub functions: Vec<Routine>,
+}
+
+// One overload of a function that can be called from a query, with its
+// signature as the server prints it
+#[derive(Debug, Clone)]
+pub struct Routine {
     pub schema: String,
     pub name: String,
-    pub function_type: String,
+    // function, aggregate or window
+    pub kind: String,
+    // "a integer, b text DEFAULT 'x'::text"
+    pub arguments: String,
+    pub result: String,
+}
+
+// What the schema search found: a relation, one of its columns or indexes,
+// or a function
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ObjectKind {
+    Table,
+    View,
+    MaterializedView,
+    Function,
+    Column,
+    Index,
+}
+
+impl ObjectKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            ObjectKind::Table => "table",
+            ObjectKind::View => "view",
+            ObjectKind::MaterializedView => "materialized view",
+            ObjectKind::Function => "function",
+            ObjectKind::Column => "column",
+            ObjectKind::Index => "index",
+        }
+    }
+}
+
+// A named object in any schema of the database
+#[derive(Debug, Clone)]
+pub struct SchemaObject {
+    pub kind: ObjectKind,
+    pub schema: String,
+    pub name: String,
+    // The table or view a column or index belongs to, and what it is
+    pub parent: Option<(ObjectKind, String)>,
+    // Column type, index method or function arguments
+    pub detail: String,
+}
+
+// A server configuration parameter, for SET/SHOW autocompletion
+#[derive(Debug, Clone)]
+pub struct Setting {
+    pub name: String,
+    // Allowed values of enum and boolean settings, empty otherwise
+    pub values: Vec<String>,
+}
+
+// A server process as listed by pg_stat_activity
+#[derive(Debug, Clone)]
+pub struct Activity {
+    pub pid: i32,
+    pub user: String,
+    pub database: String,
+    pub state: String,
+    // "type: event" while waiting, empty otherwise
+    pub wait_event: String,
+    pub query: String,
+    // Seconds since the current (or, when idle, the last) query started
+    pub duration: Option<f64>,
+}
+
+// A backend waiting for a lock, or holding one that others wait for
+#[derive(Debug, Clone)]
+pub struct LockWait {
+    pub pid: i32,
+    pub user: String,
+    pub state: String,
+    pub query: String,
+    pub duration: Option<f64>,
+    // Backends this one waits for; empty for one that only blocks others
+    pub blocked_by: Vec<i32>,
+    // Lock mode and object it waits for, e.g. "AccessExclusiveLock on orders"
+    pub waiting_for: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Sequence {
+    pub name: String,
+}
+
+// A sequence as described by pg_sequences
+#[derive(Debug, Clone)]
+pub struct SequenceDetails {
+    pub data_type: String,
+    // None until nextval() was first called, or without USAGE/SELECT on it
+    pub last_value: Option<i64>,
+    pub start_value: i64,
+    pub increment: i64,
+    pub min_value: i64,
+    pub max_value: i64,
+    pub cache_size: i64,
+    pub cycle: bool,
+    // table.column of a serial/identity column the sequence belongs to
+    pub owned_by: Option<String>,
+}
+
+// One overload of a function/procedure as described by pg_proc
+#[derive(Debug, Clone)]
+pub struct FunctionDetails {
+    pub arguments: String,
+    pub result: String,
+    pub language: String,
+    pub volatility: String,
+    pub kind: String,
+    pub definition: Option<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct QueryResult {
     pub columns: Vec<String>,
-    pub rows: Vec<Vec<String>>,
+    // Cells as text; None for NULL
+    pub rows: Vec<Vec<Option<String>>>,
+    // Each column's type as the server names it (int4, bytea, ...); empty
+    // for tables the app puts together
+    pub types: Vec<String>,
     pub row_count: usize,
+    // Set when every table column in the result comes from one table
+    pub source: Option<ResultSource>,
+}
+
+impl QueryResult {
+    /// A table the app put together itself, with no NULLs in it.
+    pub fn from_text(columns: Vec<String>, rows: Vec<Vec<String>>) -> Self {
+        QueryResult {
+            columns,
+            row_count: rows.len(),
+            rows: rows.into_iter().map(|row| row.into_iter().map(Some).collect()).collect(),
+            types: Vec::new(),
+            source: None,
+        }
+    }
+
+    pub fn column_type(&self, col: usize) -> Option<&str> {
+        self.types.get(col).map(String::as_str)
+    }
+}
+
+// Where a result's columns came from, so cells can be written back
+#[derive(Debug, Clone)]
+pub struct ResultSource {
+    pub schema: String,
+    pub table: String,
+    // Table column name behind each result column; None for expressions
+    pub column_names: Vec<Option<String>>,
+    // Result column indexes of the primary key; empty when the table has
+    // no primary key or the result does not include all of it
+    pub pk_columns: Vec<usize>,
+}
+
+impl ResultSource {
+    pub fn is_editable(&self) -> bool {
+        !self.pk_columns.is_empty()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -63,6 +412,13 @@ pub struct Index {
     pub columns: String,
     pub is_unique: bool,
     pub is_primary: bool,
+    // Backs a primary key, unique or exclusion constraint, or a foreign key
+    // references it, so DROP INDEX alone is refused
+    pub backs_constraint: bool,
+    // Since the statistics were last reset; None where none are collected
+    pub scans: Option<i64>,
+    pub tuples_read: Option<i64>,
+    pub size: String,
 }
 
 #[derive(Debug, Clone)]
@@ -73,6 +429,38 @@ pub struct Trigger {
     pub action_statement: String,
 }
 
+// Size and maintenance figures of a table; sizes as pg_size_pretty gives them
+#[derive(Debug, Clone, Default)]
+pub struct TableStats {
+    pub total_size: String,
+    pub table_size: String,
+    pub index_size: String,
+    pub toast_size: String,
+    // None when the statistics collector has nothing on the table
+    pub live_tuples: Option<i64>,
+    pub dead_tuples: Option<i64>,
+    pub last_vacuum: Option<String>,
+    pub last_autovacuum: Option<String>,
+    pub last_analyze: Option<String>,
+    pub last_autoanalyze: Option<String>,
+}
+
+// A table of an ER diagram with its primary key columns
+#[derive(Debug, Clone)]
+pub struct ErTable {
+    pub name: String,
+    pub primary_key: Vec<String>,
+}
+
+// A foreign key between two tables of the same schema
+#[derive(Debug, Clone)]
+pub struct ErLink {
+    pub table: String,
+    pub columns: String,
+    pub referenced_table: String,
+    pub referenced_columns: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct ForeignKey {
     pub name: String,
@@ -80,3 +468,98 @@ pub struct ForeignKey {
     pub referenced_table: String,
     pub referenced_columns: String,
 }
+
+// What the connected role and server can do; checked once on connect
+#[derive(Debug, Clone, Default)]
+pub struct Capabilities {
+    pub server_version: String,
+    pub can_read_information_schema: bool,
+    pub has_pg_stat_statements: bool,
+    pub is_superuser: bool,
+    pub is_replica: bool,
+    // Can see other sessions' queries in pg_stat_activity
+    pub can_read_all_stats: bool,
+}
+
+impl Capabilities {
+    /// Actionable notes about missing capabilities, shown to the user.
+    pub fn notes(&self) -> Vec<String> {
+        let mut notes = Vec::new();
+        if !self.can_read_information_schema {
+            notes.push(
+                "Cannot read information_schema: the browser will be empty. Ask an admin to GRANT USAGE on the schemas you need."
+                    .to_string(),
+            );
+        }
+        if !self.has_pg_stat_statements {
+            notes.push(
+                "pg_stat_statements is not installed: run CREATE EXTENSION pg_stat_statements; to enable query statistics."
+                    .to_string(),
+            );
+        }
+        if !self.can_read_all_stats {
+            notes.push(
+                "Limited pg_stat_activity visibility: other sessions' queries are hidden. GRANT pg_read_all_stats to see them."
+                    .to_string(),
+            );
+        }
+        if self.is_replica {
+            notes.push("Connected to a read-only replica: writes and DDL will fail.".to_string());
+        }
+        notes
+    }
+}
+
+// A server to run against: PGTUI_TEST_DB names the database, the rest
+// comes from the usual PG* variables. Without it the test is skipped.
+#[cfg(test)]
+pub(crate) async fn test_connection() -> Option<DbConnection> {
+    use crate::libpq::env_or;
+
+    let database = std::env::var("PGTUI_TEST_DB").ok()?;
+    let mut db = DbConnection::new();
+    let port = env_or("PGPORT", "5432").parse().unwrap();
+    db.connect(
+        &env_or("PGHOST", "localhost"),
+        port,
+        &database,
+        &env_or("PGUSER", "postgres"),
+        &env_or("PGPASSWORD", ""),
+        SessionSettings::default(),
+    )
+    .await
+    .unwrap();
+    Some(db)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_names_stay_bare() {
+        assert_eq!(quote_ident("users"), "users");
+        assert_eq!(quote_ident("_order_2"), "_order_2");
+    }
+
+    #[test]
+    fn reserved_words_are_quoted() {
+        assert_eq!(quote_ident("user"), "\"user\"");
+        assert_eq!(quote_ident("order"), "\"order\"");
+        assert_eq!(quote_ident("table"), "\"table\"");
+        // Unreserved keywords are fine bare
+        assert_eq!(quote_ident("name"), "name");
+    }
+
+    #[test]
+    fn other_names_are_quoted_with_quotes_doubled() {
+        assert_eq!(quote_ident("Users"), "\"Users\"");
+        assert_eq!(quote_ident("my table"), "\"my table\"");
+        assert_eq!(quote_ident("a\"b"), "\"a\"\"b\"");
+    }
+
+    #[test]
+    fn reserved_list_is_sorted() {
+        assert!(RESERVED.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
@@ -0,0 +1,126 @@
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use tokio_postgres::Client;
+
+// A DDL preview must not sit behind someone else's lock indefinitely
+const LOCK_TIMEOUT: &str = "5s";
+
+// Every user-visible schema object as (kind, qualified name) -> definition
+const SNAPSHOT_SQL: &str = "
+    SELECT 'schema'::text, nspname::text, ''::text
+    FROM pg_catalog.pg_namespace
+    WHERE nspname NOT LIKE 'pg\\_%' AND nspname <> 'information_schema'
+    UNION ALL
+    SELECT CASE c.relkind
+            WHEN 'r' THEN 'table' WHEN 'p' THEN 'table' WHEN 'v' THEN 'view'
+            WHEN 'm' THEN 'materialized view' WHEN 'i' THEN 'index' WHEN 'I' THEN 'index'
+            WHEN 'S' THEN 'sequence' WHEN 'f' THEN 'foreign table' ELSE 'relation' END,
+        n.nspname || '.' || c.relname,
+        CASE WHEN c.relkind IN ('i', 'I') THEN pg_catalog.pg_get_indexdef(c.oid) ELSE '' END
+    FROM pg_catalog.pg_class c
+    JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+    WHERE n.nspname NOT LIKE 'pg\\_%' AND n.nspname <> 'information_schema' AND c.relkind NOT IN ('t', 'c')
+    UNION ALL
+    SELECT 'column', table_schema || '.' || table_name || '.' || column_name,
+        data_type || CASE WHEN is_nullable = 'NO' THEN ' NOT NULL' ELSE '' END
+            || COALESCE(' DEFAULT ' || column_default, '')
+    FROM information_schema.columns
+    WHERE table_schema NOT LIKE 'pg\\_%' AND table_schema <> 'information_schema'
+    UNION ALL
+    SELECT 'constraint', n.nspname || '.' || c.relname || '.' || co.conname, pg_catalog.pg_get_constraintdef(co.oid)
+    FROM pg_catalog.pg_constraint co
+    JOIN pg_catalog.pg_class c ON c.oid = co.conrelid
+    JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+    WHERE n.nspname NOT LIKE 'pg\\_%' AND n.nspname <> 'information_schema'
+    UNION ALL
+    SELECT 'function', n.nspname || '.' || p.proname || '(' || pg_catalog.pg_get_function_identity_arguments(p.oid) || ')',
+        COALESCE(pg_catalog.pg_get_function_result(p.oid), '')
+    FROM pg_catalog.pg_proc p
+    JOIN pg_catalog.pg_namespace n ON n.oid = p.pronamespace
+    WHERE n.nspname NOT LIKE 'pg\\_%' AND n.nspname <> 'information_schema'";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+// One difference between the schema before and after a previewed statement
+#[derive(Debug, Clone)]
+pub struct SchemaChange {
+    pub change: ChangeKind,
+    pub object: String,
+    pub name: String,
+    // Definition of an added or removed object; "old → new" when changed
+    pub definition: String,
+}
+
+type Snapshot = BTreeMap<(String, String), String>;
+
+async fn snapshot(client: &Client) -> Result<Snapshot> {
+    let rows = client
+        .query(SNAPSHOT_SQL, &[])
+        .await
+        .context("Failed to read the schema")?;
+    Ok(rows
+        .iter()
+        .map(|row| ((row.get(0), row.get(1)), row.get(2)))
+        .collect())
+}
+
+/// Run `sql` inside a transaction that is always rolled back, and report
+/// how the schema looked different inside it. When the session already has
+/// a transaction open, a savepoint is used so it stays as it was.
+pub async fn preview_ddl(client: &Client, sql: &str) -> Result<Vec<SchemaChange>> {
+    // now() is the transaction's start time, so it only differs from the
+    // statement's own inside a transaction block
+    let in_transaction: bool = client
+        .query_one("SELECT now() <> pg_catalog.statement_timestamp()", &[])
+        .await
+        .context("Failed to check the transaction state")?
+        .get(0);
+    let (begin, rollback) = if in_transaction {
+        ("SAVEPOINT pgtui_preview", "ROLLBACK TO SAVEPOINT pgtui_preview; RELEASE SAVEPOINT pgtui_preview")
+    } else {
+        ("BEGIN", "ROLLBACK")
+    };
+
+    client.batch_execute(begin).await.context("Failed to start the preview transaction")?;
+    let changes = run_previewed(client, sql).await;
+    client
+        .batch_execute(rollback)
+        .await
+        .context("Failed to roll back the preview transaction")?;
+    changes
+}
+
+async fn run_previewed(client: &Client, sql: &str) -> Result<Vec<SchemaChange>> {
+    client
+        .batch_execute(&format!("SET LOCAL lock_timeout = '{}'", LOCK_TIMEOUT))
+        .await
+        .context("Failed to set lock_timeout")?;
+    let before = snapshot(client).await?;
+    client.batch_execute(sql).await.context("Statement failed")?;
+    let after = snapshot(client).await?;
+
+    let added_or_changed = after.iter().filter_map(|(key, definition)| match before.get(key) {
+        None => Some((ChangeKind::Added, key, definition.clone())),
+        Some(old) if old != definition => Some((ChangeKind::Changed, key, format!("{} → {}", old, definition))),
+        Some(_) => None,
+    });
+    let removed = before
+        .iter()
+        .filter(|(key, _)| !after.contains_key(*key))
+        .map(|(key, definition)| (ChangeKind::Removed, key, definition.clone()));
+    Ok(added_or_changed
+        .chain(removed)
+        .map(|(change, (object, name), definition)| SchemaChange {
+            change,
+            object: object.clone(),
+            name: name.clone(),
+            definition,
+        })
+        .collect())
+}
@@ -0,0 +1,62 @@
+use anyhow::{Context, Result};
+use tokio_postgres::Client;
+
+// Grants on a relation, on its schema, and the default privileges new
+// tables there will get, read from the ACLs themselves: unlike
+// information_schema.role_table_grants these list every role's grants,
+// not only the current role's. A NULL ACL means the owner's defaults.
+const PRIVILEGES_SQL: &str = "
+    WITH target AS (
+        SELECT c.relkind, coalesce(c.relacl, pg_catalog.acldefault('r', c.relowner)) AS acl,
+            n.oid AS nsp, coalesce(n.nspacl, pg_catalog.acldefault('n', n.nspowner)) AS nspacl
+        FROM pg_catalog.pg_class c
+        JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+        WHERE n.nspname = $1 AND c.relname = $2
+    ),
+    acls (ord, scope, acl) AS (
+        SELECT 1, CASE t.relkind WHEN 'v' THEN 'view' WHEN 'm' THEN 'materialized view' ELSE 'table' END, t.acl
+        FROM target t
+        UNION ALL
+        SELECT 2, 'schema', t.nspacl FROM target t
+        UNION ALL
+        SELECT 3, format('new tables of %s%s', pg_catalog.pg_get_userbyid(d.defaclrole),
+                CASE WHEN d.defaclnamespace = 0 THEN ' (all schemas)' ELSE '' END),
+            d.defaclacl
+        FROM target t
+        JOIN pg_catalog.pg_default_acl d ON d.defaclobjtype = 'r' AND d.defaclnamespace IN (t.nsp, 0)
+    )
+    SELECT g.scope,
+        CASE WHEN a.grantee = 0 THEN 'PUBLIC' ELSE pg_catalog.pg_get_userbyid(a.grantee) END,
+        string_agg(a.privilege_type || CASE WHEN a.is_grantable THEN '*' ELSE '' END, ', ' ORDER BY a.privilege_type),
+        pg_catalog.pg_get_userbyid(a.grantor)
+    FROM acls g, LATERAL pg_catalog.aclexplode(g.acl) a
+    GROUP BY g.ord, g.scope, a.grantee, a.grantor
+    ORDER BY g.ord, g.scope, 2, 4";
+
+// The privileges one grantor gave one grantee on the relation, its schema,
+// or tables created later
+#[derive(Debug, Clone)]
+pub struct Privilege {
+    // "table", "schema", "new tables of alice", ...
+    pub scope: String,
+    pub grantee: String,
+    // "INSERT*, SELECT", where * marks WITH GRANT OPTION
+    pub privileges: String,
+    pub grantor: String,
+}
+
+pub async fn object_privileges(client: &Client, schema: &str, name: &str) -> Result<Vec<Privilege>> {
+    let rows = client
+        .query(PRIVILEGES_SQL, &[&schema, &name])
+        .await
+        .context("Failed to load privileges")?;
+    Ok(rows
+        .iter()
+        .map(|row| Privilege {
+            scope: row.get(0),
+            grantee: row.get(1),
+            privileges: row.get(2),
+            grantor: row.get(3),
+        })
+        .collect())
+}
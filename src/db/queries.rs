@@ -1,7 +1,11 @@
+use std::pin::Pin;
+
 use anyhow::{Context, Result};
-use tokio_postgres::Client;
+use futures_util::StreamExt;
+use tokio_postgres::{Client, Row, RowStream, Statement};
 
-use super::{Column, Constraint, Database, ForeignKey, Function, Index, QueryResult, Schema, Table, Trigger, View};
+use super::values::{display_value, needs_text, TextParam};
+use super::{qualified_name, Activity, Capabilities, Column, CompletionCatalog, Constraint, Database, DatabaseDetails, ErLink, ErTable, ForeignKey, Function, FunctionDetails, Index, LockWait, MaterializedView, ObjectKind, QueryResult, ResultSource, Routine, Schema, SchemaObject, Sequence, SequenceDetails, Setting, Table, TableStats, Trigger, View};
 
 pub async fn list_databases(client: &Client) -> Result<Vec<Database>> {
     let rows = client
@@ -26,6 +30,93 @@ pub async fn list_databases(client: &Client) -> Result<Vec<Database>> {
     Ok(databases)
 }
 
+// Role names, without the predefined pg_* roles
+pub async fn list_roles(client: &Client) -> Result<Vec<String>> {
+    let rows = client
+        .query(
+            "SELECT rolname FROM pg_catalog.pg_roles WHERE rolname NOT LIKE 'pg\\_%' ORDER BY rolname",
+            &[],
+        )
+        .await
+        .context("Failed to list roles")?;
+
+    Ok(rows.iter().map(|row| row.get(0)).collect())
+}
+
+pub async fn get_database_details(client: &Client, database: &str) -> Result<Option<DatabaseDetails>> {
+    let Some(row) = client
+        .query_opt(
+            "SELECT d.datname, pg_catalog.pg_get_userbyid(d.datdba), pg_catalog.pg_encoding_to_char(d.encoding),
+                d.datcollate::text, d.datctype::text,
+                CASE WHEN pg_catalog.has_database_privilege(d.oid, 'CONNECT')
+                    THEN pg_catalog.pg_size_pretty(pg_catalog.pg_database_size(d.oid)) END,
+                d.datconnlimit
+             FROM pg_catalog.pg_database d
+             WHERE d.datname = $1",
+            &[&database],
+        )
+        .await
+        .context("Failed to describe database")?
+    else {
+        return Ok(None);
+    };
+
+    let settings = client
+        .query(
+            "SELECT COALESCE(r.rolname::text, 'all roles'), pg_catalog.unnest(s.setconfig)
+             FROM pg_catalog.pg_db_role_setting s
+             JOIN pg_catalog.pg_database d ON d.oid = s.setdatabase
+             LEFT JOIN pg_catalog.pg_roles r ON r.oid = s.setrole
+             WHERE d.datname = $1
+             ORDER BY 1, 2",
+            &[&database],
+        )
+        .await
+        .context("Failed to list database settings")?
+        .iter()
+        .map(|row| (row.get(0), row.get(1)))
+        .collect();
+
+    Ok(Some(DatabaseDetails {
+        name: row.get(0),
+        owner: row.get(1),
+        encoding: row.get(2),
+        collation: row.get(3),
+        ctype: row.get(4),
+        size: row.get(5),
+        connection_limit: row.get(6),
+        settings,
+    }))
+}
+
+/// Everything completion draws on. The columns of every table and view come
+/// from a single query rather than one per table.
+pub async fn load_completion_catalog(client: &Client) -> Result<CompletionCatalog> {
+    let tables = client
+        .query(
+            "SELECT table_name::text, array_agg(column_name::text ORDER BY ordinal_position)
+             FROM information_schema.columns
+             WHERE table_schema NOT IN ('pg_catalog', 'information_schema', 'pg_toast')
+             GROUP BY table_schema, table_name
+             ORDER BY table_schema, table_name",
+            &[],
+        )
+        .await
+        .context("Failed to load table columns")?
+        .iter()
+        .map(|row| (row.get(0), row.get(1)))
+        .collect();
+
+    Ok(CompletionCatalog {
+        tables,
+        roles: list_roles(client).await?,
+        schemas: list_schemas(client, "").await?.into_iter().map(|s| s.name).collect(),
+        databases: list_databases(client).await?.into_iter().map(|d| d.name).collect(),
+        settings: list_settings(client).await?,
+        functions: list_routines(client).await?,
+    })
+}
+
 pub async fn list_schemas(client: &Client, _database: &str) -> Result<Vec<Schema>> {
     let rows = client
         .query(
@@ -51,11 +142,14 @@ pub async fn list_schemas(client: &Client, _database: &str) -> Result<Vec<Schema
 pub async fn list_tables(client: &Client, schema: &str) -> Result<Vec<Table>> {
     let rows = client
         .query(
-            "SELECT table_schema, table_name 
-             FROM information_schema.tables 
-             WHERE table_schema = $1 
-             AND table_type = 'BASE TABLE'
-             ORDER BY table_name",
+            "SELECT t.table_name,
+                 CASE WHEN c.reltuples >= 0 THEN c.reltuples::bigint END
+             FROM information_schema.tables t
+             LEFT JOIN pg_catalog.pg_namespace n ON n.nspname = t.table_schema
+             LEFT JOIN pg_catalog.pg_class c ON c.relnamespace = n.oid AND c.relname = t.table_name
+             WHERE t.table_schema = $1
+             AND t.table_type = 'BASE TABLE'
+             ORDER BY t.table_name",
             &[&schema],
         )
         .await
@@ -64,9 +158,9 @@ pub async fn list_tables(client: &Client, schema: &str) -> Result<Vec<Table>> {
     let tables = rows
         .iter()
         .map(|row| Table {
-            schema: row.get(0),
-            name: row.get(1),
-            row_count: None,
+            name: row.get(0),
+            // The planner's estimate, not a count
+            row_count: row.get(1),
         })
         .collect();
 
@@ -76,7 +170,7 @@ pub async fn list_tables(client: &Client, schema: &str) -> Result<Vec<Table>> {
 pub async fn list_views(client: &Client, schema: &str) -> Result<Vec<View>> {
     let rows = client
         .query(
-            "SELECT table_schema, table_name 
+            "SELECT table_name 
              FROM information_schema.views 
              WHERE table_schema = $1
              ORDER BY table_name",
@@ -88,8 +182,7 @@ pub async fn list_views(client: &Client, schema: &str) -> Result<Vec<View>> {
     let views = rows
         .iter()
         .map(|row| View {
-            schema: row.get(0),
-            name: row.get(1),
+            name: row.get(0),
         })
         .collect();
 
@@ -99,7 +192,7 @@ pub async fn list_views(client: &Client, schema: &str) -> Result<Vec<View>> {
 pub async fn list_functions(client: &Client, schema: &str) -> Result<Vec<Function>> {
     let rows = client
         .query(
-            "SELECT routine_schema, routine_name, routine_type
+            "SELECT DISTINCT routine_name
              FROM information_schema.routines
              WHERE routine_schema = $1
              ORDER BY routine_name",
@@ -111,13 +204,212 @@ pub async fn list_functions(client: &Client, schema: &str) -> Result<Vec<Functio
     let functions = rows
         .iter()
         .map(|row| Function {
+            name: row.get(0),
+        })
+        .collect();
+
+    Ok(functions)
+}
+
+pub async fn list_materialized_views(client: &Client, schema: &str) -> Result<Vec<MaterializedView>> {
+    let rows = client
+        .query(
+            "SELECT matviewname
+             FROM pg_catalog.pg_matviews
+             WHERE schemaname = $1
+             ORDER BY matviewname",
+            &[&schema],
+        )
+        .await
+        .context("Failed to list materialized views")?;
+
+    let views = rows
+        .iter()
+        .map(|row| MaterializedView {
+            name: row.get(0),
+        })
+        .collect();
+
+    Ok(views)
+}
+
+pub async fn list_sequences(client: &Client, schema: &str) -> Result<Vec<Sequence>> {
+    let rows = client
+        .query(
+            "SELECT sequence_name
+             FROM information_schema.sequences
+             WHERE sequence_schema = $1
+             ORDER BY sequence_name",
+            &[&schema],
+        )
+        .await
+        .context("Failed to list sequences")?;
+
+    let sequences = rows
+        .iter()
+        .map(|row| Sequence {
+            name: row.get(0),
+        })
+        .collect();
+
+    Ok(sequences)
+}
+
+// How many tables, views, materialized views, functions and sequences the
+// listings above return for `schema`, in that order, in one round trip
+pub async fn folder_counts(client: &Client, schema: &str) -> Result<[i64; 5]> {
+    let row = client
+        .query_one(
+            "SELECT
+                 (SELECT count(*) FROM information_schema.tables
+                  WHERE table_schema = $1 AND table_type = 'BASE TABLE'),
+                 (SELECT count(*) FROM information_schema.views WHERE table_schema = $1),
+                 (SELECT count(*) FROM pg_catalog.pg_matviews WHERE schemaname = $1),
+                 (SELECT count(DISTINCT (routine_name, routine_type)) FROM information_schema.routines
+                  WHERE routine_schema = $1),
+                 (SELECT count(*) FROM information_schema.sequences WHERE sequence_schema = $1)",
+            &[&schema],
+        )
+        .await
+        .context("Failed to count schema objects")?;
+    Ok([row.get(0), row.get(1), row.get(2), row.get(3), row.get(4)])
+}
+
+pub async fn get_sequence_details(client: &Client, schema: &str, sequence: &str) -> Result<Option<SequenceDetails>> {
+    let row = client
+        .query_opt(
+            "SELECT s.data_type::text, s.last_value, s.start_value, s.increment_by,
+                s.min_value, s.max_value, s.cache_size, s.cycle,
+                (SELECT d.refobjid::regclass::text || '.' || a.attname
+                 FROM pg_catalog.pg_depend d
+                 JOIN pg_catalog.pg_attribute a ON a.attrelid = d.refobjid AND a.attnum = d.refobjsubid
+                 WHERE d.classid = 'pg_catalog.pg_class'::regclass
+                   AND d.objid = c.oid
+                   AND d.refclassid = 'pg_catalog.pg_class'::regclass
+                   AND d.deptype IN ('a', 'i')
+                 LIMIT 1)
+             FROM pg_catalog.pg_sequences s
+             JOIN pg_catalog.pg_namespace n ON n.nspname = s.schemaname
+             JOIN pg_catalog.pg_class c ON c.relnamespace = n.oid AND c.relname = s.sequencename
+             WHERE s.schemaname = $1 AND s.sequencename = $2",
+            &[&schema, &sequence],
+        )
+        .await
+        .context("Failed to describe sequence")?;
+
+    Ok(row.map(|row| SequenceDetails {
+        data_type: row.get(0),
+        last_value: row.get(1),
+        start_value: row.get(2),
+        increment: row.get(3),
+        min_value: row.get(4),
+        max_value: row.get(5),
+        cache_size: row.get(6),
+        cycle: row.get(7),
+        owned_by: row.get(8),
+    }))
+}
+
+/// Make the next nextval() of a sequence return `value`.
+pub async fn restart_sequence(client: &Client, schema: &str, sequence: &str, value: i64) -> Result<()> {
+    client
+        .batch_execute(&format!(
+            "ALTER SEQUENCE {} RESTART WITH {}",
+            qualified_name(schema, sequence),
+            value
+        ))
+        .await
+        .context("Failed to restart sequence")
+}
+
+// Every overload of the functions, aggregates and window functions a query
+// can call, built-in ones included, for autocompletion. Left out: type I/O
+// and handler functions, and the ones behind operators and aggregates.
+pub async fn list_routines(client: &Client) -> Result<Vec<Routine>> {
+    let rows = client
+        .query(
+            "SELECT n.nspname, p.proname,
+                CASE p.prokind WHEN 'a' THEN 'aggregate' WHEN 'w' THEN 'window' ELSE 'function' END,
+                pg_catalog.pg_get_function_arguments(p.oid),
+                pg_catalog.pg_get_function_result(p.oid)
+             FROM pg_catalog.pg_proc p
+             JOIN pg_catalog.pg_namespace n ON n.oid = p.pronamespace
+             WHERE p.prokind IN ('f', 'a', 'w')
+               AND n.nspname <> 'information_schema'
+               AND (n.nspname = 'pg_catalog' OR n.nspname NOT LIKE 'pg\\_%')
+               AND p.proname NOT LIKE '\\_%'
+               AND p.prorettype::regtype::text NOT IN ('internal', 'cstring', 'trigger', 'event_trigger',
+                   'language_handler', 'fdw_handler', 'index_am_handler', 'table_am_handler', 'tsm_handler')
+               AND NOT p.proargtypes::oid[] && ARRAY['internal'::regtype, 'cstring'::regtype]::oid[]
+               AND NOT EXISTS (SELECT 1 FROM pg_catalog.pg_operator o WHERE o.oprcode::oid = p.oid)
+               AND NOT EXISTS (SELECT 1 FROM pg_catalog.pg_aggregate a
+                               WHERE p.oid IN (a.aggtransfn::oid, a.aggfinalfn::oid))
+             ORDER BY 2, 1, p.pronargs",
+            &[],
+        )
+        .await
+        .context("Failed to list routines")?;
+
+    Ok(rows
+        .iter()
+        .map(|row| Routine {
             schema: row.get(0),
             name: row.get(1),
-            function_type: row.get(2),
+            kind: row.get(2),
+            arguments: row.get(3),
+            result: row.get(4),
+        })
+        .collect())
+}
+
+pub async fn list_settings(client: &Client) -> Result<Vec<Setting>> {
+    let rows = client
+        .query("SELECT name, vartype, enumvals FROM pg_catalog.pg_settings ORDER BY name", &[])
+        .await
+        .context("Failed to list settings")?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let vartype: String = row.get(1);
+            let values = match row.get::<_, Option<Vec<String>>>(2) {
+                Some(values) => values,
+                None if vartype == "bool" => vec!["on".to_string(), "off".to_string()],
+                None => Vec::new(),
+            };
+            Setting { name: row.get(0), values }
+        })
+        .collect())
+}
+
+// information_schema.columns leaves materialized views out, so their
+// columns come from pg_attribute
+pub async fn describe_materialized_view(client: &Client, schema: &str, view: &str) -> Result<Vec<Column>> {
+    let rows = client
+        .query(
+            "SELECT a.attname::text, pg_catalog.format_type(a.atttypid, a.atttypmod),
+                CASE WHEN a.attnotnull THEN 'NO' ELSE 'YES' END
+             FROM pg_catalog.pg_attribute a
+             JOIN pg_catalog.pg_class c ON c.oid = a.attrelid
+             JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+             WHERE n.nspname = $1 AND c.relname = $2 AND a.attnum > 0 AND NOT a.attisdropped
+             ORDER BY a.attnum",
+            &[&schema, &view],
+        )
+        .await
+        .context("Failed to describe materialized view")?;
+
+    let columns = rows
+        .iter()
+        .map(|row| Column {
+            name: row.get(0),
+            data_type: row.get(1),
+            is_nullable: row.get(2),
+            column_default: None,
         })
         .collect();
 
-    Ok(functions)
+    Ok(columns)
 }
 
 pub async fn describe_table(client: &Client, schema: &str, table: &str) -> Result<Vec<Column>> {
@@ -146,8 +438,10 @@ pub async fn describe_table(client: &Client, schema: &str, table: &str) -> Resul
 }
 
 pub async fn execute_query(client: &Client, sql: &str) -> Result<QueryResult> {
+    let statement = client.prepare(sql).await.context("Failed to execute query")?;
+    let run = display_statement(client, sql, &statement).await;
     let rows = client
-        .query(sql, &[])
+        .query(&run, &[])
         .await
         .context("Failed to execute query")?;
 
@@ -155,38 +449,270 @@ pub async fn execute_query(client: &Client, sql: &str) -> Result<QueryResult> {
         return Ok(QueryResult {
             columns: vec![],
             rows: vec![],
+            types: vec![],
             row_count: 0,
+            source: None,
         });
     }
 
-    let columns: Vec<String> = rows[0]
-        .columns()
-        .iter()
-        .map(|col| col.name().to_string())
-        .collect();
+    // Named and typed as written, whatever the columns were cast to
+    let columns: Vec<String> = statement.columns().iter().map(|col| col.name().to_string()).collect();
+    let types: Vec<String> = statement.columns().iter().map(|col| col.type_().name().to_string()).collect();
 
-    let data_rows: Vec<Vec<String>> = rows
-        .iter()
-        .map(|row| {
-            (0..row.len())
-                .map(|i| {
-                    row.try_get::<_, Option<String>>(i)
-                        .unwrap_or(None)
-                        .unwrap_or_else(|| "NULL".to_string())
-                })
-                .collect()
-        })
-        .collect();
+    let data_rows: Vec<Vec<Option<String>>> = rows.iter().map(row_strings).collect();
 
     let row_count = data_rows.len();
+    // Provenance is a nice-to-have; a failed lookup just makes the result read-only
+    let source = result_source(client, statement.columns()).await.unwrap_or(None);
 
     Ok(QueryResult {
         columns,
         rows: data_rows,
+        types,
         row_count,
+        source,
     })
 }
 
+/// Rows of a query that have not been read yet. They hold up the
+/// connection until read or dropped; dropping discards them.
+pub struct PendingRows {
+    stream: Pin<Box<RowStream>>,
+    // Column names as returned, to line fetched rows up with the grid
+    pub columns: Vec<String>,
+}
+
+impl PendingRows {
+    /// Up to `limit` more rows, and whether any may be left after them.
+    pub async fn fetch(&mut self, limit: usize) -> Result<(Vec<Vec<Option<String>>>, bool)> {
+        let rows = next_rows(&mut self.stream, limit).await?;
+        let more = rows.len() == limit;
+        Ok((rows.iter().map(row_strings).collect(), more))
+    }
+}
+
+/// Run a query reading only its first `limit` rows; the rest, if there may
+/// be more, are left for `PendingRows::fetch`. `params` are bound to $1, $2,
+/// ... as text, None for NULL. When every row was read, the row count of the
+/// command tag (rows inserted, updated, deleted or selected) comes back with
+/// the result.
+pub async fn start_query(
+    client: &Client,
+    sql: &str,
+    params: &[Option<String>],
+    limit: usize,
+) -> Result<(QueryResult, Option<PendingRows>, Option<u64>)> {
+    let statement = client.prepare(sql).await.context("Failed to execute query")?;
+    // Looked up before running: once rows are pending, nothing else can run
+    // on the connection. Provenance is a nice-to-have; a failed lookup just
+    // makes the result read-only.
+    let source = result_source(client, statement.columns()).await.unwrap_or(None);
+    let run = display_statement(client, sql, &statement).await;
+    let params = params.iter().map(|value| value.as_deref().map(TextParam));
+    let stream = client
+        .query_raw(&run, params)
+        .await
+        .context("Failed to execute query")?;
+    let mut stream = Box::pin(stream);
+    let rows = next_rows(&mut stream, limit).await?;
+
+    if rows.is_empty() {
+        return Ok((
+            QueryResult {
+                columns: vec![],
+                rows: vec![],
+                types: vec![],
+                row_count: 0,
+                source: None,
+            },
+            None,
+            stream.rows_affected(),
+        ));
+    }
+
+    let columns: Vec<String> = statement.columns().iter().map(|col| col.name().to_string()).collect();
+    let types: Vec<String> = statement.columns().iter().map(|col| col.type_().name().to_string()).collect();
+    let data_rows: Vec<Vec<Option<String>>> = rows.iter().map(row_strings).collect();
+    let affected = stream.rows_affected();
+    let pending = (rows.len() == limit).then(|| PendingRows {
+        stream,
+        columns: columns.clone(),
+    });
+
+    Ok((
+        QueryResult {
+            columns,
+            row_count: data_rows.len(),
+            rows: data_rows,
+            types,
+            source,
+        },
+        pending,
+        affected,
+    ))
+}
+
+/// The types of a statement's $1, $2, ... parameters, as the server infers
+/// them.
+pub async fn param_types(client: &Client, sql: &str) -> Result<Vec<String>> {
+    let statement = client.prepare(sql).await.context("Failed to prepare the statement")?;
+    Ok(statement.params().iter().map(|ty| ty.name().to_string()).collect())
+}
+
+// What to run for the prepared `statement` of `sql`: itself, or when some
+// of its columns are better printed by the server (see `needs_text`), the
+// same statement with those columns cast to text. tokio-postgres asks for
+// every column in binary, so the cast is how a column comes back as text.
+// Only statements sure to be valid inside a WITH query are wrapped, since
+// a failed prepare would abort an open transaction.
+async fn display_statement(client: &Client, sql: &str, statement: &Statement) -> Statement {
+    let columns = statement.columns();
+    if !columns.iter().any(|col| needs_text(col.type_())) || !wrappable(sql) {
+        return statement.clone();
+    }
+    let names: Vec<String> = (1..=columns.len()).map(|i| format!("\"{}\"", i)).collect();
+    let outputs: Vec<String> = columns
+        .iter()
+        .zip(&names)
+        .map(|(col, name)| if needs_text(col.type_()) { format!("{}::text", name) } else { name.clone() })
+        .collect();
+    let body = sql.trim_end_matches(|c: char| c == ';' || c.is_whitespace());
+    // The query on a line of its own, so a trailing comment ends before the parenthesis
+    let wrapped = format!(
+        "WITH q({}) AS (\n{}\n) SELECT {} FROM q",
+        names.join(", "),
+        body,
+        outputs.join(", ")
+    );
+    match client.prepare_typed(&wrapped, statement.params()).await {
+        Ok(wrapped) => wrapped,
+        Err(_) => statement.clone(),
+    }
+}
+
+// A query, or INSERT, UPDATE or DELETE with RETURNING, not under a WITH
+// that itself writes (those have to stay at the top level). Anything it is
+// unsure of, like a write word in a literal, is left unwrapped.
+fn wrappable(sql: &str) -> bool {
+    let mut rest = sql.trim_start();
+    loop {
+        if let Some(comment) = rest.strip_prefix("--") {
+            rest = comment.split_once('\n').map_or("", |(_, after)| after).trim_start();
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            rest = comment.split_once("*/").map_or("", |(_, after)| after).trim_start();
+        } else {
+            break;
+        }
+    }
+    if rest.starts_with('(') {
+        return true;
+    }
+    let words: Vec<String> = rest
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .filter(|w| !w.is_empty())
+        .map(str::to_ascii_uppercase)
+        .collect();
+    match words.first().map(String::as_str) {
+        Some("SELECT" | "VALUES" | "TABLE" | "INSERT" | "UPDATE" | "DELETE") => true,
+        Some("WITH") => !words
+            .iter()
+            .any(|w| matches!(w.as_str(), "INSERT" | "UPDATE" | "DELETE" | "MERGE")),
+        _ => false,
+    }
+}
+
+async fn next_rows(stream: &mut Pin<Box<RowStream>>, limit: usize) -> Result<Vec<Row>> {
+    let mut rows = Vec::new();
+    while rows.len() < limit {
+        match stream.next().await {
+            Some(row) => rows.push(row.context("Failed to fetch rows")?),
+            None => break,
+        }
+    }
+    Ok(rows)
+}
+
+// Cells as text, decoded from each column's type
+fn row_strings(row: &Row) -> Vec<Option<String>> {
+    (0..row.len()).map(|i| display_value(row, i)).collect()
+}
+
+/// Run a statement, discarding any rows it returns, and report how many rows
+/// it affected or returned.
+pub async fn execute_statement(client: &Client, sql: &str) -> Result<u64> {
+    client
+        .execute(sql, &[])
+        .await
+        .context("Failed to execute statement")
+}
+
+async fn result_source(
+    client: &Client,
+    columns: &[tokio_postgres::Column],
+) -> Result<Option<ResultSource>> {
+    let mut oids = columns.iter().filter_map(|col| col.table_oid()).filter(|&oid| oid != 0);
+    let Some(table_oid) = oids.next() else {
+        return Ok(None);
+    };
+    if oids.any(|oid| oid != table_oid) {
+        return Ok(None);
+    }
+
+    let Some(table_row) = client
+        .query_opt(
+            "SELECT n.nspname, c.relname
+             FROM pg_class c
+             JOIN pg_namespace n ON n.oid = c.relnamespace
+             WHERE c.oid = $1 AND c.relkind IN ('r', 'p')",
+            &[&table_oid],
+        )
+        .await
+        .context("Failed to look up result table")?
+    else {
+        return Ok(None);
+    };
+
+    let attributes = client
+        .query(
+            "SELECT a.attnum, a.attname, coalesce(a.attnum = ANY(i.indkey), false) AS is_pk
+             FROM pg_attribute a
+             LEFT JOIN pg_index i ON i.indrelid = a.attrelid AND i.indisprimary
+             WHERE a.attrelid = $1 AND a.attnum > 0 AND NOT a.attisdropped",
+            &[&table_oid],
+        )
+        .await
+        .context("Failed to look up result columns")?;
+
+    let column_of = |col: &tokio_postgres::Column| {
+        if col.table_oid() != Some(table_oid) {
+            return None;
+        }
+        attributes
+            .iter()
+            .find(|attr| Some(attr.get::<_, i16>(0)) == col.column_id())
+    };
+
+    let column_names = columns
+        .iter()
+        .map(|col| column_of(col).map(|attr| attr.get::<_, String>(1)))
+        .collect();
+
+    let pk_total = attributes.iter().filter(|attr| attr.get::<_, bool>(2)).count();
+    let pk_columns: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .filter(|(_, col)| column_of(col).is_some_and(|attr| attr.get::<_, bool>(2)))
+        .map(|(i, _)| i)
+        .collect();
+
+    Ok(Some(ResultSource {
+        schema: table_row.get(0),
+        table: table_row.get(1),
+        column_names,
+        pk_columns: if pk_columns.len() == pk_total { pk_columns } else { Vec::new() },
+    }))
+}
+
 pub async fn list_table_constraints(client: &Client, schema: &str, table: &str) -> Result<Vec<Constraint>> {
     let rows = client
         .query(
@@ -221,21 +747,24 @@ pub async fn list_table_constraints(client: &Client, schema: &str, table: &str)
 pub async fn list_table_indexes(client: &Client, schema: &str, table: &str) -> Result<Vec<Index>> {
     let rows = client
         .query(
-            "SELECT 
-                i.indexname as name,
-                string_agg(a.attname, ', ' ORDER BY array_position(ix.indkey, a.attnum)) as columns,
-                ix.indisunique as is_unique,
-                ix.indisprimary as is_primary
-             FROM pg_indexes i
-             JOIN pg_class c ON c.relname = i.indexname
-             JOIN pg_index ix ON ix.indexrelid = c.oid
-             JOIN pg_class t ON t.oid = ix.indrelid
-             JOIN pg_attribute a ON a.attrelid = t.oid
-             WHERE i.schemaname = $1 
-                AND i.tablename = $2
-                AND a.attnum = ANY(ix.indkey)
-             GROUP BY i.indexname, ix.indisunique, ix.indisprimary
-             ORDER BY i.indexname",
+            "SELECT
+                i.relname,
+                string_agg(a.attname, ', ' ORDER BY array_position(ix.indkey, a.attnum)),
+                ix.indisunique,
+                ix.indisprimary,
+                EXISTS (SELECT 1 FROM pg_catalog.pg_constraint con WHERE con.conindid = i.oid),
+                s.idx_scan,
+                s.idx_tup_read,
+                pg_catalog.pg_size_pretty(pg_catalog.pg_relation_size(i.oid))
+             FROM pg_catalog.pg_index ix
+             JOIN pg_catalog.pg_class i ON i.oid = ix.indexrelid
+             JOIN pg_catalog.pg_class t ON t.oid = ix.indrelid
+             JOIN pg_catalog.pg_namespace n ON n.oid = t.relnamespace
+             LEFT JOIN pg_catalog.pg_attribute a ON a.attrelid = t.oid AND a.attnum = ANY(ix.indkey)
+             LEFT JOIN pg_catalog.pg_stat_user_indexes s ON s.indexrelid = i.oid
+             WHERE n.nspname = $1 AND t.relname = $2
+             GROUP BY i.oid, i.relname, ix.indisunique, ix.indisprimary, s.idx_scan, s.idx_tup_read
+             ORDER BY i.relname",
             &[&schema, &table],
         )
         .await
@@ -248,6 +777,10 @@ pub async fn list_table_indexes(client: &Client, schema: &str, table: &str) -> R
             columns: row.get::<_, Option<String>>(1).unwrap_or_else(|| "-".to_string()),
             is_unique: row.get(2),
             is_primary: row.get(3),
+            backs_constraint: row.get(4),
+            scans: row.get(5),
+            tuples_read: row.get(6),
+            size: row.get(7),
         })
         .collect();
 
@@ -321,3 +854,638 @@ pub async fn list_table_foreign_keys(client: &Client, schema: &str, table: &str)
 
     Ok(foreign_keys)
 }
+
+/// Tables of `schema` with their primary keys, and the foreign keys between
+/// them, for the relationship diagram.
+pub async fn schema_relationships(client: &Client, schema: &str) -> Result<(Vec<ErTable>, Vec<ErLink>)> {
+    let tables = client
+        .query(
+            "SELECT c.relname::text,
+                COALESCE((SELECT array_agg(a.attname::text ORDER BY k.ord)
+                          FROM pg_catalog.pg_constraint co,
+                               unnest(co.conkey) WITH ORDINALITY k(attnum, ord)
+                          JOIN pg_catalog.pg_attribute a ON a.attnum = k.attnum
+                          WHERE co.conrelid = c.oid AND co.contype = 'p' AND a.attrelid = c.oid), '{}')
+             FROM pg_catalog.pg_class c
+             JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+             WHERE n.nspname = $1 AND c.relkind IN ('r', 'p') AND NOT c.relispartition
+             ORDER BY c.relname",
+            &[&schema],
+        )
+        .await
+        .context("Failed to list tables")?
+        .iter()
+        .map(|row| ErTable {
+            name: row.get(0),
+            primary_key: row.get(1),
+        })
+        .collect();
+
+    let links = client
+        .query(
+            "SELECT cl.relname::text,
+                (SELECT string_agg(a.attname, ', ' ORDER BY k.ord)
+                 FROM unnest(co.conkey) WITH ORDINALITY k(attnum, ord)
+                 JOIN pg_catalog.pg_attribute a ON a.attrelid = co.conrelid AND a.attnum = k.attnum),
+                rl.relname::text,
+                (SELECT string_agg(a.attname, ', ' ORDER BY k.ord)
+                 FROM unnest(co.confkey) WITH ORDINALITY k(attnum, ord)
+                 JOIN pg_catalog.pg_attribute a ON a.attrelid = co.confrelid AND a.attnum = k.attnum)
+             FROM pg_catalog.pg_constraint co
+             JOIN pg_catalog.pg_class cl ON cl.oid = co.conrelid
+             JOIN pg_catalog.pg_namespace n ON n.oid = cl.relnamespace
+             JOIN pg_catalog.pg_class rl ON rl.oid = co.confrelid
+             JOIN pg_catalog.pg_namespace rn ON rn.oid = rl.relnamespace
+             WHERE co.contype = 'f' AND n.nspname = $1 AND rn.nspname = $1 AND NOT cl.relispartition
+             ORDER BY 1, co.conname",
+            &[&schema],
+        )
+        .await
+        .context("Failed to list foreign keys")?
+        .iter()
+        .map(|row| ErLink {
+            table: row.get(0),
+            columns: row.get(1),
+            referenced_table: row.get(2),
+            referenced_columns: row.get(3),
+        })
+        .collect();
+
+    Ok((tables, links))
+}
+
+// Size columns shared by the Stats tab and the largest-tables listing;
+// the main fork, TOAST and indexes add up to the total with the small
+// free space and visibility maps
+const SIZE_COLUMNS: &str = "
+    pg_catalog.pg_size_pretty(pg_catalog.pg_total_relation_size(c.oid)),
+    pg_catalog.pg_size_pretty(pg_catalog.pg_relation_size(c.oid)),
+    pg_catalog.pg_size_pretty(pg_catalog.pg_indexes_size(c.oid)),
+    pg_catalog.pg_size_pretty(COALESCE(pg_catalog.pg_total_relation_size(NULLIF(c.reltoastrelid, 0)), 0))";
+
+pub async fn get_table_stats(client: &Client, schema: &str, table: &str) -> Result<Option<TableStats>> {
+    let sql = format!(
+        "SELECT {},
+            s.n_live_tup, s.n_dead_tup,
+            to_char(s.last_vacuum, 'YYYY-MM-DD HH24:MI:SS'),
+            to_char(s.last_autovacuum, 'YYYY-MM-DD HH24:MI:SS'),
+            to_char(s.last_analyze, 'YYYY-MM-DD HH24:MI:SS'),
+            to_char(s.last_autoanalyze, 'YYYY-MM-DD HH24:MI:SS')
+         FROM pg_catalog.pg_class c
+         JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+         LEFT JOIN pg_catalog.pg_stat_user_tables s ON s.relid = c.oid
+         WHERE n.nspname = $1 AND c.relname = $2",
+        SIZE_COLUMNS
+    );
+    let row = client
+        .query_opt(&sql, &[&schema, &table])
+        .await
+        .context("Failed to load table statistics")?;
+
+    Ok(row.map(|row| TableStats {
+        total_size: row.get(0),
+        table_size: row.get(1),
+        index_size: row.get(2),
+        toast_size: row.get(3),
+        live_tuples: row.get(4),
+        dead_tuples: row.get(5),
+        last_vacuum: row.get(6),
+        last_autovacuum: row.get(7),
+        last_analyze: row.get(8),
+        last_autoanalyze: row.get(9),
+    }))
+}
+
+/// The largest tables of `schema` by total size, as a result grid.
+pub async fn largest_tables(client: &Client, schema: &str, limit: i64) -> Result<QueryResult> {
+    let sql = format!(
+        "SELECT c.relname, {}, COALESCE(s.n_live_tup, 0), COALESCE(s.n_dead_tup, 0)
+         FROM pg_catalog.pg_class c
+         JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+         LEFT JOIN pg_catalog.pg_stat_user_tables s ON s.relid = c.oid
+         WHERE n.nspname = $1 AND c.relkind IN ('r', 'p', 'm')
+         ORDER BY pg_catalog.pg_total_relation_size(c.oid) DESC
+         LIMIT $2",
+        SIZE_COLUMNS
+    );
+    let rows = client
+        .query(&sql, &[&schema, &limit])
+        .await
+        .context("Failed to list table sizes")?;
+
+    let rows: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            vec![
+                row.get(0),
+                row.get(1),
+                row.get(2),
+                row.get(3),
+                row.get(4),
+                row.get::<_, i64>(5).to_string(),
+                row.get::<_, i64>(6).to_string(),
+            ]
+        })
+        .collect();
+    let columns = ["Table", "Total", "Table data", "Indexes", "TOAST", "Live rows", "Dead rows"]
+        .iter()
+        .map(|c| c.to_string())
+        .collect();
+    Ok(QueryResult::from_text(columns, rows))
+}
+
+pub async fn get_table_ddl(client: &Client, schema: &str, table: &str) -> Result<String> {
+    table_ddl(client, schema, table, true).await
+}
+
+// CREATE TABLE with its constraints and indexes; without `foreign_keys`
+// those are left for the caller to add once the tables they point at exist
+pub(super) async fn table_ddl(client: &Client, schema: &str, table: &str, foreign_keys: bool) -> Result<String> {
+    let columns = client
+        .query(
+            "SELECT 
+                quote_ident(a.attname),
+                pg_catalog.format_type(a.atttypid, a.atttypmod),
+                a.attnotnull,
+                pg_catalog.pg_get_expr(d.adbin, d.adrelid),
+                a.attidentity::text,
+                a.attgenerated::text,
+                s.seqstart, s.seqincrement, s.seqmin, s.seqmax, s.seqcache, s.seqcycle
+             FROM pg_catalog.pg_attribute a
+             JOIN pg_catalog.pg_class c ON c.oid = a.attrelid
+             JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+             LEFT JOIN pg_catalog.pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum
+             LEFT JOIN pg_catalog.pg_sequence s ON a.attidentity <> ''
+                AND s.seqrelid = pg_catalog.pg_get_serial_sequence(
+                    pg_catalog.quote_ident(n.nspname) || '.' || pg_catalog.quote_ident(c.relname), a.attname
+                )::regclass
+             WHERE n.nspname = $1 AND c.relname = $2
+                AND a.attnum > 0 AND NOT a.attisdropped
+             ORDER BY a.attnum",
+            &[&schema, &table],
+        )
+        .await
+        .context("Failed to load table columns for DDL")?;
+
+    let constraints = client
+        .query(
+            "SELECT quote_ident(con.conname), pg_catalog.pg_get_constraintdef(con.oid, true)
+             FROM pg_catalog.pg_constraint con
+             JOIN pg_catalog.pg_class c ON c.oid = con.conrelid
+             JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+             WHERE n.nspname = $1 AND c.relname = $2 AND ($3 OR con.contype <> 'f')
+             ORDER BY CASE con.contype WHEN 'p' THEN 0 WHEN 'u' THEN 1 WHEN 'f' THEN 2 ELSE 3 END,
+                con.conname",
+            &[&schema, &table, &foreign_keys],
+        )
+        .await
+        .context("Failed to load table constraints for DDL")?;
+
+    // Indexes backing constraints are already covered by the constraint clauses
+    let indexes = client
+        .query(
+            "SELECT pg_catalog.pg_get_indexdef(ix.indexrelid)
+             FROM pg_catalog.pg_index ix
+             JOIN pg_catalog.pg_class c ON c.oid = ix.indrelid
+             JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+             JOIN pg_catalog.pg_class i ON i.oid = ix.indexrelid
+             WHERE n.nspname = $1 AND c.relname = $2
+                AND NOT EXISTS (
+                    SELECT 1 FROM pg_catalog.pg_constraint con WHERE con.conindid = ix.indexrelid
+                )
+             ORDER BY i.relname",
+            &[&schema, &table],
+        )
+        .await
+        .context("Failed to load table indexes for DDL")?;
+
+    let mut definitions: Vec<String> = columns
+        .iter()
+        .map(|row| {
+            let name: String = row.get(0);
+            let data_type: String = row.get(1);
+            let not_null: bool = row.get(2);
+            let default: Option<String> = row.get(3);
+            let identity: Option<String> = row.get(4);
+            let generated: Option<String> = row.get(5);
+
+            let mut line = format!("{} {}", name, data_type);
+            // The default of a generated column is its expression
+            match (identity.as_deref(), generated.as_deref(), default) {
+                (Some("a"), _, _) => {
+                    line.push_str(" GENERATED ALWAYS AS IDENTITY");
+                    line.push_str(&identity_options(row, &data_type));
+                }
+                (Some("d"), _, _) => {
+                    line.push_str(" GENERATED BY DEFAULT AS IDENTITY");
+                    line.push_str(&identity_options(row, &data_type));
+                }
+                (_, Some("s"), Some(expression)) => {
+                    line.push_str(&format!(" GENERATED ALWAYS AS ({}) STORED", expression))
+                }
+                (_, Some("v"), Some(expression)) => {
+                    line.push_str(&format!(" GENERATED ALWAYS AS ({}) VIRTUAL", expression))
+                }
+                (_, _, Some(default)) => line.push_str(&format!(" DEFAULT {}", default)),
+                _ => {}
+            }
+            if not_null {
+                line.push_str(" NOT NULL");
+            }
+            line
+        })
+        .collect();
+
+    definitions.extend(constraints.iter().map(|row| {
+        let name: String = row.get(0);
+        let definition: String = row.get(1);
+        format!("CONSTRAINT {} {}", name, definition)
+    }));
+
+    let mut ddl = format!(
+        "CREATE TABLE {} (\n    {}\n);\n",
+        qualified_name(schema, table),
+        definitions.join(",\n    ")
+    );
+    for row in &indexes {
+        let definition: String = row.get(0);
+        ddl.push_str(&format!("\n{};\n", definition));
+    }
+
+    Ok(ddl)
+}
+
+// " (START WITH 100 INCREMENT BY 10)": the options of an identity column's
+// sequence that differ from what GENERATED ... AS IDENTITY would pick
+fn identity_options(row: &Row, data_type: &str) -> String {
+    let Some(start) = row.get::<_, Option<i64>>(6) else {
+        return String::new();
+    };
+    let (increment, min, max, cache): (i64, i64, i64, i64) = (row.get(7), row.get(8), row.get(9), row.get(10));
+    let cycle: bool = row.get(11);
+    let (type_min, type_max) = match data_type {
+        "smallint" => (i16::MIN.into(), i16::MAX.into()),
+        "integer" => (i32::MIN.into(), i32::MAX.into()),
+        _ => (i64::MIN, i64::MAX),
+    };
+    let (default_min, default_max) = if increment > 0 { (1, type_max) } else { (type_min, -1) };
+    let default_start = if increment > 0 { min } else { max };
+
+    let mut options = Vec::new();
+    if start != default_start {
+        options.push(format!("START WITH {}", start));
+    }
+    if increment != 1 {
+        options.push(format!("INCREMENT BY {}", increment));
+    }
+    if min != default_min {
+        options.push(format!("MINVALUE {}", min));
+    }
+    if max != default_max {
+        options.push(format!("MAXVALUE {}", max));
+    }
+    if cache != 1 {
+        options.push(format!("CACHE {}", cache));
+    }
+    if cycle {
+        options.push("CYCLE".to_string());
+    }
+    if options.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", options.join(" "))
+    }
+}
+
+// Each check degrades to `false` on error so a restricted role can still connect
+pub async fn check_capabilities(client: &Client) -> Capabilities {
+    let server_version = client
+        .query_one("SHOW server_version", &[])
+        .await
+        .map(|row| row.get::<_, String>(0))
+        .unwrap_or_else(|_| "unknown".to_string());
+
+    let can_read_information_schema = client
+        .query("SELECT 1 FROM information_schema.schemata LIMIT 1", &[])
+        .await
+        .map(|rows| !rows.is_empty())
+        .unwrap_or(false);
+
+    let has_pg_stat_statements = client
+        .query_one(
+            "SELECT EXISTS (SELECT 1 FROM pg_catalog.pg_extension WHERE extname = 'pg_stat_statements')",
+            &[],
+        )
+        .await
+        .map(|row| row.get::<_, bool>(0))
+        .unwrap_or(false);
+
+    let is_superuser = client
+        .query_one("SELECT current_setting('is_superuser') = 'on'", &[])
+        .await
+        .map(|row| row.get::<_, bool>(0))
+        .unwrap_or(false);
+
+    let is_replica = client
+        .query_one("SELECT pg_catalog.pg_is_in_recovery()", &[])
+        .await
+        .map(|row| row.get::<_, bool>(0))
+        .unwrap_or(false);
+
+    let can_read_all_stats = is_superuser
+        || client
+            .query_one(
+                "SELECT pg_catalog.pg_has_role(current_user, 'pg_read_all_stats', 'MEMBER')",
+                &[],
+            )
+            .await
+            .map(|row| row.get::<_, bool>(0))
+            .unwrap_or(false);
+
+    Capabilities {
+        server_version,
+        can_read_information_schema,
+        has_pg_stat_statements,
+        is_superuser,
+        is_replica,
+        can_read_all_stats,
+    }
+}
+
+pub async fn get_view_definition(client: &Client, schema: &str, view: &str) -> Result<String> {
+    let row = client
+        .query_one(
+            "SELECT pg_catalog.pg_get_viewdef(c.oid, true)
+             FROM pg_catalog.pg_class c
+             JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+             WHERE n.nspname = $1 AND c.relname = $2",
+            &[&schema, &view],
+        )
+        .await
+        .context("Failed to load view definition")?;
+
+    let definition: String = row.get(0);
+    Ok(format!(
+        "CREATE OR REPLACE VIEW {} AS\n{}",
+        qualified_name(schema, view),
+        definition.trim_end()
+    ))
+}
+
+pub async fn get_materialized_view_definition(client: &Client, schema: &str, view: &str) -> Result<String> {
+    let row = client
+        .query_one(
+            "SELECT definition FROM pg_catalog.pg_matviews WHERE schemaname = $1 AND matviewname = $2",
+            &[&schema, &view],
+        )
+        .await
+        .context("Failed to load materialized view definition")?;
+
+    let definition: String = row.get(0);
+    Ok(format!(
+        "CREATE MATERIALIZED VIEW {} AS\n{}",
+        qualified_name(schema, view),
+        definition.trim_end()
+    ))
+}
+
+pub fn refresh_materialized_view_sql(schema: &str, view: &str, concurrently: bool) -> String {
+    format!(
+        "REFRESH MATERIALIZED VIEW {}{}",
+        if concurrently { "CONCURRENTLY " } else { "" },
+        qualified_name(schema, view)
+    )
+}
+
+pub async fn get_function_details(client: &Client, schema: &str, function: &str) -> Result<Vec<FunctionDetails>> {
+    let rows = client
+        .query(
+            "SELECT 
+                pg_catalog.pg_get_function_identity_arguments(p.oid),
+                COALESCE(pg_catalog.pg_get_function_result(p.oid), ''),
+                l.lanname,
+                CASE p.provolatile WHEN 'i' THEN 'IMMUTABLE' WHEN 's' THEN 'STABLE' ELSE 'VOLATILE' END,
+                CASE p.prokind WHEN 'p' THEN 'procedure' WHEN 'a' THEN 'aggregate' WHEN 'w' THEN 'window' ELSE 'function' END,
+                CASE WHEN p.prokind IN ('f', 'p') THEN pg_catalog.pg_get_functiondef(p.oid) END
+             FROM pg_catalog.pg_proc p
+             JOIN pg_catalog.pg_namespace n ON n.oid = p.pronamespace
+             JOIN pg_catalog.pg_language l ON l.oid = p.prolang
+             WHERE n.nspname = $1 AND p.proname = $2
+             ORDER BY pg_catalog.pg_get_function_identity_arguments(p.oid)",
+            &[&schema, &function],
+        )
+        .await
+        .context("Failed to load function details")?;
+
+    let details = rows
+        .iter()
+        .map(|row| FunctionDetails {
+            arguments: row.get(0),
+            result: row.get(1),
+            language: row.get(2),
+            volatility: row.get(3),
+            kind: row.get(4),
+            definition: row.get(5),
+        })
+        .collect();
+
+    Ok(details)
+}
+
+/// Client backends of the server other than this connection's own, longest
+/// running first.
+pub async fn list_activity(client: &Client) -> Result<Vec<Activity>> {
+    let rows = client
+        .query(
+            "SELECT pid,
+                COALESCE(usename::text, ''),
+                COALESCE(datname::text, ''),
+                state,
+                COALESCE(wait_event_type || ': ' || wait_event, ''),
+                COALESCE(query, ''),
+                EXTRACT(EPOCH FROM now() - query_start)::float8
+             FROM pg_catalog.pg_stat_activity
+             WHERE state IS NOT NULL AND pid <> pg_catalog.pg_backend_pid()
+             ORDER BY query_start NULLS LAST",
+            &[],
+        )
+        .await
+        .context("Failed to read pg_stat_activity")?;
+
+    Ok(rows
+        .iter()
+        .map(|row| Activity {
+            pid: row.get(0),
+            user: row.get(1),
+            database: row.get(2),
+            state: row.get(3),
+            wait_event: row.get(4),
+            query: row.get(5),
+            duration: row.get(6),
+        })
+        .collect())
+}
+
+/// Backends waiting for an ungranted lock, together with the backends
+/// blocking them.
+pub async fn list_lock_waits(client: &Client) -> Result<Vec<LockWait>> {
+    let rows = client
+        .query(
+            "WITH waiting AS (
+                SELECT DISTINCT ON (pid) pid,
+                    mode || ' on ' || COALESCE(relation::regclass::text, locktype) AS waiting_for
+                FROM pg_catalog.pg_locks
+                WHERE NOT granted
+                ORDER BY pid
+             ),
+             involved AS (
+                SELECT pid FROM waiting
+                UNION
+                SELECT unnest(pg_catalog.pg_blocking_pids(pid)) FROM waiting
+             )
+             SELECT a.pid,
+                COALESCE(a.usename::text, ''),
+                COALESCE(a.state, ''),
+                COALESCE(a.query, ''),
+                EXTRACT(EPOCH FROM now() - a.query_start)::float8,
+                pg_catalog.pg_blocking_pids(a.pid),
+                COALESCE(w.waiting_for, '')
+             FROM involved i
+             JOIN pg_catalog.pg_stat_activity a ON a.pid = i.pid
+             LEFT JOIN waiting w ON w.pid = a.pid
+             ORDER BY a.query_start NULLS LAST",
+            &[],
+        )
+        .await
+        .context("Failed to read pg_locks")?;
+
+    Ok(rows
+        .iter()
+        .map(|row| LockWait {
+            pid: row.get(0),
+            user: row.get(1),
+            state: row.get(2),
+            query: row.get(3),
+            duration: row.get(4),
+            blocked_by: row.get(5),
+            waiting_for: row.get(6),
+        })
+        .collect())
+}
+
+/// Cancel the running query of a backend, or with `terminate` end its
+/// session; false when the server found no such backend.
+pub async fn signal_backend(client: &Client, pid: i32, terminate: bool) -> Result<bool> {
+    let sql = if terminate {
+        "SELECT pg_catalog.pg_terminate_backend($1)"
+    } else {
+        "SELECT pg_catalog.pg_cancel_backend($1)"
+    };
+    let row = client
+        .query_one(sql, &[&pid])
+        .await
+        .with_context(|| format!("Failed to signal backend {}", pid))?;
+    Ok(row.get(0))
+}
+
+/// Every table, view, materialized view, column, index and function outside
+/// the system schemas, in one pass over the catalog, for the schema search.
+pub async fn search_objects(client: &Client) -> Result<Vec<SchemaObject>> {
+    let rows = client
+        .query(
+            "WITH rel AS (
+                SELECT c.oid, n.nspname, c.relname, c.relkind
+                FROM pg_catalog.pg_class c
+                JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+                WHERE c.relkind IN ('r', 'p', 'v', 'm')
+                  AND n.nspname <> 'information_schema' AND n.nspname NOT LIKE 'pg\\_%'
+             )
+             SELECT relkind::text, nspname, relname, NULL::text, NULL::text, ''
+             FROM rel
+             UNION ALL
+             SELECT 'c', r.nspname, a.attname, r.relkind::text, r.relname,
+                pg_catalog.format_type(a.atttypid, a.atttypmod)
+             FROM rel r
+             JOIN pg_catalog.pg_attribute a ON a.attrelid = r.oid
+             WHERE a.attnum > 0 AND NOT a.attisdropped
+             UNION ALL
+             SELECT 'i', r.nspname, i.relname, r.relkind::text, r.relname, am.amname::text
+             FROM rel r
+             JOIN pg_catalog.pg_index x ON x.indrelid = r.oid
+             JOIN pg_catalog.pg_class i ON i.oid = x.indexrelid
+             JOIN pg_catalog.pg_am am ON am.oid = i.relam
+             UNION ALL
+             SELECT DISTINCT ON (n.nspname, p.proname) 'f', n.nspname, p.proname, NULL, NULL,
+                pg_catalog.pg_get_function_identity_arguments(p.oid)
+             FROM pg_catalog.pg_proc p
+             JOIN pg_catalog.pg_namespace n ON n.oid = p.pronamespace
+             WHERE n.nspname <> 'information_schema' AND n.nspname NOT LIKE 'pg\\_%'",
+            &[],
+        )
+        .await
+        .context("Failed to search the catalog")?;
+
+    // r/p/v/m as pg_class has them
+    let relation = |relkind: &str| match relkind {
+        "v" => ObjectKind::View,
+        "m" => ObjectKind::MaterializedView,
+        _ => ObjectKind::Table,
+    };
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let code: String = row.get(0);
+            let parent_kind: Option<String> = row.get(3);
+            let parent: Option<String> = row.get(4);
+            SchemaObject {
+                kind: match code.as_str() {
+                    "c" => ObjectKind::Column,
+                    "i" => ObjectKind::Index,
+                    "f" => ObjectKind::Function,
+                    relkind => relation(relkind),
+                },
+                schema: row.get(1),
+                name: row.get(2),
+                parent: parent_kind.zip(parent).map(|(kind, name)| (relation(&kind), name)),
+                detail: row.get(5),
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_connection;
+
+    #[tokio::test]
+    async fn table_ddl_keeps_generated_and_identity_columns() {
+        let Some(db) = test_connection().await else {
+            return;
+        };
+        let client = db.client().unwrap();
+        client
+            .batch_execute(
+                "BEGIN;
+                 CREATE SCHEMA ddl_test;
+                 CREATE TABLE ddl_test.t (
+                     id integer GENERATED ALWAYS AS IDENTITY,
+                     n bigint GENERATED BY DEFAULT AS IDENTITY (START WITH 100 INCREMENT BY 10 CACHE 5),
+                     price numeric DEFAULT 0,
+                     total numeric GENERATED ALWAYS AS (price * 2) STORED
+                 )",
+            )
+            .await
+            .unwrap();
+        let ddl = table_ddl(client, "ddl_test", "t", true).await;
+        client.batch_execute("ROLLBACK").await.unwrap();
+        assert_eq!(
+            ddl.unwrap(),
+            "CREATE TABLE ddl_test.t (\n    \
+             id integer GENERATED ALWAYS AS IDENTITY NOT NULL,\n    \
+             n bigint GENERATED BY DEFAULT AS IDENTITY (START WITH 100 INCREMENT BY 10 CACHE 5) NOT NULL,\n    \
+             price numeric DEFAULT 0,\n    \
+             total numeric GENERATED ALWAYS AS ((price * (2)::numeric)) STORED\n);\n"
+        );
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{Context, Result};
+use tokio_postgres::Client;
+
+use super::queries::{get_materialized_view_definition, get_view_definition, table_ddl};
+use super::{qualified_name, quote_ident};
+
+const SEQUENCES_SQL: &str = "
+    SELECT c.relname, pg_catalog.format_type(s.seqtypid, NULL), s.seqstart, s.seqincrement,
+        s.seqmin, s.seqmax, s.seqcache, s.seqcycle, t.relname, a.attname
+    FROM pg_catalog.pg_sequence s
+    JOIN pg_catalog.pg_class c ON c.oid = s.seqrelid
+    JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+    LEFT JOIN pg_catalog.pg_depend d ON d.classid = 'pg_catalog.pg_class'::regclass
+        AND d.objid = c.oid AND d.refclassid = 'pg_catalog.pg_class'::regclass AND d.deptype = 'a'
+    LEFT JOIN pg_catalog.pg_class t ON t.oid = d.refobjid AND t.relnamespace = c.relnamespace
+    LEFT JOIN pg_catalog.pg_attribute a ON a.attrelid = t.oid AND a.attnum = d.refobjsubid
+    WHERE n.nspname = $1
+        AND NOT EXISTS (
+            SELECT 1 FROM pg_catalog.pg_depend i
+            WHERE i.classid = 'pg_catalog.pg_class'::regclass AND i.objid = c.oid AND i.deptype = 'i'
+        )
+    ORDER BY c.relname";
+
+// Functions and procedures not owned by an extension; those taking or
+// returning a table's row type can only be created after the tables
+const FUNCTIONS_SQL: &str = "
+    SELECT pg_catalog.pg_get_functiondef(p.oid),
+        EXISTS (
+            SELECT 1 FROM pg_catalog.pg_depend d
+            JOIN pg_catalog.pg_type t ON t.oid = d.refobjid
+            WHERE d.classid = 'pg_catalog.pg_proc'::regclass AND d.objid = p.oid
+                AND d.refclassid = 'pg_catalog.pg_type'::regclass AND t.typrelid <> 0
+        )
+    FROM pg_catalog.pg_proc p
+    JOIN pg_catalog.pg_namespace n ON n.oid = p.pronamespace
+    WHERE n.nspname = $1 AND p.prokind IN ('f', 'p')
+        AND NOT EXISTS (
+            SELECT 1 FROM pg_catalog.pg_depend e
+            WHERE e.classid = 'pg_catalog.pg_proc'::regclass AND e.objid = p.oid AND e.deptype = 'e'
+        )
+    ORDER BY p.proname, pg_catalog.pg_get_function_identity_arguments(p.oid)";
+
+const TABLES_SQL: &str = "
+    SELECT c.relname
+    FROM pg_catalog.pg_class c
+    JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+    WHERE n.nspname = $1 AND c.relkind IN ('r', 'p')
+    ORDER BY c.relname";
+
+const FOREIGN_KEYS_SQL: &str = "
+    SELECT c.relname, quote_ident(con.conname), pg_catalog.pg_get_constraintdef(con.oid, true)
+    FROM pg_catalog.pg_constraint con
+    JOIN pg_catalog.pg_class c ON c.oid = con.conrelid
+    JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+    WHERE n.nspname = $1 AND con.contype = 'f'
+    ORDER BY c.relname, con.conname";
+
+// Views and materialized views with the ones of the same schema they read
+const VIEWS_SQL: &str = "
+    SELECT v.relname, v.relkind = 'm',
+        ARRAY(
+            SELECT DISTINCT r.relname::text
+            FROM pg_catalog.pg_rewrite w
+            JOIN pg_catalog.pg_depend d ON d.classid = 'pg_catalog.pg_rewrite'::regclass AND d.objid = w.oid
+            JOIN pg_catalog.pg_class r ON r.oid = d.refobjid
+            WHERE w.ev_class = v.oid AND r.oid <> v.oid
+                AND r.relnamespace = v.relnamespace AND r.relkind IN ('v', 'm')
+        )
+    FROM pg_catalog.pg_class v
+    JOIN pg_catalog.pg_namespace n ON n.oid = v.relnamespace
+    WHERE n.nspname = $1 AND v.relkind IN ('v', 'm')
+    ORDER BY v.relname";
+
+const MATVIEW_INDEXES_SQL: &str = "
+    SELECT pg_catalog.pg_get_indexdef(ix.indexrelid)
+    FROM pg_catalog.pg_index ix
+    JOIN pg_catalog.pg_class c ON c.oid = ix.indrelid
+    JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+    JOIN pg_catalog.pg_class i ON i.oid = ix.indexrelid
+    WHERE n.nspname = $1 AND c.relname = $2
+    ORDER BY i.relname";
+
+/// CREATE statements for the sequences, functions, tables with their
+/// indexes, and views of `schema`, in an order a fresh database accepts
+/// them: foreign keys and sequence ownership come after every table, and
+/// views after the views they read. Types, extensions, triggers and
+/// grants are not included.
+pub async fn schema_ddl(client: &Client, schema: &str) -> Result<String> {
+    let mut out = format!(
+        "-- DDL of schema {}\n\nSET check_function_bodies = false;\n\nCREATE SCHEMA IF NOT EXISTS {};\n",
+        schema,
+        quote_ident(schema)
+    );
+
+    let sequences = client
+        .query(SEQUENCES_SQL, &[&schema])
+        .await
+        .context("Failed to load sequences")?;
+    let mut ownership = Vec::new();
+    for row in &sequences {
+        let name: String = row.get(0);
+        let (start, increment, min, max, cache): (i64, i64, i64, i64, i64) =
+            (row.get(2), row.get(3), row.get(4), row.get(5), row.get(6));
+        let cycle: bool = row.get(7);
+        out.push_str(&format!(
+            "\nCREATE SEQUENCE {} AS {} START WITH {} INCREMENT BY {} MINVALUE {} MAXVALUE {} CACHE {}{};\n",
+            qualified_name(schema, &name),
+            row.get::<_, String>(1),
+            start,
+            increment,
+            min,
+            max,
+            cache,
+            if cycle { " CYCLE" } else { "" }
+        ));
+        if let (Some(table), Some(column)) = (row.get::<_, Option<String>>(8), row.get::<_, Option<String>>(9)) {
+            ownership.push(format!(
+                "ALTER SEQUENCE {} OWNED BY {}.{};\n",
+                qualified_name(schema, &name),
+                qualified_name(schema, &table),
+                quote_ident(&column)
+            ));
+        }
+    }
+
+    let functions = client
+        .query(FUNCTIONS_SQL, &[&schema])
+        .await
+        .context("Failed to load functions")?;
+    let (after_tables, before_tables): (Vec<_>, Vec<_>) = functions.iter().partition(|row| row.get::<_, bool>(1));
+    for row in &before_tables {
+        out.push_str(&format!("\n{};\n", row.get::<_, String>(0).trim_end()));
+    }
+
+    let tables = client.query(TABLES_SQL, &[&schema]).await.context("Failed to load tables")?;
+    for row in &tables {
+        let table: String = row.get(0);
+        out.push('\n');
+        out.push_str(&table_ddl(client, schema, &table, false).await?);
+    }
+    for row in &after_tables {
+        out.push_str(&format!("\n{};\n", row.get::<_, String>(0).trim_end()));
+    }
+    if !ownership.is_empty() {
+        out.push('\n');
+        ownership.iter().for_each(|line| out.push_str(line));
+    }
+
+    let foreign_keys = client
+        .query(FOREIGN_KEYS_SQL, &[&schema])
+        .await
+        .context("Failed to load foreign keys")?;
+    if !foreign_keys.is_empty() {
+        out.push('\n');
+    }
+    for row in &foreign_keys {
+        let (table, name, definition): (String, String, String) = (row.get(0), row.get(1), row.get(2));
+        out.push_str(&format!(
+            "ALTER TABLE {} ADD CONSTRAINT {} {};\n",
+            qualified_name(schema, &table),
+            name,
+            definition
+        ));
+    }
+
+    let views = client.query(VIEWS_SQL, &[&schema]).await.context("Failed to load views")?;
+    let kinds: BTreeMap<String, bool> = views.iter().map(|row| (row.get(0), row.get(1))).collect();
+    let reads: BTreeMap<String, Vec<String>> = views.iter().map(|row| (row.get(0), row.get(2))).collect();
+    for view in dependency_order(&reads) {
+        let definition = match kinds[&view] {
+            true => get_materialized_view_definition(client, schema, &view).await?,
+            false => get_view_definition(client, schema, &view).await?,
+        };
+        out.push_str(&format!("\n{};\n", definition.trim_end().trim_end_matches(';')));
+        if kinds[&view] {
+            let indexes = client
+                .query(MATVIEW_INDEXES_SQL, &[&schema, &view])
+                .await
+                .context("Failed to load materialized view indexes")?;
+            for index in &indexes {
+                out.push_str(&format!("{};\n", index.get::<_, String>(0)));
+            }
+        }
+    }
+    Ok(out)
+}
+
+// Names ordered so each comes after everything it reads, alphabetically
+// where that leaves a choice; a cycle, which the server doesn't allow,
+// would be put at the end as it is
+fn dependency_order(reads: &BTreeMap<String, Vec<String>>) -> Vec<String> {
+    let mut done: BTreeSet<&str> = BTreeSet::new();
+    let mut order = Vec::new();
+    while order.len() < reads.len() {
+        let ready = reads.iter().find(|(name, needs)| {
+            !done.contains(name.as_str())
+                && needs
+                    .iter()
+                    .all(|need| done.contains(need.as_str()) || !reads.contains_key(need))
+        });
+        let Some((name, _)) = ready.or_else(|| reads.iter().find(|(name, _)| !done.contains(name.as_str()))) else {
+            break;
+        };
+        done.insert(name);
+        order.push(name.clone());
+    }
+    order
+}
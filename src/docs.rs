@@ -0,0 +1,198 @@
+// Short built-in reference for SQL keywords and PostgreSQL functions, shown
+// by "inspect token" in the query editor.
+
+pub struct DocEntry {
+    pub name: &'static str,
+    pub syntax: &'static str,
+    pub summary: &'static str,
+    pub example: &'static str,
+}
+
+const fn entry(name: &'static str, syntax: &'static str, summary: &'static str, example: &'static str) -> DocEntry {
+    DocEntry { name, syntax, summary, example }
+}
+
+pub static ENTRIES: &[DocEntry] = &[
+    // Clauses
+    entry("SELECT", "SELECT [DISTINCT [ON (expr, ...)]] expr [AS name], ... FROM ...", "Retrieve rows from tables, views or subqueries.", "SELECT id, name FROM users WHERE active"),
+    entry("DISTINCT ON", "SELECT DISTINCT ON (expr, ...) ... ORDER BY expr, ...", "Keep only the first row of each group of rows with equal expressions; ORDER BY decides which row is first.", "SELECT DISTINCT ON (user_id) * FROM logins ORDER BY user_id, at DESC"),
+    entry("WHERE", "... WHERE condition", "Keep only rows for which the condition is true (not false or NULL).", "SELECT * FROM orders WHERE total > 100"),
+    entry("GROUP BY", "GROUP BY expr, ... | ROLLUP (...) | CUBE (...) | GROUPING SETS (...)", "Collapse rows with equal grouping expressions into one row per group, for use with aggregates.", "SELECT status, count(*) FROM orders GROUP BY status"),
+    entry("GROUPING SETS", "GROUP BY GROUPING SETS ((a, b), (a), ())", "Compute several groupings in one pass; columns not in the current set are NULL. () is the grand total.", "SELECT region, product, sum(amount) FROM sales GROUP BY GROUPING SETS ((region, product), (region), ())"),
+    entry("ROLLUP", "GROUP BY ROLLUP (a, b, c)", "Shorthand for the grouping sets (a, b, c), (a, b), (a) and () - subtotals down a hierarchy.", "SELECT year, month, sum(amount) FROM sales GROUP BY ROLLUP (year, month)"),
+    entry("CUBE", "GROUP BY CUBE (a, b)", "Shorthand for every combination of the listed columns as grouping sets.", "SELECT region, product, sum(amount) FROM sales GROUP BY CUBE (region, product)"),
+    entry("GROUPING", "GROUPING(expr, ...)", "In a grouping-sets query, a bit mask telling which arguments are not part of the current grouping (1 = rolled up).", "SELECT region, GROUPING(region) AS is_total, sum(amount) FROM sales GROUP BY ROLLUP (region)"),
+    entry("HAVING", "GROUP BY ... HAVING condition", "Filter groups after aggregation; WHERE filters rows before it.", "SELECT user_id FROM orders GROUP BY user_id HAVING count(*) > 5"),
+    entry("ORDER BY", "ORDER BY expr [ASC | DESC] [NULLS FIRST | NULLS LAST], ...", "Sort the result. Without ORDER BY row order is unspecified.", "SELECT * FROM users ORDER BY created_at DESC NULLS LAST"),
+    entry("LIMIT", "LIMIT count [OFFSET skip]", "Return at most count rows, optionally after skipping some. Use with ORDER BY for stable pages.", "SELECT * FROM events ORDER BY id LIMIT 50 OFFSET 100"),
+    entry("OFFSET", "OFFSET skip [ROWS]", "Skip rows before returning any. Large offsets still read the skipped rows.", "SELECT * FROM events ORDER BY id OFFSET 100 LIMIT 50"),
+    entry("FETCH FIRST", "FETCH FIRST n ROWS { ONLY | WITH TIES }", "Standard spelling of LIMIT; WITH TIES also returns rows tied with the last one.", "SELECT * FROM scores ORDER BY points DESC FETCH FIRST 3 ROWS WITH TIES"),
+    entry("WITH", "WITH name [(cols)] AS [NOT] MATERIALIZED (query) [, ...] statement", "Common table expressions: named subqueries usable in the following statement.", "WITH recent AS (SELECT * FROM orders WHERE at > now() - interval '1 day') SELECT count(*) FROM recent"),
+    entry("RECURSIVE", "WITH RECURSIVE name AS (base UNION [ALL] recursive_term) ...", "A CTE that refers to itself, for walking trees and graphs.", "WITH RECURSIVE t AS (SELECT id FROM nodes WHERE parent IS NULL UNION ALL SELECT n.id FROM nodes n JOIN t ON n.parent = t.id) SELECT * FROM t"),
+    entry("MATERIALIZED", "WITH name AS [NOT] MATERIALIZED (query)", "Force a CTE to be computed once, or allow it to be inlined into the outer query.", "WITH x AS MATERIALIZED (SELECT * FROM big) SELECT * FROM x WHERE id = 1"),
+    entry("JOIN", "a [INNER | LEFT | RIGHT | FULL] JOIN b ON condition | USING (cols)", "Combine rows from two relations. INNER keeps matches only; outer joins keep unmatched rows with NULLs.", "SELECT * FROM orders o JOIN users u ON u.id = o.user_id"),
+    entry("LEFT JOIN", "a LEFT [OUTER] JOIN b ON condition", "All rows of a, with matching rows of b or NULLs when there is none.", "SELECT u.*, o.id FROM users u LEFT JOIN orders o ON o.user_id = u.id"),
+    entry("FULL JOIN", "a FULL [OUTER] JOIN b ON condition", "All rows of both sides, matched where possible.", "SELECT * FROM a FULL JOIN b USING (id)"),
+    entry("CROSS JOIN", "a CROSS JOIN b", "Every combination of rows (Cartesian product).", "SELECT * FROM sizes CROSS JOIN colors"),
+    entry("LATERAL", "FROM a, LATERAL (subquery referring to a)", "Let a subquery or function in FROM refer to columns of items before it - a per-row subquery.", "SELECT u.id, l.* FROM users u, LATERAL (SELECT * FROM logins WHERE user_id = u.id ORDER BY at DESC LIMIT 3) l"),
+    entry("USING", "a JOIN b USING (col, ...)", "Join on equally named columns, which appear once in the output.", "SELECT * FROM orders JOIN users USING (user_id)"),
+    entry("UNION", "query UNION [ALL] query", "Rows of both queries. UNION removes duplicates; UNION ALL keeps them and is cheaper.", "SELECT email FROM users UNION SELECT email FROM invites"),
+    entry("INTERSECT", "query INTERSECT [ALL] query", "Rows present in both queries.", "SELECT user_id FROM orders INTERSECT SELECT user_id FROM reviews"),
+    entry("EXCEPT", "query EXCEPT [ALL] query", "Rows of the first query that are not in the second.", "SELECT id FROM users EXCEPT SELECT user_id FROM orders"),
+    entry("TABLESAMPLE", "FROM table TABLESAMPLE { SYSTEM | BERNOULLI } (percent) [REPEATABLE (seed)]", "Read a random sample of a table. SYSTEM samples pages (fast), BERNOULLI rows.", "SELECT * FROM events TABLESAMPLE SYSTEM (1)"),
+    entry("WINDOW", "WINDOW name AS (PARTITION BY ... ORDER BY ...)", "Name a window definition to reuse in several OVER clauses.", "SELECT sum(x) OVER w, avg(x) OVER w FROM t WINDOW w AS (PARTITION BY g ORDER BY ts)"),
+    entry("OVER", "func(...) OVER ([PARTITION BY ...] [ORDER BY ...] [frame])", "Turn an aggregate or window function into a per-row computation over related rows.", "SELECT id, sum(amount) OVER (ORDER BY at) AS running_total FROM payments"),
+    entry("PARTITION BY", "OVER (PARTITION BY expr, ... ORDER BY ...)", "Split rows into independent windows; the function restarts for each partition.", "SELECT *, row_number() OVER (PARTITION BY user_id ORDER BY at) FROM orders"),
+    entry("FILTER", "aggregate(...) FILTER (WHERE condition)", "Aggregate only the rows matching the condition.", "SELECT count(*) FILTER (WHERE status = 'failed') AS failures FROM jobs"),
+    entry("RETURNING", "INSERT | UPDATE | DELETE ... RETURNING expr, ...", "Return values from the affected rows, e.g. generated keys.", "INSERT INTO users (name) VALUES ('ann') RETURNING id"),
+    entry("ON CONFLICT", "INSERT ... ON CONFLICT [(cols) | ON CONSTRAINT name] DO NOTHING | DO UPDATE SET ...", "Upsert: handle unique violations by skipping or updating the existing row. EXCLUDED holds the proposed row.", "INSERT INTO counters (k, n) VALUES ('a', 1) ON CONFLICT (k) DO UPDATE SET n = counters.n + EXCLUDED.n"),
+    entry("EXCLUDED", "ON CONFLICT ... DO UPDATE SET col = EXCLUDED.col", "The row that was proposed for insertion, inside ON CONFLICT DO UPDATE.", "INSERT INTO t (id, v) VALUES (1, 'x') ON CONFLICT (id) DO UPDATE SET v = EXCLUDED.v"),
+    entry("MERGE", "MERGE INTO target USING source ON cond WHEN MATCHED THEN ... WHEN NOT MATCHED THEN ...", "Insert, update or delete target rows depending on whether they match the source (PostgreSQL 15+).", "MERGE INTO stock s USING delivery d ON s.item = d.item WHEN MATCHED THEN UPDATE SET qty = s.qty + d.qty WHEN NOT MATCHED THEN INSERT VALUES (d.item, d.qty)"),
+    entry("INSERT", "INSERT INTO table [(cols)] VALUES (...), ... | query", "Add rows to a table.", "INSERT INTO tags (code, label) VALUES ('a', 'Alpha')"),
+    entry("UPDATE", "UPDATE table SET col = expr, ... [FROM ...] [WHERE ...]", "Change rows. Without WHERE every row is updated.", "UPDATE users SET active = false WHERE last_login < now() - interval '1 year'"),
+    entry("DELETE", "DELETE FROM table [USING ...] [WHERE ...]", "Remove rows. Without WHERE every row is deleted.", "DELETE FROM sessions WHERE expires_at < now()"),
+    entry("TRUNCATE", "TRUNCATE [TABLE] name, ... [RESTART IDENTITY] [CASCADE]", "Empty tables quickly without scanning them; takes an exclusive lock.", "TRUNCATE staging_rows RESTART IDENTITY"),
+    entry("EXPLAIN", "EXPLAIN [(ANALYZE, BUFFERS, FORMAT JSON ...)] statement", "Show the plan for a statement. ANALYZE actually runs it and reports real timings.", "EXPLAIN (ANALYZE, BUFFERS) SELECT * FROM orders WHERE user_id = 42"),
+    entry("ANALYZE", "ANALYZE [table [(cols)]]", "Collect planner statistics for tables.", "ANALYZE orders"),
+    entry("VACUUM", "VACUUM [(FULL, ANALYZE, VERBOSE)] [table]", "Reclaim space from dead rows; FULL rewrites the table under an exclusive lock.", "VACUUM (ANALYZE) orders"),
+    entry("COPY", "COPY table [(cols)] FROM | TO 'file' | STDIN | STDOUT [WITH (FORMAT csv, HEADER)]", "Bulk load or unload data. Files are read on the server; use psql's \\copy for client files.", "COPY (SELECT * FROM users) TO STDOUT WITH (FORMAT csv, HEADER)"),
+    entry("CASE", "CASE WHEN cond THEN value [...] [ELSE value] END", "Conditional expression; the first true branch wins.", "SELECT CASE WHEN total > 100 THEN 'big' ELSE 'small' END FROM orders"),
+    entry("EXISTS", "EXISTS (subquery)", "True if the subquery returns at least one row.", "SELECT * FROM users u WHERE EXISTS (SELECT 1 FROM orders o WHERE o.user_id = u.id)"),
+    entry("IN", "expr IN (value, ... | subquery)", "True if expr equals any listed value. NOT IN with a NULL in the list is never true.", "SELECT * FROM orders WHERE status IN ('new', 'paid')"),
+    entry("ANY", "expr op ANY (array | subquery)", "True if the comparison holds for at least one element.", "SELECT * FROM users WHERE id = ANY ('{1,2,3}'::int[])"),
+    entry("ALL", "expr op ALL (array | subquery)", "True if the comparison holds for every element.", "SELECT * FROM items WHERE price > ALL (SELECT price FROM sale_items)"),
+    entry("BETWEEN", "expr [NOT] BETWEEN low AND high", "Inclusive range test, same as expr >= low AND expr <= high.", "SELECT * FROM orders WHERE at BETWEEN '2024-01-01' AND '2024-01-31'"),
+    entry("LIKE", "expr [NOT] LIKE pattern [ESCAPE char]", "Pattern match: % any string, _ one character. Case-sensitive; ILIKE ignores case.", "SELECT * FROM users WHERE email LIKE '%@example.com'"),
+    entry("ILIKE", "expr [NOT] ILIKE pattern", "Case-insensitive LIKE (PostgreSQL extension).", "SELECT * FROM users WHERE name ILIKE 'ann%'"),
+    entry("SIMILAR TO", "expr [NOT] SIMILAR TO pattern", "SQL-standard regular expressions mixing LIKE wildcards with | * + ( ).", "SELECT 'abc' SIMILAR TO '(a|b)%'"),
+    entry("IS DISTINCT FROM", "a IS [NOT] DISTINCT FROM b", "Equality test that treats NULLs as equal values instead of returning NULL.", "SELECT * FROM t WHERE a IS DISTINCT FROM b"),
+    entry("CAST", "CAST(expr AS type) | expr::type", "Convert a value to another type.", "SELECT CAST('42' AS integer), '2024-01-01'::date"),
+    entry("INTERVAL", "INTERVAL 'quantity unit [...]'", "A span of time, for date arithmetic.", "SELECT now() - INTERVAL '7 days'"),
+    entry("COLLATE", "expr COLLATE \"collation\"", "Compare or sort a string with a specific collation.", "SELECT name FROM users ORDER BY name COLLATE \"C\""),
+    // Aggregates
+    entry("COUNT", "count(*) | count([DISTINCT] expr)", "Number of rows, or of non-NULL values of expr.", "SELECT count(*), count(DISTINCT user_id) FROM orders"),
+    entry("SUM", "sum(expr)", "Sum of non-NULL values; NULL when there are none.", "SELECT sum(total) FROM orders"),
+    entry("AVG", "avg(expr)", "Average of non-NULL values.", "SELECT avg(total) FROM orders"),
+    entry("MIN", "min(expr)", "Smallest non-NULL value.", "SELECT min(created_at) FROM users"),
+    entry("MAX", "max(expr)", "Largest non-NULL value.", "SELECT max(created_at) FROM users"),
+    entry("STRING_AGG", "string_agg(expr, delimiter [ORDER BY ...])", "Concatenate strings with a delimiter.", "SELECT string_agg(name, ', ' ORDER BY name) FROM tags"),
+    entry("ARRAY_AGG", "array_agg(expr [ORDER BY ...])", "Collect values, NULLs included, into an array.", "SELECT user_id, array_agg(id ORDER BY id) FROM orders GROUP BY user_id"),
+    entry("JSON_AGG", "json_agg(expr [ORDER BY ...]) | jsonb_agg(...)", "Collect values into a JSON array.", "SELECT json_agg(u) FROM users u"),
+    entry("JSONB_AGG", "jsonb_agg(expr [ORDER BY ...])", "Collect values into a jsonb array.", "SELECT jsonb_agg(name) FROM users"),
+    entry("JSON_OBJECT_AGG", "json_object_agg(key, value) | jsonb_object_agg(...)", "Build a JSON object from key/value pairs.", "SELECT json_object_agg(code, label) FROM tags"),
+    entry("BOOL_AND", "bool_and(expr)", "True if every non-NULL input is true.", "SELECT bool_and(paid) FROM invoices"),
+    entry("BOOL_OR", "bool_or(expr)", "True if any non-NULL input is true.", "SELECT bool_or(failed) FROM jobs"),
+    entry("PERCENTILE_CONT", "percentile_cont(fraction) WITHIN GROUP (ORDER BY expr)", "Continuous percentile, interpolating between values. 0.5 is the median.", "SELECT percentile_cont(0.5) WITHIN GROUP (ORDER BY duration) FROM requests"),
+    entry("PERCENTILE_DISC", "percentile_disc(fraction) WITHIN GROUP (ORDER BY expr)", "Discrete percentile: the first input value at or past the fraction.", "SELECT percentile_disc(0.9) WITHIN GROUP (ORDER BY duration) FROM requests"),
+    entry("MODE", "mode() WITHIN GROUP (ORDER BY expr)", "Most frequent value.", "SELECT mode() WITHIN GROUP (ORDER BY browser) FROM visits"),
+    // Window functions
+    entry("ROW_NUMBER", "row_number() OVER (...)", "Sequential number of the row within its partition, starting at 1.", "SELECT *, row_number() OVER (PARTITION BY user_id ORDER BY at) FROM orders"),
+    entry("RANK", "rank() OVER (... ORDER BY ...)", "Rank with gaps: tied rows share a rank and the next rank is skipped.", "SELECT name, rank() OVER (ORDER BY points DESC) FROM players"),
+    entry("DENSE_RANK", "dense_rank() OVER (... ORDER BY ...)", "Rank without gaps after ties.", "SELECT name, dense_rank() OVER (ORDER BY points DESC) FROM players"),
+    entry("NTILE", "ntile(buckets) OVER (... ORDER BY ...)", "Split the partition into numbered buckets of nearly equal size.", "SELECT id, ntile(4) OVER (ORDER BY total) AS quartile FROM orders"),
+    entry("LAG", "lag(expr [, offset [, default]]) OVER (...)", "Value from a previous row of the partition.", "SELECT at, value - lag(value) OVER (ORDER BY at) AS delta FROM readings"),
+    entry("LEAD", "lead(expr [, offset [, default]]) OVER (...)", "Value from a following row of the partition.", "SELECT at, lead(at) OVER (ORDER BY at) - at AS gap FROM events"),
+    entry("FIRST_VALUE", "first_value(expr) OVER (...)", "Value at the first row of the window frame.", "SELECT *, first_value(price) OVER (PARTITION BY item ORDER BY at) FROM prices"),
+    entry("LAST_VALUE", "last_value(expr) OVER (... ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING)", "Value at the last row of the frame; the default frame ends at the current row.", "SELECT *, last_value(price) OVER (PARTITION BY item ORDER BY at ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING) FROM prices"),
+    // Conditionals
+    entry("COALESCE", "coalesce(value, ...)", "First non-NULL argument.", "SELECT coalesce(nickname, name) FROM users"),
+    entry("NULLIF", "nullif(a, b)", "NULL if a equals b, otherwise a - handy to avoid division by zero.", "SELECT total / nullif(count, 0) FROM stats"),
+    entry("GREATEST", "greatest(value, ...)", "Largest argument, ignoring NULLs.", "SELECT greatest(updated_at, created_at) FROM posts"),
+    entry("LEAST", "least(value, ...)", "Smallest argument, ignoring NULLs.", "SELECT least(price, max_price) FROM items"),
+    // Strings
+    entry("LENGTH", "length(text)", "Number of characters.", "SELECT length('héllo')"),
+    entry("LOWER", "lower(text)", "Convert to lower case.", "SELECT lower('ABC')"),
+    entry("UPPER", "upper(text)", "Convert to upper case.", "SELECT upper('abc')"),
+    entry("TRIM", "trim([LEADING | TRAILING | BOTH] [chars] FROM text)", "Remove characters (spaces by default) from the ends.", "SELECT trim(BOTH 'x' FROM 'xxhixx')"),
+    entry("SUBSTRING", "substring(text FROM start [FOR count]) | substring(text FROM pattern)", "Extract part of a string by position or regular expression.", "SELECT substring('2024-05-01' FROM 1 FOR 4)"),
+    entry("POSITION", "position(substring IN text)", "1-based location of substring, 0 if absent.", "SELECT position('@' IN email) FROM users"),
+    entry("SPLIT_PART", "split_part(text, delimiter, n)", "The n-th field after splitting on delimiter (negative n counts from the end, PG 14+).", "SELECT split_part(email, '@', 2) FROM users"),
+    entry("CONCAT", "concat(value, ...)", "Concatenate arguments, ignoring NULLs.", "SELECT concat(first_name, ' ', last_name) FROM users"),
+    entry("CONCAT_WS", "concat_ws(separator, value, ...)", "Concatenate with a separator, skipping NULLs.", "SELECT concat_ws(', ', city, region, country) FROM addresses"),
+    entry("FORMAT", "format(formatstr, args...)", "printf-style formatting: %s value, %I identifier, %L literal.", "SELECT format('SELECT * FROM %I WHERE id = %L', 'users', 42)"),
+    entry("REPLACE", "replace(text, from, to)", "Replace every occurrence of a substring.", "SELECT replace(phone, '-', '') FROM users"),
+    entry("REGEXP_REPLACE", "regexp_replace(text, pattern, replacement [, flags])", "Replace regex matches; flag 'g' replaces all of them.", "SELECT regexp_replace(phone, '\\D', '', 'g') FROM users"),
+    entry("REGEXP_MATCHES", "regexp_matches(text, pattern [, flags])", "Set of text arrays with the captured groups of each match.", "SELECT regexp_matches(body, '#(\\w+)', 'g') FROM posts"),
+    entry("LEFT", "left(text, n)", "First n characters (all but the last |n| when negative).", "SELECT left(name, 1) FROM users"),
+    entry("RIGHT", "right(text, n)", "Last n characters.", "SELECT right(card_number, 4) FROM payments"),
+    entry("LPAD", "lpad(text, length [, fill])", "Pad on the left to the given length.", "SELECT lpad(id::text, 6, '0') FROM orders"),
+    entry("MD5", "md5(text)", "MD5 hash as hex text.", "SELECT md5(email) FROM users"),
+    // Dates and times
+    entry("NOW", "now()", "Start time of the current transaction, with time zone.", "SELECT now()"),
+    entry("CURRENT_DATE", "CURRENT_DATE", "Today's date.", "SELECT * FROM events WHERE at >= CURRENT_DATE"),
+    entry("DATE_TRUNC", "date_trunc(field, timestamp [, time_zone])", "Truncate to the given precision: 'hour', 'day', 'week', 'month', 'year', ...", "SELECT date_trunc('day', at), count(*) FROM events GROUP BY 1"),
+    entry("EXTRACT", "EXTRACT(field FROM source)", "A field of a date/time or interval as numeric: year, month, dow, epoch, ...", "SELECT EXTRACT(epoch FROM now() - started_at) FROM jobs"),
+    entry("DATE_PART", "date_part(field, source)", "Like EXTRACT, returning double precision.", "SELECT date_part('hour', at) FROM events"),
+    entry("AGE", "age(timestamp [, timestamp])", "Difference as a years/months/days interval.", "SELECT age(birth_date) FROM people"),
+    entry("TO_CHAR", "to_char(value, format)", "Format a date, time or number as text.", "SELECT to_char(now(), 'YYYY-MM-DD HH24:MI')"),
+    entry("TO_DATE", "to_date(text, format)", "Parse text into a date.", "SELECT to_date('05/01/2024', 'MM/DD/YYYY')"),
+    entry("TO_TIMESTAMP", "to_timestamp(text, format) | to_timestamp(epoch)", "Parse text, or convert Unix seconds, into a timestamptz.", "SELECT to_timestamp(1700000000)"),
+    entry("GENERATE_SERIES", "generate_series(start, stop [, step])", "Set of values from start to stop; works with numbers and timestamps.", "SELECT generate_series('2024-01-01'::date, '2024-01-07', interval '1 day')"),
+    // Arrays and JSON
+    entry("UNNEST", "unnest(array [, ...]) [WITH ORDINALITY]", "Expand an array into a set of rows.", "SELECT * FROM unnest(ARRAY['a', 'b']) WITH ORDINALITY AS t(value, n)"),
+    entry("ARRAY_LENGTH", "array_length(array, dimension)", "Length of the given array dimension; NULL for empty arrays.", "SELECT array_length(tags, 1) FROM posts"),
+    entry("CARDINALITY", "cardinality(array)", "Total number of elements; 0 for empty arrays.", "SELECT cardinality(tags) FROM posts"),
+    entry("JSON_BUILD_OBJECT", "json_build_object(key, value, ...) | jsonb_build_object(...)", "Build a JSON object from alternating keys and values.", "SELECT json_build_object('id', id, 'name', name) FROM users"),
+    entry("JSONB_SET", "jsonb_set(target, path text[], new_value [, create_missing])", "Replace or add the value at a path.", "UPDATE docs SET body = jsonb_set(body, '{meta,views}', '0')"),
+    entry("JSONB_PATH_QUERY", "jsonb_path_query(target, jsonpath [, vars])", "Set of items matched by a SQL/JSON path.", "SELECT jsonb_path_query(body, '$.items[*] ? (@.qty > 1)') FROM orders"),
+    entry("JSONB_EACH", "jsonb_each(object) | jsonb_each_text(object)", "Expand the top-level keys of an object into key/value rows.", "SELECT key, value FROM docs, jsonb_each(body)"),
+    entry("JSONB_ARRAY_ELEMENTS", "jsonb_array_elements(array)", "Expand a JSON array into rows.", "SELECT elem ->> 'name' FROM docs, jsonb_array_elements(body -> 'items') AS elem"),
+    // Full text search
+    entry("TO_TSVECTOR", "to_tsvector([config,] text)", "Normalize a document into searchable lexemes.", "SELECT * FROM posts WHERE to_tsvector('english', body) @@ to_tsquery('english', 'index & scan')"),
+    entry("TO_TSQUERY", "to_tsquery([config,] query)", "Parse a search query with & | ! operators.", "SELECT to_tsquery('english', 'cats & !dogs')"),
+    entry("WEBSEARCH_TO_TSQUERY", "websearch_to_tsquery([config,] text)", "Parse a search-engine style query: quotes, or, -word.", "SELECT websearch_to_tsquery('english', '\"sql tuning\" -mysql')"),
+    // System information
+    entry("PG_SIZE_PRETTY", "pg_size_pretty(bytes)", "Format a byte count as kB, MB, GB...", "SELECT pg_size_pretty(pg_database_size(current_database()))"),
+    entry("PG_TOTAL_RELATION_SIZE", "pg_total_relation_size(regclass)", "Size of a table including indexes and TOAST data.", "SELECT pg_size_pretty(pg_total_relation_size('orders'))"),
+    entry("CURRENT_SETTING", "current_setting(name [, missing_ok])", "Value of a configuration parameter.", "SELECT current_setting('work_mem')"),
+    entry("PG_CANCEL_BACKEND", "pg_cancel_backend(pid)", "Cancel the running query of another session.", "SELECT pg_cancel_backend(pid) FROM pg_stat_activity WHERE state = 'active' AND now() - query_start > interval '5 minutes'"),
+    entry("PG_TERMINATE_BACKEND", "pg_terminate_backend(pid)", "Disconnect another session.", "SELECT pg_terminate_backend(12345)"),
+];
+
+// Entry for the keyword or function under the cursor. Multi-word entries
+// (GROUPING SETS, ON CONFLICT, ...) match when the cursor is on any of
+// their words; the longest match wins.
+pub fn lookup(text: &str, cursor: usize) -> Option<&'static DocEntry> {
+    let words: Vec<(usize, usize, String)> = word_spans(text);
+    let at = words
+        .iter()
+        .position(|&(start, end, _)| start <= cursor && cursor <= end)?;
+
+    ENTRIES
+        .iter()
+        .filter(|entry| {
+            let parts: Vec<&str> = entry.name.split_whitespace().collect();
+            (at.saturating_sub(parts.len() - 1)..=at).any(|first| {
+                words.len() >= first + parts.len()
+                    && parts
+                        .iter()
+                        .zip(&words[first..first + parts.len()])
+                        .all(|(part, (_, _, word))| part.eq_ignore_ascii_case(word))
+            })
+        })
+        .max_by_key(|entry| entry.name.split_whitespace().count())
+}
+
+// Byte ranges and text of identifier-like words
+fn word_spans(text: &str) -> Vec<(usize, usize, String)> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices().chain(std::iter::once((text.len(), ' '))) {
+        let in_word = c.is_alphanumeric() || c == '_';
+        match (start, in_word) {
+            (None, true) => start = Some(i),
+            (Some(s), false) => {
+                words.push((s, i, text[s..i].to_string()));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    words
+}
+
+impl DocEntry {
+    pub fn render(&self) -> String {
+        format!("{}\n\n{}\n\nExample:\n  {}", self.syntax, self.summary, self.example)
+    }
+}
@@ -2,20 +2,12 @@ use crate::syntax::{SqlHighlighter, TokenType};
 
 pub struct SqlFormatter {
     indent_size: usize,
-    keyword_case: KeywordCase,
-}
-
-#[derive(Debug, Clone, Copy)]
-pub enum KeywordCase {
-    Upper,
-    Lower,
 }
 
 impl SqlFormatter {
     pub fn new() -> Self {
         Self {
             indent_size: 4,
-            keyword_case: KeywordCase::Upper,
         }
     }
 
@@ -31,7 +23,6 @@ impl SqlFormatter {
         
         for (i, token) in tokens.iter().enumerate() {
             let next_token = tokens.get(i + 1);
-            let prev_token = if i > 0 { tokens.get(i - 1) } else { None };
             
             match token.token_type {
                 TokenType::Keyword => {
@@ -47,7 +38,7 @@ impl SqlFormatter {
                             result.push('\n');
                         }
                         result.push_str(&self.indent(indent_level));
-                        result.push_str(&self.apply_keyword_case(&keyword_upper));
+                        result.push_str(&keyword_upper);
                         
                         if keyword_upper == "SELECT" {
                             after_select = true;
@@ -70,31 +61,25 @@ impl SqlFormatter {
                         } else {
                             result.push(' ');
                         }
-                        result.push_str(&self.apply_keyword_case(&keyword_upper));
-                    }
-                    // ON keyword
-                    else if keyword_upper == "ON" {
-                        result.push('\n');
-                        result.push_str(&self.indent(indent_level + 1));
-                        result.push_str(&self.apply_keyword_case(&keyword_upper));
+                        result.push_str(&keyword_upper);
                     }
-                    // AND/OR in WHERE clause
-                    else if matches!(keyword_upper.as_str(), "AND" | "OR") {
+                    // ON keyword, and AND/OR in WHERE clause
+                    else if matches!(keyword_upper.as_str(), "ON" | "AND" | "OR") {
                         result.push('\n');
                         result.push_str(&self.indent(indent_level + 1));
-                        result.push_str(&self.apply_keyword_case(&keyword_upper));
+                        result.push_str(&keyword_upper);
                     }
                     // BY following GROUP/ORDER
                     else if keyword_upper == "BY" {
                         result.push(' ');
-                        result.push_str(&self.apply_keyword_case(&keyword_upper));
+                        result.push_str(&keyword_upper);
                     }
                     // Other keywords
                     else {
                         if after_major_clause && !result.ends_with(' ') && !result.ends_with('\n') {
                             result.push(' ');
                         }
-                        result.push_str(&self.apply_keyword_case(&keyword_upper));
+                        result.push_str(&keyword_upper);
                     }
                 }
                 
@@ -115,9 +100,7 @@ impl SqlFormatter {
                 }
                 
                 TokenType::Punctuation if token.text == ")" => {
-                    if indent_level > 0 {
-                        indent_level -= 1;
-                    }
+                    indent_level = indent_level.saturating_sub(1);
                     result.push(')');
                 }
                 
@@ -180,13 +163,6 @@ impl SqlFormatter {
     fn indent(&self, level: usize) -> String {
         " ".repeat(self.indent_size * level)
     }
-    
-    fn apply_keyword_case(&self, keyword: &str) -> String {
-        match self.keyword_case {
-            KeywordCase::Upper => keyword.to_uppercase(),
-            KeywordCase::Lower => keyword.to_lowercase(),
-        }
-    }
 }
 
 impl Default for SqlFormatter {
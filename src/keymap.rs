@@ -0,0 +1,491 @@
+use std::collections::BTreeMap;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::config::KeySpec;
+
+// Rebindable hotkeys. Text entry, arrow navigation, Enter/Esc/Tab and the
+// keys inside popups and prompts stay fixed.
+
+// Where an action is looked up; the same key can mean different things in
+// different scopes (`s` in the browser vs. the results grid)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    // Every screen, before anything else sees the key
+    Global,
+    // Browser and query screens
+    Workspace,
+    Browser,
+    // Query screen, editor or results focused
+    Query,
+    Editor,
+    Results,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    RecordMacro,
+    ReplayMacro,
+    OpenPalette,
+    CommandSearch,
+    ShowMessages,
+    ShowTour,
+    SwitchTab(u8),
+    NewConnection,
+    CloseConnection,
+    ToggleSplit,
+    FocusSplit,
+    CycleLayout,
+    ToggleStatusBar,
+    ShrinkEditor,
+    GrowEditor,
+    SaveLayout,
+    ExportSession,
+    ActivityMonitor,
+    ReloadCompletion,
+    SchemaSearch,
+    StatementTimeout,
+    Reconnect,
+    OpenFavorites,
+    RecentObjects,
+    FilterBrowser,
+    RefreshBrowser,
+    ShowDdl,
+    InsertRow,
+    ImportCsv,
+    LargestTables,
+    SchemaDiagram,
+    ExportSchema,
+    RestartSequence,
+    RefreshMatview,
+    SwitchDatabase,
+    NarrowBrowser,
+    WidenBrowser,
+    ToggleDetails,
+    ToggleFavorite,
+    ExpandAll,
+    CollapseAll,
+    DropUnusedIndexes,
+    FormatQuery,
+    FilterResults,
+    RunScript,
+    ExecuteQuery,
+    ExpandStar,
+    QualifyColumns,
+    SaveFile,
+    OpenFile,
+    ToggleResultsFocus,
+    ShowLint,
+    InspectToken,
+    RunWithoutLimit,
+    ResultsHistory,
+    WatchQuery,
+    BenchmarkQuery,
+    PreviewDdl,
+    ExplainQuery,
+    StarQuery,
+    ScrollResultsLeft,
+    ScrollResultsRight,
+    WordLeft,
+    WordRight,
+    EditCell,
+    SortColumn,
+    GotoRow,
+    ShowRecord,
+    SearchResults,
+    FindNext,
+    FindPrevious,
+    HideColumn,
+    MoveColumnLeft,
+    MoveColumnRight,
+    SaveTableView,
+    ResetTableView,
+    FetchMore,
+    FitColumns,
+    NarrowColumn,
+    WidenColumn,
+    ExpandColumn,
+    PinColumns,
+    ChooseColumns,
+    JsonPaths,
+    DiffCell,
+    SaveCell,
+    ExportInserts,
+    CopyMarkdown,
+    CopyOrgTable,
+    OpenCell,
+}
+
+// Every action with its config name, its title in the action palette and
+// its default keys
+const DEFAULTS: &[(Action, &str, &str, &[&str])] = &[
+    (Action::RecordMacro, "record_macro", "Record a key macro", &["f7"]),
+    (Action::ReplayMacro, "replay_macro", "Replay a key macro", &["f8"]),
+    (Action::OpenPalette, "open_palette", "Run a custom command", &["ctrl+k"]),
+    (Action::CommandSearch, "command_search", "Search every action", &["ctrl+shift+p", "alt+p"]),
+    (Action::ShowMessages, "show_messages", "Show messages", &["ctrl+n"]),
+    (Action::ShowTour, "show_tour", "Take the tour", &["ctrl+t"]),
+    (Action::SwitchTab(1), "switch_tab_1", "Switch to tab 1", &["ctrl+1", "alt+1"]),
+    (Action::SwitchTab(2), "switch_tab_2", "Switch to tab 2", &["ctrl+2", "alt+2"]),
+    (Action::SwitchTab(3), "switch_tab_3", "Switch to tab 3", &["ctrl+3", "alt+3"]),
+    (Action::SwitchTab(4), "switch_tab_4", "Switch to tab 4", &["ctrl+4", "alt+4"]),
+    (Action::SwitchTab(5), "switch_tab_5", "Switch to tab 5", &["ctrl+5", "alt+5"]),
+    (Action::SwitchTab(6), "switch_tab_6", "Switch to tab 6", &["ctrl+6", "alt+6"]),
+    (Action::SwitchTab(7), "switch_tab_7", "Switch to tab 7", &["ctrl+7", "alt+7"]),
+    (Action::SwitchTab(8), "switch_tab_8", "Switch to tab 8", &["ctrl+8", "alt+8"]),
+    (Action::SwitchTab(9), "switch_tab_9", "Switch to tab 9", &["ctrl+9", "alt+9"]),
+    (Action::NewConnection, "new_connection", "Open another connection", &["ctrl+o"]),
+    (Action::CloseConnection, "close_connection", "Close this connection", &["ctrl+w"]),
+    (Action::ToggleSplit, "toggle_split", "Compare two connections side by side", &["f3"]),
+    (Action::FocusSplit, "focus_split", "Focus the other side of the split", &["f4"]),
+    (Action::CycleLayout, "cycle_layout", "Cycle layouts", &["ctrl+l"]),
+    (Action::ToggleStatusBar, "toggle_status_bar", "Show or hide the status bar", &["ctrl+b"]),
+    (Action::ShrinkEditor, "shrink_editor", "Shrink the editor", &["ctrl+up"]),
+    (Action::GrowEditor, "grow_editor", "Grow the editor", &["ctrl+down"]),
+    (Action::SaveLayout, "save_layout", "Save the layout", &["f9"]),
+    (Action::ExportSession, "export_session", "Export a session report", &["f12"]),
+    (Action::ActivityMonitor, "activity_monitor", "Activity monitor", &["alt+m"]),
+    (Action::ReloadCompletion, "reload_completion", "Reload the schema for completion", &["alt+r"]),
+    (Action::SchemaSearch, "schema_search", "Search the schema", &["ctrl+p"]),
+    (Action::StatementTimeout, "statement_timeout", "Set the statement timeout", &["alt+t"]),
+    (Action::Reconnect, "reconnect", "Reconnect", &["alt+c"]),
+    (Action::OpenFavorites, "favorites", "Open a favorite", &["alt+f"]),
+    (Action::RecentObjects, "recent_objects", "Jump to a recently opened object", &["alt+o"]),
+    (Action::FilterBrowser, "filter_browser", "Filter the browser", &["/"]),
+    (Action::RefreshBrowser, "refresh_browser", "Refresh the browser", &["r"]),
+    (Action::ShowDdl, "show_ddl", "Show the table's DDL", &["s"]),
+    (Action::InsertRow, "insert_row", "Insert a row", &["i"]),
+    (Action::ImportCsv, "import_csv", "Import a CSV file", &["m"]),
+    (Action::LargestTables, "largest_tables", "List the largest tables", &["b"]),
+    (Action::SchemaDiagram, "schema_diagram", "Relationship diagram", &["e"]),
+    (Action::ExportSchema, "export_schema", "Export the schema's DDL", &["x"]),
+    (Action::RestartSequence, "restart_sequence", "Restart the sequence", &["shift+r"]),
+    (Action::RefreshMatview, "refresh_matview", "Refresh the materialized view", &["f"]),
+    (Action::SwitchDatabase, "switch_database", "Switch database", &["shift+d"]),
+    (Action::NarrowBrowser, "narrow_browser", "Narrow the browser", &["<"]),
+    (Action::WidenBrowser, "widen_browser", "Widen the browser", &[">"]),
+    (Action::ToggleDetails, "toggle_details", "Show or hide the details pane", &["v"]),
+    (Action::ToggleFavorite, "toggle_favorite", "Star or unstar the object", &["shift+s"]),
+    (Action::ExpandAll, "expand_all", "Expand everything under the cursor", &["*"]),
+    (Action::CollapseAll, "collapse_all", "Collapse everything under the cursor", &["-"]),
+    (Action::DropUnusedIndexes, "drop_unused_indexes", "Write DROP INDEX for unused indexes", &["u"]),
+    (Action::FormatQuery, "format_query", "Format the query", &["alt+shift+f"]),
+    (Action::FilterResults, "filter_results", "Filter the results", &["ctrl+f"]),
+    (Action::RunScript, "run_script", "Run every statement", &["ctrl+shift+enter", "f6"]),
+    (Action::ExecuteQuery, "execute_query", "Execute the query at the cursor", &["ctrl+enter", "f5"]),
+    (Action::ExpandStar, "expand_star", "Expand * into columns", &["ctrl+e"]),
+    (Action::QualifyColumns, "qualify_columns", "Qualify column names", &["alt+q"]),
+    (Action::SaveFile, "save_file", "Save the SQL file", &["ctrl+s"]),
+    (Action::OpenFile, "open_file", "Open a SQL file", &["ctrl+r"]),
+    (Action::ToggleResultsFocus, "toggle_results_focus", "Switch between editor and results", &["f2"]),
+    (Action::ShowLint, "show_lint", "List lint warnings", &["f10"]),
+    (Action::InspectToken, "inspect_token", "Keyword docs", &["f1"]),
+    (Action::RunWithoutLimit, "run_without_limit", "Run again without the auto LIMIT", &["alt+l"]),
+    (Action::ResultsHistory, "results_history", "Show a recent result again", &["alt+h"]),
+    (Action::WatchQuery, "watch_query", "Run the query again every few seconds", &["alt+w"]),
+    (Action::BenchmarkQuery, "benchmark_query", "Benchmark the query", &["alt+b"]),
+    (Action::PreviewDdl, "preview_ddl", "DDL dry run", &["alt+d"]),
+    (Action::ExplainQuery, "explain_query", "Explain the query", &["alt+e"]),
+    (Action::StarQuery, "star_query", "Save the query as a favorite", &["alt+s"]),
+    (Action::ScrollResultsLeft, "scroll_results_left", "Scroll the results left", &["shift+left"]),
+    (Action::ScrollResultsRight, "scroll_results_right", "Scroll the results right", &["shift+right"]),
+    (Action::WordLeft, "word_left", "Previous word", &["ctrl+left"]),
+    (Action::WordRight, "word_right", "Next word", &["ctrl+right"]),
+    (Action::EditCell, "edit_cell", "Edit the cell", &["e"]),
+    (Action::SortColumn, "sort_column", "Sort by the column", &["s"]),
+    (Action::GotoRow, "goto_row", "Go to row", &["ctrl+g"]),
+    (Action::ShowRecord, "show_record", "Show the row as a record", &["enter"]),
+    (Action::SearchResults, "search_results", "Search the results", &["/"]),
+    (Action::FindNext, "find_next", "Next match", &["n"]),
+    (Action::FindPrevious, "find_previous", "Previous match", &["shift+n"]),
+    (Action::HideColumn, "hide_column", "Hide the column", &["x"]),
+    (Action::MoveColumnLeft, "move_column_left", "Move the column left", &["<"]),
+    (Action::MoveColumnRight, "move_column_right", "Move the column right", &[">"]),
+    (Action::SaveTableView, "save_table_view", "Save the table view", &["w"]),
+    (Action::ResetTableView, "reset_table_view", "Reset the table view", &["shift+w"]),
+    (Action::FetchMore, "fetch_more", "Fetch more rows", &["m"]),
+    (Action::FitColumns, "fit_columns", "Fit columns to their contents", &["f"]),
+    (Action::NarrowColumn, "narrow_column", "Narrow the column", &["["]),
+    (Action::WidenColumn, "widen_column", "Widen the column", &["]"]),
+    (Action::ExpandColumn, "expand_column", "Show the column at full width", &["z"]),
+    (Action::PinColumns, "pin_columns", "Pin columns up to this one", &["p"]),
+    (Action::ChooseColumns, "choose_columns", "Choose and order columns", &["c"]),
+    (Action::JsonPaths, "json_paths", "Browse JSON paths", &["j"]),
+    (Action::DiffCell, "diff_cell", "Mark or diff cells", &["d"]),
+    (Action::SaveCell, "save_cell", "Save the cell to a file", &["shift+s"]),
+    (Action::ExportInserts, "export_inserts", "Export rows as INSERTs", &["i"]),
+    (Action::CopyMarkdown, "copy_markdown", "Copy the results as a Markdown table", &["y"]),
+    (Action::CopyOrgTable, "copy_org_table", "Copy the results as an org table", &["shift+y"]),
+    (Action::OpenCell, "open_cell", "Open the cell in $EDITOR", &["o"]),
+];
+
+// Every action and its title, in the order they are listed above
+pub fn actions() -> impl Iterator<Item = (Action, &'static str)> {
+    DEFAULTS.iter().map(|&(action, _, title, _)| (action, title))
+}
+
+impl Action {
+    pub fn scope(self) -> Scope {
+        match self {
+            Action::RecordMacro | Action::ReplayMacro | Action::ShowTour => Scope::Global,
+            Action::OpenPalette
+            | Action::CommandSearch
+            | Action::ShowMessages
+            | Action::SwitchTab(_)
+            | Action::NewConnection
+            | Action::CloseConnection
+            | Action::ToggleSplit
+            | Action::FocusSplit
+            | Action::CycleLayout
+            | Action::ToggleStatusBar
+            | Action::ShrinkEditor
+            | Action::GrowEditor
+            | Action::SaveLayout
+            | Action::ExportSession
+            | Action::ActivityMonitor
+            | Action::ReloadCompletion
+            | Action::SchemaSearch
+            | Action::StatementTimeout
+            | Action::Reconnect
+            | Action::OpenFavorites
+            | Action::RecentObjects => Scope::Workspace,
+            Action::FilterBrowser
+            | Action::RefreshBrowser
+            | Action::ShowDdl
+            | Action::InsertRow
+            | Action::ImportCsv
+            | Action::LargestTables
+            | Action::SchemaDiagram
+            | Action::ExportSchema
+            | Action::RestartSequence
+            | Action::RefreshMatview
+            | Action::SwitchDatabase
+            | Action::NarrowBrowser
+            | Action::WidenBrowser
+            | Action::ToggleDetails
+            | Action::ToggleFavorite
+            | Action::ExpandAll
+            | Action::CollapseAll
+            | Action::DropUnusedIndexes => Scope::Browser,
+            Action::FormatQuery
+            | Action::FilterResults
+            | Action::RunScript
+            | Action::ExecuteQuery
+            | Action::ExpandStar
+            | Action::QualifyColumns
+            | Action::SaveFile
+            | Action::OpenFile
+            | Action::ToggleResultsFocus
+            | Action::ShowLint
+            | Action::InspectToken
+            | Action::RunWithoutLimit
+            | Action::ResultsHistory
+            | Action::WatchQuery
+            | Action::BenchmarkQuery
+            | Action::PreviewDdl
+            | Action::ExplainQuery
+            | Action::StarQuery => Scope::Query,
+            Action::ScrollResultsLeft | Action::ScrollResultsRight | Action::WordLeft | Action::WordRight => {
+                Scope::Editor
+            }
+            Action::EditCell
+            | Action::SortColumn
+            | Action::GotoRow
+            | Action::ShowRecord
+            | Action::SearchResults
+            | Action::FindNext
+            | Action::FindPrevious
+            | Action::HideColumn
+            | Action::MoveColumnLeft
+            | Action::MoveColumnRight
+            | Action::SaveTableView
+            | Action::ResetTableView
+            | Action::FetchMore
+            | Action::FitColumns
+            | Action::NarrowColumn
+            | Action::WidenColumn
+            | Action::ExpandColumn
+            | Action::PinColumns
+            | Action::ChooseColumns
+            | Action::JsonPaths
+            | Action::DiffCell
+            | Action::SaveCell
+            | Action::ExportInserts
+            | Action::CopyMarkdown
+            | Action::CopyOrgTable
+            | Action::OpenCell => Scope::Results,
+        }
+    }
+}
+
+// A key with its modifiers, compared after normalization so "alt+shift+f"
+// matches both `F` and `f` with Shift held
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct KeyBinding {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    fn from_event(key: KeyEvent) -> Self {
+        let mut modifiers = key.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        let code = match key.code {
+            KeyCode::Char(c) if c.is_ascii_uppercase() => {
+                modifiers.insert(KeyModifiers::SHIFT);
+                KeyCode::Char(c.to_ascii_lowercase())
+            }
+            // Shift is how `<`, `?` etc. are typed, not part of the binding
+            KeyCode::Char(c) if !c.is_alphabetic() => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::Char(c)
+            }
+            KeyCode::BackTab => {
+                modifiers.insert(KeyModifiers::SHIFT);
+                KeyCode::Tab
+            }
+            code => code,
+        };
+        Self { code, modifiers }
+    }
+
+    // "ctrl+shift+enter", "f5", "alt+1", "/"
+    fn parse(spec: &str) -> Option<Self> {
+        let spec = spec.trim().to_lowercase();
+        // A trailing "+" is the plus key itself
+        let (mods, key) = if spec == "+" {
+            ("", "+")
+        } else if let Some(mods) = spec.strip_suffix("++") {
+            (mods, "+")
+        } else {
+            spec.rsplit_once('+').unwrap_or(("", spec.as_str()))
+        };
+        let mut modifiers = KeyModifiers::NONE;
+        for part in mods.split('+').filter(|p| !p.is_empty()) {
+            modifiers.insert(match part {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return None,
+            });
+        }
+        let code = match key {
+            "enter" => KeyCode::Enter,
+            "esc" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backspace" => KeyCode::Backspace,
+            "delete" => KeyCode::Delete,
+            "insert" => KeyCode::Insert,
+            "space" => KeyCode::Char(' '),
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            _ if key.starts_with('f') && key.len() > 1 => KeyCode::F(key[1..].parse().ok()?),
+            _ => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => return None,
+                }
+            }
+        };
+        Some(Self::from_event(KeyEvent::new(code, modifiers)))
+    }
+
+    // "Ctrl+Enter", "F5", "/"
+    fn label(&self) -> String {
+        let mut label = String::new();
+        for (modifier, name) in [
+            (KeyModifiers::CONTROL, "Ctrl+"),
+            (KeyModifiers::ALT, "Alt+"),
+            (KeyModifiers::SHIFT, "Shift+"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                label.push_str(name);
+            }
+        }
+        match self.code {
+            KeyCode::Char(' ') => label.push_str("Space"),
+            KeyCode::Char(c) if self.modifiers.is_empty() => label.push(c),
+            KeyCode::Char(c) => label.push(c.to_ascii_uppercase()),
+            KeyCode::F(n) => label.push_str(&format!("F{}", n)),
+            KeyCode::Up => label.push('↑'),
+            KeyCode::Down => label.push('↓'),
+            KeyCode::Left => label.push('←'),
+            KeyCode::Right => label.push('→'),
+            code => label.push_str(&format!("{:?}", code)),
+        }
+        label
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(KeyBinding, Action)>,
+}
+
+impl Keymap {
+    // Defaults with the config's `keybindings` overrides applied. Unknown
+    // action names and unparsable keys are returned as warnings and skipped.
+    pub fn new(overrides: &BTreeMap<String, KeySpec>) -> (Self, Vec<String>) {
+        let mut warnings = Vec::new();
+        for name in overrides.keys() {
+            if !DEFAULTS.iter().any(|(_, n, _, _)| n == name) {
+                warnings.push(format!("Unknown action \"{}\" in keybindings", name));
+            }
+        }
+
+        let mut bindings = Vec::new();
+        // Overridden actions first, so a remapped key wins over a default
+        // that happens to use it too
+        for overridden in [true, false] {
+            for &(action, name, _, default_keys) in DEFAULTS {
+                let keys: Vec<&str> = match overrides.get(name) {
+                    Some(spec) if overridden => spec.keys(),
+                    None if !overridden => default_keys.to_vec(),
+                    _ => continue,
+                };
+                for key in keys {
+                    match KeyBinding::parse(key) {
+                        Some(binding) => bindings.push((binding, action)),
+                        None => warnings.push(format!("Invalid key \"{}\" for {}", key, name)),
+                    }
+                }
+            }
+        }
+        (Self { bindings }, warnings)
+    }
+
+    pub fn lookup(&self, key: KeyEvent, scope: Scope) -> Option<Action> {
+        let pressed = KeyBinding::from_event(key);
+        self.bindings
+            .iter()
+            .find(|(binding, action)| *binding == pressed && action.scope() == scope)
+            .map(|&(_, action)| action)
+    }
+
+    // Keys bound to `action` for the status bar, e.g. "Ctrl+Enter/F5"
+    pub fn label(&self, action: Action) -> String {
+        let labels: Vec<String> = self
+            .bindings
+            .iter()
+            .filter(|(_, a)| *a == action)
+            .map(|(binding, _)| binding.label())
+            .collect();
+        if labels.is_empty() {
+            "unbound".to_string()
+        } else {
+            labels.join("/")
+        }
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(&BTreeMap::new()).0
+    }
+}
@@ -0,0 +1,88 @@
+use std::path::PathBuf;
+
+// libpq conventions: PG* environment variables and the ~/.pgpass file
+
+/// Value of a PG* environment variable, or `fallback` when unset or empty.
+pub fn env_or(var: &str, fallback: &str) -> String {
+    std::env::var(var)
+        .ok()
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| fallback.to_string())
+}
+
+/// Password for the given connection from PGPASSWORD or the password file,
+/// matched the same way libpq does.
+pub fn lookup_password(host: &str, port: &str, database: &str, user: &str) -> Option<String> {
+    if let Some(password) = std::env::var("PGPASSWORD").ok().filter(|p| !p.is_empty()) {
+        return Some(password);
+    }
+
+    let path = pgpass_path()?;
+    if !permissions_ok(&path) {
+        return None;
+    }
+    let contents = std::fs::read_to_string(path).ok()?;
+
+    // Socket directories match the "localhost" entry
+    let host = if host.starts_with('/') { "localhost" } else { host };
+    let wanted = [host, port, database, user];
+
+    contents
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .filter_map(|line| {
+            let fields = split_fields(line);
+            (fields.len() == 5).then_some(fields)
+        })
+        .find(|fields| {
+            fields[..4]
+                .iter()
+                .zip(wanted)
+                .all(|(field, value)| field == "*" || field == value)
+        })
+        .map(|mut fields| fields.remove(4))
+}
+
+fn pgpass_path() -> Option<PathBuf> {
+    if let Ok(path) = std::env::var("PGPASSFILE") {
+        return Some(PathBuf::from(path));
+    }
+    if cfg!(windows) {
+        // %APPDATA%\postgresql\pgpass.conf
+        Some(dirs::config_dir()?.join("postgresql").join("pgpass.conf"))
+    } else {
+        Some(dirs::home_dir()?.join(".pgpass"))
+    }
+}
+
+// libpq ignores a password file that group or others can read
+#[cfg(unix)]
+fn permissions_ok(path: &std::path::Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path)
+        .map(|meta| meta.permissions().mode() & 0o077 == 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn permissions_ok(_path: &std::path::Path) -> bool {
+    true
+}
+
+// Split a pgpass line on unescaped ':', handling `\:` and `\\`
+fn split_fields(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut chars = line.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => {
+                if let Some(next) = chars.next() {
+                    fields.last_mut().unwrap().push(next);
+                }
+            }
+            ':' => fields.push(String::new()),
+            _ => fields.last_mut().unwrap().push(ch),
+        }
+    }
+    fields
+}
@@ -0,0 +1,193 @@
+use crate::syntax::{SqlHighlighter, TokenType};
+
+// Lightweight lint pass over the editor buffer. It works on the highlighter's
+// tokens, so it never needs a connection and tolerates half-typed SQL.
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    // Byte range of the offending token in the linted text
+    pub start: usize,
+    pub end: usize,
+    pub message: String,
+}
+
+struct Tok {
+    text: String,
+    upper: String,
+    kind: TokenType,
+    start: usize,
+    end: usize,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Clause {
+    Select,
+    From,
+    Where,
+    Other,
+}
+
+// Clause state of one parenthesis level
+struct Level {
+    clause: Clause,
+    has_where: bool,
+    comma_joins: Vec<usize>,
+}
+
+impl Level {
+    fn new() -> Self {
+        Self {
+            clause: Clause::Other,
+            has_where: false,
+            comma_joins: Vec::new(),
+        }
+    }
+}
+
+pub fn lint(sql: &str) -> Vec<Diagnostic> {
+    let mut tokens = Vec::new();
+    let mut offset = 0;
+    for token in SqlHighlighter::new().tokenize(sql) {
+        let end = offset + token.text.len();
+        if !matches!(token.token_type, TokenType::Whitespace | TokenType::Comment) {
+            tokens.push(Tok {
+                upper: token.text.to_uppercase(),
+                text: token.text,
+                kind: token.token_type,
+                start: offset,
+                end,
+            });
+        }
+        offset = end;
+    }
+
+    let mut diagnostics = Vec::new();
+    for statement in tokens.split(|t| t.text == ";") {
+        lint_statement(statement, &mut diagnostics);
+    }
+    diagnostics.sort_by_key(|d| d.start);
+    diagnostics
+}
+
+fn lint_statement(tokens: &[Tok], out: &mut Vec<Diagnostic>) {
+    let Some(first) = tokens.first() else {
+        return;
+    };
+
+    let mut levels = vec![Level::new()];
+    for (i, tok) in tokens.iter().enumerate() {
+        let prev = i.checked_sub(1).map(|p| tokens[p].upper.as_str()).unwrap_or("");
+        match tok.text.as_str() {
+            "(" => {
+                levels.push(Level::new());
+                continue;
+            }
+            ")" if levels.len() > 1 => {
+                let level = levels.pop().unwrap();
+                flag_comma_joins(tokens, &level, out);
+                continue;
+            }
+            _ => {}
+        }
+        let level = levels.last_mut().unwrap();
+
+        if tok.kind == TokenType::Keyword {
+            match tok.upper.as_str() {
+                "SELECT" => level.clause = Clause::Select,
+                "FROM" if level.clause == Clause::Select || first.upper == "DELETE" => level.clause = Clause::From,
+                "WHERE" => {
+                    level.clause = Clause::Where;
+                    level.has_where = true;
+                }
+                "GROUP" | "ORDER" | "HAVING" | "LIMIT" | "OFFSET" | "UNION" | "INTERSECT" | "EXCEPT"
+                | "SET" | "VALUES" | "ON" => level.clause = Clause::Other,
+                _ => {}
+            }
+        }
+
+        match level.clause {
+            Clause::Select if tok.text == "*" && matches!(prev, "SELECT" | "DISTINCT" | "ALL" | ",") => {
+                out.push(diagnostic(tok, "SELECT * fetches every column; list the ones you need"));
+            }
+            Clause::From if tok.text == "," => level.comma_joins.push(i),
+            Clause::Where => {
+                if let Some(message) = non_sargable(tokens, i) {
+                    out.push(diagnostic(tok, message));
+                }
+            }
+            _ => {}
+        }
+    }
+    for level in &levels {
+        flag_comma_joins(tokens, level, out);
+    }
+
+    if matches!(first.upper.as_str(), "UPDATE" | "DELETE") && !levels[0].has_where {
+        out.push(diagnostic(first, &format!("{} without WHERE affects every row", first.upper)));
+    }
+}
+
+fn flag_comma_joins(tokens: &[Tok], level: &Level, out: &mut Vec<Diagnostic>) {
+    let message = if level.has_where {
+        "Implicit join; prefer an explicit JOIN ... ON"
+    } else {
+        "Implicit cross join: FROM list without a WHERE clause"
+    };
+    for &i in &level.comma_joins {
+        out.push(diagnostic(&tokens[i], message));
+    }
+}
+
+// Predicates in WHERE that keep an index on the column from being used
+fn non_sargable(tokens: &[Tok], i: usize) -> Option<&'static str> {
+    let tok = &tokens[i];
+    let prev = i.checked_sub(1).map(|p| tokens[p].upper.as_str()).unwrap_or("");
+
+    if tok.kind == TokenType::String && matches!(prev, "LIKE" | "ILIKE") && tok.text.starts_with("'%") {
+        return Some("Leading wildcard in LIKE cannot use an index");
+    }
+
+    // f(column) <op> ...
+    let is_call = matches!(tok.kind, TokenType::Identifier | TokenType::Keyword)
+        && !matches!(tok.upper.as_str(), "IN" | "EXISTS" | "ANY" | "ALL" | "AND" | "OR" | "NOT" | "WHERE")
+        && tokens.get(i + 1).is_some_and(|t| t.text == "(");
+    if !is_call {
+        return None;
+    }
+    let mut depth = 0;
+    let mut wraps_column = false;
+    for (j, inner) in tokens.iter().enumerate().skip(i + 1) {
+        match inner.text.as_str() {
+            "(" => depth += 1,
+            ")" => {
+                depth -= 1;
+                if depth == 0 {
+                    let compared = tokens
+                        .get(j + 1)
+                        .is_some_and(|t| matches!(t.text.as_str(), "=" | "<" | ">" | "<=" | ">=" | "<>" | "!="));
+                    return (wraps_column && compared)
+                        .then_some("Function on a column in WHERE prevents index use");
+                }
+            }
+            _ if inner.kind == TokenType::Identifier => wraps_column = true,
+            _ => {}
+        }
+    }
+    None
+}
+
+fn diagnostic(tok: &Tok, message: &str) -> Diagnostic {
+    Diagnostic {
+        start: tok.start,
+        end: tok.end,
+        message: message.to_string(),
+    }
+}
+
+// 1-based line and column of a byte offset, for the diagnostics list
+pub fn line_col(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset.min(text.len())];
+    let line = before.matches('\n').count() + 1;
+    let col = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+    (line, col)
+}
@@ -0,0 +1,353 @@
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::oneshot;
+
+use crate::autocomplete::{Suggestion, SuggestionType};
+use crate::config::LspConfig;
+use crate::lint::Diagnostic;
+
+// Minimal client for an external SQL language server (sqls,
+// postgres-language-server, ...) over stdio. The editor buffer is exposed to
+// the server as a single document with full-text sync.
+
+const DOCUMENT_URI: &str = "file:///pg-tui/query.sql";
+const REQUEST_TIMEOUT: Duration = Duration::from_millis(500);
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(5);
+
+type PendingRequests = Arc<Mutex<HashMap<i64, oneshot::Sender<Result<Value>>>>>;
+
+// Diagnostics as published by the server, in LSP line/character positions
+struct Published {
+    items: Vec<(Value, String)>,
+    fresh: bool,
+}
+
+pub struct LspClient {
+    // The server is killed when the client is dropped
+    child: Child,
+    stdin: ChildStdin,
+    next_id: i64,
+    version: i64,
+    synced_text: String,
+    pending: PendingRequests,
+    published: Arc<Mutex<Published>>,
+}
+
+// Connection details handed to the server as libpq environment variables
+pub struct ServerEnv<'a> {
+    pub host: &'a str,
+    pub port: &'a str,
+    pub database: &'a str,
+    pub user: &'a str,
+    pub password: &'a str,
+}
+
+impl LspClient {
+    pub async fn start(config: &LspConfig, env: ServerEnv<'_>) -> Result<Self> {
+        let mut command = Command::new(&config.command);
+        command
+            .args(&config.args)
+            .env("PGHOST", env.host)
+            .env("PGPORT", env.port)
+            .env("PGDATABASE", env.database)
+            .env("PGUSER", env.user)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true);
+        if !env.password.is_empty() {
+            command.env("PGPASSWORD", env.password);
+        }
+        let mut child = command
+            .spawn()
+            .with_context(|| format!("Failed to start language server `{}`", config.command))?;
+        let stdin = child.stdin.take().ok_or_else(|| anyhow!("Language server has no stdin"))?;
+        let stdout = child.stdout.take().ok_or_else(|| anyhow!("Language server has no stdout"))?;
+
+        let pending: PendingRequests = Arc::default();
+        let published = Arc::new(Mutex::new(Published { items: Vec::new(), fresh: false }));
+        tokio::spawn(read_messages(stdout, pending.clone(), published.clone()));
+
+        let mut client = Self {
+            child,
+            stdin,
+            next_id: 1,
+            version: 1,
+            synced_text: String::new(),
+            pending,
+            published,
+        };
+
+        let params = json!({
+            "processId": std::process::id(),
+            "rootUri": null,
+            "capabilities": {
+                "textDocument": {
+                    "completion": { "completionItem": { "snippetSupport": false } },
+                    "hover": { "contentFormat": ["plaintext", "markdown"] },
+                    "publishDiagnostics": {},
+                },
+            },
+            "initializationOptions": config.initialization_options,
+        });
+        client
+            .request_with_timeout("initialize", params, STARTUP_TIMEOUT)
+            .await
+            .context("Language server did not initialize")?;
+        client.notify("initialized", json!({})).await?;
+        client
+            .notify(
+                "textDocument/didOpen",
+                json!({
+                    "textDocument": {
+                        "uri": DOCUMENT_URI,
+                        "languageId": "sql",
+                        "version": client.version,
+                        "text": "",
+                    }
+                }),
+            )
+            .await?;
+        Ok(client)
+    }
+
+    pub fn is_running(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
+    // Send the editor text if it changed since the last sync
+    pub async fn sync(&mut self, text: &str) -> Result<()> {
+        if self.synced_text == text {
+            return Ok(());
+        }
+        self.version += 1;
+        self.notify(
+            "textDocument/didChange",
+            json!({
+                "textDocument": { "uri": DOCUMENT_URI, "version": self.version },
+                "contentChanges": [{ "text": text }],
+            }),
+        )
+        .await?;
+        self.synced_text = text.to_string();
+        Ok(())
+    }
+
+    pub async fn completion(&mut self, text: &str, cursor: usize) -> Result<Vec<Suggestion>> {
+        self.sync(text).await?;
+        let response = self
+            .request("textDocument/completion", position_params(text, cursor))
+            .await?;
+        // Either CompletionItem[] or CompletionList
+        let items = response
+            .get("items")
+            .unwrap_or(&response)
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        Ok(items
+            .iter()
+            .filter_map(|item| {
+                let label = item.get("label")?.as_str()?;
+                let text = item.get("insertText").and_then(Value::as_str).unwrap_or(label);
+                let suggestion_type = match item.get("kind").and_then(Value::as_u64) {
+                    Some(5) | Some(10) => SuggestionType::Column,
+                    Some(7) | Some(9) | Some(22) => SuggestionType::Table,
+                    Some(2) | Some(3) => SuggestionType::Function,
+                    _ => SuggestionType::Keyword,
+                };
+                let detail = item.get("detail").and_then(Value::as_str).map(str::to_string);
+                Some(Suggestion::new(suggestion_type, text.to_string(), detail))
+            })
+            .take(50)
+            .collect())
+    }
+
+    pub async fn hover(&mut self, text: &str, cursor: usize) -> Result<Option<String>> {
+        self.sync(text).await?;
+        let response = self.request("textDocument/hover", position_params(text, cursor)).await?;
+        Ok(response.get("contents").map(hover_text).filter(|s| !s.trim().is_empty()))
+    }
+
+    // Diagnostics published since the last call, mapped onto the synced text
+    pub fn take_diagnostics(&mut self) -> Option<Vec<Diagnostic>> {
+        let text = &self.synced_text;
+        let mut published = self.published.lock().ok()?;
+        if !published.fresh {
+            return None;
+        }
+        published.fresh = false;
+        Some(
+            published
+                .items
+                .iter()
+                .map(|(range, message)| {
+                    let offset = |key: &str| {
+                        let pos = &range[key];
+                        byte_offset(
+                            text,
+                            pos["line"].as_u64().unwrap_or(0) as usize,
+                            pos["character"].as_u64().unwrap_or(0) as usize,
+                        )
+                    };
+                    let (start, end) = (offset("start"), offset("end"));
+                    Diagnostic {
+                        start,
+                        end: end.max(start),
+                        message: message.clone(),
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    async fn request(&mut self, method: &str, params: Value) -> Result<Value> {
+        self.request_with_timeout(method, params, REQUEST_TIMEOUT).await
+    }
+
+    async fn request_with_timeout(&mut self, method: &str, params: Value, timeout: Duration) -> Result<Value> {
+        let id = self.next_id;
+        self.next_id += 1;
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().map_err(|_| anyhow!("Language server state poisoned"))?.insert(id, tx);
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))
+            .await?;
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => bail!("Language server exited"),
+            Err(_) => {
+                if let Ok(mut pending) = self.pending.lock() {
+                    pending.remove(&id);
+                }
+                bail!("Language server did not answer {} in time", method)
+            }
+        }
+    }
+
+    async fn notify(&mut self, method: &str, params: Value) -> Result<()> {
+        self.send(json!({ "jsonrpc": "2.0", "method": method, "params": params })).await
+    }
+
+    async fn send(&mut self, message: Value) -> Result<()> {
+        let body = message.to_string();
+        let frame = format!("Content-Length: {}\r\n\r\n{}", body.len(), body);
+        self.stdin
+            .write_all(frame.as_bytes())
+            .await
+            .context("Failed to write to language server")?;
+        self.stdin.flush().await.context("Failed to write to language server")
+    }
+}
+
+// Reads framed messages until the server closes stdout
+async fn read_messages(stdout: ChildStdout, pending: PendingRequests, published: Arc<Mutex<Published>>) {
+    let mut reader = BufReader::new(stdout);
+    loop {
+        let mut length = None;
+        loop {
+            let mut header = String::new();
+            match reader.read_line(&mut header).await {
+                Ok(0) | Err(_) => return,
+                Ok(_) => {}
+            }
+            let header = header.trim();
+            if header.is_empty() {
+                break;
+            }
+            if let Some(value) = header.strip_prefix("Content-Length:") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+        let Some(length) = length else {
+            continue;
+        };
+        let mut body = vec![0; length];
+        if reader.read_exact(&mut body).await.is_err() {
+            return;
+        }
+        let Ok(message) = serde_json::from_slice::<Value>(&body) else {
+            continue;
+        };
+
+        if let Some(id) = message.get("id").and_then(Value::as_i64) {
+            if message.get("method").is_none() {
+                let result = match message.get("error") {
+                    Some(error) => Err(anyhow!(
+                        "{}",
+                        error.get("message").and_then(Value::as_str).unwrap_or("Language server error")
+                    )),
+                    None => Ok(message.get("result").cloned().unwrap_or(Value::Null)),
+                };
+                if let Some(tx) = pending.lock().ok().and_then(|mut p| p.remove(&id)) {
+                    let _ = tx.send(result);
+                }
+                continue;
+            }
+        }
+
+        if message.get("method").and_then(Value::as_str) == Some("textDocument/publishDiagnostics") {
+            let items = message["params"]["diagnostics"]
+                .as_array()
+                .map(|list| {
+                    list.iter()
+                        .map(|d| {
+                            let text = d["message"].as_str().unwrap_or_default();
+                            let message = match d["source"].as_str() {
+                                Some(source) => format!("{}: {}", source, text),
+                                None => text.to_string(),
+                            };
+                            (d["range"].clone(), message)
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            if let Ok(mut published) = published.lock() {
+                published.items = items;
+                published.fresh = true;
+            }
+        }
+    }
+}
+
+fn position_params(text: &str, cursor: usize) -> Value {
+    let mut cursor = cursor.min(text.len());
+    while !text.is_char_boundary(cursor) {
+        cursor -= 1;
+    }
+    let before = &text[..cursor];
+    let line = before.matches('\n').count();
+    let character: usize = before.rsplit('\n').next().unwrap_or("").encode_utf16().count();
+    json!({
+        "textDocument": { "uri": DOCUMENT_URI },
+        "position": { "line": line, "character": character },
+    })
+}
+
+// LSP positions count UTF-16 code units within a line
+fn byte_offset(text: &str, line: usize, character: usize) -> usize {
+    let line_start: usize = text.split_inclusive('\n').take(line).map(str::len).sum();
+    let mut units = 0;
+    for (i, c) in text[line_start.min(text.len())..].char_indices() {
+        if units >= character || c == '\n' {
+            return line_start + i;
+        }
+        units += c.len_utf16();
+    }
+    text.len()
+}
+
+// MarkupContent, MarkedString or an array of MarkedString
+fn hover_text(contents: &Value) -> String {
+    match contents {
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items.iter().map(hover_text).collect::<Vec<_>>().join("\n\n"),
+        Value::Object(map) => map.get("value").and_then(Value::as_str).unwrap_or_default().to_string(),
+        _ => String::new(),
+    }
+}
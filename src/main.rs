@@ -1,6 +1,9 @@
-use anyhow::Result;
+use anyhow::{anyhow, bail, Context, Result};
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, KeyModifiers},
+    event::{
+        self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture, Event, KeyCode,
+        KeyEvent, KeyEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -9,29 +12,45 @@ use ratatui::{
     Terminal,
 };
 use std::io;
+use std::time::{Duration, Instant};
 
 mod app;
+mod audit;
 mod autocomplete;
+mod clipboard;
 mod config;
 mod db;
-mod events;
+mod docs;
 mod formatter;
+mod keymap;
+mod libpq;
+mod lint;
+mod lsp;
+mod recovery;
+mod results_history;
+mod secrets;
+mod stats;
 mod syntax;
+mod theme;
 mod ui;
 
-use app::{App, AppMode};
+use app::{App, AppMode, MacroInput, PromptAction, QueryFocus};
+use keymap::{Action, Scope};
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let connect = connect_argument()?;
+
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture, EnableBracketedPaste)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
     // Create app state
     let mut app = App::new();
+    app.auto_connect(connect.as_deref()).await;
 
     // Run app
     let res = run_app(&mut terminal, &mut app).await;
@@ -41,100 +60,561 @@ async fn main() -> Result<()> {
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        DisableBracketedPaste
     )?;
     terminal.show_cursor()?;
 
-    if let Err(err) = res {
-        eprintln!("Error: {:?}", err);
+    if let Err(err) = app.save_column_widths() {
+        eprintln!("Error: {:#}", err);
+    }
+    // An error exit keeps the autosave snapshot for the next launch
+    match res {
+        Ok(()) => app.finish_autosave(),
+        Err(err) => eprintln!("Error: {:?}", err),
+    }
+
+    if app.config.session_summary && !app.stats.is_empty() {
+        println!("{}", app.stats.summary());
     }
 
     Ok(())
 }
 
+// `--connect <profile>`: the saved connection to open at startup, by its
+// name or as user@host:port/database
+fn connect_argument() -> Result<Option<String>> {
+    let mut args = std::env::args().skip(1);
+    let mut profile = None;
+    while let Some(arg) = args.next() {
+        if arg == "--connect" {
+            profile = Some(args.next().ok_or_else(|| anyhow!("--connect needs a connection name"))?);
+        } else if let Some(name) = arg.strip_prefix("--connect=") {
+            profile = Some(name.to_string());
+        } else {
+            bail!("Unknown argument '{}' (usage: pg-tui [--connect <connection>])", arg);
+        }
+    }
+    Ok(profile)
+}
+
+// How long typing has to pause before the editor is linted
+const LINT_IDLE_DELAY: Duration = Duration::from_millis(400);
+
+// Poll interval of low-power mode while the screen is idle
+const LOW_POWER_POLL: Duration = Duration::from_millis(500);
+
+// Toasts have to disappear on time, import and refresh progress has to move and the
+// lint pass follows a pause in typing; otherwise low-power mode can wait longer for input
+fn poll_interval(app: &App, last_key: Instant) -> Duration {
+    let interval = Duration::from_millis(app.config.poll_interval_ms.max(1));
+    let idle = app.active_notifications().next().is_none()
+        && app.import_job.is_none()
+        && app.refresh_job.is_none()
+        && app.ws.reconnect.is_none()
+        && last_key.elapsed() >= LINT_IDLE_DELAY;
+    if app.config.low_power && idle {
+        interval.max(LOW_POWER_POLL)
+    } else {
+        interval
+    }
+}
+
 async fn run_app<B: ratatui::backend::Backend>(
     terminal: &mut Terminal<B>,
     app: &mut App,
 ) -> Result<()> {
+    let mut last_key = Instant::now();
     loop {
         terminal.draw(|f| ui::render(f, app))?;
 
-        if event::poll(std::time::Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    match app.mode {
-                        AppMode::ConnectionSelector => {
-                            if handle_selector_input(app, key.code) {
-                                return Ok(());
-                            }
-                        }
-                        AppMode::ConnectionEdit => {
-                            if handle_connection_input(app, key.code).await {
-                                return Ok(());
-                            }
-                        }
-                        AppMode::Browser => {
-                            if handle_browser_input(app, key.code).await? {
-                                return Ok(());
-                            }
-                        }
-                        AppMode::Query => {
-                            // Handle results filter mode first
-                            if app.results_filter_active {
-                                match key.code {
-                                    KeyCode::Esc => {
-                                        app.clear_results_filter();
-                                    }
-                                    _ => {
-                                        app.handle_results_filter_input(key.code);
-                                    }
-                                }
-                            // Check for Alt+Shift+F to format query
-                            } else if key.modifiers.contains(KeyModifiers::ALT) 
-                                && key.modifiers.contains(KeyModifiers::SHIFT) 
-                                && key.code == KeyCode::Char('F') {
-                                app.format_current_query();
-                            // Check for Ctrl+F to activate filter
-                            } else if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('f') {
-                                if app.query_result.is_some() {
-                                    app.activate_results_filter();
-                                }
-                            // Check for Ctrl+Enter or F5 to execute query
-                            } else if (key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Enter)
-                                || key.code == KeyCode::F(5) {
-                                app.execute_query().await?;
-                                // Reset scroll offset for new results
-                                app.result_scroll_offset = 0;
-                            } else if key.modifiers.contains(KeyModifiers::SHIFT) && key.code == KeyCode::Left {
-                                // Scroll results left
-                                app.scroll_results_left();
-                            } else if key.modifiers.contains(KeyModifiers::SHIFT) && key.code == KeyCode::Right {
-                                // Scroll results right
-                                app.scroll_results_right();
-                            } else if handle_query_input(app, key.code).await? {
+        if event::poll(poll_interval(app, last_key))? {
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    last_key = Instant::now();
+                    if let MacroInput::Dispatch(keys) = app.macro_input(key) {
+                        for key in keys {
+                            if handle_key(app, key).await? {
                                 return Ok(());
                             }
                         }
                     }
                 }
+                Event::Paste(text) => {
+                    last_key = Instant::now();
+                    handle_paste(app, &text);
+                }
+                _ => {}
+            }
+        } else if last_key.elapsed() >= LINT_IDLE_DELAY {
+            app.lint_editor().await;
+        }
+        if let Some(path) = app.external_editor.take() {
+            match open_in_editor(terminal, &path) {
+                Ok(()) => app.set_status("Closed the editor; the cell itself is unchanged".to_string()),
+                Err(e) => app.set_error(format!("{:#}", e)),
+            }
+        }
+        app.poll_import().await;
+        app.poll_refresh().await;
+        app.poll_monitor().await;
+        app.poll_watch().await;
+        app.poll_catalog().await;
+        app.poll_connection().await;
+        app.poll_keepalive().await;
+        app.poll_column_widths();
+        app.autosave();
+    }
+}
+
+// Hand the terminal to $VISUAL / $EDITOR on `path` until it exits, then
+// take it back and remove the file
+fn open_in_editor<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, path: &std::path::Path) -> Result<()> {
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture, DisableBracketedPaste)?;
+    let status = app::editor_command(path).status();
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture, EnableBracketedPaste)?;
+    terminal.clear()?;
+    let _ = std::fs::remove_file(path);
+    let status = status.context("Failed to start the editor ($VISUAL or $EDITOR)")?;
+    if !status.success() {
+        bail!("The editor exited with {}", status);
+    }
+    Ok(())
+}
+
+// Bracketed paste: the whole block arrives as one event and is inserted in
+// one edit, without the per-character autocomplete typing it would trigger
+fn handle_paste(app: &mut App, text: &str) {
+    if app.prompt.is_some() {
+        app.paste_into_prompt(text);
+        return;
+    }
+    let modal = app.pending.is_some()
+        || app.destructive.is_some()
+        || app.cost_warning.is_some()
+        || app.insert_form.is_some()
+        || app.param_form.is_some()
+        || app.import_wizard.is_some()
+        || app.popup.is_some()
+        || app.palette.is_some()
+        || app.action_palette.is_some()
+        || app.results_picker.is_some()
+        || app.database_picker.is_some()
+        || app.schema_search.is_some()
+        || app.column_chooser.is_some()
+        || app.favorites_picker.is_some()
+        || app.recent_picker.is_some();
+    if app.mode == AppMode::Query && !modal && app.ws.query_focus == QueryFocus::Editor && !app.ws.results_filter_active {
+        app.paste_query_text(text);
+        let visible_lines = app.layout().editor_height.saturating_sub(2) as usize;
+        app.adjust_query_scroll(visible_lines.max(1));
+    }
+}
+
+// Dispatch one key press; returns true when the app should quit
+async fn handle_key(app: &mut App, key: KeyEvent) -> Result<bool> {
+    // An open prompt captures all input
+    if app.prompt.is_some() {
+        handle_prompt_input(app, key.code).await;
+        return Ok(false);
+    }
+    if app.pending.is_some() {
+        handle_pending_input(app, key.code).await;
+        return Ok(false);
+    }
+    if app.destructive.is_some() {
+        if let Err(e) = app.handle_destructive_input(key.code).await {
+            app.set_error(format!("{:#}", e));
+        }
+        return Ok(false);
+    }
+    if app.cost_warning.is_some() {
+        if let Err(e) = app.handle_cost_warning_input(key.code).await {
+            app.set_error(format!("{:#}", e));
+        }
+        return Ok(false);
+    }
+    if app.insert_form.is_some() {
+        handle_insert_form_input(app, key.code);
+        return Ok(false);
+    }
+    if app.param_form.is_some() {
+        handle_param_form_input(app, key.code).await;
+        return Ok(false);
+    }
+    if app.import_wizard.is_some() {
+        handle_import_wizard_input(app, key.code);
+        return Ok(false);
+    }
+    if app.popup.is_some() {
+        handle_popup_input(app, key.code);
+        return Ok(false);
+    }
+    if app.palette.is_some() {
+        handle_palette_input(app, key.code).await;
+        return Ok(false);
+    }
+    if app.results_picker.is_some() {
+        match key.code {
+            KeyCode::Esc => app.close_results_history(),
+            KeyCode::Enter => app.show_saved_result(),
+            code => app.handle_results_history_input(code),
+        }
+        return Ok(false);
+    }
+    if app.action_palette.is_some() {
+        match key.code {
+            KeyCode::Esc => app.close_action_palette(),
+            KeyCode::Enter => {
+                if let Some(action) = app.take_palette_action() {
+                    run_palette_action(app, action).await?;
+                }
+            }
+            code => app.handle_action_palette_input(code),
+        }
+        return Ok(false);
+    }
+    if app.database_picker.is_some() {
+        match key.code {
+            KeyCode::Esc => app.close_database_picker(),
+            KeyCode::Enter => app.pick_database().await,
+            code => app.handle_database_picker_input(code),
+        }
+        return Ok(false);
+    }
+    if app.schema_search.is_some() {
+        match key.code {
+            KeyCode::Esc => app.close_schema_search(),
+            KeyCode::Enter => app.jump_to_search_result().await,
+            code => app.handle_schema_search_input(code),
+        }
+        return Ok(false);
+    }
+    if app.json_path_picker.is_some() {
+        app.handle_json_paths_input(key.code);
+        return Ok(false);
+    }
+    if app.column_chooser.is_some() {
+        match key.code {
+            KeyCode::Esc => app.close_column_chooser(),
+            KeyCode::Enter => {
+                if let Err(e) = app.apply_column_chooser().await {
+                    app.set_error(format!("{:#}", e));
+                }
+            }
+            code => app.handle_column_chooser_input(code),
+        }
+        return Ok(false);
+    }
+    if app.favorites_picker.is_some() {
+        match key.code {
+            KeyCode::Esc => app.close_favorites_picker(),
+            KeyCode::Enter => app.open_picked_favorite().await,
+            code => app.handle_favorites_picker_input(code),
+        }
+        return Ok(false);
+    }
+    if app.recent_picker.is_some() {
+        match key.code {
+            KeyCode::Esc => app.close_recent_objects(),
+            KeyCode::Enter => app.jump_to_recent_object().await,
+            code => app.handle_recent_objects_input(code),
+        }
+        return Ok(false);
+    }
+    if app.cell_diff.is_some() {
+        app.handle_cell_diff_input(key.code);
+        return Ok(false);
+    }
+    if app.tour.is_some() {
+        app.handle_tour_input(key.code);
+        return Ok(false);
+    }
+    if app.keymap.lookup(key, Scope::Global) == Some(Action::ShowTour) {
+        app.start_tour();
+        return Ok(false);
+    }
+
+    if matches!(app.mode, AppMode::Browser | AppMode::Query) {
+        if let Some(action) = app.keymap.lookup(key, Scope::Workspace) {
+            run_workspace_action(app, action).await;
+            return Ok(false);
+        }
+    }
+
+    match app.mode {
+        AppMode::ConnectionSelector => {
+            if handle_selector_input(app, key.code) {
+                return Ok(true);
+            }
+        }
+        AppMode::ConnectionEdit => {
+            if handle_connection_input(app, key.code).await {
+                return Ok(true);
+            }
+        }
+        AppMode::Browser => {
+            if handle_browser_input(app, key).await? {
+                return Ok(true);
+            }
+        }
+        AppMode::Monitor => app.handle_monitor_input(key.code).await,
+        AppMode::Diagram => app.handle_diagram_input(key.code),
+        AppMode::Query => {
+            // Handle results filter mode first
+            if app.ws.results_filter_active {
+                match key.code {
+                    KeyCode::Esc => {
+                        app.clear_results_filter();
+                    }
+                    _ => {
+                        app.handle_results_filter_input(key.code);
+                    }
+                }
+            } else if let Some(action) = app.keymap.lookup(key, Scope::Query) {
+                run_query_action(app, action).await?;
+            } else if app.ws.query_focus == QueryFocus::Results {
+                handle_results_input(app, key).await?;
+            } else if let Some(action) = app.keymap.lookup(key, Scope::Editor) {
+                run_editor_action(app, action);
+            } else if handle_query_input(app, key.code).await? {
+                return Ok(true);
             }
         }
     }
+    Ok(false)
 }
 
+// An action chosen in the action palette, on the screen it belongs to
+async fn run_palette_action(app: &mut App, action: Action) -> Result<()> {
+    match action.scope() {
+        Scope::Global => {
+            if action == Action::ShowTour {
+                app.start_tour();
+            }
+        }
+        Scope::Workspace => run_workspace_action(app, action).await,
+        Scope::Browser => {
+            app.mode = AppMode::Browser;
+            run_browser_action(app, action).await?;
+        }
+        Scope::Query => {
+            app.mode = AppMode::Query;
+            run_query_action(app, action).await?;
+        }
+        Scope::Editor => {
+            app.mode = AppMode::Query;
+            app.ws.query_focus = QueryFocus::Editor;
+            run_editor_action(app, action);
+        }
+        Scope::Results => {
+            if app.ws.query_result.is_none() {
+                app.set_warning("Run a query first; that action works on its results".to_string());
+                return Ok(());
+            }
+            app.mode = AppMode::Query;
+            app.ws.query_focus = QueryFocus::Results;
+            run_results_action(app, action).await?;
+        }
+    }
+    Ok(())
+}
+
+fn run_editor_action(app: &mut App, action: Action) {
+    match action {
+        Action::ScrollResultsLeft => app.scroll_results_left(),
+        Action::ScrollResultsRight => app.scroll_results_right(),
+        Action::WordLeft => app.editor_word_jump(false),
+        Action::WordRight => app.editor_word_jump(true),
+        _ => {}
+    }
+    let visible_lines = app.layout().editor_height.saturating_sub(2) as usize;
+    app.adjust_query_scroll(visible_lines.max(1));
+}
+
+// Query screen actions, whichever of editor and results has focus
+async fn run_query_action(app: &mut App, action: Action) -> Result<()> {
+    match action {
+        Action::FormatQuery => app.format_current_query(),
+        Action::FilterResults if app.ws.query_result.is_some() => app.activate_results_filter(),
+        Action::RunScript => {
+            app.execute_script().await?;
+            app.reset_result_view();
+        }
+        Action::ExecuteQuery => {
+            app.execute_query().await?;
+            // Reset scroll offset and grid cursor for new results
+            app.reset_result_view();
+        }
+        Action::ExpandStar => app.expand_select_star().await?,
+        Action::QualifyColumns => app.qualify_columns().await?,
+        Action::SaveFile => app.save_file(),
+        Action::OpenFile => app.open_file_prompt(),
+        Action::ToggleResultsFocus => app.toggle_query_focus(),
+        Action::ShowLint => app.show_lint_popup(),
+        Action::InspectToken => app.inspect_token().await,
+        Action::BenchmarkQuery => app.begin_benchmark(),
+        Action::PreviewDdl => app.preview_ddl().await,
+        Action::ExplainQuery => app.explain_query().await,
+        Action::StarQuery => app.begin_star_query(),
+        Action::ResultsHistory => app.open_results_history(),
+        Action::WatchQuery => app.toggle_watch(),
+        Action::RunWithoutLimit => {
+            app.rerun_without_limit().await?;
+            app.reset_result_view();
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+
+async fn handle_prompt_input(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Esc => app.cancel_prompt(),
+        KeyCode::Enter => {
+            if let Err(e) = app.submit_prompt().await {
+                app.set_error(format!("{}", e));
+            }
+        }
+        _ => app.handle_prompt_input(key),
+    }
+}
+
+async fn handle_palette_input(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Esc => app.close_palette(),
+        KeyCode::Enter => {
+            if let Err(e) = app.run_palette_selection().await {
+                app.set_error(format!("{:#}", e));
+            }
+        }
+        _ => app.handle_palette_input(key),
+    }
+}
+
+async fn handle_pending_input(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Enter | KeyCode::Char('y') => {
+            if let Err(e) = app.execute_pending().await {
+                app.set_error(format!("{:#}", e));
+            }
+        }
+        KeyCode::Esc | KeyCode::Char('n') => app.cancel_pending(),
+        _ => {}
+    }
+}
+
+fn handle_insert_form_input(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Esc => app.close_insert_form(),
+        KeyCode::Enter => {
+            if let Err(e) = app.preview_insert() {
+                app.set_error(format!("{:#}", e));
+            }
+        }
+        _ => app.handle_insert_form_input(key),
+    }
+}
+
+async fn handle_param_form_input(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Esc => app.close_param_form(),
+        KeyCode::Enter => {
+            if let Err(e) = app.submit_params().await {
+                app.set_error(format!("{:#}", e));
+            }
+        }
+        _ => app.handle_param_form_input(key),
+    }
+}
+
+fn handle_import_wizard_input(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Esc => app.close_import_wizard(),
+        KeyCode::Enter => {
+            if let Err(e) = app.start_import() {
+                app.set_error(format!("{:#}", e));
+            }
+        }
+        _ => app.handle_import_wizard_input(key),
+    }
+}
+
+fn handle_popup_input(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => app.close_popup(),
+        KeyCode::Up => app.popup_scroll(-1),
+        KeyCode::Down => app.popup_scroll(1),
+        KeyCode::PageUp => app.popup_scroll(-20),
+        KeyCode::PageDown => app.popup_scroll(20),
+        KeyCode::Char('y') => app.copy_popup(),
+        _ => {}
+    }
+}
+
+// Connection tabs and layouts, shared by the browser and query screens
+async fn run_workspace_action(app: &mut App, action: Action) {
+    match action {
+        Action::OpenPalette => app.open_palette(),
+        Action::CommandSearch => app.open_action_palette(),
+        Action::ShowMessages => app.show_notification_log(),
+        Action::SwitchTab(n) => app.switch_workspace(n as usize - 1),
+        Action::NewConnection => app.add_workspace(),
+        Action::CloseConnection => app.request_close_workspace().await,
+        Action::ToggleSplit => app.toggle_split(),
+        Action::FocusSplit => app.focus_split_partner(),
+        Action::CycleLayout => app.cycle_layout(),
+        Action::ToggleStatusBar => app.toggle_status_bar(),
+        Action::ShrinkEditor => app.resize_editor(-1),
+        Action::GrowEditor => app.resize_editor(1),
+        Action::SaveLayout => {
+            let name = app.layout().name;
+            app.open_prompt("Save layout as", &name, PromptAction::SaveLayout);
+        }
+        Action::ExportSession => {
+            app.open_prompt("Export session report (.md or .json)", "session-report.md", PromptAction::ExportSession);
+        }
+        Action::ActivityMonitor => app.open_monitor(),
+        Action::ReloadCompletion => app.reload_catalog(),
+        Action::SchemaSearch => app.open_schema_search().await,
+        Action::StatementTimeout => app.begin_statement_timeout(),
+        Action::Reconnect => app.reconnect_now().await,
+        Action::OpenFavorites => app.open_favorites_picker(),
+        Action::RecentObjects => app.open_recent_objects(),
+        _ => {}
+    }
+}
 
 fn handle_selector_input(app: &mut App, key: KeyCode) -> bool {
     match key {
         KeyCode::Char('q') => return true,
+        // Back to the open connections, if any
+        KeyCode::Esc if app.has_workspaces() => app.mode = AppMode::Browser,
         KeyCode::Esc => return true,
         KeyCode::Up => app.selector_up(),
         KeyCode::Down => app.selector_down(),
-        KeyCode::Enter => {
-            if !app.config.connections.is_empty() {
-                app.load_selected_profile();
-            }
+        KeyCode::Enter if !app.config.connections.is_empty() => {
+            app.load_selected_profile();
         }
         KeyCode::Char('n') => app.create_new_connection(),
+        KeyCode::Char('t') => {
+            if let Err(e) = app.tag_selected_profile() {
+                app.set_error(format!("Failed to save profile: {}", e));
+            }
+        }
+        KeyCode::Char('r') => {
+            if let Err(e) = app.toggle_read_only() {
+                app.set_error(format!("Failed to save profile: {}", e));
+            }
+        }
+        KeyCode::Char('a') => {
+            if let Err(e) = app.toggle_auto_connect() {
+                app.set_error(format!("Failed to save profile: {}", e));
+            }
+        }
         KeyCode::Char('d') => {
             if let Err(e) = app.delete_selected_profile() {
                 app.set_error(format!("Failed to delete profile: {}", e));
@@ -166,10 +646,10 @@ async fn handle_connection_input(app: &mut App, key: KeyCode) -> bool {
     false
 }
 
-async fn handle_browser_input(app: &mut App, key: KeyCode) -> Result<bool> {
+async fn handle_browser_input(app: &mut App, key: KeyEvent) -> Result<bool> {
     // Handle filter mode
-    if app.filter_active {
-        match key {
+    if app.ws.filter_active {
+        match key.code {
             KeyCode::Esc => {
                 app.clear_filter();
                 return Ok(false);
@@ -182,9 +662,9 @@ async fn handle_browser_input(app: &mut App, key: KeyCode) -> Result<bool> {
             KeyCode::Up => {
                 // Navigate in filtered view
                 let filtered = app.get_filtered_items();
-                if let Some(current_pos) = filtered.iter().position(|&idx| idx == app.browser_selected) {
+                if let Some(current_pos) = filtered.iter().position(|&idx| idx == app.ws.browser_selected) {
                     if current_pos > 0 {
-                        app.browser_selected = filtered[current_pos - 1];
+                        app.ws.browser_selected = filtered[current_pos - 1];
                     }
                 }
                 return Ok(false);
@@ -192,21 +672,21 @@ async fn handle_browser_input(app: &mut App, key: KeyCode) -> Result<bool> {
             KeyCode::Down => {
                 // Navigate in filtered view
                 let filtered = app.get_filtered_items();
-                if let Some(current_pos) = filtered.iter().position(|&idx| idx == app.browser_selected) {
+                if let Some(current_pos) = filtered.iter().position(|&idx| idx == app.ws.browser_selected) {
                     if current_pos < filtered.len() - 1 {
-                        app.browser_selected = filtered[current_pos + 1];
+                        app.ws.browser_selected = filtered[current_pos + 1];
                     }
                 }
                 return Ok(false);
             }
             _ => {
                 // Handle filter text input
-                app.handle_filter_input(key);
+                app.handle_filter_input(key.code);
                 
                 // Auto-adjust selection to first filtered item
                 let filtered = app.get_filtered_items();
-                if !filtered.is_empty() && !filtered.contains(&app.browser_selected) {
-                    app.browser_selected = filtered[0];
+                if !filtered.is_empty() && !filtered.contains(&app.ws.browser_selected) {
+                    app.ws.browser_selected = filtered[0];
                 }
                 return Ok(false);
             }
@@ -214,36 +694,117 @@ async fn handle_browser_input(app: &mut App, key: KeyCode) -> Result<bool> {
     }
     
     // Normal browser mode
-    match key {
+    if let Some(action) = app.keymap.lookup(key, Scope::Browser) {
+        run_browser_action(app, action).await?;
+        return Ok(false);
+    }
+    match key.code {
         KeyCode::Char('q') => return Ok(true),
-        KeyCode::Char('/') => {
-            app.activate_filter();
-            return Ok(false);
-        }
         KeyCode::Up => app.browser_up(),
         KeyCode::Down => app.browser_down(),
         KeyCode::Enter => app.browser_select().await?,
         KeyCode::Tab => app.mode = AppMode::Query,
-        KeyCode::Char('r') => app.refresh_browser().await?,
+        KeyCode::PageUp => app.scroll_details_up(10),
+        KeyCode::PageDown => app.scroll_details_down(10),
+        KeyCode::Left => app.browser_left(),
+        KeyCode::Right => app.browser_right().await?,
         // Tab navigation (only when table is selected)
-        KeyCode::Left | KeyCode::Char('[') => {
-            if app.selected_table.is_some() {
-                app.prev_tab();
-            }
+        KeyCode::Char('[') if app.ws.selected_table.is_some() => app.prev_tab(),
+        KeyCode::Char(']') if app.ws.selected_table.is_some() => app.next_tab(),
+        _ => {}
+    }
+    Ok(false)
+}
+
+async fn run_browser_action(app: &mut App, action: Action) -> Result<()> {
+    match action {
+        Action::FilterBrowser => app.activate_filter(),
+        Action::RefreshBrowser => app.refresh_browser().await?,
+        Action::ShowDdl => app.show_table_ddl(),
+        Action::InsertRow if app.ws.selected_table.is_some() => app.open_insert_form(),
+        Action::ImportCsv if app.ws.selected_table.is_some() => app.begin_csv_import(),
+        Action::LargestTables => app.show_largest_tables().await,
+        Action::SchemaDiagram => app.open_diagram().await,
+        Action::ExportSchema => app.begin_schema_export(),
+        Action::RestartSequence if app.ws.selected_sequence.is_some() => app.begin_restart_sequence(),
+        Action::RefreshMatview if app.ws.selected_is_matview => app.begin_matview_refresh(),
+        Action::SwitchDatabase => app.open_database_picker().await,
+        Action::NarrowBrowser => app.resize_browser(-5),
+        Action::WidenBrowser => app.resize_browser(5),
+        Action::ToggleDetails => app.toggle_details_pane(),
+        Action::ToggleFavorite => app.toggle_favorite(),
+        Action::ExpandAll => app.expand_all().await?,
+        Action::CollapseAll => app.collapse_all(),
+        Action::DropUnusedIndexes => app.write_drop_unused_indexes(),
+        _ => {}
+    }
+    Ok(())
+}
+
+async fn handle_results_input(app: &mut App, key: KeyEvent) -> Result<()> {
+    if let Some(action) = app.keymap.lookup(key, Scope::Results) {
+        return run_results_action(app, action).await;
+    }
+    match key.code {
+        KeyCode::Esc if app.expanded_column() => app.toggle_expanded_column(),
+        KeyCode::Left if app.expanded_column() => app.scroll_expanded_column(false),
+        KeyCode::Right if app.expanded_column() => app.scroll_expanded_column(true),
+        KeyCode::Esc => app.toggle_query_focus(),
+        KeyCode::Up => app.result_row_up(1),
+        KeyCode::Down => {
+            app.result_row_down(1);
+            app.fetch_rows_at_end().await;
         }
-        KeyCode::Right | KeyCode::Char(']') => {
-            if app.selected_table.is_some() {
-                app.next_tab();
-            }
+        KeyCode::PageUp => app.result_row_up(20),
+        KeyCode::PageDown => {
+            app.result_row_down(20);
+            app.fetch_rows_at_end().await;
         }
+        KeyCode::Home => app.result_first_row(),
+        KeyCode::End => app.result_last_row(),
+        KeyCode::Left => app.result_col_left(),
+        KeyCode::Right => app.result_col_right(),
         _ => {}
     }
-    Ok(false)
+    Ok(())
+}
+
+async fn run_results_action(app: &mut App, action: Action) -> Result<()> {
+    match action {
+        Action::EditCell => app.begin_cell_edit(),
+        Action::SortColumn => app.cycle_result_sort(),
+        Action::GotoRow => app.begin_goto_row(),
+        Action::ShowRecord => app.show_record(),
+        Action::SearchResults => app.begin_result_search(),
+        Action::FindNext => app.find_next_match(true),
+        Action::FindPrevious => app.find_next_match(false),
+        Action::HideColumn => app.hide_result_column(),
+        Action::MoveColumnLeft => app.move_result_column(false),
+        Action::MoveColumnRight => app.move_result_column(true),
+        Action::SaveTableView => app.save_table_view(),
+        Action::ResetTableView => app.reset_table_view().await?,
+        Action::FetchMore => app.fetch_more_rows().await,
+        Action::FitColumns => app.fit_result_columns(),
+        Action::NarrowColumn => app.resize_result_column(-4),
+        Action::WidenColumn => app.resize_result_column(4),
+        Action::ExpandColumn => app.toggle_expanded_column(),
+        Action::ChooseColumns => app.open_column_chooser(),
+        Action::PinColumns => app.pin_result_columns(),
+        Action::JsonPaths => app.open_json_paths(),
+        Action::DiffCell => app.diff_cell(),
+        Action::SaveCell => app.begin_save_cell(),
+        Action::ExportInserts => app.begin_insert_export(),
+        Action::CopyMarkdown => app.copy_results_table(false),
+        Action::CopyOrgTable => app.copy_results_table(true),
+        Action::OpenCell => app.open_cell_in_editor()?,
+        _ => {}
+    }
+    Ok(())
 }
 
 async fn handle_query_input(app: &mut App, key: KeyCode) -> Result<bool> {
     // Handle autocomplete navigation if visible
-    if app.show_autocomplete {
+    if app.ws.show_autocomplete {
         match key {
             KeyCode::Down => {
                 app.select_next_suggestion();
@@ -269,15 +830,29 @@ async fn handle_query_input(app: &mut App, key: KeyCode) -> Result<bool> {
     }
     
     match key {
-        KeyCode::Char('q') if app.query_input.is_empty() => return Ok(true),
-        KeyCode::Tab if !app.show_autocomplete => app.mode = AppMode::Browser,
+        KeyCode::Char('q') if app.ws.query_input.is_empty() => return Ok(true),
+        // Tab moves through an expanded snippet's placeholders
+        KeyCode::Tab if app.in_snippet() => app.snippet_jump(true),
+        KeyCode::BackTab if app.in_snippet() => app.snippet_jump(false),
+        KeyCode::Tab if !app.ws.show_autocomplete => app.mode = AppMode::Browser,
         _ => {
+            if key == KeyCode::Esc {
+                app.end_snippet();
+            }
             // Handle text input in query editor
-            app.handle_query_input(key);
-            // Auto-scroll to keep cursor visible (10 height - 2 for borders = 8 visible lines)
-            app.adjust_query_scroll(8);
-            // Update autocomplete suggestions (may load schema on first call)
-            app.update_autocomplete().await?;
+            app.snippet_editor_key(key);
+            // Auto-scroll to keep cursor visible (editor height minus borders)
+            let visible_lines = app.layout().editor_height.saturating_sub(2) as usize;
+            app.adjust_query_scroll(visible_lines.max(1));
+            // Keys still queued (typing ahead, or a paste the terminal didn't
+            // bracket) would make every lookup stale before it is shown;
+            // vim commands outside insert mode don't complete at all
+            if !app.editor_inserting() || event::poll(Duration::ZERO)? {
+                app.hide_autocomplete();
+            } else {
+                // Update autocomplete suggestions (may load schema on first call)
+                app.update_autocomplete().await?;
+            }
         }
     }
     Ok(false)
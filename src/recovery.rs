@@ -0,0 +1,129 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+// Editor text not yet saved to a file. Every running session keeps a
+// snapshot of its buffers in the recovery directory and removes it on a
+// clean exit, so a snapshot nobody is updating any more was left behind by
+// a crash.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoveredBuffer {
+    // Keyring account of the connection profile, which identifies it
+    pub profile: String,
+    pub connection: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_path: Option<String>,
+    pub text: String,
+}
+
+fn recovery_dir() -> Result<PathBuf> {
+    let mut path = dirs::data_local_dir().ok_or_else(|| anyhow!("Could not find data directory"))?;
+    path.push("psql_cli");
+    path.push("recovery");
+    Ok(path)
+}
+
+// This process's snapshot
+fn session_file() -> Result<PathBuf> {
+    Ok(recovery_dir()?.join(format!("{}.json", std::process::id())))
+}
+
+// Replace this session's snapshot; written aside and renamed so a crash
+// mid-write leaves the previous one intact
+pub fn save(buffers: &[RecoveredBuffer]) -> Result<()> {
+    if buffers.is_empty() {
+        return discard();
+    }
+    let path = session_file()?;
+    let dir = recovery_dir()?;
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    write_private(&path, &serde_json::to_string(buffers)?)
+}
+
+// Write `path` aside and rename it into place. Snapshots and kept results
+// hold query text and data, so only the user can read them.
+pub fn write_private(path: &Path, contents: &str) -> Result<()> {
+    use std::io::Write;
+
+    let temp = path.with_extension("tmp");
+    // A temp file left by a crash would keep its old permissions
+    let _ = std::fs::remove_file(&temp);
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options
+        .open(&temp)
+        .and_then(|mut file| file.write_all(contents.as_bytes()))
+        .with_context(|| format!("Failed to write {}", temp.display()))?;
+    std::fs::rename(&temp, path).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+// Drop this session's snapshot, on a clean exit
+pub fn discard() -> Result<()> {
+    let path = session_file()?;
+    match std::fs::remove_file(&path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(e).with_context(|| format!("Failed to remove {}", path.display()))
+        }
+        _ => Ok(()),
+    }
+}
+
+// Snapshots of other sessions not updated for `stale`; a running session
+// rewrites its own more often than that
+pub fn orphaned(stale: Duration) -> Vec<PathBuf> {
+    let Ok(own) = session_file() else {
+        return Vec::new();
+    };
+    let Some(entries) = recovery_dir().ok().and_then(|dir| std::fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| *path != own && path.extension().is_some_and(|ext| ext == "json"))
+        .filter(|path| {
+            std::fs::metadata(path)
+                .and_then(|meta| meta.modified())
+                .is_ok_and(|modified| SystemTime::now().duration_since(modified).unwrap_or_default() >= stale)
+        })
+        .collect()
+}
+
+pub fn load(path: &Path) -> Result<Vec<RecoveredBuffer>> {
+    let contents = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(serde_json::from_str(&contents)?)
+}
+
+pub fn remove(paths: &[PathBuf]) {
+    for path in paths {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn private_files_are_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("pgtui-private-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("buffers.json");
+        // Replacing a readable file makes it private too
+        std::fs::write(&path, "old").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        write_private(&path, "new").unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new");
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
@@ -0,0 +1,97 @@
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::db::QueryResult;
+
+// Most rows kept of one result; the rest is cut off
+pub const MAX_ROWS: usize = 5000;
+
+// A query result as it was shown, kept on disk per connection profile so it
+// can be looked at again without running the query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedResult {
+    pub sql: String,
+    // Seconds since the Unix epoch
+    pub ran_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elapsed_ms: Option<u64>,
+    pub columns: Vec<String>,
+    #[serde(default)]
+    pub types: Vec<String>,
+    pub rows: Vec<Vec<Option<String>>>,
+    // Rows were left out, by MAX_ROWS or because they were never fetched
+    #[serde(default)]
+    pub partial: bool,
+}
+
+impl SavedResult {
+    pub fn new(sql: &str, result: &QueryResult, tag: Option<String>, elapsed_ms: Option<u64>, partial: bool) -> Self {
+        Self {
+            sql: sql.to_string(),
+            ran_at: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()),
+            tag,
+            elapsed_ms,
+            columns: result.columns.clone(),
+            types: result.types.clone(),
+            rows: result.rows.iter().take(MAX_ROWS).cloned().collect(),
+            partial: partial || result.rows.len() > MAX_ROWS,
+        }
+    }
+
+    pub fn to_result(&self) -> QueryResult {
+        QueryResult {
+            columns: self.columns.clone(),
+            row_count: self.rows.len(),
+            rows: self.rows.clone(),
+            types: self.types.clone(),
+            source: None,
+        }
+    }
+
+    // Seconds since it ran
+    pub fn age(&self) -> u64 {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        now.saturating_sub(self.ran_at)
+    }
+}
+
+fn history_dir() -> Result<PathBuf> {
+    let mut path = dirs::data_local_dir().ok_or_else(|| anyhow!("Could not find data directory"))?;
+    path.push("psql_cli");
+    path.push("results");
+    Ok(path)
+}
+
+// One file per profile, named after its keyring account with anything that
+// isn't safe in a file name replaced
+fn history_file(profile: &str) -> Result<PathBuf> {
+    let name: String = profile
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '.' { c } else { '_' })
+        .collect();
+    Ok(history_dir()?.join(format!("{}.json", name)))
+}
+
+// Newest first; none when nothing was kept yet
+pub fn load(profile: &str) -> Result<Vec<SavedResult>> {
+    let path = history_file(profile)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&contents).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+// Written aside and renamed, readable only by the user, like the recovery
+// snapshots
+pub fn save(profile: &str, results: &[SavedResult]) -> Result<()> {
+    let path = history_file(profile)?;
+    let dir = history_dir()?;
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    crate::recovery::write_private(&path, &serde_json::to_string(results)?)
+}
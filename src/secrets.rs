@@ -0,0 +1,39 @@
+use anyhow::{Context, Result};
+use keyring::Entry;
+
+use crate::config::ConnectionProfile;
+
+const SERVICE: &str = "pg-tui";
+
+fn entry(profile: &ConnectionProfile) -> Result<Entry> {
+    Entry::new(SERVICE, &profile.keyring_account()).context("Failed to open keyring entry")
+}
+
+// The Secret Service backend spins up its own runtime for each call, which
+// tokio refuses to do on an async worker thread
+fn blocking<T>(f: impl FnOnce() -> T) -> T {
+    tokio::task::block_in_place(f)
+}
+
+/// Look up the stored password for a profile; `None` when there is none.
+pub fn load_password(profile: &ConnectionProfile) -> Result<Option<String>> {
+    let entry = entry(profile)?;
+    match blocking(|| entry.get_password()) {
+        Ok(password) => Ok(Some(password)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e).context("Failed to read password from keyring"),
+    }
+}
+
+pub fn store_password(profile: &ConnectionProfile, password: &str) -> Result<()> {
+    let entry = entry(profile)?;
+    blocking(|| entry.set_password(password)).context("Failed to store password in keyring")
+}
+
+pub fn delete_password(profile: &ConnectionProfile) -> Result<()> {
+    let entry = entry(profile)?;
+    match blocking(|| entry.delete_credential()) {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e).context("Failed to remove password from keyring"),
+    }
+}
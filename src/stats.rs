@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+// Per-session usage counters. Kept in memory only and never sent anywhere;
+// the summary is printed to the terminal when the app exits.
+#[derive(Debug, Default)]
+pub struct SessionStats {
+    connected_at: Option<Instant>,
+    connected_total: Duration,
+    pub queries_run: usize,
+    pub queries_failed: usize,
+    pub rows_fetched: usize,
+    table_usage: HashMap<String, usize>,
+}
+
+impl SessionStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_connect(&mut self) {
+        self.record_disconnect();
+        self.connected_at = Some(Instant::now());
+    }
+
+    pub fn record_disconnect(&mut self) {
+        if let Some(since) = self.connected_at.take() {
+            self.connected_total += since.elapsed();
+        }
+    }
+
+    pub fn time_connected(&self) -> Duration {
+        self.connected_total + self.connected_at.map(|since| since.elapsed()).unwrap_or_default()
+    }
+
+    // Count a statement and the tables it references
+    pub fn record_query(&mut self, sql: &str, rows: Option<usize>) {
+        self.queries_run += 1;
+        match rows {
+            Some(rows) => self.rows_fetched += rows,
+            None => self.queries_failed += 1,
+        }
+        for table in referenced_tables(sql) {
+            self.record_table(&table);
+        }
+    }
+
+    pub fn record_table(&mut self, table: &str) {
+        *self.table_usage.entry(table.to_string()).or_insert(0) += 1;
+    }
+
+    // Most-used tables, highest count first
+    pub fn top_tables(&self, limit: usize) -> Vec<(&str, usize)> {
+        let mut tables: Vec<(&str, usize)> = self
+            .table_usage
+            .iter()
+            .map(|(name, count)| (name.as_str(), *count))
+            .collect();
+        tables.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        tables.truncate(limit);
+        tables
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.connected_at.is_none() && self.connected_total.is_zero() && self.queries_run == 0
+    }
+
+    pub fn summary(&self) -> String {
+        let mut text = format!(
+            "Session summary\n  Time connected - {}\n  Queries run - {} ({} failed)\n  Rows fetched - {}",
+            format_duration(self.time_connected()),
+            self.queries_run,
+            self.queries_failed,
+            self.rows_fetched,
+        );
+        let top = self.top_tables(5);
+        if !top.is_empty() {
+            text.push_str("\n  Most-used tables:");
+            for (name, count) in top {
+                text.push_str(&format!("\n    {} ({})", name, count));
+            }
+        }
+        text
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs >= 3600 {
+        format!("{}h {:02}m", secs / 3600, (secs % 3600) / 60)
+    } else if secs >= 60 {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+// Table names that follow FROM / JOIN / UPDATE / INTO. This is a rough scan,
+// good enough for usage counts; it does not try to understand subqueries.
+fn referenced_tables(sql: &str) -> Vec<String> {
+    let mut tables = Vec::new();
+    let mut expect_table = false;
+
+    for word in sql.split(|c: char| c.is_whitespace() || c == ',' || c == ';') {
+        if word.is_empty() {
+            continue;
+        }
+        let upper = word.to_uppercase();
+        if expect_table {
+            if matches!(upper.as_str(), "ONLY" | "LATERAL") {
+                continue;
+            }
+            expect_table = false;
+            if !word.starts_with('(') {
+                tables.push(normalize_name(word.trim_end_matches(')')));
+                continue;
+            }
+        }
+        if matches!(upper.as_str(), "FROM" | "JOIN" | "UPDATE" | "INTO") {
+            expect_table = true;
+        }
+    }
+
+    tables
+}
+
+// Unquoted identifiers fold to lower case, quoted ones keep their spelling
+fn normalize_name(name: &str) -> String {
+    name.split('.')
+        .map(|part| {
+            if part.starts_with('"') {
+                part.trim_matches('"').to_string()
+            } else {
+                part.to_lowercase()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
@@ -1,4 +1,9 @@
-use ratatui::style::{Color, Style};
+use std::ops::Range;
+
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum TokenType {
@@ -79,7 +84,7 @@ impl SqlHighlighter {
         let mut tokens = Vec::new();
         let mut chars = input.char_indices().peekable();
 
-        while let Some((i, ch)) = chars.next() {
+        while let Some((_, ch)) = chars.next() {
             match ch {
                 // Whitespace
                 ' ' | '\t' | '\n' | '\r' => {
@@ -100,7 +105,7 @@ impl SqlHighlighter {
                     let mut text = String::from(ch);
                     let mut escaped = false;
                     
-                    while let Some((_, next_ch)) = chars.next() {
+                    for (_, next_ch) in chars.by_ref() {
                         text.push(next_ch);
                         if escaped {
                             escaped = false;
@@ -198,6 +203,80 @@ impl SqlHighlighter {
     }
 }
 
+/// Highlight SQL text and split it into display lines.
+pub fn highlight_lines(text: &str) -> Vec<Line<'static>> {
+    highlight_lines_marked(text, &[])
+}
+
+/// Like `highlight_lines`, with the given byte ranges underlined.
+pub fn highlight_lines_marked(text: &str, marks: &[Range<usize>]) -> Vec<Line<'static>> {
+    highlight_lines_selected(text, marks, None)
+}
+
+/// Like `highlight_lines_marked`, with a selected byte range shown reversed.
+pub fn highlight_lines_selected(text: &str, marks: &[Range<usize>], selection: Option<Range<usize>>) -> Vec<Line<'static>> {
+    let tokens = SqlHighlighter::new().tokenize(text);
+
+    // Cut tokens at mark boundaries so each piece has a single style
+    let mut pieces: Vec<(String, Style)> = Vec::new();
+    let mut offset = 0;
+    for token in tokens {
+        let style = token.style();
+        let end = offset + token.text.len();
+        let mut cuts: Vec<usize> = marks
+            .iter()
+            .chain(&selection)
+            .flat_map(|m| [m.start, m.end])
+            .filter(|&c| c > offset && c < end && text.is_char_boundary(c))
+            .collect();
+        cuts.sort_unstable();
+        cuts.dedup();
+        let mut from = offset;
+        for to in cuts.into_iter().chain(std::iter::once(end)) {
+            let marked = marks.iter().any(|m| m.start <= from && from < m.end);
+            let style = if marked {
+                style.add_modifier(Modifier::UNDERLINED).underline_color(Color::Yellow)
+            } else {
+                style
+            };
+            let style = if selection.as_ref().is_some_and(|s| s.start <= from && from < s.end) {
+                style.add_modifier(Modifier::REVERSED)
+            } else {
+                style
+            };
+            pieces.push((text[from..to].to_string(), style));
+            from = to;
+        }
+        offset = end;
+    }
+
+    let mut current_line_spans: Vec<Span> = Vec::new();
+    let mut lines: Vec<Line> = Vec::new();
+
+    for (piece, style) in pieces {
+        // Tokens such as whitespace may span several lines
+        if piece.contains('\n') {
+            let parts: Vec<&str> = piece.split('\n').collect();
+            for (i, part) in parts.iter().enumerate() {
+                if !part.is_empty() {
+                    current_line_spans.push(Span::styled(part.to_string(), style));
+                }
+                if i < parts.len() - 1 {
+                    lines.push(Line::from(std::mem::take(&mut current_line_spans)));
+                }
+            }
+        } else {
+            current_line_spans.push(Span::styled(piece, style));
+        }
+    }
+
+    if !current_line_spans.is_empty() {
+        lines.push(Line::from(current_line_spans));
+    }
+
+    lines
+}
+
 impl Default for SqlHighlighter {
     fn default() -> Self {
         Self::new()
@@ -0,0 +1,108 @@
+use ratatui::style::{Color, Modifier, Style};
+use serde::{Deserialize, Serialize};
+
+// Colors are stored as strings ("cyan", "dark gray", "#1e1e2e", "236")
+// so the config file stays hand-editable.
+pub fn parse_color(value: &str) -> Color {
+    value.parse().unwrap_or(Color::Reset)
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    pub grid: GridTheme,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GridTheme {
+    pub header_fg: String,
+    pub header_bg: String,
+    pub header_bold: bool,
+    pub row_fg: String,
+    pub row_bg: String,
+    // Alternating row background, only used when `zebra` is on
+    pub alt_row_bg: String,
+    pub zebra: bool,
+    pub selected_row_fg: String,
+    pub selected_row_bg: String,
+    pub selected_cell_fg: String,
+    pub selected_cell_bg: String,
+    // Cells matching the results search
+    pub match_fg: String,
+    pub match_bg: String,
+    // What NULL cells show, dimmed, so they can't be taken for text
+    pub null_text: String,
+    pub null_fg: String,
+}
+
+impl Default for GridTheme {
+    fn default() -> Self {
+        Self {
+            header_fg: "yellow".to_string(),
+            header_bg: "reset".to_string(),
+            header_bold: true,
+            row_fg: "reset".to_string(),
+            row_bg: "reset".to_string(),
+            alt_row_bg: "236".to_string(),
+            zebra: true,
+            selected_row_fg: "white".to_string(),
+            selected_row_bg: "24".to_string(),
+            selected_cell_fg: "black".to_string(),
+            selected_cell_bg: "cyan".to_string(),
+            match_fg: "black".to_string(),
+            match_bg: "yellow".to_string(),
+            null_text: "∅".to_string(),
+            null_fg: "dark gray".to_string(),
+        }
+    }
+}
+
+impl GridTheme {
+    pub fn header_style(&self) -> Style {
+        let style = Style::default()
+            .fg(parse_color(&self.header_fg))
+            .bg(parse_color(&self.header_bg));
+        if self.header_bold {
+            style.add_modifier(Modifier::BOLD)
+        } else {
+            style
+        }
+    }
+
+    pub fn row_style(&self, row_index: usize) -> Style {
+        let bg = if self.zebra && row_index % 2 == 1 {
+            &self.alt_row_bg
+        } else {
+            &self.row_bg
+        };
+        Style::default()
+            .fg(parse_color(&self.row_fg))
+            .bg(parse_color(bg))
+    }
+
+    pub fn selected_row_style(&self) -> Style {
+        Style::default()
+            .fg(parse_color(&self.selected_row_fg))
+            .bg(parse_color(&self.selected_row_bg))
+    }
+
+    pub fn selected_cell_style(&self) -> Style {
+        Style::default()
+            .fg(parse_color(&self.selected_cell_fg))
+            .bg(parse_color(&self.selected_cell_bg))
+            .add_modifier(Modifier::BOLD)
+    }
+
+    pub fn match_style(&self) -> Style {
+        Style::default()
+            .fg(parse_color(&self.match_fg))
+            .bg(parse_color(&self.match_bg))
+    }
+
+    pub fn null_style(&self) -> Style {
+        Style::default()
+            .fg(parse_color(&self.null_fg))
+            .add_modifier(Modifier::DIM)
+    }
+}
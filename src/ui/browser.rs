@@ -1,11 +1,13 @@
 use ratatui::{
     layout::Rect,
     style::{Color, Modifier, Style},
+    text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, Paragraph, Row, Table},
     Frame,
 };
 
-use crate::app::{App, BrowserItem};
+use crate::app::{unused_indexes, App, BrowserItem};
+use crate::keymap::Action;
 
 pub fn render_browser(f: &mut Frame, app: &mut App, area: Rect) {
     use ratatui::layout::{Constraint, Direction, Layout};
@@ -20,13 +22,13 @@ pub fn render_browser(f: &mut Frame, app: &mut App, area: Rect) {
         .split(area);
     
     // Render filter input box
-    let filter_text = if app.filter_active {
-        format!(" Filter: {}_", app.filter_input)
+    let filter_text = if app.ws.filter_active {
+        format!(" Filter: {}_", app.ws.filter_input)
     } else {
         " Press '/' to filter".to_string()
     };
     
-    let filter_style = if app.filter_active {
+    let filter_style = if app.ws.filter_active {
         Style::default().fg(Color::Yellow)
     } else {
         Style::default().fg(Color::DarkGray)
@@ -37,7 +39,7 @@ pub fn render_browser(f: &mut Frame, app: &mut App, area: Rect) {
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(if app.filter_active { Color::Yellow } else { Color::Cyan }))
+                .border_style(Style::default().fg(if app.ws.filter_active { Color::Yellow } else { Color::Cyan }))
         );
     
     f.render_widget(filter_widget, chunks[0]);
@@ -47,7 +49,7 @@ pub fn render_browser(f: &mut Frame, app: &mut App, area: Rect) {
     let visible_height = chunks[1].height.saturating_sub(2) as usize;
     
     // Adjust scroll offset for filtered view
-    let filtered_selected = filtered_indices.iter().position(|&idx| idx == app.browser_selected).unwrap_or(0);
+    let filtered_selected = filtered_indices.iter().position(|&idx| idx == app.ws.browser_selected).unwrap_or(0);
     let scroll_offset = if filtered_selected >= visible_height {
         filtered_selected.saturating_sub(visible_height - 1)
     } else {
@@ -60,27 +62,40 @@ pub fn render_browser(f: &mut Frame, app: &mut App, area: Rect) {
         .skip(scroll_offset)
         .take(visible_height)
         .map(|&idx| {
-            let item = &app.browser_items[idx];
+            let item = &app.ws.browser_items[idx];
+            let favorite;
             let (icon, name, indent) = match item {
+                BrowserItem::Database(name) => ("🛢️", name.as_str(), 0),
+                BrowserItem::Favorite(index) => {
+                    favorite = app.favorites().get(*index).map(|f| f.label()).unwrap_or_default();
+                    ("★", favorite.as_str(), 2)
+                }
                 BrowserItem::Schema(name) => ("📁", name.as_str(), 0),
                 BrowserItem::Folder(_, folder_type) => {
                     use crate::app::FolderType;
                     let folder_name = match folder_type {
                         FolderType::Tables => "Tables",
                         FolderType::Views => "Views",
+                        FolderType::MaterializedViews => "Materialized Views",
                         FolderType::Functions => "Functions",
+                        FolderType::Sequences => "Sequences",
                     };
                     ("📂", folder_name, 2)
                 }
                 BrowserItem::Table(_, name) => ("📊", name.as_str(), 4),
                 BrowserItem::View(_, name) => ("👁️", name.as_str(), 4),
+                BrowserItem::MaterializedView(_, name) => ("🧊", name.as_str(), 4),
                 BrowserItem::Function(_, name) => ("⚙️", name.as_str(), 4),
+                BrowserItem::Sequence(_, name) => ("🔢", name.as_str(), 4),
             };
 
             let indent_str = " ".repeat(indent);
-            let content = format!("{}{} {}", indent_str, icon, name);
+            let mut spans = vec![Span::raw(format!("{}{} {}", indent_str, icon, name))];
+            if let Some(note) = app.browser_note(idx) {
+                spans.push(Span::styled(format!(" {}", note), Style::default().fg(Color::DarkGray)));
+            }
             
-            let style = if idx == app.browser_selected {
+            let style = if idx == app.ws.browser_selected {
                 Style::default()
                     .fg(Color::Yellow)
                     .add_modifier(Modifier::BOLD)
@@ -88,14 +103,14 @@ pub fn render_browser(f: &mut Frame, app: &mut App, area: Rect) {
                 Style::default().fg(Color::White)
             };
 
-            ListItem::new(content).style(style)
+            ListItem::new(Line::from(spans)).style(style)
         })
         .collect();
     
-    let title = if app.filter_active && !app.filter_input.is_empty() {
-        format!("Database Browser ({} filtered / {} total)", filtered_indices.len(), app.browser_items.len())
+    let title = if app.ws.filter_active && !app.ws.filter_input.is_empty() {
+        format!("Database Browser ({} filtered / {} total)", filtered_indices.len(), app.ws.browser_items.len())
     } else {
-        format!("Database Browser ({}/{})", app.browser_selected + 1, app.browser_items.len())
+        format!("Database Browser ({}/{})", app.ws.browser_selected + 1, app.ws.browser_items.len())
     };
 
     let list = List::new(items).block(
@@ -111,8 +126,40 @@ pub fn render_browser(f: &mut Frame, app: &mut App, area: Rect) {
 pub fn render_details(f: &mut Frame, app: &App, area: Rect) {
     use ratatui::layout::{Constraint, Direction, Layout};
 
-    if app.selected_table.is_none() {
-        let help = Paragraph::new("Select a table to view its structure\n\nKeyboard shortcuts:\n  ↑/↓ - Navigate\n  Enter - Expand/View\n  Tab - Switch to query mode\n  r - Refresh\n  q - Quit")
+    if app.ws.selected_function.is_some() {
+        render_function_details(f, app, area);
+        return;
+    }
+
+    if app.ws.selected_sequence.is_some() {
+        render_sequence_details(f, app, area);
+        return;
+    }
+
+    if app.ws.database_details.is_some() {
+        render_database_details(f, app, area);
+        return;
+    }
+
+    if app.ws.selected_table.is_none() {
+        let key = |action| app.keymap.label(action);
+        let mut help_text = format!(
+            "Select a table to view its structure\n\nKeyboard shortcuts:\n  ↑/↓ - Navigate\n  Enter - Expand/View\n  ←/→ - Collapse/Expand\n  {}/{} - Expand/Collapse all\n  Tab - Switch to query mode\n  {} - Refresh\n  {} - Open another connection\n  {} (2..9 likewise) - Switch connection\n  {} - Close connection\n  {} - Split query view\n  q - Quit",
+            key(Action::ExpandAll),
+            key(Action::CollapseAll),
+            key(Action::RefreshBrowser),
+            key(Action::NewConnection),
+            key(Action::SwitchTab(1)),
+            key(Action::CloseConnection),
+            key(Action::ToggleSplit),
+        );
+        if let Some(caps) = &app.ws.capabilities {
+            help_text.push_str(&capabilities_summary(caps));
+        }
+        help_text.push_str("\n\n");
+        help_text.push_str(&app.stats.summary());
+        let help = Paragraph::new(help_text)
+            .wrap(ratatui::widgets::Wrap { trim: false })
             .block(
                 Block::default()
                     .borders(Borders::ALL)
@@ -133,23 +180,16 @@ pub fn render_details(f: &mut Frame, app: &App, area: Rect) {
         .split(area);
 
     // Render tab bar
-    let tabs = vec!["Columns", "Constraints", "Indexes", "Triggers", "Foreign Keys"];
-    let active_tab_index = match app.table_detail_tab {
-        crate::app::TableDetailTab::Columns => 0,
-        crate::app::TableDetailTab::Constraints => 1,
-        crate::app::TableDetailTab::Indexes => 2,
-        crate::app::TableDetailTab::Triggers => 3,
-        crate::app::TableDetailTab::ForeignKeys => 4,
-    };
-
-    let tab_titles: Vec<String> = tabs
+    let tab_titles: Vec<String> = app
+        .detail_tabs()
         .iter()
-        .enumerate()
-        .map(|(i, name)| {
-            if i == active_tab_index {
-                format!(" [{}] ", name)
+        .map(|&tab| {
+            // Mark panes the role cannot read
+            let lock = if app.ws.detail_notes.contains_key(&tab) { " 🔒" } else { "" };
+            if tab == app.ws.table_detail_tab {
+                format!(" [{}{}] ", tab.title(), lock)
             } else {
-                format!("  {}  ", name)
+                format!("  {}{}  ", tab.title(), lock)
             }
         })
         .collect();
@@ -161,18 +201,32 @@ pub fn render_details(f: &mut Frame, app: &App, area: Rect) {
     
     f.render_widget(tab_widget, chunks[0]);
 
+    if let Some(note) = app.ws.detail_notes.get(&app.ws.table_detail_tab) {
+        render_note(f, chunks[1], app.ws.table_detail_tab.title(), note);
+        return;
+    }
+
     // Render content based on active tab
-    match app.table_detail_tab {
+    match app.ws.table_detail_tab {
         crate::app::TableDetailTab::Columns => render_columns_tab(f, app, chunks[1]),
         crate::app::TableDetailTab::Constraints => render_constraints_tab(f, app, chunks[1]),
         crate::app::TableDetailTab::Indexes => render_indexes_tab(f, app, chunks[1]),
         crate::app::TableDetailTab::Triggers => render_triggers_tab(f, app, chunks[1]),
         crate::app::TableDetailTab::ForeignKeys => render_foreign_keys_tab(f, app, chunks[1]),
+        crate::app::TableDetailTab::Ddl => {
+            render_sql_tab(f, app, chunks[1], "DDL", app.ws.table_ddl.as_deref(), "DDL is only available for tables")
+        }
+        crate::app::TableDetailTab::Stats => render_stats_tab(f, app, chunks[1]),
+        crate::app::TableDetailTab::Definition => {
+            render_sql_tab(f, app, chunks[1], "Definition", app.ws.view_definition.as_deref(), "No definition available")
+        }
+        crate::app::TableDetailTab::Dependencies => render_dependencies_tab(f, app, chunks[1]),
+        crate::app::TableDetailTab::Privileges => render_privileges_tab(f, app, chunks[1]),
     }
 }
 
 fn render_columns_tab(f: &mut Frame, app: &App, area: Rect) {
-    if app.columns.is_empty() {
+    if app.ws.columns.is_empty() {
         let empty = Paragraph::new("No columns found")
             .block(
                 Block::default()
@@ -188,7 +242,7 @@ fn render_columns_tab(f: &mut Frame, app: &App, area: Rect) {
         .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
         .bottom_margin(1);
 
-    let rows: Vec<Row> = app
+    let rows: Vec<Row> = app.ws
         .columns
         .iter()
         .map(|col| {
@@ -198,6 +252,7 @@ fn render_columns_tab(f: &mut Frame, app: &App, area: Rect) {
                 col.is_nullable.clone(),
                 col.column_default.clone().unwrap_or_else(|| "-".to_string()),
             ])
+            .style(found_style(app, &col.name))
         })
         .collect();
 
@@ -221,8 +276,67 @@ fn render_columns_tab(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(table, area);
 }
 
+// The column or index the schema search jumped to stands out
+fn found_style(app: &App, name: &str) -> Style {
+    if app.ws.found_detail.as_deref() == Some(name) {
+        Style::default().fg(Color::Black).bg(Color::Yellow)
+    } else {
+        Style::default()
+    }
+}
+
+fn render_stats_tab(f: &mut Frame, app: &App, area: Rect) {
+    let Some(stats) = &app.ws.table_stats else {
+        let empty = Paragraph::new("No statistics available")
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Stats")
+                    .border_style(Style::default().fg(Color::Cyan)),
+            );
+        f.render_widget(empty, area);
+        return;
+    };
+
+    let header = Row::new(vec!["Statistic", "Value"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+        .bottom_margin(1);
+
+    let count = |n: Option<i64>| n.map(|n| n.to_string()).unwrap_or_else(|| "-".to_string());
+    let time = |t: &Option<String>| t.clone().unwrap_or_else(|| "never".to_string());
+    let rows = vec![
+        Row::new(vec!["Total size".to_string(), stats.total_size.clone()]),
+        Row::new(vec!["Table data".to_string(), stats.table_size.clone()]),
+        Row::new(vec!["Indexes".to_string(), stats.index_size.clone()]),
+        Row::new(vec!["TOAST".to_string(), stats.toast_size.clone()]),
+        Row::new(vec!["Live rows".to_string(), count(stats.live_tuples)]),
+        Row::new(vec!["Dead rows".to_string(), count(stats.dead_tuples)]),
+        Row::new(vec!["Last vacuum".to_string(), time(&stats.last_vacuum)]),
+        Row::new(vec!["Last autovacuum".to_string(), time(&stats.last_autovacuum)]),
+        Row::new(vec!["Last analyze".to_string(), time(&stats.last_analyze)]),
+        Row::new(vec!["Last autoanalyze".to_string(), time(&stats.last_autoanalyze)]),
+    ];
+
+    let table = Table::new(
+        rows,
+        [
+            ratatui::layout::Constraint::Percentage(40),
+            ratatui::layout::Constraint::Percentage(60),
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Stats")
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+
+    f.render_widget(table, area);
+}
+
 fn render_constraints_tab(f: &mut Frame, app: &App, area: Rect) {
-    if app.constraints.is_empty() {
+    if app.ws.constraints.is_empty() {
         let empty = Paragraph::new("No constraints defined")
             .block(
                 Block::default()
@@ -238,7 +352,7 @@ fn render_constraints_tab(f: &mut Frame, app: &App, area: Rect) {
         .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
         .bottom_margin(1);
 
-    let rows: Vec<Row> = app
+    let rows: Vec<Row> = app.ws
         .constraints
         .iter()
         .map(|con| {
@@ -270,7 +384,7 @@ fn render_constraints_tab(f: &mut Frame, app: &App, area: Rect) {
 }
 
 fn render_indexes_tab(f: &mut Frame, app: &App, area: Rect) {
-    if app.indexes.is_empty() {
+    if app.ws.indexes.is_empty() {
         let empty = Paragraph::new("No indexes defined")
             .block(
                 Block::default()
@@ -282,37 +396,66 @@ fn render_indexes_tab(f: &mut Frame, app: &App, area: Rect) {
         return;
     }
 
-    let header = Row::new(vec!["Name", "Columns", "Unique", "Primary"])
+    let header = Row::new(vec!["Name", "Columns", "Unique", "Primary", "Scans", "Tuples Read", "Size"])
         .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
         .bottom_margin(1);
 
-    let rows: Vec<Row> = app
+    let rows: Vec<Row> = app.ws
         .indexes
         .iter()
         .map(|idx| {
-            Row::new(vec![
+            let never_scanned = idx.scans == Some(0);
+            let scans = match idx.scans {
+                Some(0) => "never".to_string(),
+                Some(scans) => scans.to_string(),
+                None => "-".to_string(),
+            };
+            let row = Row::new(vec![
                 idx.name.clone(),
                 idx.columns.clone(),
                 if idx.is_unique { "Yes" } else { "No" }.to_string(),
                 if idx.is_primary { "Yes" } else { "No" }.to_string(),
-            ])
+                scans,
+                idx.tuples_read.map_or_else(|| "-".to_string(), |n| n.to_string()),
+                idx.size.clone(),
+            ]);
+            if never_scanned && app.ws.found_detail.as_deref() != Some(idx.name.as_str()) {
+                row.style(Style::default().fg(Color::LightRed))
+            } else {
+                row.style(found_style(app, &idx.name))
+            }
         })
         .collect();
 
+    // Scan counts run from the last statistics reset
+    let unused = unused_indexes(&app.ws.indexes).count();
+    let title = if unused > 0 {
+        format!(
+            "Indexes ({} unused, {} writes DROP INDEX)",
+            unused,
+            app.keymap.label(Action::DropUnusedIndexes)
+        )
+    } else {
+        "Indexes".to_string()
+    };
+
     let table = Table::new(
         rows,
         [
-            ratatui::layout::Constraint::Percentage(35),
-            ratatui::layout::Constraint::Percentage(35),
-            ratatui::layout::Constraint::Percentage(15),
-            ratatui::layout::Constraint::Percentage(15),
+            ratatui::layout::Constraint::Percentage(28),
+            ratatui::layout::Constraint::Percentage(24),
+            ratatui::layout::Constraint::Length(7),
+            ratatui::layout::Constraint::Length(8),
+            ratatui::layout::Constraint::Length(8),
+            ratatui::layout::Constraint::Length(12),
+            ratatui::layout::Constraint::Length(11),
         ],
     )
     .header(header)
     .block(
         Block::default()
             .borders(Borders::ALL)
-            .title("Indexes")
+            .title(title)
             .border_style(Style::default().fg(Color::Cyan)),
     );
 
@@ -320,7 +463,7 @@ fn render_indexes_tab(f: &mut Frame, app: &App, area: Rect) {
 }
 
 fn render_triggers_tab(f: &mut Frame, app: &App, area: Rect) {
-    if app.triggers.is_empty() {
+    if app.ws.triggers.is_empty() {
         let empty = Paragraph::new("No triggers defined")
             .block(
                 Block::default()
@@ -336,7 +479,7 @@ fn render_triggers_tab(f: &mut Frame, app: &App, area: Rect) {
         .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
         .bottom_margin(1);
 
-    let rows: Vec<Row> = app
+    let rows: Vec<Row> = app.ws
         .triggers
         .iter()
         .map(|trg| {
@@ -370,7 +513,7 @@ fn render_triggers_tab(f: &mut Frame, app: &App, area: Rect) {
 }
 
 fn render_foreign_keys_tab(f: &mut Frame, app: &App, area: Rect) {
-    if app.foreign_keys.is_empty() {
+    if app.ws.foreign_keys.is_empty() {
         let empty = Paragraph::new("No foreign keys defined")
             .block(
                 Block::default()
@@ -386,7 +529,7 @@ fn render_foreign_keys_tab(f: &mut Frame, app: &App, area: Rect) {
         .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
         .bottom_margin(1);
 
-    let rows: Vec<Row> = app
+    let rows: Vec<Row> = app.ws
         .foreign_keys
         .iter()
         .map(|fk| {
@@ -418,3 +561,336 @@ fn render_foreign_keys_tab(f: &mut Frame, app: &App, area: Rect) {
 
     f.render_widget(table, area);
 }
+
+// What the relation uses, then what uses it: the objects a DROP would
+// break or, with CASCADE, take along
+fn render_dependencies_tab(f: &mut Frame, app: &App, area: Rect) {
+    let used_by = app.ws.dependencies.iter().filter(|d| d.used_by).count();
+    let title = format!(
+        "Dependencies (uses {}, used by {})",
+        app.ws.dependencies.len() - used_by,
+        used_by
+    );
+    if app.ws.dependencies.is_empty() {
+        let empty = Paragraph::new("Nothing outside the catalog depends on it or is used by it")
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(title)
+                    .border_style(Style::default().fg(Color::Cyan)),
+            );
+        f.render_widget(empty, area);
+        return;
+    }
+
+    let header = Row::new(vec!["Direction", "Kind", "Name", "Via"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+        .bottom_margin(1);
+
+    let rows: Vec<Row> = app.ws
+        .dependencies
+        .iter()
+        .map(|dep| {
+            let row = Row::new(vec![
+                if dep.used_by { "Used by" } else { "Depends on" }.to_string(),
+                dep.kind.clone(),
+                dep.name.clone(),
+                dep.via.clone(),
+            ]);
+            // The rows a DROP would affect
+            if dep.used_by {
+                row.style(Style::default().fg(Color::LightRed))
+            } else {
+                row
+            }
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            ratatui::layout::Constraint::Length(11),
+            ratatui::layout::Constraint::Length(18),
+            ratatui::layout::Constraint::Percentage(45),
+            ratatui::layout::Constraint::Percentage(35),
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+
+    f.render_widget(table, area);
+}
+
+fn render_privileges_tab(f: &mut Frame, app: &App, area: Rect) {
+    if app.ws.privileges.is_empty() {
+        let empty = Paragraph::new("No privileges granted")
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Privileges")
+                    .border_style(Style::default().fg(Color::Cyan)),
+            );
+        f.render_widget(empty, area);
+        return;
+    }
+
+    let header = Row::new(vec!["On", "Grantee", "Privileges", "Grantor"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+        .bottom_margin(1);
+
+    let rows: Vec<Row> = app.ws
+        .privileges
+        .iter()
+        .map(|p| {
+            let row = Row::new(vec![p.scope.clone(), p.grantee.clone(), p.privileges.clone(), p.grantor.clone()]);
+            // Anyone can connect as PUBLIC, so its grants deserve a look first
+            if p.grantee == "PUBLIC" {
+                row.style(Style::default().fg(Color::LightRed))
+            } else {
+                row
+            }
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            ratatui::layout::Constraint::Percentage(28),
+            ratatui::layout::Constraint::Percentage(18),
+            ratatui::layout::Constraint::Percentage(38),
+            ratatui::layout::Constraint::Percentage(16),
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Privileges (* = with grant option)")
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+
+    f.render_widget(table, area);
+}
+
+// Syntax-highlighted, scrollable SQL source (table DDL, view definition, ...)
+fn render_sql_tab(f: &mut Frame, app: &App, area: Rect, title: &str, sql: Option<&str>, empty_message: &str) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("{} (PgUp/PgDn to scroll)", title))
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let Some(sql) = sql else {
+        let empty = Paragraph::new(empty_message.to_string()).block(block);
+        f.render_widget(empty, area);
+        return;
+    };
+
+    let sql_widget = Paragraph::new(crate::syntax::highlight_lines(sql))
+        .block(block)
+        .scroll((app.ws.details_scroll, 0));
+
+    f.render_widget(sql_widget, area);
+}
+
+fn capabilities_summary(caps: &crate::db::Capabilities) -> String {
+    let yes_no = |value: bool| if value { "yes" } else { "no" };
+    let mut text = format!(
+        "\n\nServer:\n  Version - {}\n  Role - {}\n  Superuser - {}\n  information_schema readable - {}\n  pg_stat_statements - {}",
+        caps.server_version,
+        if caps.is_replica { "replica (read-only)" } else { "primary" },
+        yes_no(caps.is_superuser),
+        yes_no(caps.can_read_information_schema),
+        yes_no(caps.has_pg_stat_statements),
+    );
+    for note in caps.notes() {
+        text.push_str(&format!("\n\n! {}", note));
+    }
+    text
+}
+
+fn render_function_details(f: &mut Frame, app: &App, area: Rect) {
+    use ratatui::text::{Line, Span};
+
+    let Some((schema, name)) = &app.ws.selected_function else {
+        return;
+    };
+
+    if let Some(note) = app.ws.detail_notes.get(&crate::app::TableDetailTab::Definition) {
+        render_note(f, area, &format!("Function {}", name), note);
+        return;
+    }
+
+    let label = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
+    let mut lines: Vec<Line> = Vec::new();
+
+    for (i, details) in app.ws.function_details.iter().enumerate() {
+        if i > 0 {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled("─".repeat(40), Style::default().fg(Color::DarkGray))));
+        }
+        lines.push(Line::from(vec![
+            Span::styled("Signature:  ", label),
+            Span::raw(format!("{}.{}({})", schema, name, details.arguments)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("Returns:    ", label),
+            Span::raw(if details.result.is_empty() { "-".to_string() } else { details.result.clone() }),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("Kind:       ", label),
+            Span::raw(details.kind.clone()),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("Language:   ", label),
+            Span::raw(details.language.clone()),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("Volatility: ", label),
+            Span::raw(details.volatility.clone()),
+        ]));
+        lines.push(Line::from(""));
+
+        match &details.definition {
+            // SQL and plpgsql bodies highlight well enough with the SQL highlighter
+            Some(definition) if matches!(details.language.as_str(), "sql" | "plpgsql") => {
+                lines.extend(crate::syntax::highlight_lines(definition));
+            }
+            Some(definition) => lines.extend(definition.lines().map(|l| Line::from(l.to_string()))),
+            None => lines.push(Line::from(Span::styled(
+                "No source available (aggregate or window function)",
+                Style::default().fg(Color::DarkGray),
+            ))),
+        }
+    }
+
+    if app.ws.function_details.is_empty() {
+        lines.push(Line::from("Function not found"));
+    }
+
+    let title = if app.ws.function_details.len() > 1 {
+        format!("Function {} ({} overloads, PgUp/PgDn to scroll)", name, app.ws.function_details.len())
+    } else {
+        format!("Function {} (PgUp/PgDn to scroll)", name)
+    };
+
+    let widget = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .scroll((app.ws.details_scroll, 0));
+
+    f.render_widget(widget, area);
+}
+
+fn render_sequence_details(f: &mut Frame, app: &App, area: Rect) {
+    use ratatui::text::{Line, Span};
+
+    let Some((_, name)) = &app.ws.selected_sequence else {
+        return;
+    };
+
+    if let Some(note) = app.ws.detail_notes.get(&crate::app::TableDetailTab::Definition) {
+        render_note(f, area, &format!("Sequence {}", name), note);
+        return;
+    }
+
+    let label = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
+    let field = |name: &str, value: String| Line::from(vec![Span::styled(format!("{:<12}", name), label), Span::raw(value)]);
+    let lines: Vec<Line> = match &app.ws.sequence_details {
+        Some(details) => vec![
+            field("Type:", details.data_type.clone()),
+            field("Last value:", details.last_value.map(|v| v.to_string()).unwrap_or_else(|| "- (not used yet)".to_string())),
+            field("Start:", details.start_value.to_string()),
+            field("Increment:", details.increment.to_string()),
+            field("Minimum:", details.min_value.to_string()),
+            field("Maximum:", details.max_value.to_string()),
+            field("Cache:", details.cache_size.to_string()),
+            field("Cycles:", if details.cycle { "yes" } else { "no" }.to_string()),
+            field("Owned by:", details.owned_by.clone().unwrap_or_else(|| "-".to_string())),
+            Line::from(""),
+            Line::from(Span::styled(
+                format!("{} - restart the sequence", app.keymap.label(Action::RestartSequence)),
+                Style::default().fg(Color::DarkGray),
+            )),
+        ],
+        None => vec![Line::from("Sequence not found")],
+    };
+
+    let widget = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Sequence {}", name))
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+
+    f.render_widget(widget, area);
+}
+
+fn render_database_details(f: &mut Frame, app: &App, area: Rect) {
+    use ratatui::text::{Line, Span};
+
+    let Some(details) = &app.ws.database_details else {
+        return;
+    };
+
+    let label = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
+    let field = |name: &str, value: String| Line::from(vec![Span::styled(format!("{:<18}", name), label), Span::raw(value)]);
+    let mut lines = vec![
+        field("Owner:", details.owner.clone()),
+        field("Encoding:", details.encoding.clone()),
+        field("Collation:", details.collation.clone()),
+        field("Character type:", details.ctype.clone()),
+        field("Size:", details.size.clone().unwrap_or_else(|| "- (no CONNECT privilege)".to_string())),
+        field(
+            "Connection limit:",
+            match details.connection_limit {
+                -1 => "none".to_string(),
+                limit => limit.to_string(),
+            },
+        ),
+        Line::from(""),
+        Line::from(Span::styled("Settings", label)),
+    ];
+    if details.settings.is_empty() {
+        lines.push(Line::from(Span::styled("  none set for this database", Style::default().fg(Color::DarkGray))));
+    }
+    lines.extend(
+        details
+            .settings
+            .iter()
+            .map(|(role, setting)| Line::from(format!("  {} ({})", setting, role))),
+    );
+
+    let widget = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Database {}", details.name))
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .scroll((app.ws.details_scroll, 0));
+
+    f.render_widget(widget, area);
+}
+
+// Explains why a pane is unavailable instead of showing a raw error
+fn render_note(f: &mut Frame, area: Rect, title: &str, note: &str) {
+    let widget = Paragraph::new(format!("🔒 {}", note))
+        .style(Style::default().fg(Color::DarkGray))
+        .wrap(ratatui::widgets::Wrap { trim: true })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title.to_string())
+                .border_style(Style::default().fg(Color::DarkGray)),
+        );
+    f.render_widget(widget, area);
+}
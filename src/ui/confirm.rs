@@ -0,0 +1,138 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+
+// Preview of generated SQL that runs only after confirmation
+pub fn render_pending(f: &mut Frame, app: &App) {
+    let Some(pending) = &app.pending else {
+        return;
+    };
+
+    let title = format!("{} (Enter/y:execute | Esc/n:cancel)", pending.title);
+    let lines = crate::syntax::highlight_lines(&pending.sql);
+    let area = f.area();
+    let content_width = lines.iter().map(|l| l.width()).max().unwrap_or(0).max(title.chars().count());
+    let width = (content_width as u16 + 4).min(area.width.saturating_sub(4));
+    let height = (lines.len() as u16 + 2).min(area.height.saturating_sub(2));
+    let popup_area = Rect {
+        x: area.x + (area.width.saturating_sub(width)) / 2,
+        y: area.y + (area.height.saturating_sub(height)) / 2,
+        width,
+        height,
+    };
+
+    let preview = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(Color::Yellow)),
+    );
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(preview, popup_area);
+}
+
+// DROP, TRUNCATE or a DELETE/UPDATE without WHERE, waiting for the user to
+// confirm it: what it does to what, the statement, and the answer so far
+pub fn render_destructive(f: &mut Frame, app: &App) {
+    let Some(check) = &app.destructive else {
+        return;
+    };
+
+    let warning = Style::default().fg(Color::Red).add_modifier(Modifier::BOLD);
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(format!("{} ", check.action), warning),
+            Span::raw(format!("on {}", app.ws.name)),
+        ]),
+        Line::from(vec![Span::raw("Affects: "), Span::styled(check.object.clone(), warning)]),
+    ];
+    if check.others > 0 {
+        lines.push(Line::from(format!("…and {} more destructive statement(s) in this run", check.others)));
+    }
+    lines.push(Line::from(""));
+    lines.extend(crate::syntax::highlight_lines(&check.statement));
+    lines.push(Line::from(""));
+    let title = match &check.expected {
+        Some(expected) => {
+            lines.push(Line::from(format!("Type {} and press Enter to run it:", expected)));
+            lines.push(Line::from(Span::styled(
+                format!("> {}█", check.input),
+                Style::default().fg(Color::Yellow),
+            )));
+            "Confirm destructive statement (Enter:run | Esc:cancel)"
+        }
+        None => "Confirm destructive statement (y:run | Esc/n:cancel)",
+    };
+
+    let area = f.area();
+    let content_width = lines.iter().map(|l| l.width()).max().unwrap_or(0).max(title.chars().count());
+    let width = (content_width as u16 + 4).min(area.width.saturating_sub(4));
+    let height = (lines.len() as u16 + 2).min(area.height.saturating_sub(2));
+    let popup_area = Rect {
+        x: area.x + (area.width.saturating_sub(width)) / 2,
+        y: area.y + (area.height.saturating_sub(height)) / 2,
+        width,
+        height,
+    };
+
+    let dialog = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(Color::Red)),
+    );
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(dialog, popup_area);
+}
+
+// A query whose plan goes over the pre-flight limits: the planner's
+// estimate, which limits it passes, and the query
+pub fn render_cost_warning(f: &mut Frame, app: &App) {
+    let Some(warning) = &app.cost_warning else {
+        return;
+    };
+
+    let warning_style = Style::default().fg(Color::Red).add_modifier(Modifier::BOLD);
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("Expensive query ", warning_style),
+            Span::raw(format!(
+                "on {}: estimated cost {:.0}, {:.0} rows",
+                app.ws.name, warning.estimate.cost, warning.estimate.rows
+            )),
+        ]),
+        Line::from(vec![Span::raw("Over the limit: "), Span::styled(warning.exceeded.join(", "), warning_style)]),
+        Line::from(""),
+    ];
+    lines.extend(crate::syntax::highlight_lines(&warning.sql));
+    let title = "Run the query anyway? (Enter/y:run | Esc/n:cancel)";
+
+    let area = f.area();
+    let content_width = lines.iter().map(|l| l.width()).max().unwrap_or(0).max(title.chars().count());
+    let width = (content_width as u16 + 4).min(area.width.saturating_sub(4));
+    let height = (lines.len() as u16 + 2).min(area.height.saturating_sub(2));
+    let popup_area = Rect {
+        x: area.x + (area.width.saturating_sub(width)) / 2,
+        y: area.y + (area.height.saturating_sub(height)) / 2,
+        width,
+        height,
+    };
+
+    let dialog = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(Color::Red)),
+    );
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(dialog, popup_area);
+}
@@ -19,6 +19,7 @@ pub fn render_connection(f: &mut Frame, app: &App, area: Rect) {
             Constraint::Length(3),
             Constraint::Length(3),
             Constraint::Length(3),
+            Constraint::Length(3),
             Constraint::Min(0),
         ])
         .split(area);
@@ -76,16 +77,32 @@ pub fn render_connection(f: &mut Frame, app: &App, area: Rect) {
         chunks[5],
     );
 
+    // Save password toggle
+    let save_password = if !app.config.use_keyring {
+        "[-] Save password in OS keyring (disabled in config)"
+    } else if app.save_password {
+        "[x] Save password in OS keyring"
+    } else {
+        "[ ] Save password in OS keyring"
+    };
+    render_input_field(
+        f,
+        "Password Storage (Space to toggle)",
+        save_password,
+        app.connection_field == ConnectionField::SavePassword,
+        chunks[6],
+    );
+
     // Instructions
     let instructions = Paragraph::new(vec![
         Line::from("Tab/Shift+Tab: Next/Previous field | Enter: Connect | q: Quit"),
         Line::from(Span::styled(
-            "Note: Connection details (except password) are saved after first login",
+            "Note: Connection details are saved after first login; passwords only go to the OS keyring when enabled",
             Style::default().fg(Color::DarkGray),
         )),
     ])
     .alignment(Alignment::Center);
-    f.render_widget(instructions, chunks[6]);
+    f.render_widget(instructions, chunks[7]);
 }
 
 fn render_input_field(
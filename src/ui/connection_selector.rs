@@ -7,6 +7,7 @@ use ratatui::{
 };
 
 use crate::app::App;
+use crate::theme::parse_color;
 
 pub fn render_connection_selector(f: &mut Frame, app: &App, area: Rect) {
     let chunks = Layout::default()
@@ -46,10 +47,23 @@ pub fn render_connection_selector(f: &mut Frame, app: &App, area: Rect) {
             .enumerate()
             .map(|(i, profile)| {
                 let content = format!(
-                    "{} - {}:{}/{}",
-                    profile.name, profile.host, profile.port, profile.database
+                    "{} - {}:{}/{}{}{}",
+                    profile.name,
+                    profile.host,
+                    profile.port,
+                    profile.database,
+                    if profile.read_only { "  (read-only)" } else { "" },
+                    if profile.auto_connect { "  (auto-connect)" } else { "" }
                 );
-                
+                let mut spans = Vec::new();
+                if let (Some(environment), Some(color)) = (profile.environment, profile.environment_color()) {
+                    spans.push(Span::styled(
+                        format!("[{}]", environment.label()),
+                        Style::default().fg(parse_color(&color)).add_modifier(Modifier::BOLD),
+                    ));
+                    spans.push(Span::raw(" "));
+                }
+
                 let style = if i == app.selected_profile {
                     Style::default()
                         .fg(Color::Yellow)
@@ -58,7 +72,8 @@ pub fn render_connection_selector(f: &mut Frame, app: &App, area: Rect) {
                     Style::default().fg(Color::White)
                 };
 
-                ListItem::new(content).style(style)
+                spans.push(Span::styled(content, style));
+                ListItem::new(Line::from(spans))
             })
             .collect();
 
@@ -75,7 +90,7 @@ pub fn render_connection_selector(f: &mut Frame, app: &App, area: Rect) {
     // Instructions
     let instructions = Paragraph::new(vec![
         Line::from("↑/↓: Navigate | Enter: Connect | n: New Connection"),
-        Line::from("e: Edit Selected | d: Delete Selected | q: Quit"),
+        Line::from("t: Tag dev/staging/prod | r: Read-only | a: Auto-connect at startup | d: Delete Selected | q: Quit"),
     ])
     .style(Style::default().fg(Color::DarkGray))
     .alignment(Alignment::Center);
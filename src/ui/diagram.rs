@@ -0,0 +1,29 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Text},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+
+pub fn render_diagram(f: &mut Frame, app: &App, area: Rect) {
+    let Some(diagram) = &app.diagram else {
+        return;
+    };
+    let text = Text::from(diagram.lines.iter().map(|line| Line::raw(line.as_str())).collect::<Vec<_>>());
+    let title = format!(
+        "Relationships in {} - {} tables, {} foreign keys (referenced tables above)",
+        diagram.schema, diagram.tables, diagram.links
+    );
+    let paragraph = Paragraph::new(text)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .scroll((diagram.scroll_y, diagram.scroll_x));
+    f.render_widget(paragraph, area);
+}
@@ -0,0 +1,135 @@
+use ratatui::{
+    layout::{Alignment, Constraint, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Clear, Gauge, Paragraph, Row, Table, TableState},
+    Frame,
+};
+
+use crate::app::App;
+
+pub fn render_import_wizard(f: &mut Frame, app: &App) {
+    let Some(wizard) = &app.import_wizard else {
+        return;
+    };
+
+    let area = f.area();
+    let width = 110.min(area.width.saturating_sub(4));
+    let height = (wizard.headers.len() as u16 + 4).min(area.height.saturating_sub(2));
+    let popup_area = Rect {
+        x: area.x + (area.width.saturating_sub(width)) / 2,
+        y: area.y + (area.height.saturating_sub(height)) / 2,
+        width,
+        height,
+    };
+
+    let header = Row::new(vec!["CSV field", "Column", "Type", "Sample"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+
+    let rows: Vec<Row> = wizard
+        .headers
+        .iter()
+        .zip(&wizard.mapping)
+        .enumerate()
+        .map(|(i, (field, target))| {
+            let sample: Vec<&str> = wizard
+                .samples
+                .iter()
+                .filter_map(|row| row.get(i).map(String::as_str))
+                .collect();
+            let (column, data_type) = match target.map(|c| &wizard.columns[c]) {
+                Some(column) => (
+                    Cell::from(column.name.clone()).style(Style::default().fg(Color::Green)),
+                    column.data_type.clone(),
+                ),
+                None => (Cell::from("(skip)").style(Style::default().fg(Color::DarkGray)), String::new()),
+            };
+            Row::new(vec![
+                Cell::from(field.clone()),
+                column,
+                Cell::from(data_type).style(Style::default().fg(Color::Cyan)),
+                Cell::from(sample.join(" | ")).style(Style::default().fg(Color::DarkGray)),
+            ])
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Percentage(20),
+            Constraint::Percentage(20),
+            Constraint::Percentage(15),
+            Constraint::Min(20),
+        ],
+    )
+    .header(header)
+    .row_highlight_style(Style::default().bg(Color::DarkGray))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(
+                "Import {} into {} (↑↓:field | ←→:column | Del:skip | Enter:import | Esc:cancel)",
+                wizard.path, wizard.table
+            ))
+            .border_style(Style::default().fg(Color::Yellow)),
+    );
+
+    let mut state = TableState::default();
+    state.select(Some(wizard.selected));
+
+    f.render_widget(Clear, popup_area);
+    f.render_stateful_widget(table, popup_area, &mut state);
+}
+
+// Elapsed time along the bottom of `area` while a materialized view refreshes;
+// the server gives no progress for it
+pub fn render_refresh_progress(f: &mut Frame, app: &App, area: Rect) {
+    let Some(job) = &app.refresh_job else {
+        return;
+    };
+    let width = 60.min(area.width);
+    let box_area = Rect {
+        x: area.x + area.width.saturating_sub(width) / 2,
+        y: area.y + area.height.saturating_sub(3),
+        width,
+        height: 3.min(area.height),
+    };
+    let widget = Paragraph::new(format!("{:.1}s elapsed", job.started.elapsed().as_secs_f64()))
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Refreshing {}", job.view))
+                .border_style(Style::default().fg(Color::Yellow)),
+        );
+
+    f.render_widget(Clear, box_area);
+    f.render_widget(widget, box_area);
+}
+
+// Progress bar along the bottom of `area` while an import runs
+pub fn render_import_progress(f: &mut Frame, app: &App, area: Rect) {
+    let Some(job) = &app.import_job else {
+        return;
+    };
+    let width = 60.min(area.width);
+    let gauge_area = Rect {
+        x: area.x + area.width.saturating_sub(width) / 2,
+        y: area.y + area.height.saturating_sub(3),
+        width,
+        height: 3.min(area.height),
+    };
+    let ratio = job.ratio();
+    let gauge = Gauge::default()
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Importing into {}", job.table))
+                .border_style(Style::default().fg(Color::Yellow)),
+        )
+        .gauge_style(Style::default().fg(Color::Green))
+        .ratio(ratio)
+        .label(format!("{:.0}% of {} KB", ratio * 100.0, job.total_bytes.div_ceil(1024)));
+
+    f.render_widget(Clear, gauge_area);
+    f.render_widget(gauge, gauge_area);
+}
@@ -0,0 +1,138 @@
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Clear, Row, Table, TableState},
+    Frame,
+};
+
+use crate::app::App;
+
+pub fn render_insert_form(f: &mut Frame, app: &App) {
+    let Some(form) = &app.insert_form else {
+        return;
+    };
+
+    let area = f.area();
+    let width = 110.min(area.width.saturating_sub(4));
+    let height = (form.columns.len() as u16 + 4).min(area.height.saturating_sub(2));
+    let popup_area = Rect {
+        x: area.x + (area.width.saturating_sub(width)) / 2,
+        y: area.y + (area.height.saturating_sub(height)) / 2,
+        width,
+        height,
+    };
+
+    let header = Row::new(vec!["Column", "Type", "Null", "Default", "Value"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+
+    let rows: Vec<Row> = form
+        .columns
+        .iter()
+        .zip(&form.values)
+        .enumerate()
+        .map(|(i, (column, value))| {
+            let required = column.is_required();
+            let name_style = if required {
+                Style::default().fg(Color::Red)
+            } else {
+                Style::default()
+            };
+            let null = if column.is_nullable == "NO" { "NOT NULL" } else { "" };
+            let placeholder = if value.is_empty() {
+                if column.column_default.is_some() { "(default)" } else { "(omit)" }
+            } else {
+                ""
+            };
+            let cursor = if i == form.selected { "█" } else { "" };
+            Row::new(vec![
+                Cell::from(format!("{}{}", column.name, if required { " *" } else { "" })).style(name_style),
+                Cell::from(column.data_type.clone()).style(Style::default().fg(Color::Cyan)),
+                Cell::from(null),
+                Cell::from(column.column_default.clone().unwrap_or_default()).style(Style::default().fg(Color::DarkGray)),
+                Cell::from(format!("{}{}{}", value, cursor, placeholder)),
+            ])
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Percentage(20),
+            Constraint::Percentage(15),
+            Constraint::Length(8),
+            Constraint::Percentage(20),
+            Constraint::Min(20),
+        ],
+    )
+    .header(header)
+    .row_highlight_style(Style::default().bg(Color::DarkGray))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(
+                "Insert into {} (↑↓/Tab:field | Enter:preview | Esc:cancel | NULL for null, * required)",
+                form.table
+            ))
+            .border_style(Style::default().fg(Color::Yellow)),
+    );
+
+    let mut state = TableState::default();
+    state.select(Some(form.selected));
+
+    f.render_widget(Clear, popup_area);
+    f.render_stateful_widget(table, popup_area, &mut state);
+}
+
+pub fn render_param_form(f: &mut Frame, app: &App) {
+    let Some(form) = &app.param_form else {
+        return;
+    };
+
+    let area = f.area();
+    let width = 90.min(area.width.saturating_sub(4));
+    let height = (form.names.len() as u16 + 4).min(area.height.saturating_sub(2));
+    let popup_area = Rect {
+        x: area.x + (area.width.saturating_sub(width)) / 2,
+        y: area.y + (area.height.saturating_sub(height)) / 2,
+        width,
+        height,
+    };
+
+    let header = Row::new(vec!["Parameter", "Type", "Value"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+
+    let rows: Vec<Row> = form
+        .names
+        .iter()
+        .zip(&form.values)
+        .enumerate()
+        .map(|(i, (name, value))| {
+            let cursor = if i == form.selected { "█" } else { "" };
+            let data_type = form.types.get(i).cloned().unwrap_or_default();
+            Row::new(vec![
+                Cell::from(name.clone()),
+                Cell::from(data_type).style(Style::default().fg(Color::Cyan)),
+                Cell::from(format!("{}{}", value, cursor)),
+            ])
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [Constraint::Percentage(25), Constraint::Percentage(20), Constraint::Min(20)],
+    )
+    .header(header)
+    .row_highlight_style(Style::default().bg(Color::DarkGray))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Query parameters (↑↓/Tab:field | Enter:run | Esc:cancel | NULL for null)")
+            .border_style(Style::default().fg(Color::Yellow)),
+    );
+
+    let mut state = TableState::default();
+    state.select(Some(form.selected));
+
+    f.render_widget(Clear, popup_area);
+    f.render_stateful_widget(table, popup_area, &mut state);
+}
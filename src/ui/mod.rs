@@ -1,42 +1,198 @@
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
-    text::Span,
-    widgets::{Block, Borders, Paragraph},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Tabs},
     Frame,
 };
 
-use crate::app::{App, AppMode};
+use crate::app::{App, AppMode, MacroState, QueryFocus};
+use crate::config::Environment;
+use crate::keymap::Action;
+use crate::theme::parse_color;
 
 mod connection_selector;
 mod connection;
 mod browser;
+mod confirm;
+mod diagram;
+mod import;
+mod insert_form;
+mod monitor;
+mod notifications;
+mod palette;
+mod prompt;
 mod query;
+mod tour;
 
 pub fn render(f: &mut Frame, app: &mut App) {
-    let chunks = Layout::default()
+    let layout = app.layout();
+    let status_height = if layout.show_status_bar { 3 } else { 0 };
+    // Connection tab bar, only once there is more than one to switch between
+    let connected = matches!(app.mode, AppMode::Browser | AppMode::Query | AppMode::Monitor | AppMode::Diagram);
+    let show_tabs = matches!(app.mode, AppMode::Browser | AppMode::Query) && app.workspaces.len() > 1;
+    // Environment banner of a tagged connection, always in view
+    let show_banner = connected && (app.ws.environment.is_some() || app.ws.read_only);
+    let outer = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Min(0), Constraint::Length(3)])
+        .constraints([
+            Constraint::Length(if show_banner { 1 } else { 0 }),
+            Constraint::Length(if show_tabs { 1 } else { 0 }),
+            Constraint::Min(0),
+        ])
         .split(f.area());
+    if show_banner {
+        render_environment_banner(f, app, outer[0]);
+    }
+    if show_tabs {
+        render_workspace_tabs(f, app, outer[1]);
+    }
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(status_height)])
+        .split(outer[2]);
 
     // Main content area
     match app.mode {
         AppMode::ConnectionSelector => connection_selector::render_connection_selector(f, app, chunks[0]),
         AppMode::ConnectionEdit => connection::render_connection(f, app, chunks[0]),
-        AppMode::Browser => {
+        AppMode::Browser if layout.show_details => {
             let main_chunks = Layout::default()
                 .direction(Direction::Horizontal)
-                .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
+                .constraints([
+                    Constraint::Percentage(layout.browser_width),
+                    Constraint::Percentage(100 - layout.browser_width),
+                ])
                 .split(chunks[0]);
             
             browser::render_browser(f, app, main_chunks[0]);
             browser::render_details(f, app, main_chunks[1]);
         }
-        AppMode::Query => query::render_query(f, app, chunks[0]),
+        AppMode::Browser => browser::render_browser(f, app, chunks[0]),
+        AppMode::Monitor => monitor::render_monitor(f, app, chunks[0]),
+        AppMode::Diagram => diagram::render_diagram(f, app, chunks[0]),
+        AppMode::Query => match app.split_partner {
+            Some(partner) => {
+                let panes = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .split(chunks[0]);
+                // Panes keep their tab order so switching focus doesn't swap sides
+                let (active, other) = if app.active_workspace < partner {
+                    (panes[0], panes[1])
+                } else {
+                    (panes[1], panes[0])
+                };
+                query::render_query(f, app, active);
+                query::render_workspace_query(f, app, &app.workspaces[partner], other);
+            }
+            None => query::render_query(f, app, chunks[0]),
+        },
     }
 
     // Status bar
-    render_status_bar(f, app, chunks[1]);
+    if layout.show_status_bar {
+        render_status_bar(f, app, chunks[1]);
+    }
+
+    if app.popup.is_some() {
+        palette::render_result_popup(f, app);
+    }
+    if app.palette.is_some() {
+        palette::render_palette(f, app);
+    }
+    if app.action_palette.is_some() {
+        palette::render_action_palette(f, app);
+    }
+    if app.results_picker.is_some() {
+        palette::render_results_history(f, app);
+    }
+    if app.database_picker.is_some() {
+        palette::render_database_picker(f, app);
+    }
+    if app.schema_search.is_some() {
+        palette::render_schema_search(f, app);
+    }
+    if app.json_path_picker.is_some() {
+        palette::render_json_path_picker(f, app);
+    }
+    if app.column_chooser.is_some() {
+        palette::render_column_chooser(f, app);
+    }
+    if app.favorites_picker.is_some() {
+        palette::render_favorites_picker(f, app);
+    }
+    if app.recent_picker.is_some() {
+        palette::render_recent_objects(f, app);
+    }
+    if app.cell_diff.is_some() {
+        palette::render_cell_diff(f, app);
+    }
+    if app.insert_form.is_some() {
+        insert_form::render_insert_form(f, app);
+    }
+    if app.param_form.is_some() {
+        insert_form::render_param_form(f, app);
+    }
+    if app.import_wizard.is_some() {
+        import::render_import_wizard(f, app);
+    }
+    import::render_import_progress(f, app, chunks[0]);
+    import::render_refresh_progress(f, app, chunks[0]);
+    if app.pending.is_some() {
+        confirm::render_pending(f, app);
+    }
+    if app.destructive.is_some() {
+        confirm::render_destructive(f, app);
+    }
+    if app.cost_warning.is_some() {
+        confirm::render_cost_warning(f, app);
+    }
+    if app.prompt.is_some() {
+        prompt::render_prompt(f, app);
+    }
+    tour::render_tour(f, app, chunks[0]);
+    notifications::render_toasts(f, app, chunks[0]);
+}
+
+fn render_environment_banner(f: &mut Frame, app: &App, area: Rect) {
+    let mut text = match app.ws.environment {
+        Some(environment) => format!("{} | {}", environment.banner(), app.ws.name),
+        None => app.ws.name.clone(),
+    };
+    if app.ws.read_only {
+        text.push_str(" | READ ONLY");
+    } else if app.ws.environment == Some(Environment::Prod) {
+        text.push_str(" | writes ask for confirmation");
+    }
+    // Untagged read-only connections get a neutral banner
+    let color = match app.ws.environment {
+        Some(_) => parse_color(&app.ws.environment_color),
+        None => Color::Gray,
+    };
+    let banner = Paragraph::new(text).alignment(Alignment::Center).style(
+        Style::default()
+            .fg(Color::Black)
+            .bg(color)
+            .add_modifier(Modifier::BOLD),
+    );
+    f.render_widget(banner, area);
+}
+
+fn render_workspace_tabs(f: &mut Frame, app: &App, area: Rect) {
+    let titles: Vec<String> = app
+        .workspace_names()
+        .iter()
+        .enumerate()
+        .map(|(i, name)| format!(" {}:{} ", i + 1, name))
+        .collect();
+    let tabs = Tabs::new(titles)
+        .select(app.active_workspace)
+        .style(Style::default().fg(Color::DarkGray))
+        .highlight_style(Style::default().fg(Color::Black).bg(Color::Cyan))
+        .divider("|")
+        .padding("", "");
+    f.render_widget(tabs, area);
 }
 
 fn render_status_bar(f: &mut Frame, app: &App, area: Rect) {
@@ -45,40 +201,101 @@ fn render_status_bar(f: &mut Frame, app: &App, area: Rect) {
         AppMode::ConnectionEdit => "EDIT CONNECTION",
         AppMode::Browser => "BROWSER",
         AppMode::Query => "QUERY",
+        AppMode::Monitor => "ACTIVITY",
+        AppMode::Diagram => "DIAGRAM",
     };
 
-    let status_text = if let Some(err) = &app.error_message {
-        format!(" {} | ERROR: {} ", mode_text, err)
-    } else {
-        match app.mode {
-            AppMode::ConnectionSelector => {
-                if app.config.connections.is_empty() {
-                    format!(" {} | n:new connection | q:quit ", mode_text)
-                } else {
-                    format!(" {} | ↑↓:navigate | Enter:select | n:new | d:delete | q:quit ", mode_text)
-                }
+    let key = |action| app.keymap.label(action);
+    let status_text = match app.mode {
+        AppMode::ConnectionSelector => {
+            if app.config.connections.is_empty() {
+                format!(" {} | n:new connection | q:quit ", mode_text)
+            } else {
+                format!(" {} | ↑↓:navigate | Enter:select | n:new | t:tag | d:delete | q:quit ", mode_text)
             }
-            AppMode::ConnectionEdit => format!(" {} | Tab:next field | Enter:connect | Esc:back | q:quit ", mode_text),
-            AppMode::Browser => {
-                if app.filter_active {
-                    format!(" {} | FILTER MODE | Esc:clear filter | ↑↓:navigate | Enter:select | q:quit ", mode_text)
-                } else if app.selected_table.is_some() {
-                    format!(" {} | ←→:[/]:switch tabs | /:filter | ↑↓:navigate | Enter:expand | Tab:query mode | r:refresh | q:quit ", mode_text)
-                } else {
-                    format!(" {} | /:filter | ↑↓:navigate | Enter:expand | Tab:query mode | r:refresh | q:quit ", mode_text)
-                }
+        }
+        AppMode::ConnectionEdit => format!(" {} | Tab:next field | Enter:connect | Esc:back | q:quit ", mode_text),
+        AppMode::Browser => {
+            if app.ws.filter_active {
+                format!(" {} | FILTER MODE | Esc:clear filter | ↑↓:navigate | Enter:select | q:quit ", mode_text)
+            } else if app.ws.selected_table.is_some() {
+                format!(
+                    " {} | [/]:switch tabs | {}:DDL/source | {}:insert row | PgUp/PgDn:scroll | {}:filter | ↑↓←→:navigate | Enter:expand | Tab:query mode | {}:refresh | {}:layout [{}] | q:quit ",
+                    mode_text,
+                    key(Action::ShowDdl),
+                    key(Action::InsertRow),
+                    key(Action::FilterBrowser),
+                    key(Action::RefreshBrowser),
+                    key(Action::CycleLayout),
+                    app.layout().name,
+                )
+            } else {
+                format!(
+                    " {} | {}:filter | ↑↓←→:navigate | Enter:expand | Tab:query mode | {}:refresh | {}/{}:resize | {}:details | {}:layout [{}] | q:quit ",
+                    mode_text,
+                    key(Action::FilterBrowser),
+                    key(Action::RefreshBrowser),
+                    key(Action::NarrowBrowser),
+                    key(Action::WidenBrowser),
+                    key(Action::ToggleDetails),
+                    key(Action::CycleLayout),
+                    app.layout().name,
+                )
             }
-            AppMode::Query => format!(" {} | Ctrl+Enter/F5:execute | Tab:browser mode | q:quit ", mode_text),
         }
+        AppMode::Query if app.ws.query_focus == QueryFocus::Results => {
+            format!(
+                " {} | RESULTS | ↑↓←→:move | PgUp/PgDn:page | Home/End:first/last | {}:edit cell | {}:sort | {}:filter | {}/Esc:editor ",
+                mode_text,
+                key(Action::EditCell),
+                key(Action::SortColumn),
+                key(Action::FilterResults),
+                key(Action::ToggleResultsFocus),
+            )
+        }
+        AppMode::Query => format!(
+            " {} | {}:execute | {}:run script | {}:docs | {}:results | {}:split | Tab:browser mode | {}:messages | q:quit ",
+            mode_text,
+            key(Action::ExecuteQuery),
+            key(Action::RunScript),
+            key(Action::InspectToken),
+            key(Action::ToggleResultsFocus),
+            key(Action::ToggleSplit),
+            key(Action::ShowMessages),
+        ),
+        AppMode::Monitor => format!(
+            " {} | Tab:activity/locks | ↑↓:navigate | Enter:full query | s:sort by pid/duration | r:refresh | c:cancel query | k:terminate | Esc:back ",
+            mode_text,
+        ),
+        AppMode::Diagram => format!(" {} | ↑↓←→:scroll | PgUp/PgDn:page | Home:top left | End:bottom | Esc:back ", mode_text),
     };
 
-    let status_style = if app.error_message.is_some() {
-        Style::default().fg(Color::Red).bg(Color::Black)
-    } else {
-        Style::default().fg(Color::Cyan).bg(Color::Black)
+    let status_text = match app.macro_state {
+        MacroState::Recording(register) => format!(" ● REC @{} |{}", register, status_text),
+        _ => status_text,
     };
 
-    let status = Paragraph::new(status_text)
+    let status_style = Style::default().fg(Color::Cyan).bg(Color::Black);
+
+    // Connection health first, on the screens that use the connection
+    let mut spans = Vec::new();
+    if matches!(app.mode, AppMode::Browser | AppMode::Query) && app.ws.db.is_connected() {
+        spans.push(match &app.ws.reconnect {
+            Some(reconnect) => Span::styled(
+                format!(
+                    " ✖ DISCONNECTED ({}; retry in {}s, {}:now) |",
+                    reconnect.reason,
+                    reconnect.due_in(),
+                    key(Action::Reconnect)
+                ),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ),
+            None => Span::styled(" ●", Style::default().fg(Color::Green)),
+        });
+    }
+    spans.push(Span::raw(status_text));
+
+    let status = Paragraph::new(Line::from(spans))
         .style(status_style)
         .block(Block::default().borders(Borders::ALL));
 
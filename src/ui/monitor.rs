@@ -0,0 +1,150 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::Text,
+    widgets::{Block, Borders, Paragraph, Row, Table, TableState, Tabs},
+    Frame,
+};
+
+use crate::app::{format_duration, ActivityMonitor, App, MonitorTab};
+use crate::theme::GridTheme;
+
+pub fn render_monitor(f: &mut Frame, app: &App, area: Rect) {
+    let Some(monitor) = &app.monitor else {
+        return;
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(if monitor.error.is_some() { 1 } else { 0 }),
+        ])
+        .split(area);
+
+    let tabs = Tabs::new(vec![" Activity ", " Locks "])
+        .select(match monitor.tab {
+            MonitorTab::Activity => 0,
+            MonitorTab::Locks => 1,
+        })
+        .style(Style::default().fg(Color::DarkGray))
+        .highlight_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+        .divider("|");
+    f.render_widget(tabs, chunks[0]);
+
+    let grid_theme = &app.config.theme.grid;
+    let (table, len) = match monitor.tab {
+        MonitorTab::Activity => (activity_table(monitor, grid_theme), monitor.sessions.len()),
+        MonitorTab::Locks => (locks_table(monitor, grid_theme), monitor.lock_tree.len()),
+    };
+    let title = match monitor.tab {
+        MonitorTab::Activity => format!(
+            "Server Activity - {} backends, by {} (refreshed every {}s)",
+            len,
+            if monitor.by_duration { "duration" } else { "pid" },
+            app.monitor_interval().as_secs(),
+        ),
+        MonitorTab::Locks if len == 0 => "Locks - no backend is waiting for a lock".to_string(),
+        MonitorTab::Locks => format!("Locks - blocker → blocked (refreshed every {}s)", app.monitor_interval().as_secs()),
+    };
+    let table = table.row_highlight_style(grid_theme.selected_row_style()).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+
+    let mut state = TableState::default();
+    if len > 0 {
+        state.select(Some(monitor.selected));
+    }
+    f.render_stateful_widget(table, chunks[1], &mut state);
+
+    if let Some(error) = &monitor.error {
+        f.render_widget(Paragraph::new(error.as_str()).style(Style::default().fg(Color::Red)), chunks[2]);
+    }
+}
+
+// One line per query; Enter shows it in full
+fn one_line(query: &str) -> String {
+    query.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn activity_table<'a>(monitor: &'a ActivityMonitor, grid_theme: &GridTheme) -> Table<'a> {
+    let header = Row::new(["PID", "User", "Database", "State", "Wait event", "Duration", "Query"])
+        .style(grid_theme.header_style());
+    let rows: Vec<Row> = monitor
+        .sessions
+        .iter()
+        .enumerate()
+        .map(|(i, session)| {
+            let state_color = match session.state.as_str() {
+                "active" => Color::Green,
+                "idle in transaction" | "idle in transaction (aborted)" => Color::Yellow,
+                _ => Color::Reset,
+            };
+            Row::new(vec![
+                Text::raw(session.pid.to_string()),
+                Text::raw(session.user.clone()),
+                Text::raw(session.database.clone()),
+                Text::styled(session.state.clone(), Style::default().fg(state_color)),
+                Text::raw(session.wait_event.clone()),
+                Text::raw(session.duration.map(format_duration).unwrap_or_default()),
+                Text::raw(one_line(&session.query)),
+            ])
+            .style(grid_theme.row_style(i))
+        })
+        .collect();
+
+    let widths = [
+        Constraint::Length(8),
+        Constraint::Length(14),
+        Constraint::Length(14),
+        Constraint::Length(20),
+        Constraint::Length(22),
+        Constraint::Length(9),
+        Constraint::Min(10),
+    ];
+    Table::new(rows, widths).header(header)
+}
+
+fn locks_table<'a>(monitor: &'a ActivityMonitor, grid_theme: &GridTheme) -> Table<'a> {
+    let header = Row::new(["PID", "User", "Waiting for", "Duration", "Query"]).style(grid_theme.header_style());
+    let rows: Vec<Row> = monitor
+        .lock_tree
+        .iter()
+        .enumerate()
+        .map(|(i, &(depth, index))| {
+            let lock = &monitor.locks[index];
+            let pid = if depth == 0 {
+                lock.pid.to_string()
+            } else {
+                format!("{}└ {}", "  ".repeat(depth - 1), lock.pid)
+            };
+            // Roots hold the locks everything below them waits for
+            let pid_style = if depth == 0 {
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            Row::new(vec![
+                Text::styled(pid, pid_style),
+                Text::raw(lock.user.clone()),
+                Text::raw(lock.waiting_for.clone()),
+                Text::raw(lock.duration.map(format_duration).unwrap_or_default()),
+                Text::raw(one_line(&lock.query)),
+            ])
+            .style(grid_theme.row_style(i))
+        })
+        .collect();
+
+    let widths = [
+        Constraint::Length(16),
+        Constraint::Length(14),
+        Constraint::Length(36),
+        Constraint::Length(9),
+        Constraint::Min(10),
+    ];
+    Table::new(rows, widths).header(header)
+}
@@ -0,0 +1,54 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::{App, NotificationLevel};
+
+const TOAST_WIDTH: u16 = 60;
+
+// Active notifications stacked up from the bottom-right corner of `area`,
+// newest at the bottom
+pub fn render_toasts(f: &mut Frame, app: &App, area: Rect) {
+    let width = TOAST_WIDTH.min(area.width.saturating_sub(2));
+    if width < 10 {
+        return;
+    }
+    let inner_width = (width - 2) as usize;
+    let x = area.x + area.width - width - 1;
+    let mut bottom = area.y + area.height;
+
+    let toasts: Vec<_> = app.active_notifications().collect();
+    for toast in toasts.into_iter().rev() {
+        let lines: usize = toast
+            .message
+            .lines()
+            .map(|line| line.chars().count().max(1).div_ceil(inner_width))
+            .sum();
+        let height = (lines.max(1) as u16 + 2).min(area.height / 2);
+        if bottom < area.y + height {
+            break;
+        }
+        bottom -= height;
+
+        let color = match toast.level {
+            NotificationLevel::Info => Color::Green,
+            NotificationLevel::Warn => Color::Yellow,
+            NotificationLevel::Error => Color::Red,
+        };
+        let toast_area = Rect { x, y: bottom, width, height };
+        let paragraph = Paragraph::new(toast.message.as_str())
+            .style(Style::default().fg(color))
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(toast.level.label())
+                    .border_style(Style::default().fg(color)),
+            );
+        f.render_widget(Clear, toast_area);
+        f.render_widget(paragraph, toast_area);
+    }
+}
@@ -0,0 +1,585 @@
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Clear, List, ListItem, ListState, Paragraph, Row, Table, Wrap},
+    Frame,
+};
+
+use crate::app::{format_duration, search_label, App, Change};
+use crate::config::{CommandTarget, Favorite};
+use crate::keymap::Scope;
+
+// Centered rectangle of at most `width` x `height` cells
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width.saturating_sub(4));
+    let height = height.min(area.height.saturating_sub(2));
+    Rect {
+        x: area.x + (area.width.saturating_sub(width)) / 2,
+        y: area.y + (area.height.saturating_sub(height)) / 2,
+        width,
+        height,
+    }
+}
+
+pub fn render_palette(f: &mut Frame, app: &App) {
+    let Some(palette) = &app.palette else {
+        return;
+    };
+
+    let commands = app.palette_commands();
+    let items: Vec<ListItem> = commands
+        .iter()
+        .map(|cmd| {
+            let target = match &cmd.target {
+                _ if cmd.script.is_some() => "script".to_string(),
+                CommandTarget::Results => "results".to_string(),
+                CommandTarget::Popup => "popup".to_string(),
+                CommandTarget::File(path) => format!("→ {}", path),
+            };
+            ListItem::new(Line::from(vec![
+                Span::raw(cmd.name.clone()),
+                Span::styled(format!("  [{}]", target), Style::default().fg(Color::DarkGray)),
+            ]))
+        })
+        .collect();
+
+    let area = centered(f.area(), 70, commands.len() as u16 + 2 + 1);
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Commands: {}█ (Enter:run | Esc:close)", palette.filter))
+                .border_style(Style::default().fg(Color::Yellow)),
+        )
+        .highlight_style(Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD));
+
+    let mut state = ListState::default();
+    if !commands.is_empty() {
+        state.select(Some(palette.selected));
+    }
+
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+pub fn render_database_picker(f: &mut Frame, app: &App) {
+    let Some(picker) = &app.database_picker else {
+        return;
+    };
+
+    let items: Vec<ListItem> = picker
+        .databases
+        .iter()
+        .map(|db| {
+            let current = if db.name == app.database { "  (current)" } else { "" };
+            ListItem::new(Line::from(vec![
+                Span::raw(db.name.clone()),
+                Span::styled(format!("  [{}]{}", db.owner, current), Style::default().fg(Color::DarkGray)),
+            ]))
+        })
+        .collect();
+
+    let area = centered(f.area(), 60, picker.databases.len() as u16 + 2);
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Switch database (Enter:connect | Esc:close)")
+                .border_style(Style::default().fg(Color::Yellow)),
+        )
+        .highlight_style(Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD));
+
+    let mut state = ListState::default();
+    if !picker.databases.is_empty() {
+        state.select(Some(picker.selected));
+    }
+
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+pub fn render_action_palette(f: &mut Frame, app: &App) {
+    let Some(palette) = &app.action_palette else {
+        return;
+    };
+
+    // The title with the filter's characters highlighted, then its keys and
+    // where it applies
+    let matched_style = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
+    let items: Vec<ListItem> = palette
+        .matches
+        .iter()
+        .map(|(action, title, matched)| {
+            let mut spans: Vec<Span> = title
+                .chars()
+                .enumerate()
+                .map(|(j, c)| {
+                    if matched.contains(&j) {
+                        Span::styled(c.to_string(), matched_style)
+                    } else {
+                        Span::raw(c.to_string())
+                    }
+                })
+                .collect();
+            let screen = match action.scope() {
+                Scope::Global | Scope::Workspace => "",
+                Scope::Browser => ", browser",
+                Scope::Query | Scope::Editor => ", editor",
+                Scope::Results => ", results",
+            };
+            spans.push(Span::styled(
+                format!("  [{}{}]", app.keymap.label(*action), screen),
+                Style::default().fg(Color::DarkGray),
+            ));
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let area = centered(f.area(), 80, 22);
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Actions: {}█ (Enter:run | Esc:close)", palette.filter))
+                .border_style(Style::default().fg(Color::Yellow)),
+        )
+        .highlight_style(Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD));
+
+    let mut state = ListState::default();
+    if !palette.matches.is_empty() {
+        state.select(Some(palette.selected));
+    }
+
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+pub fn render_results_history(f: &mut Frame, app: &App) {
+    let Some(picker) = &app.results_picker else {
+        return;
+    };
+
+    // When it ran and how big it is, then the start of its query
+    let items: Vec<ListItem> = picker
+        .entries
+        .iter()
+        .map(|saved| {
+            let sql = saved.sql.split_whitespace().collect::<Vec<_>>().join(" ");
+            let partial = if saved.partial { "+" } else { "" };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!(
+                        "{:>10} ago  {:>6}{} rows × {:<3} ",
+                        format_duration(saved.age() as f64),
+                        saved.rows.len(),
+                        partial,
+                        saved.columns.len()
+                    ),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::raw(sql),
+            ]))
+        })
+        .collect();
+
+    let area = centered(f.area(), 100, picker.entries.len() as u16 + 2);
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Results history of {} (Enter:show | Esc:close)", app.ws.name))
+                .border_style(Style::default().fg(Color::Yellow)),
+        )
+        .highlight_style(Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD));
+
+    let mut state = ListState::default();
+    state.select(Some(picker.selected));
+
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+pub fn render_column_chooser(f: &mut Frame, app: &App) {
+    let Some(chooser) = &app.column_chooser else {
+        return;
+    };
+
+    let items: Vec<ListItem> = chooser
+        .columns
+        .iter()
+        .map(|(name, shown)| match shown {
+            true => ListItem::new(format!("[x] {}", name)),
+            false => ListItem::new(format!("[ ] {}", name)).style(Style::default().fg(Color::DarkGray)),
+        })
+        .collect();
+
+    let area = centered(f.area(), 70, chooser.columns.len() as u16 + 2);
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Columns (Space:show/hide | K/J:move | Enter:apply | Esc:cancel)")
+                .border_style(Style::default().fg(Color::Yellow)),
+        )
+        .highlight_style(Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD));
+
+    let mut state = ListState::default();
+    state.select(Some(chooser.selected));
+
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+pub fn render_schema_search(f: &mut Frame, app: &App) {
+    let Some(search) = &app.schema_search else {
+        return;
+    };
+
+    // The filter's characters highlighted in each name, then what it is
+    let matched_style = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
+    let qualified = search.qualified();
+    let items: Vec<ListItem> = search
+        .matches
+        .iter()
+        .map(|(i, matched)| {
+            let object = &search.objects[*i];
+            let mut spans: Vec<Span> = search_label(object, qualified)
+                .chars()
+                .enumerate()
+                .map(|(j, c)| {
+                    if matched.contains(&j) {
+                        Span::styled(c.to_string(), matched_style)
+                    } else {
+                        Span::raw(c.to_string())
+                    }
+                })
+                .collect();
+            let place = match &object.parent {
+                Some((_, parent)) => format!("{}.{}", object.schema, parent),
+                None => object.schema.clone(),
+            };
+            let detail = if object.detail.is_empty() { String::new() } else { format!(", {}", object.detail) };
+            spans.push(Span::styled(
+                format!("  [{} in {}{}]", object.kind.label(), place, detail),
+                Style::default().fg(Color::DarkGray),
+            ));
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let area = centered(f.area(), 90, 22);
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(
+                    "Search schema: {}█ ({} of {} objects) (Enter:show in browser | Esc:close)",
+                    search.filter,
+                    search.matches.len(),
+                    search.objects.len()
+                ))
+                .border_style(Style::default().fg(Color::Yellow)),
+        )
+        .highlight_style(Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD));
+
+    let mut state = ListState::default();
+    if !search.matches.is_empty() {
+        state.select(Some(search.selected));
+    }
+
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+pub fn render_favorites_picker(f: &mut Frame, app: &App) {
+    let Some(picker) = &app.favorites_picker else {
+        return;
+    };
+
+    // The filter's characters highlighted in each label, then its kind and,
+    // for a query, the start of its SQL
+    let matched_style = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
+    let favorites = app.favorites();
+    let items: Vec<ListItem> = picker
+        .matches
+        .iter()
+        .filter_map(|(i, matched)| {
+            let favorite = favorites.get(*i)?;
+            let mut spans: Vec<Span> = favorite
+                .label()
+                .chars()
+                .enumerate()
+                .map(|(j, c)| {
+                    if matched.contains(&j) {
+                        Span::styled(c.to_string(), matched_style)
+                    } else {
+                        Span::raw(c.to_string())
+                    }
+                })
+                .collect();
+            let detail = match favorite {
+                Favorite::Query { sql, .. } => format!(": {}", sql.split_whitespace().collect::<Vec<_>>().join(" ")),
+                _ => String::new(),
+            };
+            spans.push(Span::styled(
+                format!("  [{}{}]", favorite.kind_label(), detail),
+                Style::default().fg(Color::DarkGray),
+            ));
+            Some(ListItem::new(Line::from(spans)))
+        })
+        .collect();
+
+    let area = centered(f.area(), 80, 22);
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Favorites: {}█ (Enter:open | Del:unstar | Esc:close)", picker.filter))
+                .border_style(Style::default().fg(Color::Yellow)),
+        )
+        .highlight_style(Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD));
+
+    let mut state = ListState::default();
+    if !picker.matches.is_empty() {
+        state.select(Some(picker.selected));
+    }
+
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+pub fn render_recent_objects(f: &mut Frame, app: &App) {
+    let Some(picker) = &app.recent_picker else {
+        return;
+    };
+
+    // The filter's characters highlighted in each name, then what it is
+    let matched_style = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
+    let items: Vec<ListItem> = picker
+        .matches
+        .iter()
+        .map(|(i, matched)| {
+            let (kind, schema, name) = &app.ws.recent_objects[*i];
+            let mut spans: Vec<Span> = format!("{}.{}", schema, name)
+                .chars()
+                .enumerate()
+                .map(|(j, c)| {
+                    if matched.contains(&j) {
+                        Span::styled(c.to_string(), matched_style)
+                    } else {
+                        Span::raw(c.to_string())
+                    }
+                })
+                .collect();
+            spans.push(Span::styled(format!("  [{}]", kind.label()), Style::default().fg(Color::DarkGray)));
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let area = centered(f.area(), 70, 22);
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Recent: {}█ (Enter:show in browser | Esc:close)", picker.filter))
+                .border_style(Style::default().fg(Color::Yellow)),
+        )
+        .highlight_style(Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD));
+
+    let mut state = ListState::default();
+    if !picker.matches.is_empty() {
+        state.select(Some(picker.selected));
+    }
+
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+pub fn render_json_path_picker(f: &mut Frame, app: &App) {
+    let Some(picker) = &app.json_path_picker else {
+        return;
+    };
+
+    let items: Vec<ListItem> = picker
+        .paths
+        .iter()
+        .map(|path| {
+            ListItem::new(Line::from(vec![
+                Span::raw(path.expression(picker.jsonpath).to_string()),
+                Span::styled(
+                    format!("  [{}, {}/{} rows]", path.types.join("|"), path.rows, picker.sampled),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]))
+        })
+        .collect();
+
+    let form = if picker.jsonpath { "jsonpath" } else { "operators" };
+    let area = centered(f.area(), 90, picker.paths.len() as u16 + 2);
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(
+                    "Paths in {} ({}) (Enter:insert | Tab:operators/jsonpath | Esc:close)",
+                    picker.column, form
+                ))
+                .border_style(Style::default().fg(Color::Yellow)),
+        )
+        .highlight_style(Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD));
+
+    let mut state = ListState::default();
+    if !picker.paths.is_empty() {
+        state.select(Some(picker.selected));
+    }
+
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+pub fn render_cell_diff(f: &mut Frame, app: &App) {
+    let Some(diff) = &app.cell_diff else {
+        return;
+    };
+
+    let removed = Style::default().fg(Color::Black).bg(Color::Red);
+    let added = Style::default().fg(Color::Black).bg(Color::Green);
+    let mut lines = vec![
+        Line::from(Span::styled(format!("- {}", diff.before), Style::default().fg(Color::Red))),
+        Line::from(Span::styled(format!("+ {}", diff.after), Style::default().fg(Color::Green))),
+        Line::default(),
+    ];
+    let mut spans: Vec<Span> = Vec::new();
+    for (change, text) in &diff.pieces {
+        for (i, part) in text.split('\n').enumerate() {
+            if i > 0 {
+                // A changed line break gets a visible mark before the break
+                if *change != Change::Same {
+                    spans.push(Span::styled("↵", if *change == Change::Removed { removed } else { added }));
+                }
+                lines.push(Line::from(std::mem::take(&mut spans)));
+            }
+            if part.is_empty() {
+                continue;
+            }
+            let span = match change {
+                Change::Same => Span::raw(part.to_string()),
+                // Changed whitespace would otherwise be invisible
+                Change::Removed => Span::styled(part.replace(' ', "·").replace('\t', "→"), removed),
+                Change::Added => Span::styled(part.replace(' ', "·").replace('\t', "→"), added),
+            };
+            spans.push(span);
+        }
+    }
+    lines.push(Line::from(spans));
+
+    let area = centered(f.area(), f.area().width * 4 / 5, f.area().height * 4 / 5);
+    let kind = if diff.json { "JSON diff, keys sorted" } else { "Cell diff" };
+    let paragraph = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .scroll((diff.scroll as u16, 0))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(
+                    "{} (-{} / +{} words) (↑↓:scroll | Esc:close)",
+                    kind, diff.words_removed, diff.words_added
+                ))
+                .border_style(Style::default().fg(Color::Yellow)),
+        );
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+pub fn render_result_popup(f: &mut Frame, app: &App) {
+    let Some(popup) = &app.popup else {
+        return;
+    };
+    let result = &popup.result;
+
+    let area = centered(f.area(), f.area().width * 4 / 5, f.area().height * 4 / 5);
+    let grid_theme = &app.config.theme.grid;
+    let null_text = &grid_theme.null_text;
+    let widths: Vec<Constraint> = result
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let widest = result
+                .rows
+                .iter()
+                .filter_map(|row| row.get(i))
+                .map(|value| value.as_ref().unwrap_or(null_text).chars().count())
+                .chain(std::iter::once(name.chars().count()))
+                .max()
+                .unwrap_or(0);
+            // The last column takes whatever width is left
+            if i + 1 == result.columns.len() {
+                Constraint::Min(widest as u16)
+            } else {
+                Constraint::Length(widest.min(40) as u16)
+            }
+        })
+        .collect();
+
+    // Long values in the last column wrap onto more lines instead of being cut
+    let fixed: u16 = widths
+        .iter()
+        .map(|w| match w {
+            Constraint::Length(n) => n + 1,
+            _ => 0,
+        })
+        .sum();
+    let wrap_width = area.width.saturating_sub(2 + fixed).max(10) as usize;
+
+    let header = Row::new(result.columns.clone()).style(grid_theme.header_style());
+    let rows: Vec<Row> = result
+        .rows
+        .iter()
+        .enumerate()
+        .skip(popup.scroll)
+        .map(|(i, row)| {
+            let mut height = 1;
+            let last = row.len().saturating_sub(1);
+            let cells: Vec<Cell> = row
+                .iter()
+                .enumerate()
+                .map(|(col, value)| match value {
+                    None => Cell::from(null_text.clone()).style(grid_theme.null_style()),
+                    Some(value) if col == last => {
+                        let lines = wrap(value, wrap_width);
+                        height = lines.len().max(1);
+                        Cell::from(lines.join("\n"))
+                    }
+                    Some(value) => Cell::from(value.clone()),
+                })
+                .collect();
+            Row::new(cells).height(height as u16).style(grid_theme.row_style(i))
+        })
+        .collect();
+
+    let table = Table::new(rows, widths).header(header).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!("{} ({} rows) (↑↓:scroll | y:copy | Esc:close)", popup.title, result.row_count))
+            .border_style(Style::default().fg(Color::Yellow)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(table, area);
+}
+
+// Lines of `text` cut to at most `width` characters
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for line in text.lines() {
+        let chars: Vec<char> = line.chars().collect();
+        if chars.is_empty() {
+            lines.push(String::new());
+        }
+        lines.extend(chars.chunks(width).map(|chunk| chunk.iter().collect::<String>()));
+    }
+    lines
+}
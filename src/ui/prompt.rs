@@ -0,0 +1,38 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+
+pub fn render_prompt(f: &mut Frame, app: &App) {
+    let Some(prompt) = &app.prompt else {
+        return;
+    };
+
+    let area = f.area();
+    // Wide enough for the title where the screen allows
+    let title = format!("{} (Enter:ok | Esc:cancel)", prompt.title);
+    let width = (title.chars().count() as u16 + 4).max(60).min(area.width.saturating_sub(4));
+    let popup_area = Rect {
+        x: area.x + (area.width.saturating_sub(width)) / 2,
+        y: area.y + area.height.saturating_sub(3) / 2,
+        width,
+        height: 3.min(area.height),
+    };
+
+    let text = if prompt.secret { "*".repeat(prompt.input.chars().count()) } else { prompt.input.clone() };
+    let input = Paragraph::new(format!("{}█", text))
+        .style(Style::default().fg(Color::White))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .border_style(Style::default().fg(Color::Yellow)),
+        );
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(input, popup_area);
+}
@@ -1,98 +1,179 @@
+use std::borrow::Cow;
+use std::time::Duration;
+
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
-    widgets::{Block, Borders, Paragraph, Row, Table, Wrap},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState, Wrap},
     Frame,
 };
+use unicode_width::UnicodeWidthStr;
+
+use crate::app::{
+    column_stats, format_duration, format_number, App, QueryFocus, SortDirection, Workspace, AUTO_COLUMN_WIDTH,
+};
+use crate::db::QueryResult;
+use crate::keymap::Action;
+
+// Values longer than this show only their start in the grid
+const LONG_VALUE: usize = 1024;
+// How much of a long value, or of a bytea's hex, the grid shows
+const PREVIEW_CHARS: usize = 200;
+const PREVIEW_BYTES: usize = 16;
+// Longest min/max value the stats footer shows in full
+const STATS_VALUE_WIDTH: usize = 20;
+
+// What the grid shows for a value: bytea as a hex preview with its size,
+// very long text cut short with its size
+fn cell_preview<'a>(text: &'a str, column_type: Option<&str>) -> Cow<'a, str> {
+    if column_type == Some("bytea") {
+        let bytes = text.len().saturating_sub(2) / 2;
+        let hex = &text[..text.len().min(2 + PREVIEW_BYTES * 2)];
+        let more = if bytes > PREVIEW_BYTES { "…" } else { "" };
+        return Cow::Owned(format!("{}{} ({})", hex, more, format_size(bytes)));
+    }
+    if text.len() <= LONG_VALUE {
+        return Cow::Borrowed(text);
+    }
+    let end = text.char_indices().nth(PREVIEW_CHARS).map_or(text.len(), |(i, _)| i);
+    Cow::Owned(format!("{}… ({})", &text[..end], format_size(text.len())))
+}
 
-use crate::app::App;
+// A value in full for an expanded column, on one line
+fn expanded_text(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\n' => '↵',
+            c if c.is_control() => ' ',
+            c => c,
+        })
+        .collect()
+}
+
+// "512 bytes", "32 KB", "1.5 MB"
+fn format_size(bytes: usize) -> String {
+    match bytes {
+        0..1024 => format!("{} bytes", bytes),
+        1024..1_048_576 => format!("{} KB", bytes / 1024),
+        _ => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+// "12 ms" below a second, "4.2s" or "3m 05s" above
+fn elapsed_label(elapsed: Duration) -> String {
+    match elapsed.as_millis() {
+        0..1000 => format!("{} ms", elapsed.as_millis()),
+        _ => format_duration(elapsed.as_secs_f64()),
+    }
+}
 
 pub fn render_query(f: &mut Frame, app: &App, area: Rect) {
+    render_workspace_query(f, app, &app.ws, area);
+}
+
+// Editor, script summary and results of one workspace
+pub fn render_workspace_query(f: &mut Frame, app: &App, ws: &Workspace, area: Rect) {
+    let editor_height = app.layout().editor_height;
+    if let Some(outcomes) = &ws.script_outcomes {
+        let summary_height = (outcomes.len() as u16 + 3).min(12);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(editor_height),
+                Constraint::Length(summary_height),
+                Constraint::Min(0),
+            ])
+            .split(area);
+
+        render_query_editor(f, app, ws, chunks[0]);
+        render_script_summary(f, ws, chunks[1]);
+        render_query_results(f, app, ws, chunks[2]);
     // Only show results panel if there are actual results
-    if app.query_result.is_some() {
+    } else if ws.query_result.is_some() {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Length(10), Constraint::Min(0)])
+            .constraints([Constraint::Length(editor_height), Constraint::Min(0)])
             .split(area);
 
         // Query editor
-        render_query_editor(f, app, chunks[0]);
+        render_query_editor(f, app, ws, chunks[0]);
 
         // Results
-        render_query_results(f, app, chunks[1]);
+        render_query_results(f, app, ws, chunks[1]);
     } else {
         // No results yet - give full space to editor
-        render_query_editor(f, app, area);
+        render_query_editor(f, app, ws, area);
     }
 }
 
-fn render_query_editor(f: &mut Frame, app: &App, area: Rect) {
+fn render_query_editor(f: &mut Frame, app: &App, ws: &Workspace, area: Rect) {
     use ratatui::text::{Line, Span};
-    use crate::syntax::SqlHighlighter;
     
-    let help_text = if app.query_input.is_empty() {
-        "\n  Type your SQL query here\n  Press Ctrl+Enter or F5 to execute\n  Tab to switch to browser mode"
+    // In split view each editor is labelled with its connection and only the
+    // focused one is highlighted
+    let focused = std::ptr::eq(ws, &app.ws);
+    let name = if app.split_partner.is_some() { ws.name.as_str() } else { "SQL Query Editor" };
+    let file = match &ws.file_path {
+        Some(path) => {
+            let file_name = std::path::Path::new(path).file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+            format!(" - {}{}", file_name, if ws.is_modified() { " ● unsaved" } else { "" })
+        }
+        None => String::new(),
+    };
+    let execute = app.keymap.label(Action::ExecuteQuery);
+    let title = format!("{}{} ({} to execute)", name, file, execute);
+    let border = if focused { Color::Cyan } else { Color::DarkGray };
+    let title = match ws.current_diagnostics().len() {
+        0 => title,
+        n => format!("{} ⚠ {} lint warning(s), {}:list", title, n, app.keymap.label(Action::ShowLint)),
+    };
+    let title = if app.config.vim_mode { format!("{} -- {} --", title, ws.vim.mode.label()) } else { title };
+
+    let help_text = if ws.query_input.is_empty() {
+        format!("\n  Type your SQL query here\n  Press {} to execute\n  Tab to switch to browser mode", execute)
     } else {
-        ""
+        String::new()
     };
 
-    if app.query_input.is_empty() {
+    if ws.query_input.is_empty() {
         // Show help text
         let editor = Paragraph::new(help_text)
             .style(Style::default().fg(Color::DarkGray))
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title("SQL Query Editor (Ctrl+Enter or F5 to execute)")
-                    .border_style(Style::default().fg(Color::Cyan)),
+                    .title(title.clone())
+                    .border_style(Style::default().fg(border)),
             )
             .wrap(Wrap { trim: false });
         
         f.render_widget(editor, area);
     } else {
-        // Apply syntax highlighting
-        let highlighter = SqlHighlighter::new();
-        
         // Insert cursor marker
-        let mut display_text = app.query_input.clone();
-        let cursor_pos = app.query_cursor.min(display_text.len());
+        let mut display_text = ws.query_input.clone();
+        let cursor_pos = ws.query_cursor.min(display_text.len());
         display_text.insert(cursor_pos, '█');
         
-        // Tokenize with syntax highlighting
-        let tokens = highlighter.tokenize(&display_text);
-        
-        // Build styled lines
-        let mut current_line_spans: Vec<Span> = Vec::new();
-        let mut lines: Vec<Line> = Vec::new();
-        
-        for token in tokens {
-            // Check if token contains newlines
-            if token.text.contains('\n') {
-                let parts: Vec<&str> = token.text.split('\n').collect();
-                for (i, part) in parts.iter().enumerate() {
-                    if !part.is_empty() {
-                        current_line_spans.push(Span::styled(part.to_string(), token.style()));
-                    }
-                    if i < parts.len() - 1 {
-                        // End of line
-                        lines.push(Line::from(current_line_spans.clone()));
-                        current_line_spans.clear();
-                    }
-                }
-            } else {
-                current_line_spans.push(Span::styled(token.text.clone(), token.style()));
-            }
-        }
-        
-        // Add remaining spans as last line
-        if !current_line_spans.is_empty() {
-            lines.push(Line::from(current_line_spans));
-        }
+        // Build syntax-highlighted lines, underlining lint warnings; ranges
+        // after the cursor shift by the width of the cursor marker
+        let shift = |pos: usize| if pos >= cursor_pos { pos + '█'.len_utf8() } else { pos };
+        let marks: Vec<_> = ws
+            .current_diagnostics()
+            .iter()
+            .map(|d| shift(d.start)..shift(d.end))
+            .collect();
+        // A snippet placeholder waiting to be typed over shows as selected
+        let selection = app
+            .editor_selection()
+            .or_else(|| ws.snippet.as_ref()?.highlighted())
+            .filter(|_| focused)
+            .map(|s| shift(s.start)..shift(s.end));
+        let lines = crate::syntax::highlight_lines_selected(&display_text, &marks, selection);
         
         // Handle scrolling
         let total_lines = lines.len();
         let visible_lines = (area.height.saturating_sub(2)) as usize;
-        let start = app.query_scroll_offset;
+        let start = ws.query_scroll_offset;
         let end = (start + visible_lines).min(total_lines);
         
         let mut visible_lines_vec: Vec<Line> = lines[start..end].to_vec();
@@ -115,30 +196,122 @@ fn render_query_editor(f: &mut Frame, app: &App, area: Rect) {
             ));
         }
         
-        let editor = Paragraph::new(visible_lines_vec)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title("SQL Query Editor (Ctrl+Enter or F5 to execute)")
-                    .border_style(Style::default().fg(Color::Cyan)),
-            )
-            .wrap(Wrap { trim: false });
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .title(title.clone())
+            .border_style(Style::default().fg(border));
+        if focused && ws.query_focus == QueryFocus::Editor {
+            if let Some(hint) = ws.autocomplete_engine.signature_hint(&ws.query_input, ws.query_cursor) {
+                block = block.title_bottom(signature_line(&hint));
+            }
+        }
+        let editor = Paragraph::new(visible_lines_vec).block(block).wrap(Wrap { trim: false });
         
         f.render_widget(editor, area);
     }
     
     // Render autocomplete popup if active
-    if app.show_autocomplete && !app.suggestions.is_empty() {
-        render_autocomplete_popup(f, app, area);
+    if ws.show_autocomplete && !ws.suggestions.is_empty() {
+        render_autocomplete_popup(f, ws, area);
     }
 }
 
-fn render_autocomplete_popup(f: &mut Frame, app: &App, editor_area: Rect) {
+// name(a integer, b text) → result with the argument under the cursor
+// highlighted, for the editor's bottom border
+fn signature_line(hint: &crate::autocomplete::SignatureHint) -> ratatui::text::Line<'static> {
+    use ratatui::text::{Line, Span};
+
+    let signature = &hint.signatures[0];
+    let arguments = &signature.arguments;
+    let variadic = arguments.last().is_some_and(|last| last.starts_with("VARIADIC "));
+    let active = if variadic { hint.argument.min(arguments.len() - 1) } else { hint.argument };
+    let mut spans = vec![Span::raw(format!(" {}(", hint.name))];
+    for (i, argument) in arguments.iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::raw(", "));
+        }
+        let style = if i == active {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        spans.push(Span::styled(argument.clone(), style));
+    }
+    spans.push(Span::raw(format!(") → {} ", signature.result)));
+    if hint.signatures.len() > 1 {
+        spans.push(Span::styled(
+            format!("+{} overloads ", hint.signatures.len() - 1),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    Line::from(spans)
+}
+
+fn render_script_summary(f: &mut Frame, ws: &Workspace, area: Rect) {
+    use crate::app::StatementStatus;
+
+    let Some(outcomes) = &ws.script_outcomes else {
+        return;
+    };
+
+    let header = Row::new(vec!["#", "Status", "Rows", "Time", "Statement"])
+        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+
+    let rows: Vec<Row> = outcomes
+        .iter()
+        .enumerate()
+        .map(|(i, outcome)| {
+            let (status, style) = match &outcome.status {
+                StatementStatus::Ok => ("OK".to_string(), Style::default().fg(Color::Green)),
+                StatementStatus::Failed(err) => (format!("ERROR: {}", err), Style::default().fg(Color::Red)),
+                StatementStatus::Skipped => ("skipped".to_string(), Style::default().fg(Color::DarkGray)),
+            };
+            let statement = outcome.sql.lines().next().unwrap_or("").to_string();
+            Row::new(vec![
+                (i + 1).to_string(),
+                status,
+                outcome.row_count.to_string(),
+                format!("{} ms", outcome.elapsed.as_millis()),
+                statement,
+            ])
+            .style(style)
+        })
+        .collect();
+
+    let failed = outcomes
+        .iter()
+        .filter(|o| matches!(o.status, StatementStatus::Failed(_)))
+        .count();
+    let title = format!("Script ({} statements, {} failed)", outcomes.len(), failed);
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(4),
+            Constraint::Percentage(30),
+            Constraint::Length(8),
+            Constraint::Length(10),
+            Constraint::Min(10),
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+
+    f.render_widget(table, area);
+}
+
+fn render_autocomplete_popup(f: &mut Frame, ws: &Workspace, editor_area: Rect) {
     use crate::autocomplete::SuggestionType;
+    use ratatui::text::{Line, Span};
     
     // Calculate popup position (below the first few lines of editor)
-    let popup_height = (app.suggestions.len() as u16 + 2).min(12); // Max 10 suggestions + 2 for borders
-    let popup_width = 40;
+    let popup_height = (ws.suggestions.len() as u16 + 2).min(12); // Max 10 suggestions + 2 for borders
+    let popup_width = 72;
     
     // Position popup in the editor area
     let popup_x = editor_area.x + 2;
@@ -151,22 +324,40 @@ fn render_autocomplete_popup(f: &mut Frame, app: &App, editor_area: Rect) {
         height: popup_height,
     };
     
-    // Build suggestion list
-    let suggestions_text: Vec<String> = app.suggestions.iter().enumerate().map(|(idx, suggestion)| {
+    // Build suggestion list, with the typed characters highlighted
+    let matched_style = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
+    let suggestions_text: Vec<Line> = ws.suggestions.iter().enumerate().map(|(idx, suggestion)| {
         let icon = match suggestion.suggestion_type {
             SuggestionType::Keyword => "K",
             SuggestionType::Table => "T",
             SuggestionType::Column => "C",
             SuggestionType::Function => "F",
+            SuggestionType::Setting => "S",
+            SuggestionType::Value => "V",
+            SuggestionType::Role => "R",
+            SuggestionType::Schema => "N",
+            SuggestionType::Database => "D",
+            SuggestionType::Snippet => "~",
         };
         
-        let marker = if idx == app.suggestion_selected { "» " } else { "  " };
-        format!("{}{} {}", marker, icon, suggestion.text)
+        let marker = if idx == ws.suggestion_selected { "» " } else { "  " };
+        let mut spans = vec![Span::raw(format!("{}{} ", marker, icon))];
+        for (i, c) in suggestion.text.chars().enumerate() {
+            if suggestion.matched.contains(&i) {
+                spans.push(Span::styled(c.to_string(), matched_style));
+            } else {
+                spans.push(Span::raw(c.to_string()));
+            }
+        }
+        if let (SuggestionType::Function | SuggestionType::Snippet, Some(description)) =
+            (&suggestion.suggestion_type, &suggestion.description)
+        {
+            spans.push(Span::raw(format!("  {}", description)));
+        }
+        Line::from(spans)
     }).collect();
     
-    let text_content = suggestions_text.join("\n");
-    
-    let popup = Paragraph::new(text_content)
+    let popup = Paragraph::new(suggestions_text)
         .style(Style::default().fg(Color::White).bg(Color::Black))
         .block(
             Block::default()
@@ -180,10 +371,58 @@ fn render_autocomplete_popup(f: &mut Frame, app: &App, editor_area: Rect) {
     f.render_widget(popup, popup_area);
 }
 
-fn render_query_results(f: &mut Frame, app: &App, area: Rect) {
-    if let Some(result) = &app.query_result {
+// Most notice lines shown under the results; the message log has them all
+const NOTICE_LINES: usize = 5;
+
+// NOTICE/WARNING messages of the statement behind the results
+fn render_notices(f: &mut Frame, app: &App, ws: &Workspace, area: Rect) {
+    use ratatui::text::Line;
+
+    let lines: Vec<Line> = ws
+        .result_notices
+        .iter()
+        .take(NOTICE_LINES)
+        .map(|notice| {
+            let color = if notice.is_warning() { Color::Yellow } else { Color::Cyan };
+            Line::styled(notice.describe(), Style::default().fg(color))
+        })
+        .collect();
+    let count = ws.result_notices.len();
+    let title = if count > NOTICE_LINES {
+        format!("Notices ({}, {}:all)", count, app.keymap.label(Action::ShowMessages))
+    } else {
+        format!("Notices ({})", count)
+    };
+    let pane = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(Color::DarkGray)),
+    );
+    f.render_widget(pane, area);
+}
+
+fn render_query_results(f: &mut Frame, app: &App, ws: &Workspace, area: Rect) {
+    let area = if ws.result_notices.is_empty() {
+        area
+    } else {
+        let height = ws.result_notices.len().min(NOTICE_LINES) as u16 + 2;
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(height)])
+            .split(area);
+        render_notices(f, app, ws, chunks[1]);
+        chunks[0]
+    };
+    if let Some(result) = &ws.query_result {
         if result.rows.is_empty() {
-            let empty = Paragraph::new("Query executed successfully. No rows returned.")
+            let message = match (&ws.result_tag, ws.result_elapsed) {
+                (Some(tag), Some(elapsed)) => format!("{} ({})", tag, elapsed_label(elapsed)),
+                (Some(tag), None) => tag.clone(),
+                (None, Some(elapsed)) => format!("Query executed successfully in {}. No rows returned.", elapsed_label(elapsed)),
+                (None, None) => "Query executed successfully. No rows returned.".to_string(),
+            };
+            let empty = Paragraph::new(message)
                 .style(Style::default().fg(Color::Green))
                 .block(
                     Block::default()
@@ -196,7 +435,7 @@ fn render_query_results(f: &mut Frame, app: &App, area: Rect) {
         }
 
         // Split area for filter input if active
-        let (filter_area, table_area) = if app.results_filter_active {
+        let (filter_area, table_area) = if ws.results_filter_active {
             let chunks = Layout::default()
                 .direction(Direction::Vertical)
                 .constraints([Constraint::Length(3), Constraint::Min(0)])
@@ -208,10 +447,14 @@ fn render_query_results(f: &mut Frame, app: &App, area: Rect) {
 
         // Render filter input if active
         if let Some(filter_area) = filter_area {
-            let filter_text = if app.results_filter_input.is_empty() {
-                "Type to filter rows... (ESC to clear)".to_string()
+            let filter_text = if ws.results_filter_input.is_empty() {
+                "Type to filter rows, or col=value, col>10, col~regex... (ESC to clear)".to_string()
             } else {
-                app.results_filter_input.clone()
+                ws.results_filter_input.clone()
+            };
+            let filter_title = match ws.results_filter().and_then(|f| f.error) {
+                Some(error) => format!("Filter ({})", error),
+                None => "Filter".to_string(),
             };
             
             let filter_widget = Paragraph::new(filter_text)
@@ -219,49 +462,86 @@ fn render_query_results(f: &mut Frame, app: &App, area: Rect) {
                 .block(
                     Block::default()
                         .borders(Borders::ALL)
-                        .title("Filter")
+                        .title(filter_title)
                         .border_style(Style::default().fg(Color::Yellow)),
                 );
             f.render_widget(filter_widget, filter_area);
         }
 
         // Get filtered row indices if filtering is active
-        let filtered_indices = app.get_filtered_rows();
-        let rows_to_display: Vec<&Vec<String>> = if let Some(indices) = &filtered_indices {
+        let filtered_indices = ws.filtered_rows();
+        let rows_to_display: Vec<&Vec<Option<String>>> = if let Some(indices) = &filtered_indices {
             indices.iter().map(|&idx| &result.rows[idx]).collect()
         } else {
             result.rows.iter().collect()
         };
 
+        // Rows sampled for column widths: the first 10, or with auto-fit
+        // the page around the cursor, whichever way the grid has scrolled
+        let sample = if app.config.auto_fit_on_scroll {
+            let page = table_area.height as usize;
+            let start = ws.result_selected_row.saturating_sub(page).min(rows_to_display.len());
+            &rows_to_display[start..(start + 2 * page).min(rows_to_display.len())]
+        } else {
+            &rows_to_display[..rows_to_display.len().min(10)]
+        };
+
+        let null_text = &app.config.theme.grid.null_text;
+
         // Calculate optimal column widths based on content
         let mut col_widths: Vec<usize> = Vec::new();
         for (col_idx, col_name) in result.columns.iter().enumerate() {
-            let mut max_width = col_name.len();
-            // Check first 10 displayed rows to determine width
-            for row in rows_to_display.iter().take(10) {
+            // A width set by hand is kept as it is
+            if let Some(&width) = ws.result_col_widths.get(col_name) {
+                col_widths.push(width);
+                continue;
+            }
+            // Room for the sort indicator
+            let indicator = if ws.result_sort.is_some_and(|(col, _)| col == col_idx) { 2 } else { 0 };
+            let fitted = ws.result_fitted_widths.get(col_name).copied().unwrap_or(0);
+            let mut max_width = (col_name.width() + indicator).max(fitted);
+            for row in sample {
                 if let Some(cell) = row.get(col_idx) {
-                    max_width = max_width.max(cell.len());
+                    let text = match cell {
+                        Some(text) => cell_preview(text, result.column_type(col_idx)),
+                        None => Cow::Borrowed(null_text.as_str()),
+                    };
+                    max_width = max_width.max(text.width());
                 }
             }
             // Limit individual column width to 30 characters
-            col_widths.push(max_width.min(30));
+            col_widths.push(max_width.min(AUTO_COLUMN_WIDTH));
         }
         
         // Calculate visible columns based on scroll offset and available width
         let available_width = table_area.width.saturating_sub(4) as usize; // subtract borders and padding
-        let mut visible_cols: Vec<usize> = Vec::new();
-        let mut used_width = 0;
-        let scroll_offset = app.result_scroll_offset;
-        
-        // Start from scroll offset and add columns until width is full
-        for col_idx in scroll_offset..result.columns.len() {
-            let col_width = col_widths[col_idx] + 3; // Add padding
-            if used_width + col_width <= available_width || visible_cols.is_empty() {
-                visible_cols.push(col_idx);
-                used_width += col_width;
-            } else {
-                break;
-            }
+        let focused = ws.query_focus == QueryFocus::Results;
+        let selected_col = ws.result_selected_col.min(result.columns.len().saturating_sub(1));
+
+        // An expanded column is as wide as its longest value around the
+        // cursor needs, up to the whole grid, and scrolls through the rest
+        let expanded = ws.result_expanded.filter(|_| !col_widths.is_empty()).map(|offset| {
+            let widest = sample
+                .iter()
+                .filter_map(|row| row.get(selected_col)?.as_ref())
+                .map(|text| expanded_text(text).chars().count())
+                .max()
+                .unwrap_or(0)
+                .max(result.columns[selected_col].len());
+            let width = widest.min(available_width.saturating_sub(3)).max(1);
+            let scrollable = widest - width.min(widest);
+            ws.result_expanded_view.set((width, scrollable));
+            col_widths[selected_col] = width;
+            offset.min(scrollable)
+        });
+        let pinned = ws.result_pinned.min(col_widths.len());
+        let mut scroll_offset = ws.result_scroll_offset;
+        let mut visible_cols = visible_columns(&col_widths, pinned, scroll_offset, available_width);
+
+        // Shift right until the grid cursor's column fits on screen
+        while focused && visible_cols.last().is_some_and(|&last| last < selected_col) {
+            scroll_offset += 1;
+            visible_cols = visible_columns(&col_widths, pinned, scroll_offset, available_width);
         }
         
         // Build title with scroll indicators and filter info
@@ -269,28 +549,72 @@ fn render_query_results(f: &mut Frame, app: &App, area: Rect) {
         let displayed_rows = rows_to_display.len();
         let total_rows = result.row_count;
         
-        let filter_info = if filtered_indices.is_some() {
-            format!(" [filtered: {}/{}]", displayed_rows, total_rows)
+        // Rows still on the server, or left there at the configured maximum
+        let (plus, more) = if ws.db.has_pending_rows() {
+            ("+", format!(" {}:more", app.keymap.label(Action::FetchMore)))
+        } else if ws.result_capped {
+            ("", " max_result_rows reached".to_string())
         } else {
-            format!(" ({} rows)", total_rows)
+            ("", String::new())
         };
-        
-        let title = if scroll_offset > 0 && scroll_offset + visible_cols.len() < total_cols {
+        let took = ws.result_elapsed.map(|elapsed| format!(", {}", elapsed_label(elapsed))).unwrap_or_default();
+        let filter_info = if ws.results_filter_active && !ws.results_filter_input.is_empty() {
+            format!(" [filtered: {}/{}{}{}]{}", displayed_rows, total_rows, plus, took, more)
+        } else {
+            format!(" ({}{} rows{}){}", total_rows, plus, took, more)
+        };
+        let filter_info = if ws.auto_limited.is_some() {
+            format!("{} (auto-limited, {}:all)", filter_info, app.keymap.label(Action::RunWithoutLimit))
+        } else {
+            filter_info
+        };
+        let filter_info = match &ws.watch {
+            Some(watch) => format!(
+                "{} (watching every {}s, {}:stop)",
+                filter_info,
+                watch.interval.as_secs(),
+                app.keymap.label(Action::WatchQuery)
+            ),
+            None => filter_info,
+        };
+        let filter_info = match &ws.result_search {
+            Some(term) => format!("{} /{}", filter_info, term),
+            None => filter_info,
+        };
+        let filter_info = match expanded {
+            Some(offset) => format!(
+                "{} [{} expanded, from char {}, ←→:scroll {}:back]",
+                filter_info,
+                result.columns[selected_col],
+                offset + 1,
+                app.keymap.label(Action::ExpandColumn)
+            ),
+            None => filter_info,
+        };
+        let filter_info = match pinned {
+            0 => filter_info,
+            n => format!("{} [{} pinned]", filter_info, n),
+        };
+
+        // The scrolling columns after any pinned ones
+        let first_col = scroll_offset.max(pinned);
+        let end_col = visible_cols.last().map_or(first_col, |&last| last + 1);
+        let title = if first_col > pinned && end_col < total_cols {
             format!("Results{} ◄ cols {}-{}/{} ►", 
                 filter_info,
-                scroll_offset + 1, 
-                scroll_offset + visible_cols.len(),
+                first_col + 1, 
+                end_col,
                 total_cols)
-        } else if scroll_offset > 0 {
+        } else if first_col > pinned {
             format!("Results{} ◄ cols {}-{}/{}", 
                 filter_info,
-                scroll_offset + 1, 
+                first_col + 1, 
                 total_cols,
                 total_cols)
-        } else if scroll_offset + visible_cols.len() < total_cols {
+        } else if end_col < total_cols {
             format!("Results{} cols 1-{}/{} ►", 
                 filter_info,
-                visible_cols.len(),
+                end_col,
                 total_cols)
         } else {
             format!("Results{}", filter_info)
@@ -298,20 +622,38 @@ fn render_query_results(f: &mut Frame, app: &App, area: Rect) {
         
         // Create header with only visible columns
         let header_cells: Vec<String> = visible_cols.iter()
-            .map(|&idx| result.columns[idx].clone())
+            .map(|&idx| match ws.result_sort {
+                Some((col, SortDirection::Ascending)) if col == idx => format!("{} ▲", result.columns[idx]),
+                Some((col, SortDirection::Descending)) if col == idx => format!("{} ▼", result.columns[idx]),
+                _ => result.columns[idx].clone(),
+            })
             .collect();
+        let grid_theme = &app.config.theme.grid;
         let header = Row::new(header_cells)
-            .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+            .style(grid_theme.header_style())
             .bottom_margin(1);
 
         // Create table rows with only visible columns from filtered rows
         let rows: Vec<Row> = rows_to_display
             .iter()
-            .map(|row| {
-                let cells: Vec<String> = visible_cols.iter()
-                    .map(|&idx| row.get(idx).cloned().unwrap_or_else(|| "".to_string()))
+            .enumerate()
+            .map(|(row_idx, row)| {
+                let cells: Vec<Cell> = visible_cols.iter()
+                    .map(|&idx| match row.get(idx) {
+                        Some(Some(text)) if idx == selected_col && expanded.is_some() => {
+                            let scrolled: String = expanded_text(text).chars().skip(expanded.unwrap_or(0)).collect();
+                            Cell::from(scrolled)
+                        }
+                        Some(Some(text)) if ws.cell_matches_search(text) => {
+                            Cell::from(cell_preview(text, result.column_type(idx)).into_owned())
+                                .style(grid_theme.match_style())
+                        }
+                        Some(Some(text)) => Cell::from(cell_preview(text, result.column_type(idx)).into_owned()),
+                        Some(None) => Cell::from(null_text.clone()).style(grid_theme.null_style()),
+                        None => Cell::from(""),
+                    })
                     .collect();
-                Row::new(cells)
+                Row::new(cells).style(grid_theme.row_style(row_idx))
             })
             .collect();
 
@@ -323,16 +665,26 @@ fn render_query_results(f: &mut Frame, app: &App, area: Rect) {
             })
             .collect();
 
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(if focused { Color::Yellow } else { Color::Cyan }));
+        if focused {
+            block = block.title_bottom(stats_footer(&rows_to_display, result, selected_col));
+        }
         let table = Table::new(rows, constraints)
             .header(header)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title(title)
-                    .border_style(Style::default().fg(Color::Cyan)),
-            );
+            .row_highlight_style(grid_theme.selected_row_style())
+            .cell_highlight_style(grid_theme.selected_cell_style())
+            .block(block);
+
+        let mut state = TableState::default();
+        if focused {
+            state.select(Some(ws.result_selected_row.min(displayed_rows.saturating_sub(1))));
+            state.select_column(visible_cols.iter().position(|&idx| idx == selected_col));
+        }
 
-        f.render_widget(table, table_area);
+        f.render_stateful_widget(table, table_area, &mut state);
     } else {
         let help = Paragraph::new("No query results yet.\n\nWrite a SQL query above and press !e to execute.")
             .style(Style::default().fg(Color::DarkGray))
@@ -345,3 +697,46 @@ fn render_query_results(f: &mut Frame, app: &App, area: Rect) {
         f.render_widget(help, area);
     }
 }
+
+// Figures over the selected column of the rows shown, filtered or not
+fn stats_footer(rows: &[&Vec<Option<String>>], result: &QueryResult, col: usize) -> String {
+    let stats = column_stats(rows, col, result.column_type(col));
+    let mut parts = vec![
+        format!("{} rows", stats.rows),
+        format!("{} null", stats.nulls),
+        format!("{} distinct", stats.distinct),
+    ];
+    if let (Some(sum), Some(avg)) = (stats.sum, stats.avg) {
+        parts.push(format!("sum {}", format_number(sum)));
+        parts.push(format!("avg {}", format_number(avg)));
+    }
+    let short = |value: &str| match value.char_indices().nth(STATS_VALUE_WIDTH) {
+        Some((end, _)) => format!("{}…", &value[..end]),
+        None => value.to_string(),
+    };
+    if let (Some(min), Some(max)) = (&stats.min, &stats.max) {
+        parts.push(format!("min {}", short(min)));
+        parts.push(format!("max {}", short(max)));
+    }
+    format!(" {}: {} ", result.columns[col], parts.join(" · "))
+}
+
+// The first `pinned` columns, then those that fit in the rest of
+// `available_width` starting at `offset`; at least one of those is always
+// shown so the grid can scroll to any column
+fn visible_columns(col_widths: &[usize], pinned: usize, offset: usize, available_width: usize) -> Vec<usize> {
+    let mut visible_cols: Vec<usize> = (0..pinned).collect();
+    let mut used_width: usize = col_widths[..pinned].iter().map(|width| width + 3).sum();
+
+    for (col_idx, width) in col_widths.iter().enumerate().skip(offset.max(pinned)) {
+        let col_width = width + 3; // Add padding
+        if used_width + col_width <= available_width || visible_cols.len() == pinned {
+            visible_cols.push(col_idx);
+            used_width += col_width;
+        } else {
+            break;
+        }
+    }
+
+    visible_cols
+}
@@ -0,0 +1,59 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::{tour_steps, App, TourAnchor};
+
+// Current walkthrough step, in a box next to the part of `area` it describes
+pub fn render_tour(f: &mut Frame, app: &App, area: Rect) {
+    let Some(index) = app.tour else {
+        return;
+    };
+    let steps = tour_steps(&app.keymap);
+    let Some(step) = steps.get(index) else {
+        return;
+    };
+
+    let width = 64.min(area.width.saturating_sub(4));
+    let text_lines: u16 = step
+        .text
+        .lines()
+        .map(|line| (line.chars().count() as u16).div_ceil(width.saturating_sub(2).max(1)).max(1))
+        .sum();
+    let height = (text_lines + 4).min(area.height);
+    let center_x = area.x + area.width.saturating_sub(width) / 2;
+    let center_y = area.y + area.height.saturating_sub(height) / 2;
+    let (x, y) = match step.anchor {
+        TourAnchor::Center => (center_x, center_y),
+        TourAnchor::Browser => (area.x + 2.min(area.width), center_y),
+        TourAnchor::Editor => (center_x, area.y + 2.min(area.height)),
+        TourAnchor::Results => (center_x, area.y + area.height.saturating_sub(height + 1)),
+    };
+    let popup_area = Rect { x, y, width, height };
+
+    let mut text = step.text.clone();
+    text.push_str(&format!("\n\n{}", navigation_hint(index, steps.len())));
+    let paragraph = Paragraph::new(text)
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("{} ({}/{})", step.title, index + 1, steps.len()))
+                .title_style(Style::default().add_modifier(Modifier::BOLD))
+                .border_style(Style::default().fg(Color::Cyan)),
+        );
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(paragraph, popup_area);
+}
+
+fn navigation_hint(index: usize, count: usize) -> &'static str {
+    match index {
+        0 => "Enter:next | Esc:close",
+        i if i + 1 == count => "Enter:done | ←:back",
+        _ => "Enter:next | ←:back | Esc:close",
+    }
+}